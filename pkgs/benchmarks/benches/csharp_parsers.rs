@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_CS: &str = r#"
@@ -222,6 +223,7 @@ public class Program
 "#;
 
 fn bench_csharp_parsers(c: &mut Criterion) {
+    let options = ParseOptions::default();
     let mut group = c.benchmark_group("csharp_parsers");
 
     // Small benchmark
@@ -231,7 +233,18 @@ fn bench_csharp_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "small"),
         &SMALL_CS,
         |b, code| {
-            b.iter(|| volumen_parser_cs::ParserCs::parse(black_box(code), "test.cs"));
+            b.iter(|| {
+                volumen_parser_cs::ParserCs::new().parse(black_box(code), "test.cs", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "small"),
+        &SMALL_CS,
+        |b, code| {
+            let mut parser = volumen_parser_cs::ParserCs::new();
+            b.iter(|| parser.parse(black_box(code), "test.cs", &options));
         },
     );
 
@@ -242,7 +255,18 @@ fn bench_csharp_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "medium"),
         &MEDIUM_CS,
         |b, code| {
-            b.iter(|| volumen_parser_cs::ParserCs::parse(black_box(code), "test.cs"));
+            b.iter(|| {
+                volumen_parser_cs::ParserCs::new().parse(black_box(code), "test.cs", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "medium"),
+        &MEDIUM_CS,
+        |b, code| {
+            let mut parser = volumen_parser_cs::ParserCs::new();
+            b.iter(|| parser.parse(black_box(code), "test.cs", &options));
         },
     );
 
@@ -253,7 +277,18 @@ fn bench_csharp_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "large"),
         &LARGE_CS,
         |b, code| {
-            b.iter(|| volumen_parser_cs::ParserCs::parse(black_box(code), "test.cs"));
+            b.iter(|| {
+                volumen_parser_cs::ParserCs::new().parse(black_box(code), "test.cs", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "large"),
+        &LARGE_CS,
+        |b, code| {
+            let mut parser = volumen_parser_cs::ParserCs::new();
+            b.iter(|| parser.parse(black_box(code), "test.cs", &options));
         },
     );
 
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_GO: &str = r#"
@@ -223,6 +224,7 @@ func main() {
 "#;
 
 fn bench_go_parsers(c: &mut Criterion) {
+    let options = ParseOptions::default();
     let mut group = c.benchmark_group("go_parsers");
 
     // Small benchmark
@@ -232,7 +234,18 @@ fn bench_go_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "small"),
         &SMALL_GO,
         |b, code| {
-            b.iter(|| volumen_parser_go::ParserGo::parse(black_box(code), "test.go"));
+            b.iter(|| {
+                volumen_parser_go::ParserGo::new().parse(black_box(code), "test.go", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "small"),
+        &SMALL_GO,
+        |b, code| {
+            let mut parser = volumen_parser_go::ParserGo::new();
+            b.iter(|| parser.parse(black_box(code), "test.go", &options));
         },
     );
 
@@ -243,7 +256,18 @@ fn bench_go_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "medium"),
         &MEDIUM_GO,
         |b, code| {
-            b.iter(|| volumen_parser_go::ParserGo::parse(black_box(code), "test.go"));
+            b.iter(|| {
+                volumen_parser_go::ParserGo::new().parse(black_box(code), "test.go", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "medium"),
+        &MEDIUM_GO,
+        |b, code| {
+            let mut parser = volumen_parser_go::ParserGo::new();
+            b.iter(|| parser.parse(black_box(code), "test.go", &options));
         },
     );
 
@@ -254,7 +278,18 @@ fn bench_go_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "large"),
         &LARGE_GO,
         |b, code| {
-            b.iter(|| volumen_parser_go::ParserGo::parse(black_box(code), "test.go"));
+            b.iter(|| {
+                volumen_parser_go::ParserGo::new().parse(black_box(code), "test.go", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "large"),
+        &LARGE_GO,
+        |b, code| {
+            let mut parser = volumen_parser_go::ParserGo::new();
+            b.iter(|| parser.parse(black_box(code), "test.go", &options));
         },
     );
 
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_JAVA: &str = r#"
@@ -197,6 +198,7 @@ public class Main {
 "#;
 
 fn bench_java_parsers(c: &mut Criterion) {
+    let options = ParseOptions::default();
     let mut group = c.benchmark_group("java_parsers");
 
     // Small benchmark
@@ -206,7 +208,18 @@ fn bench_java_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "small"),
         &SMALL_JAVA,
         |b, code| {
-            b.iter(|| volumen_parser_java::ParserJava::parse(black_box(code), "test.java"));
+            b.iter(|| {
+                volumen_parser_java::ParserJava::new().parse(black_box(code), "test.java", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "small"),
+        &SMALL_JAVA,
+        |b, code| {
+            let mut parser = volumen_parser_java::ParserJava::new();
+            b.iter(|| parser.parse(black_box(code), "test.java", &options));
         },
     );
 
@@ -217,7 +230,18 @@ fn bench_java_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "medium"),
         &MEDIUM_JAVA,
         |b, code| {
-            b.iter(|| volumen_parser_java::ParserJava::parse(black_box(code), "test.java"));
+            b.iter(|| {
+                volumen_parser_java::ParserJava::new().parse(black_box(code), "test.java", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "medium"),
+        &MEDIUM_JAVA,
+        |b, code| {
+            let mut parser = volumen_parser_java::ParserJava::new();
+            b.iter(|| parser.parse(black_box(code), "test.java", &options));
         },
     );
 
@@ -228,7 +252,18 @@ fn bench_java_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "large"),
         &LARGE_JAVA,
         |b, code| {
-            b.iter(|| volumen_parser_java::ParserJava::parse(black_box(code), "test.java"));
+            b.iter(|| {
+                volumen_parser_java::ParserJava::new().parse(black_box(code), "test.java", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "large"),
+        &LARGE_JAVA,
+        |b, code| {
+            let mut parser = volumen_parser_java::ParserJava::new();
+            b.iter(|| parser.parse(black_box(code), "test.java", &options));
         },
     );
 
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_PHP: &str = r#"
@@ -195,6 +196,7 @@ function main() {
 "#;
 
 fn bench_php_parsers(c: &mut Criterion) {
+    let options = ParseOptions::default();
     let mut group = c.benchmark_group("php_parsers");
 
     // Small benchmark
@@ -204,7 +206,18 @@ fn bench_php_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "small"),
         &SMALL_PHP,
         |b, code| {
-            b.iter(|| volumen_parser_php::ParserPhp::parse(black_box(code), "test.php"));
+            b.iter(|| {
+                volumen_parser_php::ParserPhp::new().parse(black_box(code), "test.php", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "small"),
+        &SMALL_PHP,
+        |b, code| {
+            let mut parser = volumen_parser_php::ParserPhp::new();
+            b.iter(|| parser.parse(black_box(code), "test.php", &options));
         },
     );
 
@@ -215,7 +228,18 @@ fn bench_php_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "medium"),
         &MEDIUM_PHP,
         |b, code| {
-            b.iter(|| volumen_parser_php::ParserPhp::parse(black_box(code), "test.php"));
+            b.iter(|| {
+                volumen_parser_php::ParserPhp::new().parse(black_box(code), "test.php", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "medium"),
+        &MEDIUM_PHP,
+        |b, code| {
+            let mut parser = volumen_parser_php::ParserPhp::new();
+            b.iter(|| parser.parse(black_box(code), "test.php", &options));
         },
     );
 
@@ -226,7 +250,18 @@ fn bench_php_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "large"),
         &LARGE_PHP,
         |b, code| {
-            b.iter(|| volumen_parser_php::ParserPhp::parse(black_box(code), "test.php"));
+            b.iter(|| {
+                volumen_parser_php::ParserPhp::new().parse(black_box(code), "test.php", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "large"),
+        &LARGE_PHP,
+        |b, code| {
+            let mut parser = volumen_parser_php::ParserPhp::new();
+            b.iter(|| parser.parse(black_box(code), "test.php", &options));
         },
     );
 
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_PYTHON: &str = r#"
@@ -154,6 +155,7 @@ async def main():
 "#;
 
 fn bench_python_parsers(c: &mut Criterion) {
+    let options = ParseOptions::default();
     let mut group = c.benchmark_group("python_parsers");
 
     // Small benchmark
@@ -163,7 +165,9 @@ fn bench_python_parsers(c: &mut Criterion) {
         BenchmarkId::new("Ruff", "small"),
         &SMALL_PYTHON,
         |b, code| {
-            b.iter(|| volumen_parser_py::ParserPy::parse(black_box(code), "test.py"));
+            b.iter(|| {
+                volumen_parser_py::ParserPy::new().parse(black_box(code), "test.py", &options)
+            });
         },
     );
 
@@ -171,7 +175,22 @@ fn bench_python_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "small"),
         &SMALL_PYTHON,
         |b, code| {
-            b.iter(|| volumen_parser_py_tree_sitter::ParserPy::parse(black_box(code), "test.py"));
+            b.iter(|| {
+                volumen_parser_py_tree_sitter::ParserPy::new().parse(
+                    black_box(code),
+                    "test.py",
+                    &options,
+                )
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "small"),
+        &SMALL_PYTHON,
+        |b, code| {
+            let mut parser = volumen_parser_py_tree_sitter::ParserPy::new();
+            b.iter(|| parser.parse(black_box(code), "test.py", &options));
         },
     );
 
@@ -182,7 +201,9 @@ fn bench_python_parsers(c: &mut Criterion) {
         BenchmarkId::new("Ruff", "medium"),
         &MEDIUM_PYTHON,
         |b, code| {
-            b.iter(|| volumen_parser_py::ParserPy::parse(black_box(code), "test.py"));
+            b.iter(|| {
+                volumen_parser_py::ParserPy::new().parse(black_box(code), "test.py", &options)
+            });
         },
     );
 
@@ -190,7 +211,22 @@ fn bench_python_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "medium"),
         &MEDIUM_PYTHON,
         |b, code| {
-            b.iter(|| volumen_parser_py_tree_sitter::ParserPy::parse(black_box(code), "test.py"));
+            b.iter(|| {
+                volumen_parser_py_tree_sitter::ParserPy::new().parse(
+                    black_box(code),
+                    "test.py",
+                    &options,
+                )
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "medium"),
+        &MEDIUM_PYTHON,
+        |b, code| {
+            let mut parser = volumen_parser_py_tree_sitter::ParserPy::new();
+            b.iter(|| parser.parse(black_box(code), "test.py", &options));
         },
     );
 
@@ -201,7 +237,9 @@ fn bench_python_parsers(c: &mut Criterion) {
         BenchmarkId::new("Ruff", "large"),
         &LARGE_PYTHON,
         |b, code| {
-            b.iter(|| volumen_parser_py::ParserPy::parse(black_box(code), "test.py"));
+            b.iter(|| {
+                volumen_parser_py::ParserPy::new().parse(black_box(code), "test.py", &options)
+            });
         },
     );
 
@@ -209,7 +247,22 @@ fn bench_python_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "large"),
         &LARGE_PYTHON,
         |b, code| {
-            b.iter(|| volumen_parser_py_tree_sitter::ParserPy::parse(black_box(code), "test.py"));
+            b.iter(|| {
+                volumen_parser_py_tree_sitter::ParserPy::new().parse(
+                    black_box(code),
+                    "test.py",
+                    &options,
+                )
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "large"),
+        &LARGE_PYTHON,
+        |b, code| {
+            let mut parser = volumen_parser_py_tree_sitter::ParserPy::new();
+            b.iter(|| parser.parse(black_box(code), "test.py", &options));
         },
     );
 
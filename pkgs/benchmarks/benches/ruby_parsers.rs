@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_RUBY: &str = r#"
@@ -174,6 +175,7 @@ end
 "#;
 
 fn bench_ruby_parsers(c: &mut Criterion) {
+    let options = ParseOptions::default();
     let mut group = c.benchmark_group("ruby_parsers");
 
     // Small benchmark
@@ -183,7 +185,18 @@ fn bench_ruby_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "small"),
         &SMALL_RUBY,
         |b, code| {
-            b.iter(|| volumen_parser_rb::ParserRb::parse(black_box(code), "test.rb"));
+            b.iter(|| {
+                volumen_parser_rb::ParserRb::new().parse(black_box(code), "test.rb", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "small"),
+        &SMALL_RUBY,
+        |b, code| {
+            let mut parser = volumen_parser_rb::ParserRb::new();
+            b.iter(|| parser.parse(black_box(code), "test.rb", &options));
         },
     );
 
@@ -194,7 +207,18 @@ fn bench_ruby_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "medium"),
         &MEDIUM_RUBY,
         |b, code| {
-            b.iter(|| volumen_parser_rb::ParserRb::parse(black_box(code), "test.rb"));
+            b.iter(|| {
+                volumen_parser_rb::ParserRb::new().parse(black_box(code), "test.rb", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "medium"),
+        &MEDIUM_RUBY,
+        |b, code| {
+            let mut parser = volumen_parser_rb::ParserRb::new();
+            b.iter(|| parser.parse(black_box(code), "test.rb", &options));
         },
     );
 
@@ -205,7 +229,18 @@ fn bench_ruby_parsers(c: &mut Criterion) {
         BenchmarkId::new("Tree-sitter", "large"),
         &LARGE_RUBY,
         |b, code| {
-            b.iter(|| volumen_parser_rb::ParserRb::parse(black_box(code), "test.rb"));
+            b.iter(|| {
+                volumen_parser_rb::ParserRb::new().parse(black_box(code), "test.rb", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Tree-sitter reused", "large"),
+        &LARGE_RUBY,
+        |b, code| {
+            let mut parser = volumen_parser_rb::ParserRb::new();
+            b.iter(|| parser.parse(black_box(code), "test.rb", &options));
         },
     );
 
@@ -1,5 +1,6 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use volumen_parser_core::VolumenParser;
+use volumen_types::ParseOptions;
 
 // Small code sample - basic prompt detection
 const SMALL_TYPESCRIPT: &str = r#"
@@ -193,17 +194,76 @@ export { AIAssistant, ConversationManager, createSpecializedPrompt };
 "#;
 
 fn bench_typescript_parsers(c: &mut Criterion) {
-    c.bench_function("typescript_parsers/Oxc/small", |b| {
-        b.iter(|| volumen_parser_ts::ParserTs::parse(black_box(SMALL_TYPESCRIPT), "test.ts"));
-    });
-
-    c.bench_function("typescript_parsers/Oxc/medium", |b| {
-        b.iter(|| volumen_parser_ts::ParserTs::parse(black_box(MEDIUM_TYPESCRIPT), "test.ts"));
-    });
-
-    c.bench_function("typescript_parsers/Oxc/large", |b| {
-        b.iter(|| volumen_parser_ts::ParserTs::parse(black_box(LARGE_TYPESCRIPT), "test.ts"));
-    });
+    let options = ParseOptions::default();
+    let mut group = c.benchmark_group("typescript_parsers");
+
+    // Small benchmark
+    group.throughput(Throughput::Bytes(SMALL_TYPESCRIPT.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("Oxc", "small"),
+        &SMALL_TYPESCRIPT,
+        |b, code| {
+            b.iter(|| {
+                volumen_parser_ts::ParserTs::new().parse(black_box(code), "test.ts", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Oxc reused", "small"),
+        &SMALL_TYPESCRIPT,
+        |b, code| {
+            let mut parser = volumen_parser_ts::ParserTs::new();
+            b.iter(|| parser.parse(black_box(code), "test.ts", &options));
+        },
+    );
+
+    // Medium benchmark
+    group.throughput(Throughput::Bytes(MEDIUM_TYPESCRIPT.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("Oxc", "medium"),
+        &MEDIUM_TYPESCRIPT,
+        |b, code| {
+            b.iter(|| {
+                volumen_parser_ts::ParserTs::new().parse(black_box(code), "test.ts", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Oxc reused", "medium"),
+        &MEDIUM_TYPESCRIPT,
+        |b, code| {
+            let mut parser = volumen_parser_ts::ParserTs::new();
+            b.iter(|| parser.parse(black_box(code), "test.ts", &options));
+        },
+    );
+
+    // Large benchmark
+    group.throughput(Throughput::Bytes(LARGE_TYPESCRIPT.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("Oxc", "large"),
+        &LARGE_TYPESCRIPT,
+        |b, code| {
+            b.iter(|| {
+                volumen_parser_ts::ParserTs::new().parse(black_box(code), "test.ts", &options)
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Oxc reused", "large"),
+        &LARGE_TYPESCRIPT,
+        |b, code| {
+            let mut parser = volumen_parser_ts::ParserTs::new();
+            b.iter(|| parser.parse(black_box(code), "test.ts", &options));
+        },
+    );
+
+    group.finish();
 }
 
 criterion_group!(benches, bench_typescript_parsers);
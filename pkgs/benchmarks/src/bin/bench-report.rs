@@ -131,11 +131,12 @@ fn parse_benchmark(
         0
     };
 
-    // Get token count from JSON
+    // Get token count from JSON, the reused parser variants parse the same
+    // code as the parsers they're based on
     let tokens = token_counts
         .languages
         .get(bench_name)?
-        .get(parser)?
+        .get(parser.trim_end_matches(" reused"))?
         .get(size)
         .copied()
         .unwrap_or(0);
@@ -177,20 +178,40 @@ fn main() {
     let mut all_large: Vec<ParserResult> = Vec::new();
 
     let languages = vec![
-        ("go_parsers", "Go Parser", vec!["Tree-sitter"]),
-        ("java_parsers", "Java Parser", vec!["Tree-sitter"]),
-        ("csharp_parsers", "C# Parser", vec!["Tree-sitter"]),
-        ("php_parsers", "PHP Parser", vec!["Tree-sitter"]),
-        ("ruby_parsers", "Ruby Parser", vec!["Tree-sitter"]),
+        (
+            "go_parsers",
+            "Go Parser",
+            vec!["Tree-sitter", "Tree-sitter reused"],
+        ),
+        (
+            "java_parsers",
+            "Java Parser",
+            vec!["Tree-sitter", "Tree-sitter reused"],
+        ),
+        (
+            "csharp_parsers",
+            "C# Parser",
+            vec!["Tree-sitter", "Tree-sitter reused"],
+        ),
+        (
+            "php_parsers",
+            "PHP Parser",
+            vec!["Tree-sitter", "Tree-sitter reused"],
+        ),
+        (
+            "ruby_parsers",
+            "Ruby Parser",
+            vec!["Tree-sitter", "Tree-sitter reused"],
+        ),
         (
             "typescript_parsers",
             "TypeScript Parser",
-            vec!["Oxc", "Tree-sitter"],
+            vec!["Oxc", "Oxc reused", "Tree-sitter"],
         ),
         (
             "python_parsers",
             "Python Parser",
-            vec!["Ruff", "RustPython", "Tree-sitter"],
+            vec!["Ruff", "RustPython", "Tree-sitter", "Tree-sitter reused"],
         ),
     ];
 
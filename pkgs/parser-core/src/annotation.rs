@@ -1,4 +1,8 @@
-const PROMPT_LENGTH: usize = "@prompt".len();
+use crate::parse_role;
+use volumen_types::{PromptAnnotationAttribute, PromptRole, SpanShape};
+
+/// Default annotation marker.
+pub(crate) const PROMPT_MARKER: &str = "@prompt";
 
 /// Computes the inner span offsets for a comment text.
 /// Returns (inner_start_offset, inner_end_offset) relative to the comment start.
@@ -25,23 +29,157 @@ pub fn compute_comment_inner_offsets(text: &str) -> (u32, u32) {
 
 /// Parses annotation text to determine if it contains a valid @prompt marker.
 pub fn parse_annotation(annotation: &str) -> Option<bool> {
-    let comment = annotation.to_lowercase();
-    if let Some(pos) = comment.find("@prompt") {
-        let before_char = if pos == 0 {
-            None
-        } else {
-            comment.chars().nth(pos - 1)
+    parse_marker_annotation(annotation, PROMPT_MARKER)
+}
+
+/// Parses annotation text to determine if it contains a valid marker, e.g.,
+/// `@prompt`. The marker must be lowercase.
+pub(crate) fn parse_marker_annotation(annotation: &str, marker: &str) -> Option<bool> {
+    annotation
+        .to_ascii_lowercase()
+        .contains(marker)
+        .then(|| find_marker(annotation, marker).is_some())
+}
+
+/// Parses `key=value` attributes that follow a valid @prompt marker in
+/// the annotation span shapes, e.g., `// @prompt name=greeting model=gpt-4o`.
+/// Only the marker line is considered, and the attribute spans are absolute
+/// source offsets.
+pub fn parse_annotation_attributes(
+    source: &str,
+    spans: &[SpanShape],
+) -> Vec<PromptAnnotationAttribute> {
+    parse_marker_attributes(source, spans, &[PROMPT_MARKER])
+}
+
+/// Parses `key=value` attributes that follow the first valid marker from
+/// the list. The markers must be lowercase.
+pub(crate) fn parse_marker_attributes<Marker: AsRef<str>>(
+    source: &str,
+    spans: &[SpanShape],
+    markers: &[Marker],
+) -> Vec<PromptAnnotationAttribute> {
+    let mut attributes = Vec::new();
+    for span in spans {
+        let start = span.inner.0 as usize;
+        let Some(text) = source.get(start..span.inner.1 as usize) else {
+            continue;
+        };
+        let Some(marker_end) = markers
+            .iter()
+            .filter_map(|marker| find_marker(text, marker.as_ref()))
+            .min_by_key(|(pos, _)| *pos)
+            .map(|(_, end)| end)
+        else {
+            continue;
         };
+        let line_end = text[marker_end..]
+            .find('\n')
+            .map_or(text.len(), |pos| marker_end + pos);
+        parse_attributes(
+            &text[marker_end..line_end],
+            (start + marker_end) as u32,
+            &mut attributes,
+        );
+    }
+    attributes
+}
 
-        let after_pos = pos + PROMPT_LENGTH;
-        let after_char = comment.chars().nth(after_pos);
+/// Parses the chat role hint in the first word that follows the first valid
+/// marker from the list, e.g., `// @prompt system`. The markers must be
+/// lowercase.
+pub(crate) fn parse_marker_role<Marker: AsRef<str>>(
+    source: &str,
+    spans: &[SpanShape],
+    markers: &[Marker],
+) -> Option<PromptRole> {
+    spans.iter().find_map(|span| {
+        let text = source.get(span.inner.0 as usize..span.inner.1 as usize)?;
+        let marker_end = markers
+            .iter()
+            .filter_map(|marker| find_marker(text, marker.as_ref()))
+            .min_by_key(|(pos, _)| *pos)
+            .map(|(_, end)| end)?;
+        let line = text[marker_end..].lines().next()?;
+        let word = line.split_whitespace().next()?;
+        parse_role(word.trim_end_matches(|c: char| !c.is_alphanumeric()))
+    })
+}
 
-        let valid_before = before_char.map_or(true, |c| !c.is_alphanumeric() && c != '_');
-        let valid_after = after_char.map_or(true, |c| !c.is_alphanumeric() && c != '_');
+/// Finds the first valid marker and returns the byte offsets of its start
+/// and right after it.
+fn find_marker(text: &str, marker: &str) -> Option<(usize, usize)> {
+    let lower = text.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find(marker) {
+        let pos = from + pos;
+        let end = pos + marker.len();
+        let valid_before = pos == 0 || !is_ident_byte(bytes[pos - 1]);
+        let valid_after = end >= bytes.len() || !is_ident_byte(bytes[end]);
+        if valid_before && valid_after {
+            return Some((pos, end));
+        }
+        from = end;
+    }
+    None
+}
 
-        Some(valid_before && valid_after)
-    } else {
-        None
+fn is_ident_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii()
+}
+
+fn is_attribute_key_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-' | b'.')
+}
+
+/// Parses whitespace-separated `key=value` pairs. Values can be quoted with
+/// `"` or `'` to include whitespace. Tokens without `=` are skipped.
+fn parse_attributes(text: &str, offset: u32, attributes: &mut Vec<PromptAnnotationAttribute>) {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        if bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        let token_start = pos;
+        while pos < bytes.len() && is_attribute_key_byte(bytes[pos]) {
+            pos += 1;
+        }
+        let key_end = pos;
+
+        if key_end == token_start || pos >= bytes.len() || bytes[pos] != b'=' {
+            while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            continue;
+        }
+        pos += 1;
+
+        let value = if pos < bytes.len() && matches!(bytes[pos], b'"' | b'\'') {
+            let quote = bytes[pos];
+            let value_start = pos + 1;
+            let value_end = bytes[value_start..]
+                .iter()
+                .position(|&byte| byte == quote)
+                .map_or(bytes.len(), |len| value_start + len);
+            pos = (value_end + 1).min(bytes.len());
+            &text[value_start..value_end]
+        } else {
+            let value_start = pos;
+            while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            &text[value_start..pos]
+        };
+
+        attributes.push(PromptAnnotationAttribute {
+            key: text[token_start..key_end].to_string(),
+            value: value.to_string(),
+            span: (offset + token_start as u32, offset + pos as u32),
+        });
     }
 }
 
@@ -108,5 +246,104 @@ mod tests {
     #[test]
     fn repeating() {
         assert_eq!(parse_annotation("@prompt for @prompt usage"), Some(true));
+        assert_eq!(parse_annotation("@prompting for @prompt usage"), Some(true));
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert_eq!(parse_annotation("// 你好 @prompt"), Some(true));
+        assert_eq!(parse_annotation("# привет @prompt greeting"), Some(true));
+        assert_eq!(parse_annotation("ПРИВЕТ @PROMPT"), Some(true));
+        assert_eq!(parse_annotation("İİ @prompt"), Some(true));
+        assert_eq!(parse_annotation("привет@prompt"), Some(false));
+        assert_eq!(parse_annotation("@prompté"), Some(false));
+        assert_eq!(
+            parse_marker_annotation("# привет @prompt-ignore", "@prompt-ignore"),
+            Some(true)
+        );
+    }
+
+    fn attributes(source: &str) -> Vec<(String, String, (u32, u32))> {
+        let spans = vec![SpanShape {
+            outer: (0, source.len() as u32),
+            inner: (0, source.len() as u32),
+        }];
+        parse_annotation_attributes(source, &spans)
+            .into_iter()
+            .map(|attr| (attr.key, attr.value, attr.span))
+            .collect()
+    }
+
+    #[test]
+    fn attributes_basic() {
+        assert_eq!(
+            attributes("@prompt name=greeting model=gpt-4o role=system"),
+            vec![
+                ("name".to_string(), "greeting".to_string(), (8, 21)),
+                ("model".to_string(), "gpt-4o".to_string(), (22, 34)),
+                ("role".to_string(), "system".to_string(), (35, 46)),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_quoted() {
+        assert_eq!(
+            attributes(r#"@prompt name="Hello world" tag='a b'"#),
+            vec![
+                ("name".to_string(), "Hello world".to_string(), (8, 26)),
+                ("tag".to_string(), "a b".to_string(), (27, 36)),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_mixed() {
+        assert_eq!(
+            attributes("* @PROMPT for user greeting name=hi"),
+            vec![("name".to_string(), "hi".to_string(), (28, 35))]
+        );
+        assert_eq!(attributes("name=before @prompt"), vec![]);
+        assert_eq!(
+            attributes("@prompt name=first\nmodel=second"),
+            vec![("name".to_string(), "first".to_string(), (8, 18))]
+        );
+    }
+
+    #[test]
+    fn attributes_none() {
+        assert_eq!(attributes("@prompt"), vec![]);
+        assert_eq!(attributes("@prompting name=x"), vec![]);
+        assert_eq!(attributes("regular comment name=x"), vec![]);
+    }
+
+    #[test]
+    fn role() {
+        let role = |source: &str| {
+            let spans = vec![SpanShape {
+                outer: (0, source.len() as u32),
+                inner: (0, source.len() as u32),
+            }];
+            parse_marker_role(source, &spans, &[PROMPT_MARKER])
+        };
+        assert_eq!(role("@prompt system"), Some(PromptRole::System));
+        assert_eq!(role("* @PROMPT User: greeting"), Some(PromptRole::User));
+        assert_eq!(role("@prompt greeting"), None);
+        assert_eq!(role("@prompt\nsystem"), None);
+        assert_eq!(role("system @prompt"), None);
+        assert_eq!(role("@prompt"), None);
+    }
+
+    #[test]
+    fn attributes_offset() {
+        let source = "// @prompt name=x";
+        let spans = vec![SpanShape {
+            outer: (0, 17),
+            inner: (2, 17),
+        }];
+        let attrs = parse_annotation_attributes(source, &spans);
+        assert_eq!(attrs.len(), 1);
+        assert_eq!(attrs[0].span, (11, 17));
+        assert_eq!(&source[11..17], "name=x");
     }
 }
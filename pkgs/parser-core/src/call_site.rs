@@ -0,0 +1,624 @@
+use volumen_types::*;
+
+/// Preset call site signature: callee path, argument name, argument position
+/// and the prompt role.
+type CallSitePreset = (
+    &'static str,
+    Option<&'static str>,
+    Option<u32>,
+    Option<PromptRole>,
+);
+
+/// Built-in call site signature presets for the common LLM SDKs. The presets
+/// match the callees by the path suffix, e.g., `chat.create` or
+/// `SystemMessage`, which can be unrelated code, so they're opt-in.
+const CALL_SITE_PRESETS: &[(&str, &[CallSitePreset])] = &[
+    (
+        "openai",
+        &[
+            ("chat.completions.create", Some("messages"), None, None),
+            ("chat.completions.new", Some("messages"), None, None),
+            ("CreateChatCompletion", Some("messages"), None, None),
+            ("chat.create", Some("messages"), None, None),
+            ("completions.create", Some("prompt"), None, None),
+            (
+                "responses.create",
+                Some("instructions"),
+                None,
+                Some(PromptRole::System),
+            ),
+            ("responses.create", Some("input"), None, None),
+            // The Go SDK message helpers, qualified with the package as the
+            // bare names, e.g., LangChain `SystemMessage`, are too generic
+            (
+                "openai.SystemMessage",
+                None,
+                Some(0),
+                Some(PromptRole::System),
+            ),
+            (
+                "openai.DeveloperMessage",
+                None,
+                Some(0),
+                Some(PromptRole::System),
+            ),
+            ("openai.UserMessage", None, Some(0), Some(PromptRole::User)),
+            (
+                "openai.AssistantMessage",
+                None,
+                Some(0),
+                Some(PromptRole::Assistant),
+            ),
+            ("addSystemMessage", None, Some(0), Some(PromptRole::System)),
+            (
+                "addDeveloperMessage",
+                None,
+                Some(0),
+                Some(PromptRole::System),
+            ),
+            ("addUserMessage", None, Some(0), Some(PromptRole::User)),
+            (
+                "addAssistantMessage",
+                None,
+                Some(0),
+                Some(PromptRole::Assistant),
+            ),
+            ("SystemChatMessage", None, Some(0), Some(PromptRole::System)),
+            ("UserChatMessage", None, Some(0), Some(PromptRole::User)),
+            (
+                "AssistantChatMessage",
+                None,
+                Some(0),
+                Some(PromptRole::Assistant),
+            ),
+            (
+                "CreateSystemMessage",
+                None,
+                Some(0),
+                Some(PromptRole::System),
+            ),
+            ("CreateUserMessage", None, Some(0), Some(PromptRole::User)),
+            (
+                "CreateAssistantMessage",
+                None,
+                Some(0),
+                Some(PromptRole::Assistant),
+            ),
+        ],
+    ),
+    (
+        "anthropic",
+        &[
+            (
+                "messages.create",
+                Some("system"),
+                None,
+                Some(PromptRole::System),
+            ),
+            ("messages.create", Some("messages"), None, None),
+            (
+                "messages.stream",
+                Some("system"),
+                None,
+                Some(PromptRole::System),
+            ),
+            ("messages.stream", Some("messages"), None, None),
+            (
+                "messages.new",
+                Some("system"),
+                None,
+                Some(PromptRole::System),
+            ),
+            ("messages.new", Some("messages"), None, None),
+        ],
+    ),
+    (
+        "ai-sdk",
+        &[
+            (
+                "generateText",
+                Some("system"),
+                None,
+                Some(PromptRole::System),
+            ),
+            ("generateText", Some("prompt"), None, Some(PromptRole::User)),
+            ("generateText", Some("messages"), None, None),
+            ("streamText", Some("system"), None, Some(PromptRole::System)),
+            ("streamText", Some("prompt"), None, Some(PromptRole::User)),
+            ("streamText", Some("messages"), None, None),
+            (
+                "generateObject",
+                Some("system"),
+                None,
+                Some(PromptRole::System),
+            ),
+            (
+                "generateObject",
+                Some("prompt"),
+                None,
+                Some(PromptRole::User),
+            ),
+            ("generateObject", Some("messages"), None, None),
+            (
+                "streamObject",
+                Some("system"),
+                None,
+                Some(PromptRole::System),
+            ),
+            ("streamObject", Some("prompt"), None, Some(PromptRole::User)),
+            ("streamObject", Some("messages"), None, None),
+        ],
+    ),
+    (
+        "gemini",
+        &[
+            ("generateContent", Some("contents"), Some(0), None),
+            ("generateContentStream", Some("contents"), Some(0), None),
+            (
+                "GenerativeModel",
+                Some("systemInstruction"),
+                None,
+                Some(PromptRole::System),
+            ),
+            (
+                "getGenerativeModel",
+                Some("systemInstruction"),
+                None,
+                Some(PromptRole::System),
+            ),
+        ],
+    ),
+    (
+        "langchain",
+        &[
+            (
+                "SystemMessage",
+                Some("content"),
+                Some(0),
+                Some(PromptRole::System),
+            ),
+            (
+                "HumanMessage",
+                Some("content"),
+                Some(0),
+                Some(PromptRole::User),
+            ),
+            (
+                "AIMessage",
+                Some("content"),
+                Some(0),
+                Some(PromptRole::Assistant),
+            ),
+            (
+                "PromptTemplate.fromTemplate",
+                Some("template"),
+                Some(0),
+                None,
+            ),
+        ],
+    ),
+];
+
+/// Call site signature resolved from the parse options with the callee path
+/// and the argument name normalized for matching.
+#[derive(Debug, Clone)]
+pub(crate) struct CallSiteSignature {
+    /// Normalized callee path segments, e.g., `["chat", "completions", "create"]`.
+    callee: Vec<String>,
+    /// Normalized argument name.
+    name: Option<String>,
+    /// Original signature.
+    call_site: ParseCallSite,
+}
+
+/// Resolves the call site signatures from the enabled presets and the custom
+/// signatures.
+pub(crate) fn resolve_call_sites(options: &ParseOptions) -> Vec<CallSiteSignature> {
+    let presets = CALL_SITE_PRESETS
+        .iter()
+        .filter(|(preset, _)| match &options.call_site_presets {
+            Some(enabled) => enabled.iter().any(|name| name.eq_ignore_ascii_case(preset)),
+            None => false,
+        })
+        .flat_map(|(_, signatures)| signatures.iter())
+        .map(|(callee, name, position, role)| ParseCallSite {
+            callee: callee.to_string(),
+            name: name.map(str::to_string),
+            position: *position,
+            role: *role,
+        });
+    options
+        .call_sites
+        .iter()
+        .flatten()
+        .cloned()
+        .chain(presets)
+        .filter(|call_site| call_site.name.is_some() || call_site.position.is_some())
+        .map(|call_site| CallSiteSignature {
+            callee: path_segments(&call_site.callee),
+            name: call_site.name.as_deref().map(normalize_segment),
+            call_site,
+        })
+        .filter(|signature| !signature.callee.is_empty())
+        .collect()
+}
+
+/// Call site signatures matching a call expression callee.
+#[derive(Debug, Clone)]
+pub struct CallSite<'a> {
+    signatures: Vec<&'a CallSiteSignature>,
+}
+
+impl<'a> CallSite<'a> {
+    /// Finds the signatures matching the callee source text, e.g.,
+    /// `client.chat.completions.create`. Returns `None` if no signature
+    /// matches the callee.
+    pub(crate) fn find(signatures: &'a [CallSiteSignature], callee: &str) -> Option<Self> {
+        let segments = path_segments(callee);
+        let signatures: Vec<_> = signatures
+            .iter()
+            .filter(|signature| segments.ends_with(&signature.callee))
+            .collect();
+        (!signatures.is_empty()).then_some(Self { signatures })
+    }
+
+    /// Finds the signature matching the positional argument index.
+    pub fn positional(&self, position: usize) -> Option<&'a ParseCallSite> {
+        self.signatures
+            .iter()
+            .find(|signature| signature.call_site.position == Some(position as u32))
+            .map(|signature| &signature.call_site)
+    }
+
+    /// Finds the signature matching the keyword argument or the literal key
+    /// source text, e.g., `system`, `"system"` or `:system`.
+    pub fn named(&self, name: &str) -> Option<&'a ParseCallSite> {
+        let name = normalize_segment(name);
+        self.signatures
+            .iter()
+            .find(|signature| signature.name.as_deref() == Some(name.as_str()))
+            .map(|signature| &signature.call_site)
+    }
+}
+
+/// Creates the binding of a call argument prompt from the source span of the
+/// argument name, e.g., `"system"` for `{"system": "..."}`, or the callee,
+/// e.g., `client.SystemMessage` for positional arguments. The binding takes
+/// the last identifier in the span.
+pub fn argument_binding(source: &str, span: Span) -> PromptBinding {
+    let text = &source[span.0 as usize..span.1 as usize];
+    let (offset, name) = identifiers(text).last().copied().unwrap_or((0, text));
+    let start = span.0 + offset as u32;
+    PromptBinding {
+        name: name.to_string(),
+        span: (start, start + name.len() as u32),
+        kind: PromptBindingKind::Argument,
+    }
+}
+
+/// Call argument of a Tree-sitter call node.
+#[cfg(feature = "tree-sitter")]
+#[derive(Debug, Clone, Copy)]
+pub enum CallArgument<'tree> {
+    /// Positional argument value with its position, e.g., `"..."` in
+    /// `SystemMessage("...")`.
+    Positional(usize, tree_sitter::Node<'tree>),
+    /// Named argument or literal entry key and value, e.g., `system="..."`
+    /// or `{"system": "..."}`.
+    Named(tree_sitter::Node<'tree>, tree_sitter::Node<'tree>),
+}
+
+/// Language syntax of the LLM SDK call sites in Tree-sitter syntax trees.
+/// Implemented by the Tree-sitter parsers to detect prompts passed as call
+/// arguments with [`process_call_sites`].
+#[cfg(feature = "tree-sitter")]
+pub trait CallSiteSyntax<'tree> {
+    /// Returns the callee span and the arguments node of a call node, e.g.,
+    /// `client.messages.create` and `(system="...")`.
+    fn call(
+        &self,
+        node: &tree_sitter::Node<'tree>,
+        source: &str,
+    ) -> Option<(Span, tree_sitter::Node<'tree>)>;
+
+    /// Lists the call arguments. Keyed literal arguments, e.g., dicts or
+    /// hashes, are expanded into the named arguments.
+    fn arguments(
+        &self,
+        arguments: &tree_sitter::Node<'tree>,
+        source: &str,
+    ) -> Vec<CallArgument<'tree>>;
+
+    /// Extracts the chat messages roles and contents from the argument value.
+    fn messages(
+        &self,
+        value: &tree_sitter::Node<'tree>,
+        source: &str,
+    ) -> Vec<(Option<PromptRole>, tree_sitter::Node<'tree>)>;
+
+    /// Checks if the messages argument value is a list of messages grouped
+    /// into a conversation rather than a single message.
+    fn is_conversation(&self, value: &tree_sitter::Node<'tree>, source: &str) -> bool;
+
+    /// Checks if the argument value is a string.
+    fn is_string_like(&self, value: &tree_sitter::Node<'tree>) -> bool;
+
+    /// Creates the prompt from a string argument value enclosed by the call.
+    fn create_prompt(
+        &self,
+        value: &tree_sitter::Node<'tree>,
+        source: &str,
+        filename: &str,
+        call: Span,
+        binding: &PromptBinding,
+        prompts: &mut Vec<Prompt>,
+    );
+}
+
+/// Recursively finds LLM SDK call sites to detect prompts passed as call
+/// arguments, e.g., `client.messages.create(system="...")`. The positional
+/// arguments are bound to the callee and the named ones to their names.
+#[cfg(feature = "tree-sitter")]
+pub fn process_call_sites<'tree>(
+    node: &tree_sitter::Node<'tree>,
+    source: &str,
+    filename: &str,
+    detector: &crate::PromptDetector,
+    syntax: &impl CallSiteSyntax<'tree>,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
+    if let Some((callee, arguments)) = syntax.call(node, source)
+        && let Some(call_site) = detector.call_site(&source[callee.0 as usize..callee.1 as usize])
+    {
+        let call = (node.start_byte() as u32, node.end_byte() as u32);
+        let arguments = syntax.arguments(&arguments, source);
+        let positional = arguments.iter().filter_map(|argument| match argument {
+            CallArgument::Positional(position, value) => {
+                let signature = call_site.positional(*position)?;
+                Some((signature, *value, argument_binding(source, callee)))
+            }
+            CallArgument::Named(..) => None,
+        });
+        let named = arguments.iter().filter_map(|argument| match argument {
+            CallArgument::Named(key, value) => {
+                let signature = call_site.named(key.utf8_text(source.as_bytes()).ok()?)?;
+                let key = (key.start_byte() as u32, key.end_byte() as u32);
+                Some((signature, *value, argument_binding(source, key)))
+            }
+            CallArgument::Positional(..) => None,
+        });
+        let matched: Vec<_> = positional.chain(named).collect();
+        for (signature, value, binding) in matched {
+            process_call_argument(
+                &value,
+                signature.role,
+                &binding,
+                call,
+                source,
+                filename,
+                syntax,
+                prompts,
+                groups,
+            );
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        process_call_sites(&child, source, filename, detector, syntax, prompts, groups);
+    }
+}
+
+/// Processes a call argument value as a prompt, either a string or chat
+/// messages. Messages lists are grouped into conversations.
+#[cfg(feature = "tree-sitter")]
+#[allow(clippy::too_many_arguments)]
+fn process_call_argument<'tree>(
+    value: &tree_sitter::Node<'tree>,
+    role: Option<PromptRole>,
+    binding: &PromptBinding,
+    call: Span,
+    source: &str,
+    filename: &str,
+    syntax: &impl CallSiteSyntax<'tree>,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let prompts_len = prompts.len();
+    let messages = syntax.messages(value, source);
+    if !messages.is_empty() {
+        for (message_role, content) in messages {
+            let message_len = prompts.len();
+            syntax.create_prompt(&content, source, filename, call, binding, prompts);
+            for prompt in &mut prompts[message_len..] {
+                prompt.role = message_role.or(role);
+            }
+        }
+
+        // Group the messages list into a conversation
+        if syntax.is_conversation(value, source) {
+            let span = (value.start_byte() as u32, value.end_byte() as u32);
+            if let Some(group) = crate::prompt_group(filename, span, binding, prompts, prompts_len)
+            {
+                groups.push(group);
+            }
+        }
+    } else if syntax.is_string_like(value) {
+        syntax.create_prompt(value, source, filename, call, binding, prompts);
+        for prompt in &mut prompts[prompts_len..] {
+            prompt.role = role;
+        }
+    }
+}
+
+/// Splits the callee source text into normalized path segments.
+fn path_segments(path: &str) -> Vec<String> {
+    identifiers(path)
+        .into_iter()
+        .map(|(_, identifier)| normalize_segment(identifier))
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Finds the identifiers with their offsets in the source text, skipping
+/// the separators, e.g., `.`, `->`, `::` and call parentheses.
+fn identifiers(text: &str) -> Vec<(usize, &str)> {
+    let mut identifiers = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        let is_identifier = c.is_alphanumeric() || c == '_';
+        match start {
+            None if is_identifier => start = Some(index),
+            Some(identifier_start) if !is_identifier => {
+                identifiers.push((identifier_start, &text[identifier_start..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(identifier_start) = start {
+        identifiers.push((identifier_start, &text[identifier_start..]));
+    }
+    identifiers
+}
+
+/// Normalizes a path segment or an argument name for case-insensitive
+/// matching that ignores underscores and quotes.
+fn normalize_segment(segment: &str) -> String {
+    segment
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn all_presets() -> ParseOptions {
+        ParseOptions {
+            call_site_presets: Some(
+                CALL_SITE_PRESETS
+                    .iter()
+                    .map(|(preset, _)| preset.to_string())
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn callee() {
+        let signatures = resolve_call_sites(&all_presets());
+        for callee in [
+            "client.chat.completions.create",
+            "await openai.chat.completions.create",
+            "$client->chat()->completions()->create",
+            "client.Chat.Completions.Create",
+        ] {
+            let call_site = CallSite::find(&signatures, callee).unwrap();
+            assert_eq!(call_site.named("messages").unwrap().role, None);
+        }
+        assert!(CallSite::find(&signatures, "client.completions.list").is_none());
+        assert!(CallSite::find(&signatures, "chat.completions.created").is_none());
+    }
+
+    #[test]
+    fn arguments() {
+        let signatures = resolve_call_sites(&all_presets());
+        let call_site = CallSite::find(&signatures, "client.messages.create").unwrap();
+        assert_eq!(
+            call_site.named("\"system\"").unwrap().role,
+            Some(PromptRole::System)
+        );
+        assert_eq!(
+            call_site.named(":system").unwrap().role,
+            Some(PromptRole::System)
+        );
+        assert!(call_site.named("model").is_none());
+        assert!(call_site.positional(0).is_none());
+
+        let call_site = CallSite::find(&signatures, "genai.generate_content").unwrap();
+        assert!(call_site.positional(0).is_some());
+        assert!(call_site.named("contents").is_some());
+
+        let call_site = CallSite::find(&signatures, "openai.UserMessage").unwrap();
+        assert_eq!(
+            call_site.positional(0).unwrap().role,
+            Some(PromptRole::User)
+        );
+        assert!(call_site.positional(1).is_none());
+        assert!(CallSite::find(&signatures, "UserMessage").is_none());
+
+        let call_site = CallSite::find(&signatures, "SystemMessage").unwrap();
+        assert_eq!(call_site.signatures.len(), 1);
+    }
+
+    #[test]
+    fn presets_opt_in() {
+        assert!(resolve_call_sites(&ParseOptions::default()).is_empty());
+
+        let mut signatures: Vec<_> = CALL_SITE_PRESETS
+            .iter()
+            .flat_map(|(_, signatures)| signatures.iter())
+            .map(|(callee, name, position, _)| (callee.to_ascii_lowercase(), *name, *position))
+            .collect();
+        let count = signatures.len();
+        signatures.sort();
+        signatures.dedup();
+        assert_eq!(signatures.len(), count);
+    }
+
+    #[test]
+    fn presets() {
+        let signatures = resolve_call_sites(&ParseOptions {
+            call_site_presets: Some(vec!["Anthropic".to_string()]),
+            call_sites: Some(vec![ParseCallSite {
+                callee: "llm.ask".to_string(),
+                name: None,
+                position: Some(1),
+                role: None,
+            }]),
+            ..Default::default()
+        });
+        assert!(CallSite::find(&signatures, "client.messages.create").is_some());
+        assert!(CallSite::find(&signatures, "client.chat.completions.create").is_none());
+        let call_site = CallSite::find(&signatures, "self.llm.ask").unwrap();
+        assert!(call_site.positional(1).is_some());
+
+        let signatures = resolve_call_sites(&ParseOptions {
+            call_site_presets: Some(Vec::new()),
+            ..Default::default()
+        });
+        assert!(signatures.is_empty());
+    }
+
+    #[test]
+    fn binding() {
+        let source = r#"client.messages.create("system": "Hi!")"#;
+        assert_eq!(
+            argument_binding(source, (23, 31)),
+            PromptBinding {
+                name: "system".to_string(),
+                span: (24, 30),
+                kind: PromptBindingKind::Argument,
+            }
+        );
+        assert_eq!(
+            argument_binding(source, (0, 22)),
+            PromptBinding {
+                name: "create".to_string(),
+                span: (16, 22),
+                kind: PromptBindingKind::Argument,
+            }
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{format_var, prompt_var};
+use volumen_types::*;
+
+/// Segment of a string concatenation expression, e.g., `"Hello, "`, `name`
+/// and `"!"` in `"Hello, " + name + "!"`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConcatSegment {
+    /// String literal with its span.
+    String(SpanShape),
+    /// Expression interpolated into the prompt, e.g., an identifier, a call
+    /// or a member access, with its span.
+    Variable(Span),
+    /// Primitive value (number, boolean) stringified as is, with its span.
+    Primitive(Span),
+    /// Expression that can't be stringified (object, array, etc.).
+    Other,
+}
+
+/// Prompt span, variables and content tokens of a string concatenation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcatContent {
+    pub span: SpanShape,
+    pub vars: Vec<PromptVar>,
+    pub content: Vec<PromptContentToken>,
+}
+
+/// Builds the prompt content from the string concatenation segments, where
+/// `outer` is the whole expression span and `operator` is the language
+/// concatenation operator, e.g., `+` or `.`. The variable outer spans
+/// include the surrounding operators, e.g., ` + name + `, while primitives
+/// are kept as string content. Concatenations without strings or with
+/// segments that can't be stringified aren't prompts.
+pub fn concat_content(
+    source: &str,
+    outer: Span,
+    operator: &str,
+    segments: &[ConcatSegment],
+) -> Option<ConcatContent> {
+    if segments
+        .iter()
+        .any(|segment| matches!(segment, ConcatSegment::Other))
+        || !segments
+            .iter()
+            .any(|segment| matches!(segment, ConcatSegment::String(_)))
+    {
+        return None;
+    }
+
+    let segment_span = |segment: &ConcatSegment| match segment {
+        ConcatSegment::String(span) => span.inner,
+        ConcatSegment::Variable(span) | ConcatSegment::Primitive(span) => *span,
+        ConcatSegment::Other => outer,
+    };
+    let inner = (
+        segment_span(segments.first()?).0,
+        segment_span(segments.last()?).1,
+    );
+
+    let mut vars = Vec::new();
+    let mut content = Vec::new();
+    for segment in segments {
+        match segment {
+            ConcatSegment::Variable(expression) => {
+                let span = SpanShape {
+                    outer: expand_to_operators(source, *expression, operator, outer),
+                    inner: *expression,
+                };
+                content.push(PromptContentToken::PromptContentTokenVar(
+                    PromptContentTokenVar {
+                        r#type: PromptContentTokenVarTypeVar,
+                        span: *expression,
+                        index: vars.len() as u32,
+                    },
+                ));
+                vars.push(prompt_var(source, span, *expression));
+            }
+            segment => content.push(str_token(segment_span(segment))),
+        }
+    }
+
+    Some(ConcatContent {
+        span: SpanShape { outer, inner },
+        vars,
+        content,
+    })
+}
+
+/// Builds the content tokens from the prompt span and variables. Prompts
+/// without variables have a single str token, while prompts with variables
+/// have str tokens interleaved with the var tokens.
+pub fn build_content_tokens(span: &SpanShape, vars: &[PromptVar]) -> Vec<PromptContentToken> {
+    let mut tokens = Vec::new();
+    let mut pos = span.inner.0;
+
+    for (index, var) in vars.iter().enumerate() {
+        if pos < var.span.outer.0 {
+            tokens.push(str_token((pos, var.span.outer.0)));
+        }
+        tokens.push(PromptContentToken::PromptContentTokenVar(
+            PromptContentTokenVar {
+                r#type: PromptContentTokenVarTypeVar,
+                span: var.span.outer,
+                index: index as u32,
+            },
+        ));
+        pos = var.span.outer.1;
+    }
+
+    if vars.is_empty() || pos < span.inner.1 {
+        tokens.push(str_token((pos, span.inner.1)));
+    }
+    tokens
+}
+
+/// Builds the prompt variables and content tokens of a format function call,
+/// e.g., `sprintf("Hello, %s!", name)`, from the format string inner span,
+/// the placeholder byte ranges relative to it and the argument spans. The
+/// placeholders without arguments are left out of the content.
+pub fn format_content(
+    source: &str,
+    inner: Span,
+    placeholders: &[(usize, usize)],
+    arguments: &[Span],
+) -> (Vec<PromptVar>, Vec<PromptContentToken>) {
+    let text = source.get(inner.0 as usize..inner.1 as usize).unwrap_or("");
+    let vars: Vec<_> = placeholders
+        .iter()
+        .zip(arguments)
+        .map(|((start, end), argument)| {
+            format_var(source, text.get(*start..*end).unwrap_or(""), *argument)
+        })
+        .collect();
+
+    let mut content = Vec::new();
+    let mut pos = 0;
+    for (index, (start, end)) in placeholders.iter().enumerate() {
+        let span = (inner.0 + *start as u32, inner.0 + *end as u32);
+        if pos < *start {
+            content.push(str_token((inner.0 + pos as u32, span.0)));
+        }
+        if index < vars.len() {
+            content.push(PromptContentToken::PromptContentTokenVar(
+                PromptContentTokenVar {
+                    r#type: PromptContentTokenVarTypeVar,
+                    span,
+                    index: index as u32,
+                },
+            ));
+        }
+        pos = *end;
+    }
+
+    if pos < text.len() {
+        content.push(str_token((inner.0 + pos as u32, inner.1)));
+    }
+    (vars, content)
+}
+
+/// Finds the printf-style placeholders in the format string, e.g., `%s` or
+/// `%-10.2f`, skipping the escaped `%%`. Returns the placeholder byte ranges.
+pub fn printf_placeholders(format: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = format.char_indices().collect();
+    let offset = |index: usize| chars.get(index).map_or(format.len(), |(offset, _)| *offset);
+    let char_at = |index: usize| chars.get(index).map(|(_, char)| *char);
+    let mut placeholders = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        if char_at(index) == Some('%') {
+            // Skip the escaped `%%`
+            if char_at(index + 1) == Some('%') {
+                index += 2;
+                continue;
+            }
+
+            let start = index;
+            index += 1;
+
+            // Skip the flags, width and precision
+            while char_at(index).is_some_and(|char| {
+                char.is_ascii_digit() || matches!(char, '-' | '+' | ' ' | '#' | '.' | '*')
+            }) {
+                index += 1;
+            }
+
+            // Take the conversion specifier
+            if char_at(index).is_some_and(|char| char.is_alphabetic() || char == '%') {
+                index += 1;
+                placeholders.push((offset(start), offset(index)));
+                continue;
+            }
+        }
+        index += 1;
+    }
+
+    placeholders
+}
+
+fn str_token(span: Span) -> PromptContentToken {
+    PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
+        r#type: PromptContentTokenStrTypeStr,
+        span,
+        escapes: None,
+    })
+}
+
+/// Expands the expression span to include the adjacent concatenation
+/// operators along with the surrounding spaces, e.g., ` + name + `,
+/// without leaving the concatenation expression bounds.
+fn expand_to_operators(source: &str, span: Span, operator: &str, bounds: Span) -> Span {
+    let bytes = source.as_bytes();
+    let operator = operator.as_bytes();
+    let (lower, upper) = (bounds.0 as usize, (bounds.1 as usize).min(bytes.len()));
+    let is_space = |byte: u8| byte == b' ' || byte == b'\t';
+
+    let mut start = span.0 as usize;
+    let mut pos = start;
+    while pos > lower && is_space(bytes[pos - 1]) {
+        pos -= 1;
+    }
+    if pos >= lower + operator.len() && bytes[pos - operator.len()..pos] == *operator {
+        pos -= operator.len();
+        while pos > lower && is_space(bytes[pos - 1]) {
+            pos -= 1;
+        }
+        start = pos;
+    }
+
+    let mut end = span.1 as usize;
+    let mut pos = end;
+    while pos < upper && is_space(bytes[pos]) {
+        pos += 1;
+    }
+    if pos + operator.len() <= upper && bytes[pos..pos + operator.len()] == *operator {
+        pos += operator.len();
+        while pos < upper && is_space(bytes[pos]) {
+            pos += 1;
+        }
+        end = pos;
+    }
+
+    (start as u32, end as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn string(outer: Span) -> ConcatSegment {
+        ConcatSegment::String(SpanShape {
+            outer,
+            inner: (outer.0 + 1, outer.1 - 1),
+        })
+    }
+
+    fn var_token(span: Span, index: u32) -> PromptContentToken {
+        PromptContentToken::PromptContentTokenVar(PromptContentTokenVar {
+            r#type: PromptContentTokenVarTypeVar,
+            span,
+            index,
+        })
+    }
+
+    #[test]
+    fn concat() {
+        let source = r#"x = "Hello, " + name + "!""#;
+        let segments = [
+            string((4, 13)),
+            ConcatSegment::Variable((16, 20)),
+            string((23, 26)),
+        ];
+        let result = concat_content(source, (4, 26), "+", &segments).unwrap();
+        assert_eq!(
+            result.span,
+            SpanShape {
+                outer: (4, 26),
+                inner: (5, 25)
+            }
+        );
+        assert_eq!(
+            result.content,
+            vec![
+                str_token((5, 12)),
+                var_token((16, 20), 0),
+                str_token((24, 25))
+            ]
+        );
+        assert_eq!(result.vars.len(), 1);
+        assert_eq!(result.vars[0].span.outer, (13, 23));
+        assert_eq!(result.vars[0].expression, "name");
+    }
+
+    #[test]
+    fn concat_operator() {
+        let source = r#"$x = $name  .  "!";"#;
+        let segments = [ConcatSegment::Variable((5, 10)), string((15, 18))];
+        let result = concat_content(source, (5, 18), ".", &segments).unwrap();
+        assert_eq!(result.span.inner, (5, 17));
+        assert_eq!(result.vars[0].span.outer, (5, 15));
+    }
+
+    #[test]
+    fn concat_primitives() {
+        let source = r#"x = "Count: " + 42"#;
+        let segments = [string((4, 13)), ConcatSegment::Primitive((16, 18))];
+        let result = concat_content(source, (4, 18), "+", &segments).unwrap();
+        assert_eq!(result.span.inner, (5, 18));
+        assert_eq!(
+            result.content,
+            vec![str_token((5, 12)), str_token((16, 18))]
+        );
+        assert!(result.vars.is_empty());
+    }
+
+    #[test]
+    fn concat_rejected() {
+        let source = r#"x = "Items: " + [] + a + b"#;
+        let segments = [string((4, 13)), ConcatSegment::Other];
+        assert_eq!(concat_content(source, (4, 18), "+", &segments), None);
+        let segments = [
+            ConcatSegment::Variable((21, 22)),
+            ConcatSegment::Variable((25, 26)),
+        ];
+        assert_eq!(concat_content(source, (21, 26), "+", &segments), None);
+    }
+
+    #[test]
+    fn content_tokens() {
+        let source = r#"x = "Hi, {name}!""#;
+        let span = SpanShape {
+            outer: (4, 17),
+            inner: (5, 16),
+        };
+        assert_eq!(build_content_tokens(&span, &[]), vec![str_token((5, 16))]);
+
+        let var = prompt_var(
+            source,
+            SpanShape {
+                outer: (9, 15),
+                inner: (10, 14),
+            },
+            (10, 14),
+        );
+        assert_eq!(
+            build_content_tokens(&span, &[var]),
+            vec![
+                str_token((5, 9)),
+                var_token((9, 15), 0),
+                str_token((15, 16))
+            ]
+        );
+    }
+
+    #[test]
+    fn format() {
+        let source = r#"sprintf("Hi, %s! %5.2f%%", name, price)"#;
+        let placeholders = printf_placeholders(&source[9..24]);
+        assert_eq!(placeholders, vec![(4, 6), (8, 13)]);
+        let (vars, content) = format_content(source, (9, 24), &placeholders, &[(27, 31), (33, 38)]);
+        assert_eq!(
+            content,
+            vec![
+                str_token((9, 13)),
+                var_token((13, 15), 0),
+                str_token((15, 17)),
+                var_token((17, 22), 1),
+                str_token((22, 24))
+            ]
+        );
+        assert_eq!(vars[0].expression, "name");
+        assert_eq!(vars[0].format.as_deref(), Some("%s"));
+        assert_eq!(vars[1].span.outer, (33, 38));
+        assert_eq!(vars[1].format.as_deref(), Some("%5.2f"));
+    }
+
+    #[test]
+    fn format_missing_arguments() {
+        let source = r#"format("%s and %d", a)"#;
+        let placeholders = printf_placeholders(&source[8..17]);
+        let (vars, content) = format_content(source, (8, 17), &placeholders, &[(20, 21)]);
+        assert_eq!(vars.len(), 1);
+        assert_eq!(content, vec![var_token((8, 10), 0), str_token((10, 15))]);
+    }
+
+    #[test]
+    fn printf_non_ascii() {
+        assert_eq!(printf_placeholders("Привет, %s!"), vec![(14, 16)]);
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{
+    CallSite, CallSiteSignature, PROMPT_MARKER, PromptRegionMarker, error_diagnostic,
+    parse_marker_annotation, parse_marker_attributes, parse_marker_role, parse_role,
+    resolve_call_sites,
+};
+use volumen_types::*;
+
+/// Default identifier name pattern.
+const PROMPT_NAME: &str = "prompt";
+
+/// Prompt detection rules resolved from the parse options. Parsers create it
+/// once per source and use it instead of hard-coded markers and names.
+#[derive(Debug, Clone)]
+pub struct PromptDetector {
+    /// Lowercase annotation markers including `@`, e.g., `@prompt`.
+    markers: Vec<String>,
+    /// Lowercase opt-out markers derived from the annotation markers, e.g.,
+    /// `@prompt-ignore` and `@noprompt`.
+    ignore_markers: Vec<String>,
+    /// Lowercase file-level markers, e.g., `@prompts`.
+    file_markers: Vec<String>,
+    /// Lowercase region start markers, e.g., `@prompt-begin`.
+    begin_markers: Vec<String>,
+    /// Lowercase region end markers, e.g., `@prompt-end`.
+    end_markers: Vec<String>,
+    /// Lowercase identifier name patterns.
+    names: Vec<String>,
+    /// Whether to detect prompts by identifier names.
+    name_detection: bool,
+    /// Call site signatures from the enabled presets and the options.
+    call_sites: Vec<CallSiteSignature>,
+}
+
+impl PromptDetector {
+    pub fn new(options: &ParseOptions) -> Self {
+        let markers: Vec<String> = match &options.markers {
+            Some(markers) => markers
+                .iter()
+                .map(|marker| marker.trim_start_matches('@').to_lowercase())
+                .filter(|marker| !marker.is_empty())
+                .map(|marker| format!("@{marker}"))
+                .collect(),
+            None => vec![PROMPT_MARKER.to_string()],
+        };
+        let ignore_markers = markers
+            .iter()
+            .flat_map(|marker| [format!("{marker}-ignore"), format!("@no{}", &marker[1..])])
+            .collect();
+        let file_markers = markers.iter().map(|marker| format!("{marker}s")).collect();
+        let begin_markers = markers
+            .iter()
+            .map(|marker| format!("{marker}-begin"))
+            .collect();
+        let end_markers = markers
+            .iter()
+            .map(|marker| format!("{marker}-end"))
+            .collect();
+        let names = match &options.names {
+            Some(names) => names.iter().map(|name| name.to_lowercase()).collect(),
+            None => vec![PROMPT_NAME.to_string()],
+        };
+
+        Self {
+            markers,
+            ignore_markers,
+            file_markers,
+            begin_markers,
+            end_markers,
+            names,
+            name_detection: options.name_detection.unwrap_or(true),
+            call_sites: resolve_call_sites(options),
+        }
+    }
+
+    /// Parses annotation text to determine if it contains a valid marker.
+    /// Returns `Some(false)` if markers are only found as parts of other
+    /// words, e.g., `@prompting`, and `None` if there are no markers at all.
+    /// Opt-out and region boundary annotations are not prompt annotations, so
+    /// they give `None`.
+    pub fn annotation(&self, text: &str) -> Option<bool> {
+        let region_boundary = matches!(
+            self.region_marker(text),
+            Some(PromptRegionMarker::Begin | PromptRegionMarker::End)
+        );
+        if self.ignore_annotation(text) || region_boundary {
+            return None;
+        }
+        self.markers
+            .iter()
+            .filter_map(|marker| parse_marker_annotation(text, marker))
+            .reduce(|valid, marker_valid| valid || marker_valid)
+    }
+
+    /// Checks if annotation text contains a valid opt-out marker, e.g.,
+    /// `@prompt-ignore` or `@noprompt`, that excludes the statement from
+    /// prompt detection.
+    pub fn ignore_annotation(&self, text: &str) -> bool {
+        self.ignore_markers
+            .iter()
+            .any(|marker| parse_marker_annotation(text, marker) == Some(true))
+    }
+
+    /// Finds the file-level or region marker in the annotation text, e.g.,
+    /// `@prompts`, `@prompt-begin` or `@prompt-end`.
+    pub fn region_marker(&self, text: &str) -> Option<PromptRegionMarker> {
+        let has_marker = |markers: &[String]| {
+            markers
+                .iter()
+                .any(|marker| parse_marker_annotation(text, marker) == Some(true))
+        };
+        if has_marker(&self.file_markers) {
+            Some(PromptRegionMarker::File)
+        } else if has_marker(&self.begin_markers) {
+            Some(PromptRegionMarker::Begin)
+        } else if has_marker(&self.end_markers) {
+            Some(PromptRegionMarker::End)
+        } else {
+            None
+        }
+    }
+
+    /// Parses `key=value` attributes that follow a valid marker in
+    /// the annotation span shapes. See `parse_annotation_attributes`.
+    pub fn annotation_attributes(
+        &self,
+        source: &str,
+        spans: &[SpanShape],
+    ) -> Vec<PromptAnnotationAttribute> {
+        parse_marker_attributes(source, spans, &self.markers)
+    }
+
+    /// Resolves the chat role hint from the annotations, either from the `role`
+    /// attribute, e.g., `// @prompt role=system`, or from the first word that
+    /// follows the marker, e.g., `// @prompt system`.
+    pub fn annotation_role(
+        &self,
+        source: &str,
+        annotations: &[PromptAnnotation],
+    ) -> Option<PromptRole> {
+        annotations.iter().find_map(|annotation| {
+            annotation
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key.eq_ignore_ascii_case("role"))
+                .and_then(|attribute| parse_role(&attribute.value))
+                .or_else(|| parse_marker_role(source, &annotation.spans, &self.markers))
+        })
+    }
+
+    /// Assigns annotation role hints to the prompts that don't have a role
+    /// resolved from the surrounding message structure.
+    pub fn assign_annotation_roles(&self, source: &str, prompts: &mut [Prompt]) {
+        for prompt in prompts.iter_mut().filter(|prompt| prompt.role.is_none()) {
+            prompt.role = self.annotation_role(source, &prompt.annotations);
+        }
+    }
+
+    /// Checks if the identifier name matches any of the name patterns. It is
+    /// always `false` when name-based detection is disabled.
+    pub fn is_prompt_name(&self, ident: &str) -> bool {
+        if !self.name_detection {
+            return false;
+        }
+        let ident = ident.to_lowercase();
+        self.names
+            .iter()
+            .any(|pattern| matches_pattern(&ident, pattern))
+    }
+
+    /// Finds the call site signatures matching the call expression callee
+    /// source text, e.g., `client.chat.completions.create`.
+    pub fn call_site(&self, callee: &str) -> Option<CallSite<'_>> {
+        CallSite::find(&self.call_sites, callee)
+    }
+}
+
+impl Default for PromptDetector {
+    fn default() -> Self {
+        Self::new(&ParseOptions::default())
+    }
+}
+
+/// Creates the error result if the source exceeds the `maxFileSize` option.
+pub fn file_size_error(
+    source: &str,
+    options: &ParseOptions,
+    language: ParseLanguage,
+    dialect: Option<ParseDialect>,
+    parser: &str,
+) -> Option<ParseResult> {
+    let max_size = options.max_file_size?;
+    if source.len() <= max_size as usize {
+        return None;
+    }
+
+    let error = format!(
+        "Source size {} exceeds the maximum file size of {} bytes",
+        source.len(),
+        max_size
+    );
+    Some(ParseResult::ParseResultError(ParseResultError {
+        state: ParseResultErrorStateError,
+        diagnostics: vec![error_diagnostic(
+            "volumen",
+            "file-too-large",
+            error.clone(),
+            (0, 0),
+        )],
+        error,
+        language: Some(language),
+        dialect,
+        detection: None,
+        parser: Some(parser.to_string()),
+    }))
+}
+
+/// Matches the name against the pattern. Patterns without `*` match anywhere
+/// in the name.
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    if !pattern.contains('*') {
+        return name.contains(pattern);
+    }
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let last = parts.pop().unwrap_or("");
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn detector(markers: &[&str], names: &[&str]) -> PromptDetector {
+        PromptDetector::new(&ParseOptions {
+            markers: Some(markers.iter().map(|marker| marker.to_string()).collect()),
+            names: Some(names.iter().map(|name| name.to_string()).collect()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn default_markers() {
+        let detector = PromptDetector::default();
+        assert_eq!(detector.annotation("// @prompt"), Some(true));
+        assert_eq!(detector.annotation("// @PROMPT"), Some(true));
+        assert_eq!(detector.annotation("// @prompting"), Some(false));
+        assert_eq!(detector.annotation("// @llm"), None);
+    }
+
+    #[test]
+    fn custom_markers() {
+        let detector = detector(&["llm", "@Template"], &[]);
+        assert_eq!(detector.annotation("// @llm"), Some(true));
+        assert_eq!(detector.annotation("// @template"), Some(true));
+        assert_eq!(detector.annotation("// @llms @template"), Some(true));
+        assert_eq!(detector.annotation("// @llms"), Some(false));
+        assert_eq!(detector.annotation("// @prompt"), None);
+    }
+
+    #[test]
+    fn custom_marker_attributes() {
+        let detector = detector(&["llm"], &[]);
+        let source = "// @llm name=greeting";
+        let spans = vec![SpanShape {
+            outer: (0, 21),
+            inner: (2, 21),
+        }];
+        let attributes = detector.annotation_attributes(source, &spans);
+        assert_eq!(attributes.len(), 1);
+        assert_eq!(attributes[0].key, "name");
+        assert_eq!(attributes[0].value, "greeting");
+        assert_eq!(attributes[0].span, (8, 21));
+    }
+
+    #[test]
+    fn ignore_markers() {
+        let detector = PromptDetector::default();
+        assert!(detector.ignore_annotation("// @prompt-ignore"));
+        assert!(detector.ignore_annotation("// @NoPrompt: not a prompt"));
+        assert!(!detector.ignore_annotation("// @prompt"));
+        assert!(!detector.ignore_annotation("// @noprompting"));
+        assert_eq!(detector.annotation("// @prompt-ignore"), None);
+        assert_eq!(detector.annotation("// @noprompt"), None);
+    }
+
+    #[test]
+    fn custom_ignore_markers() {
+        let detector = detector(&["llm"], &[]);
+        assert!(detector.ignore_annotation("// @llm-ignore"));
+        assert!(detector.ignore_annotation("// @nollm"));
+        assert!(!detector.ignore_annotation("// @prompt-ignore"));
+    }
+
+    #[test]
+    fn region_markers() {
+        let detector = PromptDetector::default();
+        assert_eq!(
+            detector.region_marker("// @prompts"),
+            Some(PromptRegionMarker::File)
+        );
+        assert_eq!(
+            detector.region_marker("# @Prompt-Begin"),
+            Some(PromptRegionMarker::Begin)
+        );
+        assert_eq!(
+            detector.region_marker("/* @prompt-end */"),
+            Some(PromptRegionMarker::End)
+        );
+        assert_eq!(detector.region_marker("// @prompt"), None);
+        assert_eq!(detector.region_marker("// @prompt-beginning"), None);
+        assert_eq!(detector.annotation("// @prompt-begin"), None);
+        assert_eq!(detector.annotation("// @prompt-end"), None);
+    }
+
+    #[test]
+    fn custom_region_markers() {
+        let detector = detector(&["llm"], &[]);
+        assert_eq!(
+            detector.region_marker("// @llms"),
+            Some(PromptRegionMarker::File)
+        );
+        assert_eq!(
+            detector.region_marker("// @llm-begin"),
+            Some(PromptRegionMarker::Begin)
+        );
+        assert_eq!(detector.region_marker("// @prompts"), None);
+    }
+
+    #[test]
+    fn annotation_role() {
+        let detector = detector(&["llm"], &[]);
+        let annotation = |source: &str| PromptAnnotation {
+            spans: vec![SpanShape {
+                outer: (0, source.len() as u32),
+                inner: (2, source.len() as u32),
+            }],
+            attributes: detector.annotation_attributes(
+                source,
+                &[SpanShape {
+                    outer: (0, source.len() as u32),
+                    inner: (2, source.len() as u32),
+                }],
+            ),
+        };
+        let role = |source: &str| detector.annotation_role(source, &[annotation(source)]);
+        assert_eq!(role("// @llm system"), Some(PromptRole::System));
+        assert_eq!(role("// @llm name=hi role=user"), Some(PromptRole::User));
+        assert_eq!(role("// @llm greeting"), None);
+        assert_eq!(role("// @prompt system"), None);
+    }
+
+    #[test]
+    fn default_names() {
+        let detector = PromptDetector::default();
+        assert!(detector.is_prompt_name("userPrompt"));
+        assert!(detector.is_prompt_name("SYSTEM_PROMPT"));
+        assert!(!detector.is_prompt_name("greeting"));
+    }
+
+    #[test]
+    fn custom_names() {
+        let detector = detector(&[], &["*_template", "instructions"]);
+        assert!(detector.is_prompt_name("greeting_template"));
+        assert!(detector.is_prompt_name("GREETING_TEMPLATE"));
+        assert!(detector.is_prompt_name("systemInstructions"));
+        assert!(!detector.is_prompt_name("template_greeting"));
+        assert!(!detector.is_prompt_name("userPrompt"));
+    }
+
+    #[test]
+    fn name_detection_disabled() {
+        let detector = PromptDetector::new(&ParseOptions {
+            name_detection: Some(false),
+            ..Default::default()
+        });
+        assert!(!detector.is_prompt_name("userPrompt"));
+        assert_eq!(detector.annotation("// @prompt"), Some(true));
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("user_prompt", "prompt"));
+        assert!(matches_pattern("user_prompt", "*prompt"));
+        assert!(matches_pattern("user_prompt", "user*"));
+        assert!(matches_pattern("user_prompt", "u*_*t"));
+        assert!(matches_pattern("user_prompt", "*"));
+        assert!(!matches_pattern("user_prompt", "prompt*"));
+        assert!(!matches_pattern("user_prompt", "*user"));
+        assert!(!matches_pattern("ab", "a*b*b"));
+    }
+
+    #[test]
+    fn file_size() {
+        let options = ParseOptions {
+            max_file_size: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(
+            file_size_error("prompt", &options, ParseLanguage::Rb, None, "tree-sitter"),
+            None
+        );
+        let Some(ParseResult::ParseResultError(error)) = file_size_error(
+            "prompt = \"Hi\"",
+            &options,
+            ParseLanguage::Rb,
+            None,
+            "tree-sitter",
+        ) else {
+            panic!("Expected ParseResultError");
+        };
+        assert_eq!(error.diagnostics[0].code, "file-too-large");
+        assert_eq!(error.language, Some(ParseLanguage::Rb));
+        assert_eq!(
+            file_size_error(
+                "prompt = \"Hi\"",
+                &ParseOptions::default(),
+                ParseLanguage::Rb,
+                None,
+                "tree-sitter"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use volumen_types::{ParseDiagnostic, ParseDiagnosticSeverity, Span};
+
+/// Creates an error diagnostic produced by the given parser implementation.
+pub fn error_diagnostic(parser: &str, code: &str, message: String, span: Span) -> ParseDiagnostic {
+    ParseDiagnostic {
+        message,
+        severity: ParseDiagnosticSeverity::Error,
+        code: code.to_string(),
+        span,
+        parser: parser.to_string(),
+    }
+}
+
+/// Checks if the span overlaps with any of the diagnostic spans. It allows to
+/// skip prompts affected by syntax errors in recovered syntax trees.
+pub fn overlaps_diagnostics(span: Span, diagnostics: &[ParseDiagnostic]) -> bool {
+    diagnostics.iter().any(|diagnostic| {
+        let (start, end) = diagnostic.span;
+        // Zero-width diagnostics, e.g., missing tokens, overlap if inside
+        if start == end {
+            span.0 < start && start < span.1
+        } else {
+            start < span.1 && span.0 < end
+        }
+    })
+}
+
+/// Collects the Tree-sitter syntax errors and missing nodes in the tree.
+/// Tree-sitter recovers from errors, so the parsers use the diagnostics to
+/// skip the affected prompts instead of failing.
+#[cfg(feature = "tree-sitter")]
+pub fn collect_diagnostics(
+    node: &tree_sitter::Node,
+    source: &str,
+    diagnostics: &mut Vec<ParseDiagnostic>,
+) {
+    let span = (node.start_byte() as u32, node.end_byte() as u32);
+    if node.is_missing() {
+        let message = format!("Missing `{}`", node.kind());
+        diagnostics.push(error_diagnostic(
+            "tree-sitter",
+            "missing-node",
+            message,
+            span,
+        ));
+        return;
+    }
+
+    if node.is_error() {
+        let text = node.utf8_text(source.as_bytes()).unwrap_or("");
+        let text = text
+            .lines()
+            .next()
+            .unwrap_or("")
+            .chars()
+            .take(50)
+            .collect::<String>();
+        let message = format!("Unexpected `{}`", text);
+        diagnostics.push(error_diagnostic(
+            "tree-sitter",
+            "syntax-error",
+            message,
+            span,
+        ));
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            collect_diagnostics(&cursor.node(), source, diagnostics);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics(span: Span) -> Vec<ParseDiagnostic> {
+        vec![error_diagnostic(
+            "test",
+            "syntax-error",
+            "Error".to_string(),
+            span,
+        )]
+    }
+
+    #[test]
+    fn overlaps() {
+        assert!(overlaps_diagnostics((10, 20), &diagnostics((15, 25))));
+        assert!(overlaps_diagnostics((10, 20), &diagnostics((5, 11))));
+        assert!(overlaps_diagnostics((10, 20), &diagnostics((12, 14))));
+        assert!(!overlaps_diagnostics((10, 20), &diagnostics((20, 25))));
+        assert!(!overlaps_diagnostics((10, 20), &diagnostics((0, 10))));
+    }
+
+    #[test]
+    fn overlaps_zero_width() {
+        assert!(overlaps_diagnostics((10, 20), &diagnostics((15, 15))));
+        assert!(!overlaps_diagnostics((10, 20), &diagnostics((10, 10))));
+        assert!(!overlaps_diagnostics((10, 20), &diagnostics((20, 20))));
+    }
+}
@@ -0,0 +1,479 @@
+use volumen_types::{Prompt, PromptContentToken, PromptContentTokenEscape, Span};
+
+/// String escaping rules. Parsers pick the style based on the string literal
+/// kind, e.g., single- or double-quoted string in PHP.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EscapeStyle {
+    /// String without escape sequences, e.g., Go raw string or PHP nowdoc.
+    None,
+    /// JavaScript and TypeScript strings and template literals.
+    Js,
+    /// Python strings. Raw strings have no backslash escapes, and f-strings
+    /// escape braces by doubling them, i.e., `{{` and `}}`. Named escapes,
+    /// e.g., `\N{EM DASH}`, require the Unicode names database, so they're
+    /// kept as is.
+    Py { raw: bool, fstring: bool },
+    /// Ruby double-quoted strings and heredocs.
+    Rb,
+    /// Ruby and PHP single-quoted strings where only `\\` and `\'` are escapes.
+    SingleQuoted,
+    /// PHP double-quoted strings and heredocs.
+    Php,
+    /// Go interpreted strings.
+    Go,
+    /// Java strings and text blocks.
+    Java,
+    /// C# strings. Verbatim strings escape quotes by doubling them instead of
+    /// backslash escapes, and interpolated strings escape braces by doubling.
+    Cs { verbatim: bool, interpolated: bool },
+}
+
+/// Finds escape sequences in the string chunk pointed by the span.
+pub fn find_escapes(source: &str, span: Span, style: EscapeStyle) -> Vec<PromptContentTokenEscape> {
+    let mut escapes = Vec::new();
+    let Some(text) = source.get(span.0 as usize..span.1 as usize) else {
+        return escapes;
+    };
+
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        match decode_escape(rest, style) {
+            Some((len, decoded)) => {
+                escapes.push(PromptContentTokenEscape {
+                    span: (span.0 + pos as u32, span.0 + (pos + len) as u32),
+                    text: decoded,
+                });
+                pos += len;
+            }
+
+            None => pos += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    escapes
+}
+
+/// Decodes the string chunk text pointed by the span, replacing escape
+/// sequences with their decoded text.
+pub fn decode_text(source: &str, span: Span, escapes: &[PromptContentTokenEscape]) -> String {
+    let mut text = String::new();
+    let mut pos = span.0;
+    for escape in escapes {
+        text.push_str(source_text(source, (pos, escape.span.0)));
+        text.push_str(&escape.text);
+        pos = escape.span.1;
+    }
+    text.push_str(source_text(source, (pos, span.1)));
+    text
+}
+
+/// Resolves escape sequences of all prompts string and joint tokens. The style
+/// function receives a token span and returns the escaping rules of the string
+/// literal containing it.
+pub fn resolve_escapes<Style>(prompts: &mut [Prompt], source: &str, style: Style)
+where
+    Style: Fn(Span) -> EscapeStyle,
+{
+    for prompt in prompts {
+        let joint = prompt.joint.inner;
+        for token in &mut prompt.content {
+            let (span, escapes) = match token {
+                PromptContentToken::PromptContentTokenStr(token) => {
+                    (token.span, &mut token.escapes)
+                }
+                PromptContentToken::PromptContentTokenJoint(token) => (joint, &mut token.escapes),
+                PromptContentToken::PromptContentTokenVar(_) => continue,
+            };
+
+            let found = find_escapes(source, span, style(span));
+            *escapes = if found.is_empty() { None } else { Some(found) };
+        }
+    }
+}
+
+fn source_text(source: &str, span: Span) -> &str {
+    source.get(span.0 as usize..span.1 as usize).unwrap_or("")
+}
+
+/// Decodes escape sequence at the start of the text. Returns the sequence
+/// length in bytes and the decoded text, or `None` if the text doesn't start
+/// with an escape sequence.
+fn decode_escape(text: &str, style: EscapeStyle) -> Option<(usize, String)> {
+    match style {
+        EscapeStyle::None => None,
+
+        EscapeStyle::Py { raw, fstring } => {
+            if fstring && let Some(escape) = doubled(text, &["{{", "}}"]) {
+                return Some(escape);
+            }
+            if raw { None } else { backslash(text, style) }
+        }
+
+        EscapeStyle::Cs {
+            verbatim,
+            interpolated,
+        } => {
+            if interpolated && let Some(escape) = doubled(text, &["{{", "}}"]) {
+                return Some(escape);
+            }
+            if verbatim {
+                doubled(text, &["\"\""])
+            } else {
+                backslash(text, style)
+            }
+        }
+
+        _ => backslash(text, style),
+    }
+}
+
+/// Decodes doubled character escape, e.g., `{{` in Python f-strings.
+fn doubled(text: &str, sequences: &[&str]) -> Option<(usize, String)> {
+    sequences
+        .iter()
+        .find(|sequence| text.starts_with(*sequence))
+        .map(|sequence| (2, sequence[..1].to_string()))
+}
+
+/// Decodes backslash escape sequence according to the style rules.
+fn backslash(text: &str, style: EscapeStyle) -> Option<(usize, String)> {
+    let rest = text.strip_prefix('\\')?;
+    let ch = rest.chars().next()?;
+    let simple = |decoded: char| Some((1 + ch.len_utf8(), decoded.to_string()));
+
+    // Line continuation
+    if ch == '\n' || ch == '\r' {
+        let len = if rest.starts_with("\r\n") { 2 } else { 1 };
+        return match style {
+            EscapeStyle::Js | EscapeStyle::Py { .. } | EscapeStyle::Rb | EscapeStyle::Java => {
+                Some((1 + len, String::new()))
+            }
+            _ => None,
+        };
+    }
+
+    match style {
+        EscapeStyle::SingleQuoted => match ch {
+            '\\' | '\'' => simple(ch),
+            _ => None,
+        },
+
+        EscapeStyle::Js => match ch {
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            'b' => simple('\u{8}'),
+            'f' => simple('\u{c}'),
+            'v' => simple('\u{b}'),
+            'x' => hex(rest, 1, 2, 2),
+            'u' if rest[1..].starts_with('{') => braced_hex(rest, 1),
+            'u' => utf16_hex(rest, 1),
+            '0'..='7' => octal(rest, 3),
+            _ => simple(ch),
+        },
+
+        EscapeStyle::Py { .. } => match ch {
+            '\\' | '\'' | '"' => simple(ch),
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            'a' => simple('\u{7}'),
+            'b' => simple('\u{8}'),
+            'f' => simple('\u{c}'),
+            'v' => simple('\u{b}'),
+            'x' => hex(rest, 1, 2, 2),
+            'u' => hex(rest, 1, 4, 4),
+            'U' => hex(rest, 1, 8, 8),
+            '0'..='7' => octal(rest, 3),
+            _ => None,
+        },
+
+        EscapeStyle::Rb => match ch {
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            's' => simple(' '),
+            'a' => simple('\u{7}'),
+            'b' => simple('\u{8}'),
+            'e' => simple('\u{1b}'),
+            'f' => simple('\u{c}'),
+            'v' => simple('\u{b}'),
+            'x' => hex(rest, 1, 1, 2),
+            'u' if rest[1..].starts_with('{') => braced_hex(rest, 1),
+            'u' => hex(rest, 1, 4, 4),
+            '0'..='7' => octal(rest, 3),
+            _ => simple(ch),
+        },
+
+        EscapeStyle::Php => match ch {
+            '\\' | '"' | '$' => simple(ch),
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            'e' => simple('\u{1b}'),
+            'f' => simple('\u{c}'),
+            'v' => simple('\u{b}'),
+            'x' => hex(rest, 1, 1, 2),
+            'u' if rest[1..].starts_with('{') => braced_hex(rest, 1),
+            '0'..='7' => octal(rest, 3),
+            _ => None,
+        },
+
+        EscapeStyle::Go => match ch {
+            '\\' | '\'' | '"' => simple(ch),
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            'a' => simple('\u{7}'),
+            'b' => simple('\u{8}'),
+            'f' => simple('\u{c}'),
+            'v' => simple('\u{b}'),
+            'x' => hex(rest, 1, 2, 2),
+            'u' => hex(rest, 1, 4, 4),
+            'U' => hex(rest, 1, 8, 8),
+            '0'..='7' => octal(rest, 3),
+            _ => None,
+        },
+
+        EscapeStyle::Java => match ch {
+            '\\' | '\'' | '"' => simple(ch),
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            's' => simple(' '),
+            'b' => simple('\u{8}'),
+            'f' => simple('\u{c}'),
+            'u' => {
+                // Java allows any number of `u` characters, e.g., `\uu00e9`
+                let us = rest.bytes().take_while(|&byte| byte == b'u').count();
+                utf16_hex(rest, us)
+            }
+            '0'..='7' => octal(rest, 3),
+            _ => None,
+        },
+
+        EscapeStyle::Cs { .. } => match ch {
+            '\\' | '\'' | '"' => simple(ch),
+            'n' => simple('\n'),
+            't' => simple('\t'),
+            'r' => simple('\r'),
+            '0' => simple('\0'),
+            'a' => simple('\u{7}'),
+            'b' => simple('\u{8}'),
+            'e' => simple('\u{1b}'),
+            'f' => simple('\u{c}'),
+            'v' => simple('\u{b}'),
+            'x' => hex(rest, 1, 1, 4),
+            'u' => utf16_hex(rest, 1),
+            'U' => hex(rest, 1, 8, 8),
+            _ => None,
+        },
+
+        EscapeStyle::None => None,
+    }
+}
+
+/// Decodes hex escape with the given digits range after the prefix of the
+/// given length, e.g., `x` in `\x41`. The text must start after the backslash.
+fn hex(text: &str, prefix: usize, min: usize, max: usize) -> Option<(usize, String)> {
+    let (len, code) = hex_code(text, prefix, min, max)?;
+    let decoded = char::from_u32(code)?;
+    Some((len, decoded.to_string()))
+}
+
+/// Decodes UTF-16 code unit escape, e.g., `\u00e9`, combining the surrogate
+/// pairs, e.g., `\uD83D\uDE00`, into a single character. Unpaired
+/// surrogates aren't valid characters, so they're kept as is. The text must
+/// start after the backslash.
+fn utf16_hex(text: &str, prefix: usize) -> Option<(usize, String)> {
+    let (len, code) = hex_code(text, prefix, 4, 4)?;
+    if !(0xD800..0xDC00).contains(&code) {
+        return hex(text, prefix, 4, 4);
+    }
+
+    // The length includes the backslash, so the low surrogate escape starts
+    // right after the high surrogate digits
+    let low = text[len - 1..].strip_prefix('\\')?;
+    let us = low.bytes().take_while(|&byte| byte == b'u').count();
+    if us == 0 {
+        return None;
+    }
+    let (low_len, low_code) = hex_code(low, us, 4, 4)?;
+    if !(0xDC00..0xE000).contains(&low_code) {
+        return None;
+    }
+
+    let code = 0x10000 + ((code - 0xD800) << 10) + (low_code - 0xDC00);
+    let decoded = char::from_u32(code)?;
+    Some((len + low_len, decoded.to_string()))
+}
+
+/// Parses hex digits after the prefix. Returns the escape length, including
+/// the backslash, and the code.
+fn hex_code(text: &str, prefix: usize, min: usize, max: usize) -> Option<(usize, u32)> {
+    let digits = text[prefix..]
+        .bytes()
+        .take(max)
+        .take_while(u8::is_ascii_hexdigit)
+        .count();
+    if digits < min {
+        return None;
+    }
+
+    let code = u32::from_str_radix(&text[prefix..prefix + digits], 16).ok()?;
+    Some((1 + prefix + digits, code))
+}
+
+/// Decodes braced hex escape, e.g., `\u{1F600}`. The text must start after
+/// the backslash.
+fn braced_hex(text: &str, prefix: usize) -> Option<(usize, String)> {
+    let body = text[prefix..].strip_prefix('{')?;
+    let close = body.find('}')?;
+    let code = u32::from_str_radix(&body[..close], 16).ok()?;
+    let decoded = char::from_u32(code)?;
+    Some((1 + prefix + close + 2, decoded.to_string()))
+}
+
+/// Decodes octal escape with up to the given number of digits, e.g., `\101`.
+/// The text must start after the backslash.
+fn octal(text: &str, max: usize) -> Option<(usize, String)> {
+    let mut digits = text
+        .bytes()
+        .take(max)
+        .take_while(|byte| (b'0'..=b'7').contains(byte))
+        .count();
+    let mut code = u32::from_str_radix(&text[..digits], 8).ok()?;
+    // Octal escapes are limited to a single byte
+    if code > 0o377 {
+        digits -= 1;
+        code = u32::from_str_radix(&text[..digits], 8).ok()?;
+    }
+
+    let decoded = char::from_u32(code)?;
+    Some((1 + digits, decoded.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn decode(raw: &str, style: EscapeStyle) -> String {
+        let span = (0, raw.len() as u32);
+        decode_text(raw, span, &find_escapes(raw, span, style))
+    }
+
+    #[test]
+    fn js() {
+        assert_eq!(decode(r"Line1\nLine2", EscapeStyle::Js), "Line1\nLine2");
+        assert_eq!(decode(r"It\'s \`${x}\`", EscapeStyle::Js), "It's `${x}`");
+        assert_eq!(decode(r"\x41\u00e9\u{1F600}", EscapeStyle::Js), "Aé😀");
+        assert_eq!(decode("a\\\nb", EscapeStyle::Js), "ab");
+    }
+
+    #[test]
+    fn py() {
+        let style = EscapeStyle::Py {
+            raw: false,
+            fstring: false,
+        };
+        assert_eq!(decode(r"Line1\nLine2", style), "Line1\nLine2");
+        assert_eq!(decode(r"\N{DASH} \q {{", style), r"\N{DASH} \q {{");
+        assert_eq!(decode(r"a\N{EM DASH}b", style), r"a\N{EM DASH}b");
+        assert_eq!(decode(r"\ud83d\ude00", style), r"\ud83d\ude00");
+        assert_eq!(decode(r"\U0001F600\101", style), "😀A");
+
+        let style = EscapeStyle::Py {
+            raw: true,
+            fstring: true,
+        };
+        assert_eq!(decode(r"\n {{x}}", style), r"\n {x}");
+    }
+
+    #[test]
+    fn rb() {
+        assert_eq!(
+            decode(r"Hello\sworld\e", EscapeStyle::Rb),
+            "Hello world\u{1b}"
+        );
+        assert_eq!(decode(r"\#{name}", EscapeStyle::Rb), "#{name}");
+        assert_eq!(decode(r"It\'s \n", EscapeStyle::SingleQuoted), r"It's \n");
+    }
+
+    #[test]
+    fn php() {
+        assert_eq!(decode(r"\$name\n\'", EscapeStyle::Php), "$name\n\\'");
+        assert_eq!(decode(r"\u{e9}\x41\101", EscapeStyle::Php), "éAA");
+        assert_eq!(
+            decode(r"It\'s C:\\", EscapeStyle::SingleQuoted),
+            r"It's C:\"
+        );
+    }
+
+    #[test]
+    fn go() {
+        assert_eq!(
+            decode(r"\a\x41\u00e9\U0001F600", EscapeStyle::Go),
+            "\u{7}Aé😀"
+        );
+        assert_eq!(decode(r"\n", EscapeStyle::None), r"\n");
+    }
+
+    #[test]
+    fn java() {
+        assert_eq!(decode(r"\u00e9\uu00e9\s", EscapeStyle::Java), "éé ");
+        assert_eq!(decode(r"\377\400", EscapeStyle::Java), "\u{ff}\u{20}0");
+    }
+
+    #[test]
+    fn cs() {
+        let style = EscapeStyle::Cs {
+            verbatim: false,
+            interpolated: false,
+        };
+        assert_eq!(decode(r"\x41\x00e9 {{", style), "Aé {{");
+
+        let style = EscapeStyle::Cs {
+            verbatim: true,
+            interpolated: true,
+        };
+        assert_eq!(decode(r#"C:\ ""{{x}}"""#, style), r#"C:\ "{x}""#);
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        let cs = EscapeStyle::Cs {
+            verbatim: false,
+            interpolated: false,
+        };
+        for style in [EscapeStyle::Js, EscapeStyle::Java, cs] {
+            assert_eq!(decode(r"Hi \uD83D\uDE00!", style), "Hi 😀!");
+            assert_eq!(decode(r"\uD83D", style), r"\uD83D");
+            assert_eq!(decode(r"\uDE00\uD83D", style), r"\uDE00\uD83D");
+            assert_eq!(decode(r"\uD83D\u0041", style), r"\uD83DA");
+        }
+        assert_eq!(decode(r"\uD83D\uuDE00", EscapeStyle::Java), "😀");
+
+        let source = r"\uD83D\uDE00";
+        assert_eq!(
+            find_escapes(source, (0, 12), EscapeStyle::Js),
+            vec![PromptContentTokenEscape {
+                span: (0, 12),
+                text: "😀".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn spans() {
+        let source = r#"const p = "a\nb";"#;
+        assert_eq!(
+            find_escapes(source, (11, 15), EscapeStyle::Js),
+            vec![PromptContentTokenEscape {
+                span: (12, 14),
+                text: "\n".into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::PromptDetector;
+use volumen_types::*;
+
+/// Function producing prompts, i.e., a function with a prompt-like name or
+/// a leading `@prompt` annotation, e.g., `def build_prompt(user): ...`. Its
+/// return values and arrow expression bodies are detected as prompts.
+#[derive(Debug, Clone)]
+pub struct PromptFunction {
+    /// Function name binding, e.g., `build_prompt`.
+    pub binding: PromptBinding,
+    /// Parameter names as they appear in source code, e.g., `user` or `$user`.
+    pub parameters: Vec<String>,
+    /// Leading annotations of the function definition.
+    pub annotations: Vec<PromptAnnotation>,
+}
+
+impl PromptFunction {
+    /// Creates the prompt function if the function name matches the prompt
+    /// name patterns or the function definition is annotated. Returns `None`
+    /// for other functions.
+    pub fn new(
+        detector: &PromptDetector,
+        source: &str,
+        name: Span,
+        parameters: Vec<String>,
+        annotations: Vec<PromptAnnotation>,
+    ) -> Option<Self> {
+        let text = source.get(name.0 as usize..name.1 as usize)?;
+        if annotations.is_empty() && !detector.is_prompt_name(text) {
+            return None;
+        }
+        Some(Self {
+            binding: PromptBinding {
+                name: text.to_string(),
+                span: name,
+                kind: PromptBindingKind::Function,
+            },
+            parameters,
+            annotations,
+        })
+    }
+
+    /// Marks the variables rooted at a function parameter in the prompts
+    /// added since the `start` index.
+    pub fn mark_parameter_vars(&self, prompts: &mut [Prompt], start: usize) {
+        for prompt in prompts.iter_mut().skip(start) {
+            for var in &mut prompt.vars {
+                let is_parameter = var
+                    .root
+                    .as_ref()
+                    .is_some_and(|root| self.parameters.contains(root));
+                if is_parameter {
+                    var.parameter = Some(true);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt_var;
+    use pretty_assertions::assert_eq;
+
+    fn annotation() -> PromptAnnotation {
+        PromptAnnotation {
+            spans: vec![SpanShape {
+                outer: (0, 9),
+                inner: (1, 9),
+            }],
+            attributes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn prompt_name() {
+        let source = "def build_prompt(user):";
+        let function = PromptFunction::new(
+            &PromptDetector::default(),
+            source,
+            (4, 16),
+            vec!["user".to_string()],
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            function.binding,
+            PromptBinding {
+                name: "build_prompt".to_string(),
+                span: (4, 16),
+                kind: PromptBindingKind::Function,
+            }
+        );
+        assert_eq!(function.parameters, vec!["user".to_string()]);
+    }
+
+    #[test]
+    fn annotated() {
+        let source = "#@prompt\ndef greet(user):";
+        let detector = PromptDetector::default();
+        assert!(PromptFunction::new(&detector, source, (13, 18), Vec::new(), Vec::new()).is_none());
+        assert!(
+            PromptFunction::new(&detector, source, (13, 18), Vec::new(), vec![annotation()])
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn parameter_vars() {
+        let source = r#"f"{user.name} {greeting}""#;
+        let function = PromptFunction::new(
+            &PromptDetector::default(),
+            "build_prompt",
+            (0, 12),
+            vec!["user".to_string()],
+            Vec::new(),
+        )
+        .unwrap();
+        let var = |span: Span| {
+            prompt_var(
+                source,
+                SpanShape {
+                    outer: span,
+                    inner: (span.0 + 1, span.1 - 1),
+                },
+                (span.0 + 1, span.1 - 1),
+            )
+        };
+        let mut prompts = vec![Prompt {
+            file: "prompts.py".to_string(),
+            enclosure: (0, 25),
+            span: SpanShape {
+                outer: (0, 25),
+                inner: (2, 24),
+            },
+            content: Vec::new(),
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            vars: vec![var((2, 13)), var((14, 24))],
+            annotations: Vec::new(),
+            binding: function.binding.clone(),
+            role: None,
+            references: None,
+        }];
+        function.mark_parameter_vars(&mut prompts, 0);
+        assert_eq!(prompts[0].vars[0].parameter, Some(true));
+        assert_eq!(prompts[0].vars[1].parameter, None);
+    }
+}
@@ -0,0 +1,151 @@
+use volumen_types::*;
+
+/// Creates the chat message group from the message prompts added since
+/// the `start` index. Returns `None` if no message prompts were added.
+pub fn prompt_group(
+    file: &str,
+    span: Span,
+    binding: &PromptBinding,
+    prompts: &[Prompt],
+    start: usize,
+) -> Option<PromptGroup> {
+    let first = prompts.get(start)?;
+    Some(PromptGroup {
+        file: file.to_string(),
+        enclosure: first.enclosure,
+        span,
+        binding: binding.clone(),
+        messages: prompts[start..]
+            .iter()
+            .enumerate()
+            .map(|(offset, prompt)| PromptGroupMessage {
+                prompt: (start + offset) as u32,
+                role: prompt.role,
+            })
+            .collect(),
+    })
+}
+
+/// Retains the prompts matching the predicate and updates the group message
+/// indices. Groups left without messages are removed.
+pub fn retain_prompts(
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+    mut keep: impl FnMut(&Prompt) -> bool,
+) {
+    let mut indices = Vec::with_capacity(prompts.len());
+    let mut next = 0u32;
+    for prompt in prompts.iter() {
+        if keep(prompt) {
+            indices.push(Some(next));
+            next += 1;
+        } else {
+            indices.push(None);
+        }
+    }
+
+    let mut index = 0;
+    prompts.retain(|_| {
+        index += 1;
+        indices[index - 1].is_some()
+    });
+
+    for group in groups.iter_mut() {
+        group.messages.retain_mut(|message| {
+            match indices.get(message.prompt as usize).copied().flatten() {
+                Some(new_index) => {
+                    message.prompt = new_index;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+    groups.retain(|group| !group.messages.is_empty());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn prompt(enclosure: Span, role: Option<PromptRole>) -> Prompt {
+        Prompt {
+            file: "prompts.py".to_string(),
+            enclosure,
+            span: SpanShape {
+                outer: enclosure,
+                inner: enclosure,
+            },
+            content: Vec::new(),
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            vars: Vec::new(),
+            annotations: Vec::new(),
+            binding: binding(),
+            role,
+            references: None,
+        }
+    }
+
+    fn binding() -> PromptBinding {
+        PromptBinding {
+            name: "messages".to_string(),
+            span: (0, 8),
+            kind: PromptBindingKind::Local,
+        }
+    }
+
+    fn roles(group: &PromptGroup) -> Vec<(u32, Option<PromptRole>)> {
+        group
+            .messages
+            .iter()
+            .map(|message| (message.prompt, message.role))
+            .collect()
+    }
+
+    #[test]
+    fn group() {
+        let prompts = vec![
+            prompt((0, 5), None),
+            prompt((10, 20), Some(PromptRole::System)),
+            prompt((10, 20), None),
+        ];
+        let group = prompt_group("prompts.py", (12, 20), &binding(), &prompts, 1).unwrap();
+        assert_eq!(group.enclosure, (10, 20));
+        assert_eq!(group.span, (12, 20));
+        assert_eq!(
+            roles(&group),
+            vec![(1, Some(PromptRole::System)), (2, None)]
+        );
+        assert_eq!(
+            prompt_group("prompts.py", (12, 20), &binding(), &prompts, 3),
+            None
+        );
+    }
+
+    #[test]
+    fn retain() {
+        let mut prompts = vec![
+            prompt((0, 5), None),
+            prompt((10, 20), Some(PromptRole::System)),
+            prompt((10, 20), Some(PromptRole::User)),
+            prompt((30, 40), Some(PromptRole::User)),
+        ];
+        let mut groups = vec![
+            prompt_group("prompts.py", (12, 20), &binding(), &prompts[..3], 1).unwrap(),
+            prompt_group("prompts.py", (32, 40), &binding(), &prompts, 3).unwrap(),
+        ];
+        retain_prompts(&mut prompts, &mut groups, |prompt| {
+            prompt.enclosure != (0, 5) && prompt.enclosure != (30, 40)
+        });
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            roles(&groups[0]),
+            vec![(0, Some(PromptRole::System)), (1, Some(PromptRole::User))]
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use volumen_types::{ParseDetection, ParseDialect, ParseLanguage};
+
+/// Number of lines at the start and the end of the source searched for the
+/// modelines, matching the Vim default.
+const MODELINE_LINES: usize = 5;
+
+/// Resolves the source language and dialect from the file extension or the
+/// well-known file name, e.g., `Rakefile`. Returns `None` if the file type
+/// isn't supported.
+pub fn file_language(filename: &str) -> Option<(ParseLanguage, Option<ParseDialect>)> {
+    let basename = filename.rsplit(['/', '\\']).next().unwrap_or(filename);
+    if matches!(basename, "Rakefile" | "Gemfile") {
+        return Some((ParseLanguage::Rb, None));
+    }
+
+    let lower = filename.to_ascii_lowercase();
+    let ext = lower.rsplit('.').next().unwrap_or("");
+    let language = match ext {
+        "js" | "mjs" | "cjs" | "es6" | "jsm" => (ParseLanguage::Js, None),
+        "jsx" | "mjsx" | "cjsx" => (ParseLanguage::Js, Some(ParseDialect::Jsx)),
+        "ts" => (ParseLanguage::Ts, None),
+        "tsx" => (ParseLanguage::Ts, Some(ParseDialect::Tsx)),
+        "py" => (ParseLanguage::Py, None),
+        "pyi" => (ParseLanguage::Py, Some(ParseDialect::Pyi)),
+        "rb" | "ruby" | "rake" | "gemspec" => (ParseLanguage::Rb, None),
+        "php" | "phtml" => (ParseLanguage::Php, None),
+        "go" => (ParseLanguage::Go, None),
+        "java" => (ParseLanguage::Java, None),
+        "cs" => (ParseLanguage::Cs, None),
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Detects the source language from the content of the file without a known
+/// extension, e.g., `bin/setup`. The detection rules are tried in order: the
+/// shebang, e.g., `#!/usr/bin/env python3`, the Vim or Emacs modeline, e.g.,
+/// `# vim: set ft=ruby:`, and the PHP opening tag.
+pub fn source_language(
+    source: &str,
+) -> Option<(ParseLanguage, Option<ParseDialect>, ParseDetection)> {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    if let Some((language, dialect)) = source.lines().next().and_then(shebang_language) {
+        return Some((language, dialect, ParseDetection::Shebang));
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let tail = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+    if let Some((language, dialect)) = lines[..lines.len().min(MODELINE_LINES)]
+        .iter()
+        .chain(lines.get(tail..).unwrap_or_default())
+        .find_map(|line| modeline_language(line))
+    {
+        return Some((language, dialect, ParseDetection::Modeline));
+    }
+
+    let opener = |line: &&str| {
+        line.trim_start()
+            .get(..5)
+            .is_some_and(|tag| tag.eq_ignore_ascii_case("<?php"))
+    };
+    lines
+        .iter()
+        .any(opener)
+        .then_some((ParseLanguage::Php, None, ParseDetection::Opener))
+}
+
+/// Resolves the language from the shebang interpreter, e.g., `python3` in
+/// `#!/usr/bin/python3` or `node` in `#!/usr/bin/env -S node --no-warnings`.
+fn shebang_language(line: &str) -> Option<(ParseLanguage, Option<ParseDialect>)> {
+    let mut args = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = args.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = args.find(|arg| !arg.starts_with('-') && !arg.contains('='))?;
+    }
+    // Versioned interpreters, e.g., `python3.12` or `ruby2.7`
+    let language =
+        match interpreter.trim_end_matches(|char: char| char.is_ascii_digit() || char == '.') {
+            "python" | "pypy" => ParseLanguage::Py,
+            "node" | "nodejs" | "bun" => ParseLanguage::Js,
+            "deno" | "ts-node" | "tsx" => ParseLanguage::Ts,
+            "ruby" | "jruby" | "truffleruby" => ParseLanguage::Rb,
+            "php" => ParseLanguage::Php,
+            "java" => ParseLanguage::Java,
+            _ => return None,
+        };
+    Some((language, None))
+}
+
+/// Resolves the language from the Vim modeline file type, e.g.,
+/// `# vim: set ft=python:`, or the Emacs mode, e.g., `# -*- mode: ruby -*-`.
+fn modeline_language(line: &str) -> Option<(ParseLanguage, Option<ParseDialect>)> {
+    let vim = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+        let (before, options) = line.split_once(marker)?;
+        if !before.is_empty() && !before.ends_with(char::is_whitespace) {
+            return None;
+        }
+        options.split([' ', '\t', ':']).find_map(|option| {
+            let (name, value) = option.split_once('=')?;
+            matches!(name, "ft" | "filetype" | "syn" | "syntax").then_some(value)
+        })
+    });
+    let emacs = || {
+        let (_, vars) = line.split_once("-*-")?;
+        let (vars, _) = vars.split_once("-*-")?;
+        // `-*- python -*-` is a shorthand for `-*- mode: python -*-`
+        if !vars.contains(':') {
+            return Some(vars.trim());
+        }
+        vars.split(';').find_map(|var| {
+            let (name, value) = var.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("mode")
+                .then(|| value.trim())
+        })
+    };
+
+    let mode = vim.or_else(emacs)?.to_ascii_lowercase();
+    let mode = mode.strip_suffix("-mode").unwrap_or(&mode);
+    let mode = mode.strip_suffix("-ts").unwrap_or(mode);
+    let language = match mode {
+        "javascript" | "js" | "js2" => (ParseLanguage::Js, None),
+        "javascriptreact" | "jsx" | "rjsx" | "js-jsx" => {
+            (ParseLanguage::Js, Some(ParseDialect::Jsx))
+        }
+        "typescript" | "ts" => (ParseLanguage::Ts, None),
+        "typescriptreact" | "tsx" => (ParseLanguage::Ts, Some(ParseDialect::Tsx)),
+        "python" | "py" => (ParseLanguage::Py, None),
+        "ruby" | "rb" | "enh-ruby" => (ParseLanguage::Rb, None),
+        "php" => (ParseLanguage::Php, None),
+        "go" => (ParseLanguage::Go, None),
+        "java" => (ParseLanguage::Java, None),
+        "cs" | "csharp" => (ParseLanguage::Cs, None),
+        _ => return None,
+    };
+    Some(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn languages() {
+        assert_eq!(file_language("prompts.js"), Some((ParseLanguage::Js, None)));
+        assert_eq!(
+            file_language("prompts.cjs"),
+            Some((ParseLanguage::Js, None))
+        );
+        assert_eq!(file_language("prompts.ts"), Some((ParseLanguage::Ts, None)));
+        assert_eq!(file_language("prompts.py"), Some((ParseLanguage::Py, None)));
+        assert_eq!(
+            file_language("prompts.ruby"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(file_language("Prompts.CS"), Some((ParseLanguage::Cs, None)));
+        assert_eq!(file_language("prompts.txt"), None);
+        assert_eq!(file_language("Makefile"), None);
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(file_language("lib.es6"), Some((ParseLanguage::Js, None)));
+        assert_eq!(file_language("lib.jsm"), Some((ParseLanguage::Js, None)));
+        assert_eq!(
+            file_language("tasks/prompts.rake"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(
+            file_language("prompts.gemspec"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(
+            file_language("views/index.phtml"),
+            Some((ParseLanguage::Php, None))
+        );
+        assert_eq!(file_language("Rakefile"), Some((ParseLanguage::Rb, None)));
+        assert_eq!(
+            file_language("app/Gemfile"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(file_language("rakefile.txt"), None);
+    }
+
+    #[test]
+    fn dialects() {
+        assert_eq!(
+            file_language("prompts.jsx"),
+            Some((ParseLanguage::Js, Some(ParseDialect::Jsx)))
+        );
+        assert_eq!(
+            file_language("prompts.mjsx"),
+            Some((ParseLanguage::Js, Some(ParseDialect::Jsx)))
+        );
+        assert_eq!(
+            file_language("prompts.tsx"),
+            Some((ParseLanguage::Ts, Some(ParseDialect::Tsx)))
+        );
+        assert_eq!(
+            file_language("prompts.pyi"),
+            Some((ParseLanguage::Py, Some(ParseDialect::Pyi)))
+        );
+    }
+
+    #[test]
+    fn shebangs() {
+        assert_eq!(
+            source_language("#!/usr/bin/env python3\nprompt = \"Hi!\"\n"),
+            Some((ParseLanguage::Py, None, ParseDetection::Shebang))
+        );
+        assert_eq!(
+            source_language("#!/usr/bin/python3.12\n"),
+            Some((ParseLanguage::Py, None, ParseDetection::Shebang))
+        );
+        assert_eq!(
+            source_language("#!/usr/bin/env -S node --no-warnings\n"),
+            Some((ParseLanguage::Js, None, ParseDetection::Shebang))
+        );
+        assert_eq!(
+            source_language("#!/usr/bin/env ruby\n"),
+            Some((ParseLanguage::Rb, None, ParseDetection::Shebang))
+        );
+        assert_eq!(
+            source_language("#!/usr/local/bin/php\n<?php\n"),
+            Some((ParseLanguage::Php, None, ParseDetection::Shebang))
+        );
+        assert_eq!(source_language("#!/bin/bash\necho hi\n"), None);
+    }
+
+    #[test]
+    fn modelines() {
+        assert_eq!(
+            source_language("# vim: set ft=ruby:\nprompt = \"Hi!\"\n"),
+            Some((ParseLanguage::Rb, None, ParseDetection::Modeline))
+        );
+        assert_eq!(
+            source_language("prompt = \"Hi!\"\n// vim: filetype=typescriptreact\n"),
+            Some((
+                ParseLanguage::Ts,
+                Some(ParseDialect::Tsx),
+                ParseDetection::Modeline
+            ))
+        );
+        assert_eq!(
+            source_language("# -*- mode: python; coding: utf-8 -*-\n"),
+            Some((ParseLanguage::Py, None, ParseDetection::Modeline))
+        );
+        assert_eq!(
+            source_language("// -*- js2 -*-\n"),
+            Some((ParseLanguage::Js, None, ParseDetection::Modeline))
+        );
+        assert_eq!(source_language("x = 1 # novim: ft=python\n"), None);
+        let source = format!(
+            "{}# vim: ft=python\n{}",
+            "x = 1\n".repeat(5),
+            "x = 1\n".repeat(5)
+        );
+        assert_eq!(source_language(&source), None);
+    }
+
+    #[test]
+    fn openers() {
+        assert_eq!(
+            source_language("<html>\n<?php echo $prompt; ?>\n"),
+            Some((ParseLanguage::Php, None, ParseDetection::Opener))
+        );
+        assert_eq!(
+            source_language("\u{feff}<?PHP\n$prompt = \"Hi!\";\n"),
+            Some((ParseLanguage::Php, None, ParseDetection::Opener))
+        );
+        assert_eq!(source_language("prompt = \"<?php\"\n"), None);
+    }
+}
@@ -1,5 +1,62 @@
 mod annotation;
 pub use annotation::*;
 
+mod call_site;
+pub use call_site::*;
+
+mod content;
+pub use content::*;
+
+mod detector;
+pub use detector::*;
+
+mod diagnostic;
+pub use diagnostic::*;
+
+mod escape;
+pub use escape::*;
+
+mod function;
+pub use function::*;
+
+mod group;
+pub use group::*;
+
+mod language;
+pub use language::*;
+
+mod line_index;
+pub use line_index::*;
+
+mod project;
+pub use project::*;
+
+mod reference;
+pub use reference::*;
+
+mod region;
+pub use region::*;
+
+mod registry;
+pub use registry::*;
+
+mod render;
+pub use render::*;
+
+mod result;
+pub use result::*;
+
+mod role;
+pub use role::*;
+
+mod scope;
+pub use scope::*;
+
+mod template;
+pub use template::*;
+
 mod r#trait;
 pub use r#trait::*;
+
+mod var;
+pub use var::*;
@@ -0,0 +1,200 @@
+use volumen_types::{Position, Span, SpanPositions};
+
+/// Index of line starts and multi-byte characters in a source. It allows to
+/// resolve UTF-8 byte offsets used by spans into line/column and UTF-16
+/// positions expected by editors and JavaScript consumers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineIndex {
+    /// Byte offsets of every line start, the first line always starts at 0.
+    line_starts: Vec<u32>,
+    /// Non-ASCII characters in order of appearance.
+    wide_chars: Vec<WideChar>,
+    /// Source length in bytes.
+    len: u32,
+}
+
+/// Character that takes more UTF-8 bytes than UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WideChar {
+    /// Character byte offset.
+    offset: u32,
+    /// Number of UTF-8 bytes minus number of UTF-16 code units.
+    diff: u32,
+    /// Number of UTF-8 bytes.
+    len: u32,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars = Vec::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((offset, ch)) = chars.next() {
+            match ch {
+                '\n' => line_starts.push(offset as u32 + 1),
+                // Lone `\r` is a line break too, while `\r\n` is handled by `\n`.
+                '\r' if !matches!(chars.peek(), Some((_, '\n'))) => {
+                    line_starts.push(offset as u32 + 1)
+                }
+                _ if !ch.is_ascii() => wide_chars.push(WideChar {
+                    offset: offset as u32,
+                    diff: (ch.len_utf8() - ch.len_utf16()) as u32,
+                    len: ch.len_utf8() as u32,
+                }),
+                _ => {}
+            }
+        }
+
+        Self {
+            line_starts,
+            wide_chars,
+            len: source.len() as u32,
+        }
+    }
+
+    /// Resolves byte offset into a position. Offsets past the source end are
+    /// clamped to the end, and offsets inside a multi-byte character resolve
+    /// to the character start.
+    pub fn position(&self, offset: u32) -> Position {
+        let offset = self.char_boundary(offset.min(self.len));
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        Position {
+            line: line as u32,
+            column: offset - line_start - self.wide_diff(line_start, offset),
+            utf16: offset - self.wide_diff(0, offset),
+        }
+    }
+
+    /// Resolves span into start and end positions.
+    pub fn span(&self, span: Span) -> SpanPositions {
+        SpanPositions {
+            start: self.position(span.0),
+            end: self.position(span.1),
+        }
+    }
+
+    /// Number of lines in the source. Empty source has a single line.
+    pub fn lines(&self) -> u32 {
+        self.line_starts.len() as u32
+    }
+
+    /// Moves offset back to the start of the multi-byte character it points
+    /// into, if any.
+    fn char_boundary(&self, offset: u32) -> u32 {
+        let index = self.wide_chars.partition_point(|ch| ch.offset <= offset);
+        match index.checked_sub(1).map(|index| self.wide_chars[index]) {
+            Some(ch) if offset < ch.offset + ch.len => ch.offset,
+            _ => offset,
+        }
+    }
+
+    /// Sum of UTF-8 and UTF-16 length differences of characters in the given
+    /// byte range.
+    fn wide_diff(&self, start: u32, end: u32) -> u32 {
+        let from = self.wide_chars.partition_point(|ch| ch.offset < start);
+        let to = self.wide_chars.partition_point(|ch| ch.offset < end);
+        self.wide_chars[from..to].iter().map(|ch| ch.diff).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pos(line: u32, column: u32, utf16: u32) -> Position {
+        Position {
+            line,
+            column,
+            utf16,
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        let index = LineIndex::new("const a = 1;\nconst b = 2;\n");
+        assert_eq!(index.lines(), 3);
+        assert_eq!(index.position(0), pos(0, 0, 0));
+        assert_eq!(index.position(6), pos(0, 6, 6));
+        assert_eq!(index.position(12), pos(0, 12, 12));
+        assert_eq!(index.position(13), pos(1, 0, 13));
+        assert_eq!(index.position(19), pos(1, 6, 19));
+        assert_eq!(index.position(26), pos(2, 0, 26));
+    }
+
+    #[test]
+    fn empty() {
+        let index = LineIndex::new("");
+        assert_eq!(index.lines(), 1);
+        assert_eq!(index.position(0), pos(0, 0, 0));
+        assert_eq!(index.position(10), pos(0, 0, 0));
+    }
+
+    #[test]
+    fn crlf() {
+        let index = LineIndex::new("a\r\nb\rc\n");
+        assert_eq!(index.lines(), 4);
+        assert_eq!(index.position(1), pos(0, 1, 1));
+        assert_eq!(index.position(3), pos(1, 0, 3));
+        assert_eq!(index.position(5), pos(2, 0, 5));
+        assert_eq!(index.position(7), pos(3, 0, 7));
+    }
+
+    #[test]
+    fn cjk() {
+        // Each CJK character takes 3 bytes and 1 UTF-16 code unit.
+        let source = "p = \"你好, {name}!\"\nq = 1";
+        let index = LineIndex::new(source);
+        let name = source.find("{name}").unwrap() as u32;
+        assert_eq!(name, 13);
+        assert_eq!(index.position(name), pos(0, 9, 9));
+        let q = source.find('q').unwrap() as u32;
+        assert_eq!(index.position(q), pos(1, 0, 18));
+    }
+
+    #[test]
+    fn emoji() {
+        // Emoji takes 4 bytes and 2 UTF-16 code units.
+        let source = "// 👋\nconst p = \"Hi 👋 {name}\";";
+        let index = LineIndex::new(source);
+        assert_eq!(index.position(7), pos(0, 5, 5));
+        let name = source.find("{name}").unwrap() as u32;
+        assert_eq!(index.position(name), pos(1, 17, 23));
+        let end = source.len() as u32;
+        assert_eq!(index.position(end), pos(1, 25, 31));
+        assert_eq!(
+            index.span((name, name + 6)),
+            SpanPositions {
+                start: pos(1, 17, 23),
+                end: pos(1, 23, 29),
+            }
+        );
+    }
+
+    #[test]
+    fn inside_char() {
+        let index = LineIndex::new("a👋b");
+        assert_eq!(index.position(1), pos(0, 1, 1));
+        assert_eq!(index.position(2), pos(0, 1, 1));
+        assert_eq!(index.position(4), pos(0, 1, 1));
+        assert_eq!(index.position(5), pos(0, 3, 3));
+    }
+
+    #[test]
+    fn utf16_slice() {
+        let source = "msg = \"こんにちは 🌍 {name}\"";
+        let index = LineIndex::new(source);
+        let start = source.find('"').unwrap() as u32 + 1;
+        let end = source.rfind('"').unwrap() as u32;
+        let positions = index.span((start, end));
+        let utf16: Vec<u16> = source.encode_utf16().collect();
+        let content = String::from_utf16(
+            &utf16[positions.start.utf16 as usize..positions.end.utf16 as usize],
+        )
+        .unwrap();
+        assert_eq!(content, "こんにちは 🌍 {name}");
+    }
+}
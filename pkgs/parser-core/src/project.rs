@@ -0,0 +1,598 @@
+use std::collections::{HashMap, HashSet};
+use volumen_types::*;
+
+/// Extensions tried when resolving TypeScript and JavaScript module
+/// specifiers without an extension, e.g., `./prompts`.
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Analyze the parsed project files, linking the imports to the prompts
+/// defined in other files and finding the unused prompts. Only relative
+/// imports are resolved, e.g., `./prompts` in TypeScript or `.prompts` in
+/// Python. The results must correspond to the files.
+pub fn analyze_project(files: &[ProjectFile], results: Vec<ParseResult>) -> ProjectResult {
+    let project = Project::new(files, &results);
+
+    let mut links = Vec::new();
+    let mut used = HashSet::new();
+    for (file, result) in results.iter().enumerate() {
+        let (_, imports, _) = result_parts(result);
+        for import in imports {
+            let Some(module) = project.resolve_module(file, &import.source) else {
+                continue;
+            };
+            // Namespace and wildcard imports, e.g., `import * as prompts from
+            // "./prompts"`, can't be linked, so they mark all the module
+            // prompts as used
+            if import.name == "*" {
+                used.extend(project.module_prompts(module));
+                continue;
+            }
+            for (target, prompt) in
+                project.resolve_export(module, &import.name, &mut HashSet::new())
+            {
+                used.insert((target, prompt));
+                links.push(ProjectLink {
+                    file: file as u32,
+                    span: import.span,
+                    prompt: ProjectPrompt {
+                        file: target as u32,
+                        prompt: prompt as u32,
+                    },
+                });
+            }
+        }
+    }
+
+    let mut unused = Vec::new();
+    for (file, result) in results.iter().enumerate() {
+        let (prompts, _, exports) = result_parts(result);
+        for (index, prompt) in prompts.iter().enumerate() {
+            // Prompts passed as arguments or returned from functions have no
+            // variable to reference
+            if matches!(
+                prompt.binding.kind,
+                PromptBindingKind::Argument | PromptBindingKind::Function
+            ) || used.contains(&(file, index))
+            {
+                continue;
+            }
+            // Reassignments and exports alone don't use the prompt
+            let referenced = prompt.references.iter().flatten().any(|reference| {
+                reference.kind != PromptReferenceKind::Reassign
+                    && !exports.iter().any(|export| export.span == reference.span)
+            });
+            if !referenced {
+                unused.push(ProjectPrompt {
+                    file: file as u32,
+                    prompt: index as u32,
+                });
+            }
+        }
+    }
+
+    ProjectResult {
+        files: results,
+        links,
+        unused,
+    }
+}
+
+/// Project files index resolving the modules and the exported prompts.
+struct Project<'a> {
+    files: &'a [ProjectFile],
+    results: &'a [ParseResult],
+    /// Normalized file path -> file index.
+    paths: HashMap<String, usize>,
+}
+
+impl<'a> Project<'a> {
+    fn new(files: &'a [ProjectFile], results: &'a [ParseResult]) -> Self {
+        let paths = files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (normalize_path(&file.path), index))
+            .collect();
+        Self {
+            files,
+            results,
+            paths,
+        }
+    }
+
+    /// Resolve the relative module specifier imported by the file, e.g.,
+    /// `./prompts` to `src/prompts.ts` for `src/app.ts` or `.prompts` to
+    /// `app/prompts.py` for `app/main.py`.
+    fn resolve_module(&self, file: usize, specifier: &str) -> Option<usize> {
+        let path = normalize_path(&self.files[file].path);
+        let dir = parent_path(&path);
+        let candidates = match result_language(&self.results[file])? {
+            ParseLanguage::Ts | ParseLanguage::Js => {
+                if !specifier.starts_with("./") && !specifier.starts_with("../") {
+                    return None;
+                }
+                let module = normalize_path(&format!("{dir}/{specifier}"));
+                let mut candidates = vec![module.clone()];
+                // TypeScript ESM imports use the emitted extension, e.g.,
+                // `./prompts.js` for `prompts.ts`
+                for (js, ts) in [("js", "ts"), ("jsx", "tsx")] {
+                    if let Some(stem) = module.strip_suffix(&format!(".{js}")) {
+                        candidates.push(format!("{stem}.{ts}"));
+                    }
+                }
+                for extension in JS_EXTENSIONS {
+                    candidates.push(format!("{module}.{extension}"));
+                }
+                for extension in JS_EXTENSIONS {
+                    candidates.push(format!("{module}/index.{extension}"));
+                }
+                candidates
+            }
+
+            ParseLanguage::Py => {
+                // Each dot after the first one goes up a package, e.g.,
+                // `..prompts`
+                let module = specifier.trim_start_matches('.');
+                let level = specifier.len() - module.len();
+                if level == 0 {
+                    return None;
+                }
+                let mut package = dir.to_string();
+                for _ in 1..level {
+                    package = parent_path(&package).to_string();
+                }
+                let mut candidates = Vec::new();
+                // `from . import prompts` imports from the package itself
+                let module = if module.is_empty() {
+                    package
+                } else {
+                    let module = normalize_path(&format!("{package}/{}", module.replace('.', "/")));
+                    candidates.push(format!("{module}.py"));
+                    candidates.push(format!("{module}.pyi"));
+                    module
+                };
+                candidates.push(normalize_path(&format!("{module}/__init__.py")));
+                candidates.push(normalize_path(&format!("{module}/__init__.pyi")));
+                candidates
+            }
+
+            _ => return None,
+        };
+        candidates
+            .iter()
+            .find_map(|candidate| self.paths.get(candidate).copied())
+    }
+
+    /// Resolve the prompts exported from the module with the name, following
+    /// the re-exports.
+    fn resolve_export(
+        &self,
+        module: usize,
+        name: &str,
+        visited: &mut HashSet<(usize, String)>,
+    ) -> Vec<(usize, usize)> {
+        if !visited.insert((module, name.to_string())) {
+            return Vec::new();
+        }
+        let (prompts, imports, exports) = result_parts(&self.results[module]);
+        let mut resolved = Vec::new();
+
+        match result_language(&self.results[module]) {
+            Some(ParseLanguage::Ts | ParseLanguage::Js) => {
+                for export in exports.iter().filter(|export| export.name == name) {
+                    match &export.source {
+                        // Re-exports, e.g., `export { SYSTEM } from "./prompts"`
+                        Some(source) => {
+                            if export.local == "*" {
+                                continue;
+                            }
+                            if let Some(target) = self.resolve_module(module, source) {
+                                resolved.extend(self.resolve_export(
+                                    target,
+                                    &export.local,
+                                    visited,
+                                ));
+                            }
+                        }
+                        None => resolved.extend(
+                            prompts
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, prompt)| is_exported(prompt, export))
+                                .map(|(index, _)| (module, index)),
+                        ),
+                    }
+                }
+
+                // Wildcard re-exports, e.g., `export * from "./prompts"`,
+                // don't include the default export
+                if resolved.is_empty() && name != "default" {
+                    for export in exports.iter().filter(|export| export.name == "*") {
+                        if let Some(target) = export
+                            .source
+                            .as_ref()
+                            .and_then(|source| self.resolve_module(module, source))
+                        {
+                            resolved.extend(self.resolve_export(target, name, visited));
+                        }
+                    }
+                }
+            }
+
+            Some(ParseLanguage::Py) => {
+                // Python modules export the top-level bindings
+                let source = &self.files[module].source;
+                resolved.extend(
+                    prompts
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, prompt)| {
+                            prompt.binding.name == name
+                                && prompt.binding.kind != PromptBindingKind::Argument
+                                && is_line_start(source, prompt.binding.span.0)
+                        })
+                        .map(|(index, _)| (module, index)),
+                );
+
+                // Imported names are exported too, e.g., `from .prompts import
+                // SYSTEM` in `__init__.py`
+                if resolved.is_empty() {
+                    for import in imports
+                        .iter()
+                        .filter(|import| import.local == name || import.name == "*")
+                    {
+                        if let Some(target) = self.resolve_module(module, &import.source) {
+                            let imported = if import.name == "*" {
+                                name
+                            } else {
+                                &import.name
+                            };
+                            resolved.extend(self.resolve_export(target, imported, visited));
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        resolved
+    }
+
+    /// Get all the module prompts that can be imported.
+    fn module_prompts(&self, module: usize) -> Vec<(usize, usize)> {
+        let (prompts, _, exports) = result_parts(&self.results[module]);
+        let source = &self.files[module].source;
+        let is_py = matches!(
+            result_language(&self.results[module]),
+            Some(ParseLanguage::Py)
+        );
+        prompts
+            .iter()
+            .enumerate()
+            .filter(|(_, prompt)| {
+                if is_py {
+                    is_line_start(source, prompt.binding.span.0)
+                } else {
+                    exports.iter().any(|export| is_exported(prompt, export))
+                }
+            })
+            .map(|(index, _)| (module, index))
+            .collect()
+    }
+}
+
+/// Get the parse result language, resolved by the parser from the file name.
+fn result_language(result: &ParseResult) -> Option<&ParseLanguage> {
+    match result {
+        ParseResult::ParseResultSuccess(result) => Some(&result.language),
+        ParseResult::ParseResultPartial(result) => Some(&result.language),
+        ParseResult::ParseResultError(result) => result.language.as_ref(),
+    }
+}
+
+/// Get the prompts, the imports and the exports of the parse result.
+fn result_parts(result: &ParseResult) -> (&[Prompt], &[ParseImport], &[ParseExport]) {
+    match result {
+        ParseResult::ParseResultSuccess(result) => (
+            &result.prompts,
+            result.imports.as_deref().unwrap_or_default(),
+            result.exports.as_deref().unwrap_or_default(),
+        ),
+        ParseResult::ParseResultPartial(result) => (
+            &result.prompts,
+            result.imports.as_deref().unwrap_or_default(),
+            result.exports.as_deref().unwrap_or_default(),
+        ),
+        ParseResult::ParseResultError(_) => (&[], &[], &[]),
+    }
+}
+
+/// Check if the local export points to the prompt, either by the binding,
+/// e.g., `export const SYSTEM = "..."`, or by a reference, e.g.,
+/// `export { SYSTEM }`.
+fn is_exported(prompt: &Prompt, export: &ParseExport) -> bool {
+    prompt.binding.kind != PromptBindingKind::Argument
+        && (prompt.binding.span == export.span
+            || prompt
+                .references
+                .iter()
+                .flatten()
+                .any(|reference| reference.span == export.span))
+}
+
+/// Check if the position starts a line, i.e., the binding is at the top
+/// level of a Python module.
+fn is_line_start(source: &str, position: u32) -> bool {
+    position == 0 || source.as_bytes().get(position as usize - 1) == Some(&b'\n')
+}
+
+/// Normalize the path separators and the `.` and `..` segments, e.g.,
+/// `src/./app/../prompts.ts` to `src/prompts.ts`.
+fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if matches!(segments.last(), None | Some(&"..")) {
+                    segments.push("..");
+                } else {
+                    segments.pop();
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Get the parent directory path, e.g., `src` for `src/prompts.ts`.
+fn parent_path(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn file(path: &str, source: &str) -> ProjectFile {
+        ProjectFile {
+            path: path.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    fn prompt(file: &str, name: &str, binding: Span) -> Prompt {
+        Prompt {
+            file: file.to_string(),
+            enclosure: binding,
+            span: SpanShape {
+                outer: binding,
+                inner: binding,
+            },
+            content: Vec::new(),
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            vars: Vec::new(),
+            annotations: Vec::new(),
+            binding: PromptBinding {
+                name: name.to_string(),
+                span: binding,
+                kind: PromptBindingKind::Local,
+            },
+            role: None,
+            references: None,
+        }
+    }
+
+    fn import(source: &str, name: &str, span: Span) -> ParseImport {
+        ParseImport {
+            source: source.to_string(),
+            name: name.to_string(),
+            local: name.to_string(),
+            span,
+        }
+    }
+
+    fn export(name: &str, span: Span, source: Option<&str>) -> ParseExport {
+        ParseExport {
+            name: name.to_string(),
+            local: name.to_string(),
+            span,
+            source: source.map(str::to_string),
+        }
+    }
+
+    fn result(
+        language: ParseLanguage,
+        prompts: Vec<Prompt>,
+        imports: Vec<ParseImport>,
+        exports: Vec<ParseExport>,
+    ) -> ParseResult {
+        ParseResult::ParseResultSuccess(ParseResultSuccess {
+            state: ParseResultSuccessStateSuccess,
+            language,
+            dialect: None,
+            detection: None,
+            parser: "test".to_string(),
+            prompts,
+            groups: None,
+            imports: (!imports.is_empty()).then_some(imports),
+            exports: (!exports.is_empty()).then_some(exports),
+        })
+    }
+
+    fn link(file: u32, span: Span, prompt: (u32, u32)) -> ProjectLink {
+        ProjectLink {
+            file,
+            span,
+            prompt: ProjectPrompt {
+                file: prompt.0,
+                prompt: prompt.1,
+            },
+        }
+    }
+
+    #[test]
+    fn ts() {
+        let files = vec![
+            file(
+                "src/prompts.ts",
+                "export const SYSTEM = \"You are a bot.\";\nexport const UNUSED = \"Hi!\";\n",
+            ),
+            file("src/app.ts", "import { SYSTEM } from \"./prompts.js\";\n"),
+        ];
+        let results = vec![
+            result(
+                ParseLanguage::Ts,
+                vec![
+                    prompt("src/prompts.ts", "SYSTEM", (13, 19)),
+                    prompt("src/prompts.ts", "UNUSED", (53, 59)),
+                ],
+                Vec::new(),
+                vec![
+                    export("SYSTEM", (13, 19), None),
+                    export("UNUSED", (53, 59), None),
+                ],
+            ),
+            result(
+                ParseLanguage::Ts,
+                Vec::new(),
+                vec![import("./prompts.js", "SYSTEM", (9, 15))],
+                Vec::new(),
+            ),
+        ];
+        let project = analyze_project(&files, results);
+        assert_eq!(project.links, vec![link(1, (9, 15), (0, 0))]);
+        assert_eq!(project.unused, vec![ProjectPrompt { file: 0, prompt: 1 }]);
+    }
+
+    #[test]
+    fn ts_reexports() {
+        let files = vec![
+            file(
+                "prompts/system.ts",
+                "export const SYSTEM = \"You are a bot.\";\n",
+            ),
+            file("prompts/user.ts", "export const USER = \"Hi!\";\n"),
+            file(
+                "prompts/index.ts",
+                "export * from \"./system\";\nexport * from \"./user\";\n",
+            ),
+            file(
+                "app.ts",
+                "import { SYSTEM } from \"./prompts\";\nimport * as user from \"./prompts/user\";\n",
+            ),
+        ];
+        let results = vec![
+            result(
+                ParseLanguage::Ts,
+                vec![prompt("prompts/system.ts", "SYSTEM", (13, 19))],
+                Vec::new(),
+                vec![export("SYSTEM", (13, 19), None)],
+            ),
+            result(
+                ParseLanguage::Ts,
+                vec![prompt("prompts/user.ts", "USER", (13, 17))],
+                Vec::new(),
+                vec![export("USER", (13, 17), None)],
+            ),
+            result(
+                ParseLanguage::Ts,
+                Vec::new(),
+                Vec::new(),
+                vec![
+                    export("*", (0, 25), Some("./system")),
+                    export("*", (26, 49), Some("./user")),
+                ],
+            ),
+            result(
+                ParseLanguage::Ts,
+                Vec::new(),
+                vec![
+                    import("./prompts", "SYSTEM", (9, 15)),
+                    import("./prompts/user", "*", (43, 49)),
+                ],
+                Vec::new(),
+            ),
+        ];
+        let project = analyze_project(&files, results);
+        assert_eq!(project.links, vec![link(3, (9, 15), (0, 0))]);
+        assert_eq!(project.unused, Vec::new());
+    }
+
+    #[test]
+    fn py() {
+        let files = vec![
+            file(
+                "app/prompts.py",
+                "SYSTEM = \"You are a bot.\"\nUNUSED = \"Hi!\"\n",
+            ),
+            file("app/__init__.py", "from .prompts import SYSTEM\n"),
+            file("app/chat/main.py", "from .. import SYSTEM\n"),
+        ];
+        let results = vec![
+            result(
+                ParseLanguage::Py,
+                vec![
+                    prompt("app/prompts.py", "SYSTEM", (0, 6)),
+                    prompt("app/prompts.py", "UNUSED", (26, 32)),
+                ],
+                Vec::new(),
+                Vec::new(),
+            ),
+            result(
+                ParseLanguage::Py,
+                Vec::new(),
+                vec![import(".prompts", "SYSTEM", (21, 27))],
+                Vec::new(),
+            ),
+            result(
+                ParseLanguage::Py,
+                Vec::new(),
+                vec![import("..", "SYSTEM", (15, 21))],
+                Vec::new(),
+            ),
+        ];
+        let project = analyze_project(&files, results);
+        assert_eq!(
+            project.links,
+            vec![link(1, (21, 27), (0, 0)), link(2, (15, 21), (0, 0))]
+        );
+        assert_eq!(project.unused, vec![ProjectPrompt { file: 0, prompt: 1 }]);
+    }
+
+    #[test]
+    fn references() {
+        // const used = "a"; const exported = "b"; export { exported }; send(used);
+        let files = vec![file("prompts.ts", "")];
+        let mut used = prompt("prompts.ts", "used", (6, 10));
+        used.references = Some(vec![PromptReference {
+            span: (68, 72),
+            kind: PromptReferenceKind::Argument,
+        }]);
+        let mut exported = prompt("prompts.ts", "exported", (24, 32));
+        exported.references = Some(vec![PromptReference {
+            span: (50, 58),
+            kind: PromptReferenceKind::Read,
+        }]);
+        let results = vec![result(
+            ParseLanguage::Ts,
+            vec![used, exported],
+            Vec::new(),
+            vec![export("exported", (50, 58), None)],
+        )];
+        let project = analyze_project(&files, results);
+        assert_eq!(project.unused, vec![ProjectPrompt { file: 0, prompt: 1 }]);
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_path("src/./app/../prompts.ts"), "src/prompts.ts");
+        assert_eq!(normalize_path("src\\prompts.ts"), "src/prompts.ts");
+        assert_eq!(normalize_path("../prompts.ts"), "../prompts.ts");
+    }
+}
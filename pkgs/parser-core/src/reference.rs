@@ -0,0 +1,337 @@
+use std::collections::{HashMap, HashSet};
+use volumen_types::*;
+
+/// Prompt references resolver. After detecting prompts, parsers walk the
+/// syntax tree entering and exiting scopes and passing the identifiers, and
+/// the resolver attaches each identifier to the prompts bound to the visible
+/// binding with the same name, e.g., `send(userPrompt)` to the prompt from
+/// `const userPrompt = "Hi!";`.
+pub struct PromptReferences {
+    /// Prompt binding span -> name and the position the binding becomes
+    /// visible at, i.e., the prompt enclosure end.
+    bindings: HashMap<Span, (String, u32)>,
+    /// Stack of visible binding names -> binding spans (one per scope).
+    /// Names shadowed by other variables, e.g., parameters, are `None`.
+    scopes: Vec<HashMap<String, Option<Span>>>,
+    /// Bindings waiting to become visible: scope depth, name, binding span
+    /// and visibility position.
+    pending: Vec<(usize, String, Span, u32)>,
+    /// Binding span -> references in order of appearance.
+    references: HashMap<Span, Vec<PromptReference>>,
+    /// Identifier spans already resolved.
+    visited: HashSet<Span>,
+}
+
+impl PromptReferences {
+    /// Creates the resolver for the detected prompts. Prompts bound to call
+    /// arguments or functions have no variable to reference and are skipped.
+    pub fn new(prompts: &[Prompt]) -> Self {
+        let bindings = prompts
+            .iter()
+            .filter(|prompt| {
+                !matches!(
+                    prompt.binding.kind,
+                    PromptBindingKind::Argument | PromptBindingKind::Function
+                )
+            })
+            .map(|prompt| {
+                (
+                    prompt.binding.span,
+                    (prompt.binding.name.clone(), prompt.enclosure.1),
+                )
+            })
+            .collect();
+        Self {
+            bindings,
+            scopes: vec![HashMap::new()],
+            pending: Vec::new(),
+            references: HashMap::new(),
+            visited: HashSet::new(),
+        }
+    }
+
+    /// Enter a new scope (e.g., function, class, module).
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Exit the current scope, dropping its bindings.
+    pub fn exit_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+            let depth = self.scopes.len();
+            self.pending.retain(|(scope, ..)| *scope < depth);
+        }
+    }
+
+    /// Shadow the outer bindings with the name in the current scope, e.g.,
+    /// for a function parameter.
+    pub fn shadow(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), None);
+        }
+    }
+
+    /// Resolve the variable declaration, e.g., `userPrompt` for
+    /// `let userPrompt = "Hi!";`. Prompt bindings become visible in the
+    /// current scope after the prompt enclosure, while other declarations
+    /// shadow the outer bindings with the name.
+    pub fn declaration(&mut self, name: &str, span: Span) {
+        if !self.visited.insert(span) {
+            return;
+        }
+        self.activate(span.0);
+        if !self.bind(name, span) {
+            self.shadow(name);
+        }
+    }
+
+    /// Resolve the assignment target in languages where assignments declare
+    /// the variables, e.g., Python. Names not assigned in the current scope
+    /// yet are declared, shadowing the outer bindings, e.g., in a function,
+    /// while the others are reassigned.
+    pub fn assignment(&mut self, name: &str, span: Span) {
+        self.activate(span.0);
+        let depth = self.scopes.len() - 1;
+        let declared = self
+            .scopes
+            .last()
+            .is_some_and(|scope| scope.contains_key(name))
+            || self
+                .pending
+                .iter()
+                .any(|(scope, pending, ..)| *scope == depth && pending == name);
+        if declared {
+            self.identifier(name, span, PromptReferenceKind::Reassign);
+        } else {
+            self.declaration(name, span);
+        }
+    }
+
+    /// Resolve the identifier occurrence. Prompt binding identifiers become
+    /// visible in the current scope after the prompt enclosure and reassign
+    /// the previously visible binding with the same name. Other identifiers
+    /// reference the visible binding.
+    pub fn identifier(&mut self, name: &str, span: Span, kind: PromptReferenceKind) {
+        if !self.visited.insert(span) {
+            return;
+        }
+        self.activate(span.0);
+
+        let visible = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+            .flatten()
+            .filter(|binding| *binding != span);
+
+        let kind = if self.bind(name, span) {
+            PromptReferenceKind::Reassign
+        } else {
+            kind
+        };
+        if let Some(binding) = visible {
+            self.push(binding, span, kind);
+        }
+    }
+
+    /// Assign the resolved references to the prompts.
+    pub fn apply(self, prompts: &mut [Prompt]) {
+        for prompt in prompts {
+            if let Some(references) = self.references.get(&prompt.binding.span) {
+                prompt.references = Some(references.clone());
+            }
+        }
+    }
+
+    /// Bind the prompt binding identifier in the current scope once visible.
+    /// Returns `false` if the identifier isn't a prompt binding.
+    fn bind(&mut self, name: &str, span: Span) -> bool {
+        let Some((binding_name, visible_at)) = self.bindings.get(&span) else {
+            return false;
+        };
+        if binding_name != name {
+            return false;
+        }
+        let depth = self.scopes.len() - 1;
+        self.pending
+            .push((depth, binding_name.clone(), span, *visible_at));
+        true
+    }
+
+    /// Make the pending bindings visible at the position.
+    fn activate(&mut self, position: u32) {
+        let (visible, pending) = self
+            .pending
+            .drain(..)
+            .partition::<Vec<_>, _>(|(.., visible_at)| *visible_at <= position);
+        self.pending = pending;
+        for (depth, name, binding, _) in visible {
+            if let Some(scope) = self.scopes.get_mut(depth) {
+                scope.insert(name, Some(binding));
+            }
+        }
+    }
+
+    fn push(&mut self, binding: Span, span: Span, kind: PromptReferenceKind) {
+        self.references
+            .entry(binding)
+            .or_default()
+            .push(PromptReference { span, kind });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn prompt(name: &str, binding: Span, enclosure: Span) -> Prompt {
+        Prompt {
+            file: "prompts.js".to_string(),
+            enclosure,
+            span: SpanShape {
+                outer: enclosure,
+                inner: enclosure,
+            },
+            content: Vec::new(),
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            vars: Vec::new(),
+            annotations: Vec::new(),
+            binding: PromptBinding {
+                name: name.to_string(),
+                span: binding,
+                kind: PromptBindingKind::Local,
+            },
+            role: None,
+            references: None,
+        }
+    }
+
+    #[test]
+    fn references() {
+        // let prompt = "a"; send(prompt); prompt = "b"; prompt + "!";
+        let mut prompts = vec![
+            prompt("prompt", (4, 10), (0, 17)),
+            prompt("prompt", (32, 38), (32, 45)),
+        ];
+        let mut references = PromptReferences::new(&prompts);
+        references.identifier("prompt", (4, 10), PromptReferenceKind::Read);
+        references.identifier("send", (18, 22), PromptReferenceKind::Read);
+        references.identifier("prompt", (23, 29), PromptReferenceKind::Argument);
+        references.identifier("prompt", (32, 38), PromptReferenceKind::Reassign);
+        references.identifier("prompt", (46, 52), PromptReferenceKind::Read);
+        references.apply(&mut prompts);
+        assert_eq!(
+            prompts[0].references,
+            Some(vec![
+                PromptReference {
+                    span: (23, 29),
+                    kind: PromptReferenceKind::Argument,
+                },
+                PromptReference {
+                    span: (32, 38),
+                    kind: PromptReferenceKind::Reassign,
+                },
+            ])
+        );
+        assert_eq!(
+            prompts[1].references,
+            Some(vec![PromptReference {
+                span: (46, 52),
+                kind: PromptReferenceKind::Read,
+            }])
+        );
+    }
+
+    #[test]
+    fn scopes() {
+        // function f() { let prompt = "a"; } send(prompt);
+        let mut prompts = vec![prompt("prompt", (19, 25), (15, 32))];
+        let mut references = PromptReferences::new(&prompts);
+        references.enter_scope();
+        references.identifier("prompt", (19, 25), PromptReferenceKind::Read);
+        references.exit_scope();
+        references.identifier("prompt", (40, 46), PromptReferenceKind::Argument);
+        references.apply(&mut prompts);
+        assert_eq!(prompts[0].references, None);
+    }
+
+    #[test]
+    fn declaration() {
+        // let prompt = "a"; function f() { let prompt = "b"; send(prompt); }
+        let mut prompts = vec![
+            prompt("prompt", (4, 10), (0, 17)),
+            prompt("prompt", (37, 43), (33, 50)),
+        ];
+        let mut references = PromptReferences::new(&prompts);
+        references.declaration("prompt", (4, 10));
+        references.enter_scope();
+        references.declaration("prompt", (37, 43));
+        references.identifier("prompt", (56, 62), PromptReferenceKind::Argument);
+        references.exit_scope();
+        references.apply(&mut prompts);
+        assert_eq!(prompts[0].references, None);
+        assert_eq!(
+            prompts[1].references,
+            Some(vec![PromptReference {
+                span: (56, 62),
+                kind: PromptReferenceKind::Argument,
+            }])
+        );
+    }
+
+    #[test]
+    fn assignment() {
+        // prompt = "a"
+        // def f():
+        //     prompt = "b"
+        //     send(prompt)
+        // prompt = "c"
+        let mut prompts = vec![
+            prompt("prompt", (0, 6), (0, 12)),
+            prompt("prompt", (29, 35), (29, 41)),
+            prompt("prompt", (59, 65), (59, 71)),
+        ];
+        let mut references = PromptReferences::new(&prompts);
+        references.assignment("prompt", (0, 6));
+        references.enter_scope();
+        references.assignment("prompt", (29, 35));
+        references.identifier("prompt", (51, 57), PromptReferenceKind::Argument);
+        references.exit_scope();
+        references.assignment("prompt", (59, 65));
+        references.apply(&mut prompts);
+        assert_eq!(
+            prompts[0].references,
+            Some(vec![PromptReference {
+                span: (59, 65),
+                kind: PromptReferenceKind::Reassign,
+            }])
+        );
+        assert_eq!(
+            prompts[1].references,
+            Some(vec![PromptReference {
+                span: (51, 57),
+                kind: PromptReferenceKind::Argument,
+            }])
+        );
+    }
+
+    #[test]
+    fn shadow() {
+        // let prompt = "a"; function f(prompt) { send(prompt); }
+        let mut prompts = vec![prompt("prompt", (4, 10), (0, 17))];
+        let mut references = PromptReferences::new(&prompts);
+        references.identifier("prompt", (4, 10), PromptReferenceKind::Read);
+        references.enter_scope();
+        references.shadow("prompt");
+        references.identifier("prompt", (44, 50), PromptReferenceKind::Argument);
+        references.exit_scope();
+        references.apply(&mut prompts);
+        assert_eq!(prompts[0].references, None);
+    }
+}
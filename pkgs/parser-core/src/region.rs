@@ -0,0 +1,162 @@
+use crate::PromptDetector;
+
+/// Marker that makes every string assignment in a source range a prompt.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptRegionMarker {
+    /// File-level marker, e.g., `// @prompts`, covers the whole file.
+    File,
+    /// Region start marker, e.g., `// @prompt-begin`.
+    Begin,
+    /// Region end marker, e.g., `// @prompt-end`.
+    End,
+}
+
+/// Source ranges marked with file-level and region markers. Parsers treat
+/// statements that start inside the ranges as annotated prompts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptRegions {
+    /// Byte offset ranges sorted by start.
+    ranges: Vec<(u32, u32)>,
+}
+
+impl PromptRegions {
+    /// Collects the regions from `(start, end, text)` comments sorted by start.
+    /// A region without the end marker spans to the end of the source, and
+    /// nested start markers are ignored. The file-level marker is only
+    /// honored in the leading comments, before the first code.
+    pub fn new<'a>(
+        source: &str,
+        comments: impl IntoIterator<Item = (u32, u32, &'a str)>,
+        detector: &PromptDetector,
+    ) -> Self {
+        let source_end = source.len() as u32;
+        let mut ranges = Vec::new();
+        let mut open: Option<u32> = None;
+        let mut leading_end = Some(0);
+
+        for (start, end, text) in comments {
+            leading_end = leading_end
+                .filter(|leading_end| {
+                    source
+                        .get(*leading_end as usize..start as usize)
+                        .is_some_and(is_leading_gap)
+                })
+                .map(|_| end);
+
+            match detector.region_marker(text) {
+                Some(PromptRegionMarker::File) if leading_end.is_some() => {
+                    return Self {
+                        ranges: vec![(0, source_end)],
+                    };
+                }
+                Some(PromptRegionMarker::File) => {}
+                Some(PromptRegionMarker::Begin) => {
+                    open.get_or_insert(end);
+                }
+                Some(PromptRegionMarker::End) => {
+                    if let Some(region_start) = open.take() {
+                        ranges.push((region_start, start));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if let Some(region_start) = open {
+            ranges.push((region_start, source_end));
+        }
+
+        Self { ranges }
+    }
+
+    /// Checks if the offset is inside any of the regions.
+    pub fn contains(&self, offset: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|(start, end)| offset >= *start && offset < *end)
+    }
+}
+
+/// Checks if the text between the leading comments has no code. Only the
+/// shebang and the PHP opening tag are allowed, e.g., `#!/usr/bin/env node`
+/// or `<?php`.
+fn is_leading_gap(gap: &str) -> bool {
+    gap.trim_start_matches('\u{feff}').lines().all(|line| {
+        let line = line.trim();
+        line.is_empty() || line.starts_with("#!") || line.eq_ignore_ascii_case("<?php")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn regions(source: &str) -> PromptRegions {
+        let mut comments = Vec::new();
+        let mut offset = 0;
+        for line in source.split_inclusive('\n') {
+            let text = line.trim_end();
+            if text.starts_with("//") {
+                comments.push((offset, offset + text.len() as u32, text));
+            }
+            offset += line.len() as u32;
+        }
+        PromptRegions::new(source, comments, &PromptDetector::default())
+    }
+
+    #[test]
+    fn region() {
+        let source = "x\n// @prompt-begin\ny\n// @prompt-end\nz\n";
+        let regions = regions(source);
+        assert!(!regions.contains(source.find('x').unwrap() as u32));
+        assert!(regions.contains(source.find('y').unwrap() as u32));
+        assert!(!regions.contains(source.find('z').unwrap() as u32));
+    }
+
+    #[test]
+    fn unclosed_region() {
+        let source = "x\n// @prompt-begin\ny\nz\n";
+        let regions = regions(source);
+        assert!(!regions.contains(source.find('x').unwrap() as u32));
+        assert!(regions.contains(source.find('z').unwrap() as u32));
+    }
+
+    #[test]
+    fn file() {
+        let source = "// @prompts\na\nb\n";
+        assert_eq!(
+            regions(source),
+            PromptRegions {
+                ranges: vec![(0, source.len() as u32)]
+            }
+        );
+    }
+
+    #[test]
+    fn file_leading() {
+        let source = "#!/usr/bin/env node\n// Copyright\n\n// @prompts\na\n";
+        assert_eq!(
+            regions(source),
+            PromptRegions {
+                ranges: vec![(0, source.len() as u32)]
+            }
+        );
+    }
+
+    #[test]
+    fn file_after_code() {
+        assert_eq!(regions("x\n// @prompts\ny\n"), PromptRegions::default());
+
+        let source = "// License\nx\n// @prompt-begin\n// @prompts\ny\n";
+        let regions = regions(source);
+        assert!(!regions.contains(source.find('x').unwrap() as u32));
+        assert!(regions.contains(source.find('y').unwrap() as u32));
+    }
+
+    #[test]
+    fn none() {
+        let source = "// @prompt\na\n// @prompt-end\nb\n";
+        assert_eq!(regions(source), PromptRegions::default());
+    }
+}
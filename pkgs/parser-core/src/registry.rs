@@ -0,0 +1,587 @@
+use crate::{VolumenParser, analyze_project, error_diagnostic, file_language, source_language};
+use volumen_types::*;
+
+/// Registry mapping the files to the language parsers. The files are matched
+/// by the registered exact file names, e.g., `Rakefile`, then by the globs,
+/// e.g., `**/*.jsm`, then by the extensions, e.g., `es6`, falling back to
+/// the built-in languages resolved by `file_language`. Later registrations
+/// take precedence, so they override the earlier ones. The parsers are
+/// created on the first use and reused for the following files.
+#[derive(Clone, Default)]
+pub struct ParserRegistry {
+    parsers: Vec<(ParseLanguage, ParserSlot)>,
+    files: Vec<(FilePattern, (ParseLanguage, Option<ParseDialect>))>,
+}
+
+#[derive(Clone)]
+enum FilePattern {
+    /// Exact file name, e.g., `Rakefile`.
+    Filename(String),
+    /// Glob matched against the file name or the path if it has slashes,
+    /// e.g., `*.jsm` or `views/**/*.html`.
+    Glob(String),
+    /// Case-insensitive file extension without the dot, e.g., `es6`.
+    Extension(String),
+}
+
+/// Language parser factory with the lazily created parser instance.
+struct ParserSlot {
+    create: fn() -> Box<dyn VolumenParser>,
+    parser: Option<Box<dyn VolumenParser>>,
+}
+
+impl Clone for ParserSlot {
+    /// The parser instances hold the internal state caches, so the clones
+    /// create their own parsers.
+    fn clone(&self) -> Self {
+        Self {
+            create: self.create,
+            parser: None,
+        }
+    }
+}
+
+impl ParserRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the language parser, replacing the previously registered
+    /// one, e.g., to use the Tree-sitter Python parser instead of Ruff.
+    pub fn parser<P: VolumenParser + Default + 'static>(
+        &mut self,
+        language: ParseLanguage,
+    ) -> &mut Self {
+        let slot = ParserSlot {
+            create: || Box::new(P::default()),
+            parser: None,
+        };
+        match self.parsers.iter_mut().find(|(lang, _)| *lang == language) {
+            Some(entry) => entry.1 = slot,
+            None => self.parsers.push((language, slot)),
+        }
+        self
+    }
+
+    /// Maps the file extension, e.g., `es6` or `.es6`, to the language.
+    pub fn extension(
+        &mut self,
+        extension: &str,
+        language: ParseLanguage,
+        dialect: Option<ParseDialect>,
+    ) -> &mut Self {
+        let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+        self.files
+            .push((FilePattern::Extension(extension), (language, dialect)));
+        self
+    }
+
+    /// Maps the exact file name, e.g., `Rakefile`, to the language.
+    pub fn filename(
+        &mut self,
+        filename: &str,
+        language: ParseLanguage,
+        dialect: Option<ParseDialect>,
+    ) -> &mut Self {
+        self.files.push((
+            FilePattern::Filename(filename.to_string()),
+            (language, dialect),
+        ));
+        self
+    }
+
+    /// Maps the glob, e.g., `*.jsm` or `views/**/*.html`, to the language.
+    /// `*` and `?` don't match slashes, while `**` matches any path. Globs
+    /// without slashes match the file name only.
+    pub fn glob(
+        &mut self,
+        pattern: &str,
+        language: ParseLanguage,
+        dialect: Option<ParseDialect>,
+    ) -> &mut Self {
+        self.files
+            .push((FilePattern::Glob(pattern.to_string()), (language, dialect)));
+        self
+    }
+
+    /// Resolves the file language and dialect. Returns `None` if the file
+    /// type isn't supported.
+    pub fn language(&self, filename: &str) -> Option<(ParseLanguage, Option<ParseDialect>)> {
+        let path = filename.replace('\\', "/");
+        let basename = path.rsplit('/').next().unwrap_or(&path);
+        let lower = basename.to_ascii_lowercase();
+
+        let find = |matches: &dyn Fn(&FilePattern) -> bool| {
+            self.files
+                .iter()
+                .rev()
+                .find(|(pattern, _)| matches(pattern))
+                .map(|(_, language)| language.clone())
+        };
+        find(&|pattern| matches!(pattern, FilePattern::Filename(name) if name == basename))
+            .or_else(|| {
+                find(&|pattern| match pattern {
+                    FilePattern::Glob(glob) if glob.contains('/') => {
+                        glob_match(glob.as_bytes(), path.as_bytes())
+                    }
+                    FilePattern::Glob(glob) => glob_match(glob.as_bytes(), basename.as_bytes()),
+                    _ => false,
+                })
+            })
+            .or_else(|| {
+                find(&|pattern| match pattern {
+                    FilePattern::Extension(extension) => lower
+                        .strip_suffix(extension.as_str())
+                        .is_some_and(|stem| stem.ends_with('.')),
+                    _ => false,
+                })
+            })
+            .or_else(|| file_language(filename))
+    }
+
+    /// Parses the source with the file language parser. The language of the
+    /// files without a known extension, e.g., `bin/setup`, is detected from
+    /// the content by `source_language`. Files with unsupported types or
+    /// languages without a registered parser produce an error result.
+    pub fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        let resolved = match self.language(filename) {
+            Some((language, dialect)) => Some((language, dialect, None)),
+            None => source_language(source)
+                .map(|(language, dialect, detection)| (language, dialect, Some(detection))),
+        };
+        let Some((language, dialect, detection)) = resolved else {
+            let error = format!("Unsupported file extension for file: {}", filename);
+            return ParseResult::ParseResultError(ParseResultError {
+                state: ParseResultErrorStateError,
+                diagnostics: vec![error_diagnostic(
+                    "volumen",
+                    "unsupported-file",
+                    error.clone(),
+                    (0, 0),
+                )],
+                error,
+                language: None,
+                dialect: None,
+                detection: None,
+                parser: None,
+            });
+        };
+        // The language parsers can be excluded from the build, e.g., with the
+        // `volumen_parser` cargo features
+        let Some((_, slot)) = self.parsers.iter_mut().find(|(lang, _)| *lang == language) else {
+            let error = format!("Language not enabled for file: {}", filename);
+            return ParseResult::ParseResultError(ParseResultError {
+                state: ParseResultErrorStateError,
+                diagnostics: vec![error_diagnostic(
+                    "volumen",
+                    "language-not-enabled",
+                    error.clone(),
+                    (0, 0),
+                )],
+                error,
+                language: Some(language),
+                dialect,
+                detection,
+                parser: None,
+            });
+        };
+
+        // The mapped and detected files, e.g., `App.es` or `bin/setup`, are
+        // parsed as the registry language and dialect. The results report
+        // them too, as the single language parsers ignore them
+        let parser = slot.parser.get_or_insert_with(slot.create);
+        let mut result =
+            parser.parse_as(source, filename, language.clone(), dialect.clone(), options);
+        match &mut result {
+            ParseResult::ParseResultSuccess(result) => {
+                result.language = language;
+                result.dialect = dialect;
+                result.detection = detection;
+            }
+            ParseResult::ParseResultPartial(result) => {
+                result.language = language;
+                result.dialect = dialect;
+                result.detection = detection;
+            }
+            ParseResult::ParseResultError(result) => {
+                result.language = Some(language);
+                result.dialect = dialect;
+                result.detection = detection;
+            }
+        }
+        result
+    }
+
+    /// Parse the project files and link the prompts imported across them,
+    /// e.g., `SYSTEM` in `import { SYSTEM } from "./prompts"`.
+    pub fn parse_project(
+        &mut self,
+        files: &[ProjectFile],
+        options: &ParseOptions,
+    ) -> ProjectResult {
+        let results = files
+            .iter()
+            .map(|file| self.parse(&file.source, &file.path, options))
+            .collect();
+        analyze_project(files, results)
+    }
+}
+
+/// Matches the text against the glob pattern, where `*` and `?` don't match
+/// slashes and `**` matches any path.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    // The results are memoized by the pattern and text positions, so the
+    // patterns with many wildcards, e.g., `**/*a*a*a*b`, don't backtrack
+    // exponentially
+    let mut memo = vec![None; (pattern.len() + 1) * (text.len() + 1)];
+    glob_match_at(pattern, text, 0, 0, &mut memo)
+}
+
+fn glob_match_at(
+    pattern: &[u8],
+    text: &[u8],
+    pattern_index: usize,
+    text_index: usize,
+    memo: &mut [Option<bool>],
+) -> bool {
+    let key = pattern_index * (text.len() + 1) + text_index;
+    if let Some(matched) = memo[key] {
+        return matched;
+    }
+
+    let next = text.get(text_index).copied();
+    let mut match_at =
+        |pattern_index, text_index| glob_match_at(pattern, text, pattern_index, text_index, memo);
+    let matched = match &pattern[pattern_index..] {
+        [] => next.is_none(),
+        // `**/` matches no directories too, e.g., `**/*.rb` matches `app.rb`
+        [b'*', b'*', b'/', ..] => {
+            match_at(pattern_index + 3, text_index)
+                || (text_index..=text.len()).any(|index| match_at(pattern_index + 2, index))
+        }
+        [b'*', b'*', ..] => {
+            match_at(pattern_index + 2, text_index)
+                || (next.is_some() && match_at(pattern_index, text_index + 1))
+        }
+        [b'*', ..] => {
+            match_at(pattern_index + 1, text_index)
+                || (next.is_some_and(|byte| byte != b'/')
+                    && match_at(pattern_index, text_index + 1))
+        }
+        [b'?', ..] => {
+            next.is_some_and(|byte| byte != b'/') && match_at(pattern_index + 1, text_index + 1)
+        }
+        [byte, ..] => next == Some(*byte) && match_at(pattern_index + 1, text_index + 1),
+    };
+    memo[key] = Some(matched);
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct ParserStub {
+        name: &'static str,
+        parses: usize,
+    }
+
+    impl Default for ParserStub {
+        fn default() -> Self {
+            Self {
+                name: "stub",
+                parses: 0,
+            }
+        }
+    }
+
+    impl VolumenParser for ParserStub {
+        fn parse(
+            &mut self,
+            _source: &str,
+            _filename: &str,
+            _options: &ParseOptions,
+        ) -> ParseResult {
+            self.parses += 1;
+            ParseResult::ParseResultSuccess(ParseResultSuccess {
+                state: ParseResultSuccessStateSuccess,
+                language: ParseLanguage::Ts,
+                dialect: None,
+                detection: None,
+                parser: format!("{}{}", self.name, self.parses),
+                prompts: vec![],
+                groups: None,
+                imports: None,
+                exports: None,
+            })
+        }
+    }
+
+    struct ParserOther(ParserStub);
+
+    impl Default for ParserOther {
+        fn default() -> Self {
+            Self(ParserStub {
+                name: "other",
+                parses: 0,
+            })
+        }
+    }
+
+    impl VolumenParser for ParserOther {
+        fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+            self.0.parse(source, filename, options)
+        }
+    }
+
+    /// Reports the language and dialect it parses the source as in the
+    /// parser name.
+    #[derive(Default)]
+    struct ParserAs;
+
+    impl VolumenParser for ParserAs {
+        fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+            let (language, dialect) = file_language(filename).unwrap_or((ParseLanguage::Ts, None));
+            self.parse_as(source, filename, language, dialect, options)
+        }
+
+        fn parse_as(
+            &mut self,
+            _source: &str,
+            _filename: &str,
+            language: ParseLanguage,
+            dialect: Option<ParseDialect>,
+            _options: &ParseOptions,
+        ) -> ParseResult {
+            ParseResult::ParseResultSuccess(ParseResultSuccess {
+                state: ParseResultSuccessStateSuccess,
+                language: language.clone(),
+                dialect: dialect.clone(),
+                detection: None,
+                parser: format!("{language:?} {dialect:?}"),
+                prompts: vec![],
+                groups: None,
+                imports: None,
+                exports: None,
+            })
+        }
+    }
+
+    fn parser_name(result: &ParseResult) -> Option<&str> {
+        match result {
+            ParseResult::ParseResultSuccess(result) => Some(&result.parser),
+            ParseResult::ParseResultPartial(result) => Some(&result.parser),
+            ParseResult::ParseResultError(result) => result.parser.as_deref(),
+        }
+    }
+
+    #[test]
+    fn languages() {
+        let registry = ParserRegistry::new();
+        assert_eq!(
+            registry.language("src/prompts.ts"),
+            Some((ParseLanguage::Ts, None))
+        );
+        assert_eq!(
+            registry.language("Rakefile"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(registry.language("prompts.txt"), None);
+    }
+
+    #[test]
+    fn overrides() {
+        let mut registry = ParserRegistry::new();
+        registry
+            .extension(".prompt", ParseLanguage::Js, None)
+            .extension("TXT", ParseLanguage::Py, None)
+            .extension("ts", ParseLanguage::Js, Some(ParseDialect::Jsx))
+            .filename("Dangerfile", ParseLanguage::Rb, None)
+            .glob("*.py.txt", ParseLanguage::Rb, None)
+            .glob("templates/**/*.html", ParseLanguage::Php, None);
+        assert_eq!(
+            registry.language("hello.prompt"),
+            Some((ParseLanguage::Js, None))
+        );
+        assert_eq!(
+            registry.language("notes.txt"),
+            Some((ParseLanguage::Py, None))
+        );
+        assert_eq!(
+            registry.language("App.TS"),
+            Some((ParseLanguage::Js, Some(ParseDialect::Jsx)))
+        );
+        assert_eq!(
+            registry.language("lib/Dangerfile"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(registry.language("dangerfile"), None);
+        assert_eq!(
+            registry.language("app.py.txt"),
+            Some((ParseLanguage::Rb, None))
+        );
+        assert_eq!(
+            registry.language("templates/index.html"),
+            Some((ParseLanguage::Php, None))
+        );
+        assert_eq!(
+            registry.language("templates\\users\\show.html"),
+            Some((ParseLanguage::Php, None))
+        );
+        assert_eq!(registry.language("public/index.html"), None);
+
+        registry.extension("txt", ParseLanguage::Go, None);
+        assert_eq!(
+            registry.language("notes.txt"),
+            Some((ParseLanguage::Go, None))
+        );
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match(b"*.rb", b"app.rb"));
+        assert!(!glob_match(b"*.rb", b"lib/app.rb"));
+        assert!(glob_match(b"**/*.rb", b"app.rb"));
+        assert!(glob_match(b"**/*.rb", b"lib/tasks/app.rb"));
+        assert!(glob_match(b"lib/**", b"lib/tasks/app.rb"));
+        assert!(glob_match(b"app.?s", b"app.js"));
+        assert!(!glob_match(b"app.?s", b"app.jsx"));
+        assert!(!glob_match(b"lib/*.rb", b"lib/tasks/app.rb"));
+    }
+
+    #[test]
+    fn globs_pathological() {
+        let path = format!("{}/{}", "a/".repeat(50), "a".repeat(200));
+        assert!(!glob_match(b"**/*a*a*a*a*a*a*a*a*b", path.as_bytes()));
+        assert!(!glob_match(b"**/**/**/**/**/*a*a*a*b", path.as_bytes()));
+        assert!(glob_match(b"**/*a*a*a*a*a*a*a*a", path.as_bytes()));
+    }
+
+    #[test]
+    fn parse() {
+        let mut registry = ParserRegistry::new();
+        registry
+            .parser::<ParserStub>(ParseLanguage::Ts)
+            .parser::<ParserStub>(ParseLanguage::Js)
+            .extension("es", ParseLanguage::Js, Some(ParseDialect::Jsx));
+        let options = ParseOptions::default();
+
+        let result = registry.parse("", "prompts.ts", &options);
+        assert_eq!(parser_name(&result), Some("stub1"));
+        match registry.parse("", "prompts.es", &options) {
+            ParseResult::ParseResultSuccess(result) => {
+                assert_eq!(result.language, ParseLanguage::Js);
+                assert_eq!(result.dialect, Some(ParseDialect::Jsx));
+            }
+            result => panic!("Expected success, got {result:?}"),
+        }
+
+        registry.parser::<ParserOther>(ParseLanguage::Ts);
+        let result = registry.parse("", "prompts.ts", &options);
+        assert_eq!(parser_name(&result), Some("other1"));
+    }
+
+    #[test]
+    fn parse_as() {
+        let mut registry = ParserRegistry::new();
+        registry
+            .parser::<ParserAs>(ParseLanguage::Ts)
+            .parser::<ParserAs>(ParseLanguage::Js)
+            .extension("es", ParseLanguage::Js, Some(ParseDialect::Jsx))
+            .glob("*.tsx.txt", ParseLanguage::Ts, Some(ParseDialect::Tsx));
+        let options = ParseOptions::default();
+
+        let result = registry.parse("", "App.es", &options);
+        assert_eq!(parser_name(&result), Some("Js Some(Jsx)"));
+        let result = registry.parse("", "App.tsx.txt", &options);
+        assert_eq!(parser_name(&result), Some("Ts Some(Tsx)"));
+        let result = registry.parse("", "App.ts", &options);
+        assert_eq!(parser_name(&result), Some("Ts None"));
+    }
+
+    #[test]
+    fn parse_as_detected() {
+        let mut registry = ParserRegistry::new();
+        registry
+            .parser::<ParserAs>(ParseLanguage::Ts)
+            .parser::<ParserAs>(ParseLanguage::Js);
+        let options = ParseOptions::default();
+
+        let result = registry.parse("// vim: filetype=typescriptreact\n", "bin/app", &options);
+        assert_eq!(parser_name(&result), Some("Ts Some(Tsx)"));
+        let result = registry.parse("#!/usr/bin/env node\n", "bin/cli", &options);
+        assert_eq!(parser_name(&result), Some("Js None"));
+    }
+
+    #[test]
+    fn parse_reused() {
+        let mut registry = ParserRegistry::new();
+        registry.parser::<ParserStub>(ParseLanguage::Ts);
+        let options = ParseOptions::default();
+
+        let result = registry.parse("", "a.ts", &options);
+        assert_eq!(parser_name(&result), Some("stub1"));
+        let result = registry.parse("", "b.ts", &options);
+        assert_eq!(parser_name(&result), Some("stub2"));
+
+        let mut clone = registry.clone();
+        let result = clone.parse("", "c.ts", &options);
+        assert_eq!(parser_name(&result), Some("stub1"));
+        let result = registry.parse("", "c.ts", &options);
+        assert_eq!(parser_name(&result), Some("stub3"));
+    }
+
+    #[test]
+    fn parse_detected() {
+        let mut registry = ParserRegistry::new();
+        registry.parser::<ParserStub>(ParseLanguage::Js);
+        let options = ParseOptions::default();
+
+        match registry.parse("#!/usr/bin/env node\n", "bin/cli", &options) {
+            ParseResult::ParseResultSuccess(result) => {
+                assert_eq!(result.language, ParseLanguage::Js);
+                assert_eq!(result.detection, Some(ParseDetection::Shebang));
+            }
+            result => panic!("Expected success, got {result:?}"),
+        }
+        match registry.parse("#!/usr/bin/env node\n", "bin/cli.js", &options) {
+            ParseResult::ParseResultSuccess(result) => assert_eq!(result.detection, None),
+            result => panic!("Expected success, got {result:?}"),
+        }
+        match registry.parse("#!/usr/bin/env ruby\n", "bin/setup", &options) {
+            ParseResult::ParseResultError(result) => {
+                assert_eq!(result.language, Some(ParseLanguage::Rb));
+                assert_eq!(result.detection, Some(ParseDetection::Shebang));
+            }
+            result => panic!("Expected error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_unsupported() {
+        let mut registry = ParserRegistry::new();
+        registry.parser::<ParserStub>(ParseLanguage::Ts);
+        let options = ParseOptions::default();
+
+        match registry.parse("", "prompts.txt", &options) {
+            ParseResult::ParseResultError(result) => {
+                assert_eq!(
+                    result.error,
+                    "Unsupported file extension for file: prompts.txt"
+                );
+                assert_eq!(result.diagnostics[0].code, "unsupported-file");
+                assert_eq!(result.language, None);
+            }
+            result => panic!("Expected error, got {result:?}"),
+        }
+        match registry.parse("", "prompts.py", &options) {
+            ParseResult::ParseResultError(result) => {
+                assert_eq!(result.error, "Language not enabled for file: prompts.py");
+                assert_eq!(result.diagnostics[0].code, "language-not-enabled");
+                assert_eq!(result.language, Some(ParseLanguage::Py));
+            }
+            result => panic!("Expected error, got {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::decode_text;
+use std::borrow::Cow;
+use volumen_types::{
+    Prompt, PromptContentToken, PromptRender, PromptRenderSegment, PromptRenderSegmentKind, Span,
+};
+
+/// Renders the prompt text from its content tokens. Variables are substituted
+/// with the values by their index, and joint tokens with the joint separator.
+/// Variables without a corresponding value are rendered as they appear in
+/// source code, e.g., `${name}`. String escape sequences are decoded.
+pub fn render<Value: AsRef<str>>(prompt: &Prompt, source: &str, values: &[Value]) -> PromptRender {
+    let mut text = String::new();
+    let mut map = Vec::with_capacity(prompt.content.len());
+
+    for token in &prompt.content {
+        let (chunk, span, kind) = match token {
+            PromptContentToken::PromptContentTokenStr(token) => (
+                Cow::Owned(decode_text(
+                    source,
+                    token.span,
+                    token.escapes.as_deref().unwrap_or_default(),
+                )),
+                token.span,
+                PromptRenderSegmentKind::Str,
+            ),
+
+            PromptContentToken::PromptContentTokenVar(token) => {
+                let chunk = values
+                    .get(token.index as usize)
+                    .map(AsRef::as_ref)
+                    .unwrap_or_else(|| source_text(source, token.span));
+                let chunk = Cow::Borrowed(chunk);
+                (chunk, token.span, PromptRenderSegmentKind::Var)
+            }
+
+            PromptContentToken::PromptContentTokenJoint(token) => {
+                let span = prompt.joint.inner;
+                (
+                    Cow::Owned(decode_text(
+                        source,
+                        span,
+                        token.escapes.as_deref().unwrap_or_default(),
+                    )),
+                    span,
+                    PromptRenderSegmentKind::Joint,
+                )
+            }
+        };
+
+        let start = text.len() as u32;
+        text.push_str(&chunk);
+        map.push(PromptRenderSegment {
+            span: (start, text.len() as u32),
+            source: span,
+            kind,
+        });
+    }
+
+    PromptRender { text, map }
+}
+
+fn source_text(source: &str, span: Span) -> &str {
+    source.get(span.0 as usize..span.1 as usize).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EscapeStyle, resolve_escapes};
+    use pretty_assertions::assert_eq;
+    use volumen_types::*;
+
+    fn prompt(content: Vec<PromptContentToken>, joint: Span) -> Prompt {
+        Prompt {
+            file: "prompts.ts".into(),
+            enclosure: (0, 0),
+            span: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            content,
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: joint,
+            },
+            vars: vec![],
+            annotations: vec![],
+            binding: PromptBinding {
+                name: "prompt".into(),
+                span: (0, 0),
+                kind: PromptBindingKind::Const,
+            },
+            role: None,
+            references: None,
+        }
+    }
+
+    fn str_token(span: Span) -> PromptContentToken {
+        PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
+            r#type: PromptContentTokenStrTypeStr,
+            span,
+            escapes: None,
+        })
+    }
+
+    fn var_token(span: Span, index: u32) -> PromptContentToken {
+        PromptContentToken::PromptContentTokenVar(PromptContentTokenVar {
+            r#type: PromptContentTokenVarTypeVar,
+            span,
+            index,
+        })
+    }
+
+    fn joint_token() -> PromptContentToken {
+        PromptContentToken::PromptContentTokenJoint(PromptContentTokenJoint {
+            r#type: PromptContentTokenJointTypeJoint,
+            escapes: None,
+        })
+    }
+
+    fn segment(span: Span, source: Span, kind: PromptRenderSegmentKind) -> PromptRenderSegment {
+        PromptRenderSegment { span, source, kind }
+    }
+
+    #[test]
+    fn vars() {
+        let source = "const prompt = `Hello, ${name}! How is ${city}?`;";
+        let prompt = prompt(
+            vec![
+                str_token((16, 23)),
+                var_token((23, 30), 0),
+                str_token((30, 39)),
+                var_token((39, 46), 1),
+                str_token((46, 47)),
+            ],
+            (0, 0),
+        );
+        assert_eq!(
+            render(&prompt, source, &["Sasha", "Tbilisi"]),
+            PromptRender {
+                text: "Hello, Sasha! How is Tbilisi?".into(),
+                map: vec![
+                    segment((0, 7), (16, 23), PromptRenderSegmentKind::Str),
+                    segment((7, 12), (23, 30), PromptRenderSegmentKind::Var),
+                    segment((12, 21), (30, 39), PromptRenderSegmentKind::Str),
+                    segment((21, 28), (39, 46), PromptRenderSegmentKind::Var),
+                    segment((28, 29), (46, 47), PromptRenderSegmentKind::Str),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn missing_values() {
+        let source = "const prompt = `Hello, ${name}!`;";
+        let prompt = prompt(
+            vec![
+                str_token((16, 23)),
+                var_token((23, 30), 0),
+                str_token((30, 31)),
+            ],
+            (0, 0),
+        );
+        let values: &[&str] = &[];
+        assert_eq!(render(&prompt, source, values).text, "Hello, ${name}!");
+    }
+
+    #[test]
+    fn joint() {
+        let source = r#"const prompt = ["Hello", name].join(", ");"#;
+        let prompt = prompt(
+            vec![str_token((17, 22)), joint_token(), var_token((25, 29), 0)],
+            (37, 39),
+        );
+        assert_eq!(
+            render(&prompt, source, &["world".to_string()]),
+            PromptRender {
+                text: "Hello, world".into(),
+                map: vec![
+                    segment((0, 5), (17, 22), PromptRenderSegmentKind::Str),
+                    segment((5, 7), (37, 39), PromptRenderSegmentKind::Joint),
+                    segment((7, 12), (25, 29), PromptRenderSegmentKind::Var),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let source = r#"const prompt = ["Hi,\tfriend", name].join("\n");"#;
+        let mut prompt = prompt(
+            vec![str_token((17, 28)), joint_token(), var_token((31, 35), 0)],
+            (43, 45),
+        );
+        resolve_escapes(std::slice::from_mut(&mut prompt), source, |_| {
+            EscapeStyle::Js
+        });
+        assert_eq!(
+            render(&prompt, source, &["Sasha"]),
+            PromptRender {
+                text: "Hi,\tfriend\nSasha".into(),
+                map: vec![
+                    segment((0, 10), (17, 28), PromptRenderSegmentKind::Str),
+                    segment((10, 11), (43, 45), PromptRenderSegmentKind::Joint),
+                    segment((11, 16), (31, 35), PromptRenderSegmentKind::Var),
+                ],
+            }
+        );
+    }
+}
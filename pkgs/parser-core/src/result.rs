@@ -0,0 +1,92 @@
+use volumen_types::*;
+
+/// Creates the parse result from the extracted prompts. Sources with syntax
+/// errors produce the partial result with the diagnostics.
+pub fn parse_result(
+    language: ParseLanguage,
+    dialect: Option<ParseDialect>,
+    parser: &str,
+    prompts: Vec<Prompt>,
+    groups: Vec<PromptGroup>,
+    imports: Vec<ParseImport>,
+    diagnostics: Vec<ParseDiagnostic>,
+) -> ParseResult {
+    let groups = (!groups.is_empty()).then_some(groups);
+    let imports = (!imports.is_empty()).then_some(imports);
+    if diagnostics.is_empty() {
+        ParseResult::ParseResultSuccess(ParseResultSuccess {
+            state: ParseResultSuccessStateSuccess,
+            language,
+            dialect,
+            detection: None,
+            parser: parser.to_string(),
+            prompts,
+            groups,
+            imports,
+            exports: None,
+        })
+    } else {
+        ParseResult::ParseResultPartial(ParseResultPartial {
+            state: ParseResultPartialStatePartial,
+            language,
+            dialect,
+            detection: None,
+            parser: parser.to_string(),
+            prompts,
+            groups,
+            imports,
+            exports: None,
+            diagnostics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_diagnostic;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn success() {
+        let result = parse_result(
+            ParseLanguage::Rb,
+            None,
+            "tree-sitter",
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+        let ParseResult::ParseResultSuccess(result) = result else {
+            panic!("Expected success result");
+        };
+        assert_eq!(result.parser, "tree-sitter");
+        assert_eq!(result.groups, None);
+        assert_eq!(result.imports, None);
+    }
+
+    #[test]
+    fn partial() {
+        let diagnostic = error_diagnostic(
+            "tree-sitter",
+            "syntax-error",
+            "Unexpected `)`".into(),
+            (0, 1),
+        );
+        let result = parse_result(
+            ParseLanguage::Py,
+            Some(ParseDialect::Pyi),
+            "tree-sitter",
+            vec![],
+            vec![],
+            vec![],
+            vec![diagnostic.clone()],
+        );
+        let ParseResult::ParseResultPartial(result) = result else {
+            panic!("Expected partial result");
+        };
+        assert_eq!(result.dialect, Some(ParseDialect::Pyi));
+        assert_eq!(result.diagnostics, vec![diagnostic]);
+    }
+}
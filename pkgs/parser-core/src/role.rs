@@ -0,0 +1,125 @@
+use volumen_types::PromptRole;
+
+/// Role names and aliases used by the common LLM SDKs.
+const ROLE_NAMES: &[(&str, PromptRole)] = &[
+    ("system", PromptRole::System),
+    ("developer", PromptRole::System),
+    ("user", PromptRole::User),
+    ("human", PromptRole::User),
+    ("assistant", PromptRole::Assistant),
+    ("model", PromptRole::Assistant),
+    ("ai", PromptRole::Assistant),
+    ("tool", PromptRole::Tool),
+    ("function", PromptRole::Tool),
+];
+
+/// Parses a chat message role name, e.g., `system` or `User`. Aliases such
+/// as `developer`, `human` or `model` resolve to the matching role.
+pub fn parse_role(name: &str) -> Option<PromptRole> {
+    let name = name.trim().trim_start_matches(':').to_lowercase();
+    ROLE_NAMES
+        .iter()
+        .find(|(role_name, _)| *role_name == name)
+        .map(|(_, role)| *role)
+}
+
+/// Parses a role constant reference, e.g., `openai.ChatMessageRoleSystem`,
+/// `ChatRole.User` or `Role::ASSISTANT`, by the role name that ends its last
+/// path segment.
+pub fn parse_role_constant(path: &str) -> Option<PromptRole> {
+    let segment = path
+        .rsplit(['.', ':', '>', '\\'])
+        .next()
+        .unwrap_or(path)
+        .to_lowercase();
+    ROLE_NAMES
+        .iter()
+        .filter(|(role_name, _)| role_name.len() > 2)
+        .find(|(role_name, _)| segment.ends_with(role_name))
+        .map(|(_, role)| *role)
+}
+
+/// Parses the role from the source text of a message role value, e.g.,
+/// `"system"`, `:user` or `ChatRole.Assistant`. String literals and symbols
+/// must name the role exactly, while constants are matched by the suffix.
+pub fn parse_role_value(text: &str) -> Option<PromptRole> {
+    let text = text.trim();
+    let unquoted = unquote(text);
+    if unquoted.len() != text.len() {
+        parse_role(unquoted)
+    } else {
+        parse_role_constant(text)
+    }
+}
+
+/// Checks if the source text of a message object key names the message role,
+/// e.g., `role`, `"role"`, `:role` or `Role`.
+pub fn is_role_key(key: &str) -> bool {
+    unquote(key.trim()).eq_ignore_ascii_case("role")
+}
+
+/// Checks if the source text of a message object key names the message
+/// content, e.g., `content`, `'content'` or `Content`.
+pub fn is_content_key(key: &str) -> bool {
+    unquote(key.trim()).eq_ignore_ascii_case("content")
+}
+
+/// Strips string quotes and symbol colons around a key or a value.
+fn unquote(text: &str) -> &str {
+    text.trim_matches(|c| matches!(c, '"' | '\'' | '`' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn role_names() {
+        assert_eq!(parse_role("system"), Some(PromptRole::System));
+        assert_eq!(parse_role("Developer"), Some(PromptRole::System));
+        assert_eq!(parse_role(":user"), Some(PromptRole::User));
+        assert_eq!(parse_role("model"), Some(PromptRole::Assistant));
+        assert_eq!(parse_role("function"), Some(PromptRole::Tool));
+        assert_eq!(parse_role("greeting"), None);
+        assert_eq!(parse_role("user-facing"), None);
+    }
+
+    #[test]
+    fn role_constants() {
+        assert_eq!(
+            parse_role_constant("openai.ChatMessageRoleSystem"),
+            Some(PromptRole::System)
+        );
+        assert_eq!(parse_role_constant("ChatRole.User"), Some(PromptRole::User));
+        assert_eq!(
+            parse_role_constant("Role::ASSISTANT"),
+            Some(PromptRole::Assistant)
+        );
+        assert_eq!(parse_role_constant("Roles.Default"), None);
+        assert_eq!(parse_role_constant("Chat.Mail"), None);
+    }
+
+    #[test]
+    fn role_values() {
+        assert_eq!(parse_role_value("\"system\""), Some(PromptRole::System));
+        assert_eq!(parse_role_value("'user'"), Some(PromptRole::User));
+        assert_eq!(parse_role_value(":assistant"), Some(PromptRole::Assistant));
+        assert_eq!(parse_role_value("Role.TOOL"), Some(PromptRole::Tool));
+        assert_eq!(parse_role_value("\"my_system\""), None);
+        assert_eq!(parse_role_value("role"), None);
+    }
+
+    #[test]
+    fn message_keys() {
+        assert!(is_role_key("role"));
+        assert!(is_role_key("\"role\""));
+        assert!(is_role_key(":role"));
+        assert!(is_role_key("role:"));
+        assert!(is_role_key("Role"));
+        assert!(!is_role_key("roles"));
+        assert!(is_content_key("'content'"));
+        assert!(is_content_key("Content"));
+        assert!(!is_content_key("contents"));
+    }
+}
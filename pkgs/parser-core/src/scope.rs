@@ -0,0 +1,380 @@
+use crate::{PromptDetector, PromptFunction};
+use std::collections::HashMap;
+use volumen_types::PromptAnnotation;
+
+/// Identifier definition in a scope.
+#[derive(Debug, Clone, Default)]
+struct ScopeDefinition {
+    /// Whether the identifier is a prompt (`Some(true)`) or opted out of
+    /// prompt detection (`Some(false)`). Other variables are `None`.
+    prompt: Option<bool>,
+    /// Annotations stored at definition time, preserved across
+    /// reassignments.
+    annotations: Option<Vec<PromptAnnotation>>,
+    /// Whether the identifier has a type annotation, e.g.,
+    /// `name: str = value`.
+    annotated: bool,
+}
+
+/// Tracks the identifier definitions across nested scopes (e.g., function,
+/// class, block). Each scope holds its own definitions, so declarations
+/// shadow the outer identifiers with the same name along with their prompt
+/// state and annotations.
+pub struct ScopeTracker {
+    /// Stack of identifier definitions (one per scope).
+    scopes: Vec<HashMap<String, ScopeDefinition>>,
+    /// Whether the current statement has an opt-out annotation.
+    statement_ignored: bool,
+    /// Stack of enclosing functions (one per function scope). Functions that
+    /// don't produce prompts are `None`.
+    functions_stack: Vec<Option<PromptFunction>>,
+    /// Stack of the function scope indices (one per function scope).
+    function_scopes: Vec<usize>,
+    /// Prompt detection rules used to match identifier names.
+    detector: PromptDetector,
+}
+
+impl ScopeTracker {
+    /// Create a new scope tracker with an initial global scope.
+    pub fn new(detector: PromptDetector) -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+            statement_ignored: false,
+            functions_stack: Vec::new(),
+            function_scopes: Vec::new(),
+            detector,
+        }
+    }
+
+    /// Get the prompt detection rules.
+    pub fn detector(&self) -> &PromptDetector {
+        &self.detector
+    }
+
+    /// Check if the identifier name matches the prompt name patterns and
+    /// the identifier isn't opted out of prompt detection.
+    pub fn is_prompt_name(&self, ident: &str) -> bool {
+        self.detector.is_prompt_name(ident) && !self.is_ignored_ident(ident)
+    }
+
+    /// Enter a new scope (e.g., function, class, block).
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Exit the current scope, dropping its definitions.
+    pub fn exit_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    /// Enter a function scope. Prompt functions return prompts, e.g.,
+    /// `build_prompt`, while other functions are `None`.
+    pub fn enter_function(&mut self, function: Option<PromptFunction>) {
+        self.enter_scope();
+        self.functions_stack.push(function);
+        self.function_scopes.push(self.scopes.len() - 1);
+    }
+
+    /// Exit the current function scope.
+    pub fn exit_function(&mut self) {
+        self.functions_stack.pop();
+        self.function_scopes.pop();
+        self.exit_scope();
+    }
+
+    /// Get the innermost enclosing function if it's a prompt function.
+    pub fn current_function(&self) -> Option<&PromptFunction> {
+        self.functions_stack.last()?.as_ref()
+    }
+
+    /// Declare the identifier in the current scope, e.g., for
+    /// `String prompt = ...;` or a function parameter. It shadows the outer
+    /// definitions with the name, while repeated declarations in the same
+    /// scope keep the definition, e.g., for Python assignments.
+    pub fn declare(&mut self, ident: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.entry(ident.to_string()).or_default();
+        }
+    }
+
+    /// Declare the identifier in the innermost function scope or the global
+    /// scope, e.g., for JavaScript `var` declarations hoisted out of blocks.
+    pub fn declare_hoisted(&mut self, ident: &str) {
+        let index = self.function_scopes.last().copied().unwrap_or(0);
+        self.scopes[index].entry(ident.to_string()).or_default();
+    }
+
+    /// Mark the visible identifier as a prompt identifier. Undeclared
+    /// identifiers are defined in the current scope.
+    pub fn mark_prompt_ident(&mut self, ident: &str) {
+        self.definition_mut(ident).prompt = Some(true);
+    }
+
+    /// Check if the visible identifier is a prompt identifier.
+    pub fn is_prompt_ident(&self, ident: &str) -> bool {
+        self.definition(ident)
+            .is_some_and(|definition| definition.prompt == Some(true))
+    }
+
+    /// Mark the visible identifier as opted out of prompt detection, so its
+    /// reassignments stay ignored and don't inherit the stored annotations.
+    pub fn mark_ignored_ident(&mut self, ident: &str) {
+        let definition = self.definition_mut(ident);
+        definition.prompt = Some(false);
+        definition.annotations = None;
+    }
+
+    /// Check if the visible identifier is opted out of prompt detection.
+    pub fn is_ignored_ident(&self, ident: &str) -> bool {
+        self.definition(ident)
+            .is_some_and(|definition| definition.prompt == Some(false))
+    }
+
+    /// Set whether the current statement has an opt-out annotation.
+    pub fn set_statement_ignored(&mut self, ignored: bool) {
+        self.statement_ignored = ignored;
+    }
+
+    /// Check if the current statement has an opt-out annotation.
+    pub fn is_statement_ignored(&self) -> bool {
+        self.statement_ignored
+    }
+
+    /// Store definition-time annotations for the visible identifier.
+    pub fn store_def_annotation(&mut self, ident: &str, annotations: Vec<PromptAnnotation>) {
+        self.definition_mut(ident).annotations = Some(annotations);
+    }
+
+    /// Get the stored definition annotations for the visible identifier.
+    pub fn get_def_annotation(&self, ident: &str) -> Option<Vec<PromptAnnotation>> {
+        self.definition(ident)?.annotations.clone()
+    }
+
+    /// Mark the visible identifier as having a type annotation.
+    pub fn mark_annotated(&mut self, ident: &str) {
+        self.definition_mut(ident).annotated = true;
+    }
+
+    /// Check if the visible identifier has a type annotation.
+    pub fn is_annotated(&self, ident: &str) -> bool {
+        self.definition(ident)
+            .is_some_and(|definition| definition.annotated)
+    }
+
+    /// Find the identifier definition in the innermost scope declaring it.
+    fn definition(&self, ident: &str) -> Option<&ScopeDefinition> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(ident))
+    }
+
+    /// Find the visible identifier definition or define it in the current
+    /// scope.
+    fn definition_mut(&mut self, ident: &str) -> &mut ScopeDefinition {
+        let index = self
+            .scopes
+            .iter()
+            .rposition(|scope| scope.contains_key(ident))
+            .unwrap_or(self.scopes.len() - 1);
+        self.scopes[index].entry(ident.to_string()).or_default()
+    }
+}
+
+impl Default for ScopeTracker {
+    fn default() -> Self {
+        Self::new(PromptDetector::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use volumen_types::SpanShape;
+
+    fn annotation(start: u32) -> PromptAnnotation {
+        PromptAnnotation {
+            spans: vec![SpanShape {
+                outer: (start, start + 10),
+                inner: (start + 1, start + 10),
+            }],
+            attributes: vec![],
+        }
+    }
+
+    #[test]
+    fn tracking() {
+        let mut tracker = ScopeTracker::default();
+
+        // Mark identifier in global scope
+        tracker.mark_prompt_ident("global_prompt");
+        assert!(tracker.is_prompt_ident("global_prompt"));
+
+        // Enter new scope
+        tracker.enter_scope();
+        tracker.mark_prompt_ident("local_prompt");
+
+        // Should find both global and local
+        assert!(tracker.is_prompt_ident("global_prompt"));
+        assert!(tracker.is_prompt_ident("local_prompt"));
+
+        // Exit scope
+        tracker.exit_scope();
+
+        // Should still find global, but not local
+        assert!(tracker.is_prompt_ident("global_prompt"));
+        assert!(!tracker.is_prompt_ident("local_prompt"));
+    }
+
+    #[test]
+    fn annotation_storage() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.store_def_annotation("test_var", vec![annotation(0)]);
+
+        let retrieved = tracker.get_def_annotation("test_var");
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn annotated_idents() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.mark_annotated("typed_var");
+        assert!(tracker.is_annotated("typed_var"));
+        assert!(!tracker.is_annotated("untyped_var"));
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.mark_prompt_ident("outer");
+
+        tracker.enter_scope();
+        tracker.mark_prompt_ident("middle");
+
+        tracker.enter_scope();
+        tracker.mark_prompt_ident("inner");
+
+        // All should be visible
+        assert!(tracker.is_prompt_ident("outer"));
+        assert!(tracker.is_prompt_ident("middle"));
+        assert!(tracker.is_prompt_ident("inner"));
+
+        tracker.exit_scope();
+        assert!(tracker.is_prompt_ident("outer"));
+        assert!(tracker.is_prompt_ident("middle"));
+        assert!(!tracker.is_prompt_ident("inner"));
+
+        tracker.exit_scope();
+        assert!(tracker.is_prompt_ident("outer"));
+        assert!(!tracker.is_prompt_ident("middle"));
+        assert!(!tracker.is_prompt_ident("inner"));
+    }
+
+    #[test]
+    fn ignored_idents() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.mark_ignored_ident("user_prompt");
+        assert!(tracker.is_ignored_ident("user_prompt"));
+        assert!(!tracker.is_prompt_name("user_prompt"));
+        assert!(tracker.is_prompt_name("system_prompt"));
+
+        // Inner declaration shadows the ignored identifier
+        tracker.enter_scope();
+        tracker.declare("user_prompt");
+        tracker.mark_prompt_ident("user_prompt");
+        assert!(tracker.is_prompt_ident("user_prompt"));
+        assert!(!tracker.is_ignored_ident("user_prompt"));
+
+        tracker.exit_scope();
+        assert!(tracker.is_ignored_ident("user_prompt"));
+
+        // Explicit prompt annotation lifts the opt-out
+        tracker.mark_prompt_ident("user_prompt");
+        assert!(tracker.is_prompt_ident("user_prompt"));
+        assert!(!tracker.is_ignored_ident("user_prompt"));
+    }
+
+    #[test]
+    fn shadowing() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.declare("greeting");
+        tracker.mark_prompt_ident("greeting");
+        tracker.store_def_annotation("greeting", vec![annotation(0)]);
+
+        // Inner declaration hides the outer prompt state and annotations
+        tracker.enter_scope();
+        tracker.declare("greeting");
+        assert!(!tracker.is_prompt_ident("greeting"));
+        assert_eq!(tracker.get_def_annotation("greeting"), None);
+
+        // Repeated declarations keep the definition
+        tracker.mark_prompt_ident("greeting");
+        tracker.declare("greeting");
+        assert!(tracker.is_prompt_ident("greeting"));
+        assert_eq!(tracker.get_def_annotation("greeting"), None);
+
+        tracker.exit_scope();
+        assert!(tracker.is_prompt_ident("greeting"));
+        assert_eq!(
+            tracker.get_def_annotation("greeting"),
+            Some(vec![annotation(0)])
+        );
+    }
+
+    #[test]
+    fn block_scopes() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.declare("greeting");
+
+        // Marks from nested blocks update the visible definition
+        tracker.enter_scope();
+        tracker.mark_prompt_ident("greeting");
+        tracker.store_def_annotation("greeting", vec![annotation(0)]);
+        tracker.exit_scope();
+        assert!(tracker.is_prompt_ident("greeting"));
+        assert_eq!(
+            tracker.get_def_annotation("greeting"),
+            Some(vec![annotation(0)])
+        );
+
+        // Block declarations are dropped with the block
+        tracker.enter_scope();
+        tracker.declare("farewell");
+        tracker.mark_ignored_ident("farewell");
+        tracker.exit_scope();
+        assert!(!tracker.is_ignored_ident("farewell"));
+    }
+
+    #[test]
+    fn hoisted_declarations() {
+        let mut tracker = ScopeTracker::default();
+
+        tracker.declare("greeting");
+        tracker.mark_prompt_ident("greeting");
+
+        // Hoisted declarations outlive the block
+        tracker.enter_function(None);
+        tracker.enter_scope();
+        tracker.declare_hoisted("greeting");
+        tracker.mark_ignored_ident("greeting");
+        tracker.exit_scope();
+        assert!(tracker.is_ignored_ident("greeting"));
+
+        // Outside of the function, the outer definition is visible again
+        tracker.exit_function();
+        assert!(tracker.is_prompt_ident("greeting"));
+
+        // Outside of functions, they're declared in the global scope
+        tracker.enter_scope();
+        tracker.declare_hoisted("farewell");
+        tracker.mark_prompt_ident("farewell");
+        tracker.exit_scope();
+        assert!(tracker.is_prompt_ident("farewell"));
+    }
+}
@@ -0,0 +1,267 @@
+use crate::render;
+use volumen_types::{Prompt, PromptRenderSegmentKind, PromptTemplate, PromptTemplatePlaceholder};
+
+/// Exports the prompt as a template with canonical `{{placeholder}}`
+/// variables. It allows to sync prompts with external tools regardless of
+/// the source language interpolation syntax, e.g., `${name}` in JavaScript
+/// or `#{name}` in Ruby. Literal `{{` in the prompt text is escaped as `\{{`,
+/// and single braces next to the placeholders as `\{` and `\}`, so they
+/// aren't confused with the placeholders.
+pub fn template(
+    prompt: &Prompt,
+    source: &str,
+    placeholder: PromptTemplatePlaceholder,
+) -> PromptTemplate {
+    let placeholders: Vec<String> = prompt
+        .vars
+        .iter()
+        .enumerate()
+        .map(|(index, var)| {
+            let name = match placeholder {
+                PromptTemplatePlaceholder::Index => None,
+                PromptTemplatePlaceholder::Name => placeholder_name(&var.expression),
+            };
+            name.unwrap_or_else(|| format!("var_{index}"))
+        })
+        .collect();
+
+    let values: Vec<String> = placeholders
+        .iter()
+        .map(|name| format!("{{{{{name}}}}}"))
+        .collect();
+
+    let rendered = render(prompt, source, &values);
+    let segments: Vec<_> = rendered
+        .map
+        .iter()
+        .filter(|segment| segment.span.0 < segment.span.1)
+        .collect();
+    let is_var = |index: usize| {
+        segments
+            .get(index)
+            .is_some_and(|segment| matches!(segment.kind, PromptRenderSegmentKind::Var))
+    };
+    let mut text = String::with_capacity(rendered.text.len());
+    for (index, segment) in segments.iter().enumerate() {
+        let chunk = &rendered.text[segment.span.0 as usize..segment.span.1 as usize];
+        match segment.kind {
+            PromptRenderSegmentKind::Var => text.push_str(chunk),
+            PromptRenderSegmentKind::Str | PromptRenderSegmentKind::Joint => escape_braces(
+                chunk,
+                index > 0 && is_var(index - 1),
+                is_var(index + 1),
+                &mut text,
+            ),
+        }
+    }
+
+    PromptTemplate { text, placeholders }
+}
+
+/// Escapes literal `{{` the Handlebars way, i.e., `\{{`. The backslashes
+/// preceding it are doubled, so `\{{` becomes `\\\{{`, and an odd number
+/// of backslashes before `{{` always means a literal. The single braces
+/// next to the placeholders are escaped too, e.g., `\{{{name}}\}` for
+/// `{${name}}`, so they don't form a triple-stash with the placeholder.
+fn escape_braces(chunk: &str, after_var: bool, before_var: bool, text: &mut String) {
+    let mut backslashes = 0;
+    let mut prev = None;
+    for (index, ch) in chunk.char_indices() {
+        let escaped = match ch {
+            '{' => {
+                prev != Some('{')
+                    && (chunk[index..].starts_with("{{")
+                        || (before_var && index + 1 == chunk.len()))
+            }
+            '}' => after_var && index == 0,
+            _ => false,
+        };
+        if escaped {
+            text.extend(std::iter::repeat_n('\\', backslashes + 1));
+        }
+        backslashes = if ch == '\\' { backslashes + 1 } else { 0 };
+        prev = Some(ch);
+        text.push(ch);
+    }
+}
+
+/// Resolves placeholder name from the variable expression text. Only
+/// identifiers and member access chains are supported, e.g., `user.name`,
+/// `$user->name` or `@name`. Sigils are dropped and member access is
+/// normalized to dots.
+fn placeholder_name(expression: &str) -> Option<String> {
+    let expression = expression.trim().replace("->", ".");
+    let segments = expression
+        .split('.')
+        .map(|segment| {
+            let segment = segment.trim_start_matches(['$', '@']);
+            let mut chars = segment.chars();
+            let valid = chars
+                .next()
+                .is_some_and(|ch| ch.is_alphabetic() || ch == '_')
+                && chars.all(|ch| ch.is_alphanumeric() || ch == '_');
+            valid.then_some(segment)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(segments.join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use volumen_types::*;
+
+    fn prompt(content: Vec<PromptContentToken>, vars: Vec<PromptVar>) -> Prompt {
+        Prompt {
+            file: "prompts.rb".into(),
+            enclosure: (0, 0),
+            span: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            content,
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            vars,
+            annotations: vec![],
+            binding: PromptBinding {
+                name: "prompt".into(),
+                span: (0, 0),
+                kind: PromptBindingKind::Local,
+            },
+            role: None,
+            references: None,
+        }
+    }
+
+    fn str_token(span: Span) -> PromptContentToken {
+        PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
+            r#type: PromptContentTokenStrTypeStr,
+            span,
+            escapes: None,
+        })
+    }
+
+    fn var_token(span: Span, index: u32) -> PromptContentToken {
+        PromptContentToken::PromptContentTokenVar(PromptContentTokenVar {
+            r#type: PromptContentTokenVarTypeVar,
+            span,
+            index,
+        })
+    }
+
+    fn var(source: &str, outer: Span, inner: Span) -> PromptVar {
+        crate::prompt_var(source, SpanShape { outer, inner }, inner)
+    }
+
+    #[test]
+    fn index() {
+        let source = r#"prompt = "Hi, #{user.name}! #{greeting}""#;
+        let prompt = prompt(
+            vec![
+                str_token((10, 14)),
+                var_token((14, 26), 0),
+                str_token((26, 28)),
+                var_token((28, 39), 1),
+            ],
+            vec![
+                var(source, (14, 26), (16, 25)),
+                var(source, (28, 39), (30, 38)),
+            ],
+        );
+        assert_eq!(
+            template(&prompt, source, PromptTemplatePlaceholder::Index),
+            PromptTemplate {
+                text: "Hi, {{var_0}}! {{var_1}}".into(),
+                placeholders: vec!["var_0".into(), "var_1".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn name() {
+        let source = r#"prompt = "Hi, #{user.name}! #{greeting}""#;
+        let prompt = prompt(
+            vec![
+                str_token((10, 14)),
+                var_token((14, 26), 0),
+                str_token((26, 28)),
+                var_token((28, 39), 1),
+            ],
+            vec![
+                var(source, (14, 26), (16, 25)),
+                var(source, (28, 39), (30, 38)),
+            ],
+        );
+        assert_eq!(
+            template(&prompt, source, PromptTemplatePlaceholder::Name),
+            PromptTemplate {
+                text: "Hi, {{user.name}}! {{greeting}}".into(),
+                placeholders: vec!["user.name".into(), "greeting".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn name_fallback() {
+        let source = r##"prompt = "#{price > 100 ? 'high' : 'low'}""##;
+        let prompt = prompt(
+            vec![var_token((10, 41), 0)],
+            vec![var(source, (10, 41), (12, 40))],
+        );
+        assert_eq!(
+            template(&prompt, source, PromptTemplatePlaceholder::Name).text,
+            "{{var_0}}"
+        );
+    }
+
+    #[test]
+    fn literal_braces() {
+        let source = r#"const prompt = `Use {{x}} ${name}`;"#;
+        let prompt = prompt(
+            vec![str_token((16, 26)), var_token((26, 33), 0)],
+            vec![var(source, (26, 33), (28, 32))],
+        );
+        let template = template(&prompt, source, PromptTemplatePlaceholder::Name);
+        assert_eq!(template.text, r"Use \{{x}} {{name}}");
+        assert_eq!(template.placeholders, vec!["name".to_string()]);
+
+        let mut text = String::new();
+        escape_braces(r"{{{x}}} \{{y}} {z} }}", false, false, &mut text);
+        assert_eq!(text, r"\{{{x}}} \\\{{y}} {z} }}");
+
+        let mut text = String::new();
+        escape_braces(r"} {z} \{", true, true, &mut text);
+        assert_eq!(text, r"\} {z} \\\{");
+    }
+
+    #[test]
+    fn literal_braces_placeholders() {
+        let source = r#"const prompt = `{${name}}`;"#;
+        let prompt = prompt(
+            vec![
+                str_token((16, 17)),
+                var_token((17, 24), 0),
+                str_token((24, 25)),
+            ],
+            vec![var(source, (17, 24), (19, 23))],
+        );
+        let template = template(&prompt, source, PromptTemplatePlaceholder::Name);
+        assert_eq!(template.text, r"\{{{name}}\}");
+        assert_eq!(template.placeholders, vec!["name".to_string()]);
+    }
+
+    #[test]
+    fn placeholder_names() {
+        assert_eq!(placeholder_name("name"), Some("name".into()));
+        assert_eq!(placeholder_name(" user.name "), Some("user.name".into()));
+        assert_eq!(placeholder_name("$user->name"), Some("user.name".into()));
+        assert_eq!(placeholder_name("@name"), Some("name".into()));
+        assert_eq!(placeholder_name("items[0]"), None);
+        assert_eq!(placeholder_name("fn()"), None);
+        assert_eq!(placeholder_name(""), None);
+    }
+}
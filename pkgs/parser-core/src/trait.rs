@@ -1,5 +1,23 @@
-use volumen_types::ParseResult;
+use volumen_types::{ParseDialect, ParseLanguage, ParseOptions, ParseResult};
 
 pub trait VolumenParser {
-    fn parse(source: &str, filename: &str) -> ParseResult;
+    /// Parses the source, reusing the parser internal state, e.g., the
+    /// Tree-sitter parser or the AST arena, across the calls. Create the
+    /// parser once to parse multiple sources.
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult;
+
+    /// Parses the source as the language and dialect resolved by the caller,
+    /// e.g., mapped with the `ParserRegistry` or detected from the content,
+    /// instead of resolving them from the file name. The single language
+    /// parsers have nothing to configure, so they parse the source as is.
+    fn parse_as(
+        &mut self,
+        source: &str,
+        filename: &str,
+        _language: ParseLanguage,
+        _dialect: Option<ParseDialect>,
+        options: &ParseOptions,
+    ) -> ParseResult {
+        self.parse(source, filename, options)
+    }
 }
@@ -0,0 +1,349 @@
+use volumen_types::{PromptVar, PromptVarKind, Span, SpanShape};
+
+/// Creates a prompt variable resolving the expression metadata from the
+/// expression span text. The format and conversion are left empty, so
+/// parsers must assign them when the interpolation syntax supports them.
+pub fn prompt_var(source: &str, span: SpanShape, expression: Span) -> PromptVar {
+    let expression = source
+        .get(expression.0 as usize..expression.1 as usize)
+        .unwrap_or("")
+        .trim();
+    let (kind, root) = expression_kind(expression);
+
+    PromptVar {
+        span,
+        expression: expression.to_string(),
+        kind,
+        root,
+        format: None,
+        conversion: None,
+        parameter: None,
+    }
+}
+
+/// Creates an interpolation prompt variable, resolving the conversion and
+/// format specification from the text following the expression inside
+/// the interpolation, e.g., `!r:>10` in `{name!r:>10}`.
+pub fn interpolation_var(source: &str, span: SpanShape, expression: Span) -> PromptVar {
+    let spec = source
+        .get(expression.1 as usize..span.inner.1 as usize)
+        .unwrap_or("");
+    let (format, conversion) = placeholder_format(spec);
+
+    PromptVar {
+        format,
+        conversion,
+        ..prompt_var(source, span, expression)
+    }
+}
+
+/// Creates a format function argument prompt variable, resolving the format
+/// specification and conversion from the matching placeholder, e.g., `%5.2f`
+/// for `price` in `sprintf("%5.2f", price)`.
+pub fn format_var(source: &str, placeholder: &str, argument: Span) -> PromptVar {
+    let (format, conversion) = placeholder_format(placeholder);
+
+    PromptVar {
+        format,
+        conversion,
+        ..prompt_var(
+            source,
+            SpanShape {
+                outer: argument,
+                inner: argument,
+            },
+            argument,
+        )
+    }
+}
+
+/// Resolves the expression kind and the root identifier. The expression is
+/// treated as a chain of member access, call and index operations applied to
+/// an identifier, e.g., `items.map(x => x.id).join()`, where the last
+/// operation defines the kind. Anything else is `other` without a root.
+pub fn expression_kind(expression: &str) -> (PromptVarKind, Option<String>) {
+    match expression_chain(expression) {
+        Some((kind, root_len)) => (kind, Some(expression[..root_len].to_string())),
+        None => (PromptVarKind::Other, None),
+    }
+}
+
+/// Parses the expression as an operations chain. Returns the last operation
+/// kind and the root identifier length.
+fn expression_chain(expression: &str) -> Option<(PromptVarKind, usize)> {
+    let root_len = identifier_len(expression, true)?;
+    let mut kind = PromptVarKind::Identifier;
+    let mut pos = root_len;
+
+    while pos < expression.len() {
+        let rest = &expression[pos..];
+        // TypeScript non-null assertion doesn't change the kind
+        if rest.starts_with('!') && !rest.starts_with("!=") {
+            pos += 1;
+            continue;
+        }
+
+        // Optional call or index, e.g., `fn?.()` or `items?.[0]`
+        let (group, prefix) = match rest.strip_prefix("?.") {
+            Some(group) if group.starts_with(['(', '[']) => (group, 2),
+            _ => (rest, 0),
+        };
+
+        if group.starts_with('(') {
+            kind = PromptVarKind::Call;
+            pos += prefix + group_len(group)?;
+        } else if group.starts_with('[') {
+            kind = PromptVarKind::Index;
+            pos += prefix + group_len(group)?;
+        } else {
+            let accessor = ["?->", "?.", "->", "::", "."]
+                .iter()
+                .find(|accessor| rest.starts_with(*accessor))?;
+            kind = PromptVarKind::Member;
+            pos += accessor.len() + identifier_len(&rest[accessor.len()..], false)?;
+        }
+    }
+
+    Some((kind, root_len))
+}
+
+/// Resolves the format specification and conversion from a format function
+/// placeholder, e.g., `{0,10:N2}` in C#, `{!r:>10}` in Python or `%5.2f` in
+/// printf-like functions. Returns the format and the conversion.
+pub fn placeholder_format(placeholder: &str) -> (Option<String>, Option<String>) {
+    if placeholder.starts_with('%') {
+        return (Some(placeholder.to_string()), None);
+    }
+
+    let field = placeholder
+        .strip_prefix('{')
+        .and_then(|field| field.strip_suffix('}'))
+        .unwrap_or(placeholder);
+    let Some(split) = field.find(['!', ':', ',']) else {
+        return (None, None);
+    };
+
+    let spec = &field[split..];
+    let (conversion, format) = match spec.strip_prefix('!') {
+        Some(spec) => match spec.split_once(':') {
+            Some((conversion, format)) => (Some(conversion), Some(format)),
+            None => (Some(spec), None),
+        },
+        None => (None, Some(spec.strip_prefix(':').unwrap_or(spec))),
+    };
+
+    (
+        format
+            .filter(|format| !format.is_empty())
+            .map(str::to_string),
+        conversion
+            .filter(|conversion| !conversion.is_empty())
+            .map(str::to_string),
+    )
+}
+
+/// Returns the identifier length at the start of the text. Root identifiers
+/// might start with sigils, e.g., `$name` in PHP or `@name` in Ruby.
+fn identifier_len(text: &str, root: bool) -> Option<usize> {
+    let sigils = if root {
+        text.len() - text.trim_start_matches(['$', '@']).len()
+    } else {
+        0
+    };
+    let rest = &text[sigils..];
+    let mut chars = rest.char_indices();
+    match chars.next() {
+        Some((_, ch)) if ch.is_alphabetic() || ch == '_' || ch == '$' => {}
+        _ => return None,
+    }
+
+    let len = chars
+        .find(|(_, ch)| !(ch.is_alphanumeric() || *ch == '_' || *ch == '$'))
+        .map_or(rest.len(), |(index, _)| index);
+    Some(sigils + len)
+}
+
+/// Returns the length of the bracket group at the start of the text,
+/// including the closing bracket. Brackets inside string literals are
+/// ignored.
+fn group_len(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, ch) in text.char_indices() {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == open {
+                quote = None;
+            }
+            continue;
+        }
+
+        match ch {
+            '"' | '\'' | '`' => quote = Some(ch),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn root(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    #[test]
+    fn identifier() {
+        assert_eq!(
+            expression_kind("name"),
+            (PromptVarKind::Identifier, root("name"))
+        );
+        assert_eq!(
+            expression_kind("$name"),
+            (PromptVarKind::Identifier, root("$name"))
+        );
+        assert_eq!(
+            expression_kind("@name"),
+            (PromptVarKind::Identifier, root("@name"))
+        );
+    }
+
+    #[test]
+    fn member() {
+        assert_eq!(
+            expression_kind("user.name"),
+            (PromptVarKind::Member, root("user"))
+        );
+        assert_eq!(
+            expression_kind("$user->name"),
+            (PromptVarKind::Member, root("$user"))
+        );
+        assert_eq!(
+            expression_kind("user?.name"),
+            (PromptVarKind::Member, root("user"))
+        );
+        assert_eq!(
+            expression_kind("user!.name"),
+            (PromptVarKind::Member, root("user"))
+        );
+        assert_eq!(
+            expression_kind("Foo::BAR"),
+            (PromptVarKind::Member, root("Foo"))
+        );
+        assert_eq!(
+            expression_kind("fn().name"),
+            (PromptVarKind::Member, root("fn"))
+        );
+    }
+
+    #[test]
+    fn call() {
+        assert_eq!(
+            expression_kind("items.map(x => x.id).join()"),
+            (PromptVarKind::Call, root("items"))
+        );
+        assert_eq!(
+            expression_kind(r#"get(")")"#),
+            (PromptVarKind::Call, root("get"))
+        );
+        assert_eq!(expression_kind("fn?.()"), (PromptVarKind::Call, root("fn")));
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(
+            expression_kind("items[0]"),
+            (PromptVarKind::Index, root("items"))
+        );
+        assert_eq!(
+            expression_kind(r#"user["name"]"#),
+            (PromptVarKind::Index, root("user"))
+        );
+        assert_eq!(
+            expression_kind("items?.[0]"),
+            (PromptVarKind::Index, root("items"))
+        );
+    }
+
+    #[test]
+    fn other() {
+        let other = (PromptVarKind::Other, None);
+        assert_eq!(expression_kind("price > 100 ? 'high' : 'low'"), other);
+        assert_eq!(expression_kind("(a + b)"), other);
+        assert_eq!(expression_kind("a + b"), other);
+        assert_eq!(expression_kind("a != b"), other);
+        assert_eq!(expression_kind("items[0"), other);
+        assert_eq!(expression_kind("\"Hello\""), other);
+        assert_eq!(expression_kind(""), other);
+    }
+
+    #[test]
+    fn placeholders() {
+        let format = |format: &str| Some(format.to_string());
+        assert_eq!(placeholder_format("{}"), (None, None));
+        assert_eq!(placeholder_format("{0}"), (None, None));
+        assert_eq!(placeholder_format("{:>10}"), (format(">10"), None));
+        assert_eq!(placeholder_format("{name!r}"), (None, format("r")));
+        assert_eq!(placeholder_format("{!r:>10}"), (format(">10"), format("r")));
+        assert_eq!(placeholder_format("{0,10:N2}"), (format(",10:N2"), None));
+        assert_eq!(placeholder_format("%5.2f"), (format("%5.2f"), None));
+    }
+
+    #[test]
+    fn interpolation() {
+        let source = "f\"Hello {user.name!r:>10}\"";
+        let var = interpolation_var(
+            source,
+            SpanShape {
+                outer: (8, 25),
+                inner: (9, 24),
+            },
+            (9, 18),
+        );
+        assert_eq!(var.expression, "user.name");
+        assert_eq!(var.format.as_deref(), Some(">10"));
+        assert_eq!(var.conversion.as_deref(), Some("r"));
+    }
+
+    #[test]
+    fn var() {
+        let source = "f\"Hello {user.name!r:>10}\"";
+        let var = prompt_var(
+            source,
+            SpanShape {
+                outer: (8, 25),
+                inner: (9, 24),
+            },
+            (9, 18),
+        );
+        assert_eq!(
+            var,
+            PromptVar {
+                span: SpanShape {
+                    outer: (8, 25),
+                    inner: (9, 24),
+                },
+                expression: "user.name".into(),
+                kind: PromptVarKind::Member,
+                root: Some("user".into()),
+                format: None,
+                conversion: None,
+                parameter: None,
+            }
+        );
+    }
+}
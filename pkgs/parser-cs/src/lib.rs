@@ -1,51 +1,87 @@
 mod comments;
-mod scope;
 mod spans;
 
 use comments::CommentTracker;
-use scope::ScopeTracker;
-use spans::span_shape_string_like;
+use spans::{escape_style, span_shape_string_like};
 use tree_sitter::{Node, Parser, Tree};
 pub use volumen_parser_core::VolumenParser;
+use volumen_parser_core::{
+    build_content_tokens, collect_diagnostics, concat_content, error_diagnostic, file_size_error,
+    format_content, is_content_key, is_role_key, parse_result, parse_role_value,
+    process_call_sites, prompt_group, prompt_var, resolve_escapes, CallArgument, CallSiteSyntax,
+    ConcatContent, ConcatSegment, PromptDetector, PromptFunction, PromptReferences, ScopeTracker,
+};
+
 use volumen_types::*;
 
-pub struct ParserCs {}
+pub struct ParserCs {
+    parser: Parser,
+}
 
-impl VolumenParser for ParserCs {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        // Initialize Tree-sitter parser
+impl ParserCs {
+    pub fn new() -> Self {
         let mut parser = Parser::new();
         parser
             .set_language(&tree_sitter_c_sharp::LANGUAGE.into())
             .expect("Failed to load C# grammar");
+        Self { parser }
+    }
+}
+
+impl Default for ParserCs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VolumenParser for ParserCs {
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        // Skip sources exceeding the maximum file size
+        if let Some(result) =
+            file_size_error(source, options, ParseLanguage::Cs, None, "tree-sitter")
+        {
+            return result;
+        }
 
         // Parse source
-        let tree = match parser.parse(source, None) {
+        let tree = match self.parser.parse(source, None) {
             Some(tree) => tree,
             None => {
                 return ParseResult::ParseResultError(ParseResultError {
                     state: ParseResultErrorStateError,
                     error: "Failed to parse source".to_string(),
+                    diagnostics: vec![error_diagnostic(
+                        "tree-sitter",
+                        "parse-failed",
+                        "Failed to parse source".to_string(),
+                        (0, source.len() as u32),
+                    )],
+                    language: Some(ParseLanguage::Cs),
+                    dialect: None,
+                    detection: None,
+                    parser: Some("tree-sitter".to_string()),
                 });
             }
         };
 
-        // Check for parse errors
+        // Collect parse errors. Tree-sitter recovers from errors, so the tree
+        // is still processed, skipping error nodes.
         let root = tree.root_node();
+        let mut diagnostics = Vec::new();
         if root.has_error() {
-            let error_msg = format_tree_sitter_error(&root, source);
-            return ParseResult::ParseResultError(ParseResultError {
-                state: ParseResultErrorStateError,
-                error: error_msg,
-            });
+            collect_diagnostics(&root, source, &mut diagnostics);
         }
 
+        // Resolve prompt detection rules
+        let detector = PromptDetector::new(options);
+
         // Extract comments
-        let comment_tracker = CommentTracker::new(&tree, source);
+        let comment_tracker = CommentTracker::new(&tree, source, detector.clone());
 
         // Initialize state
         let mut prompts = Vec::new();
-        let mut scope_tracker = ScopeTracker::new();
+        let mut groups = Vec::new();
+        let mut scope_tracker = ScopeTracker::new(detector.clone());
 
         // Process tree
         process_tree(
@@ -56,55 +92,48 @@ impl VolumenParser for ParserCs {
             &comment_tracker,
             &mut scope_tracker,
             &mut prompts,
+            &mut groups,
         );
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
-            prompts,
-        })
-    }
-}
+        // Detect prompts passed to LLM SDK call sites
+        let call_sites = CallSites {
+            comments: &comment_tracker,
+        };
+        process_call_sites(
+            &root,
+            source,
+            filename,
+            &detector,
+            &call_sites,
+            &mut prompts,
+            &mut groups,
+        );
 
-/// Format tree-sitter parse errors into a readable message.
-fn format_tree_sitter_error(node: &Node, source: &str) -> String {
-    let mut errors = Vec::new();
-    collect_errors(node, source, &mut errors);
-    if errors.is_empty() {
-        "Parse error: unknown".to_string()
-    } else {
-        format!("Parse errors:\n{}", errors.join("\n"))
-    }
-}
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = PromptReferences::new(&prompts);
+        process_references(&root, source, &mut references);
+        references.apply(&mut prompts);
 
-fn collect_errors(node: &Node, source: &str, errors: &mut Vec<String>) {
-    if node.is_error() || node.is_missing() {
-        let start = node.start_position();
-        let error_text = node
-            .utf8_text(source.as_bytes())
-            .unwrap_or("<unknown>")
-            .chars()
-            .take(50)
-            .collect::<String>();
-        errors.push(format!(
-            "  Line {}, Column {}: {}",
-            start.row + 1,
-            start.column + 1,
-            error_text
-        ));
-    }
+        // Decode escape sequences in string tokens
+        resolve_escapes(&mut prompts, source, |span| escape_style(&root, source, span));
 
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            collect_errors(&cursor.node(), source, errors);
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
+        // Assign role hints from annotations, e.g., `// @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        parse_result(
+            ParseLanguage::Cs,
+            None,
+            "tree-sitter",
+            prompts,
+            groups,
+            Vec::new(),
+            diagnostics,
+        )
     }
 }
 
 /// Process the entire syntax tree to extract prompts.
+#[allow(clippy::too_many_arguments)]
 fn process_tree(
     _tree: &Tree,
     root: &Node,
@@ -113,9 +142,10 @@ fn process_tree(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Recursively traverse the tree
-    traverse_node(root, source, filename, comments, scopes, prompts);
+    traverse_node(root, source, filename, comments, scopes, prompts, groups);
 }
 
 /// Recursively traverse nodes to find assignments and manage scopes.
@@ -126,15 +156,27 @@ fn traverse_node(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
     let kind = node.kind();
 
-    // Handle scope boundaries (method, class, struct)
+    // Handle method scopes, tracking prompt functions returning prompts
     if kind == "method_declaration"
-        || kind == "class_declaration"
-        || kind == "struct_declaration"
         || kind == "constructor_declaration"
+        || kind == "local_function_statement"
+        || kind == "lambda_expression"
     {
+        process_function(node, source, filename, comments, scopes, prompts, groups);
+        return;
+    }
+
+    // Handle scope boundaries (class, struct)
+    if kind == "class_declaration" || kind == "struct_declaration" {
         scopes.enter_scope();
 
         // Process children
@@ -142,7 +184,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             loop {
                 let child = cursor.node();
-                traverse_node(&child, source, filename, comments, scopes, prompts);
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -160,7 +202,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             loop {
                 let child = cursor.node();
-                traverse_node(&child, source, filename, comments, scopes, prompts);
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -169,15 +211,36 @@ fn traverse_node(
         return;
     }
 
+    // Handle block scopes, e.g., `{ string prompt = "..."; }` or `for` loop
+    // variables
+    if matches!(kind, "block" | "for_statement" | "foreach_statement") {
+        scopes.enter_scope();
+
+        // Process children
+        let mut cursor = node.walk();
+        if cursor.goto_first_child() {
+            loop {
+                let child = cursor.node();
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+
+        scopes.exit_scope();
+        return;
+    }
+
     // Handle local declarations
     if kind == "local_declaration_statement" {
-        process_local_declaration(node, source, filename, comments, scopes, prompts);
+        process_local_declaration(node, source, filename, comments, scopes, prompts, groups);
         return;
     }
 
     // Handle field declarations
     if kind == "field_declaration" {
-        process_field_declaration(node, source, filename, comments, scopes, prompts);
+        process_field_declaration(node, source, filename, comments, scopes, prompts, groups);
         return;
     }
 
@@ -187,17 +250,148 @@ fn traverse_node(
         return;
     }
 
+    // Handle prompt function returns, including expression-bodied members:
+    // `return $"Hello, {name}!";` and `=> $"Hello, {name}!"`
+    if kind == "return_statement" || kind == "arrow_expression_clause" {
+        if let Some(value) = node.named_child(0) {
+            process_function_return(
+                &value, node, source, filename, comments, scopes, prompts, groups,
+            );
+        }
+    }
+
     // Recursively process children
     let mut cursor = node.walk();
     if cursor.goto_first_child() {
         loop {
             let child = cursor.node();
-            traverse_node(&child, source, filename, comments, scopes, prompts);
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Process a method, constructor or local function scope. Methods with
+/// prompt-like names or leading `@prompt` annotations are prompt functions,
+/// so their returned values are detected as prompts. Lambdas never are, so
+/// their returns don't leak into the enclosing method.
+fn process_function(
+    node: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let stmt_start = node.start_byte() as u32;
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|parameters| function_parameters(&parameters, source))
+        .unwrap_or_default();
+    let function = node.child_by_field_name("name").and_then(|name| {
+        // Opt-out annotations exclude the function from prompt functions
+        if comments.has_ignore_annotation(stmt_start, name.end_byte() as u32) {
+            return None;
+        }
+        PromptFunction::new(
+            scopes.detector(),
+            source,
+            (name.start_byte() as u32, name.end_byte() as u32),
+            parameters.clone(),
+            comments.collect_adjacent_leading(stmt_start),
+        )
+    });
+    scopes.enter_function(function);
+
+    // Parameters shadow the outer identifiers with the same names
+    for parameter in &parameters {
+        scopes.declare(parameter);
+    }
+
+    // Process children
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
             if !cursor.goto_next_sibling() {
                 break;
             }
         }
     }
+
+    scopes.exit_function();
+}
+
+/// Collect the method parameter names, e.g., `user` and `rest` for
+/// `string BuildPrompt(User user, params string[] rest)`. Parameters with
+/// modifiers like `params` are parsed as the list fields.
+fn function_parameters(node: &Node, source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut cursor = node.walk();
+    for parameter in node.named_children(&mut cursor) {
+        if let Some(name) = parameter.child_by_field_name("name") {
+            names.push(name);
+        }
+    }
+    names.extend(node.children_by_field_name("name", &mut cursor));
+    names
+        .iter()
+        .filter_map(|name| name.utf8_text(source.as_bytes()).ok())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Process a value returned from the enclosing prompt function. The value is
+/// processed like a prompt assignment bound to the function name, marking
+/// the variables that come from the function parameters.
+#[allow(clippy::too_many_arguments)]
+fn process_function_return(
+    value: &Node,
+    stmt: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let Some(function) = scopes.current_function().cloned() else {
+        return;
+    };
+    let stmt_start = stmt.start_byte() as u32;
+    let stmt_end = stmt.end_byte() as u32;
+
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
+    // Statement annotations take precedence over the function annotations
+    let mut annotations = comments.collect_adjacent_leading(stmt_start);
+    annotations.extend(comments.collect_inline_prompt(stmt_start, stmt_end));
+    if annotations.is_empty() {
+        annotations = function.annotations.clone();
+    }
+
+    let prompts_len = prompts.len();
+    process_identifier_assignment(
+        &function.binding.name,
+        value,
+        true,
+        &annotations,
+        &function.binding,
+        stmt_start,
+        stmt_end,
+        source,
+        filename,
+        comments,
+        scopes,
+        prompts,
+        groups,
+    );
+    function.mark_parameter_vars(prompts, prompts_len);
 }
 
 /// Process a local declaration statement.
@@ -208,17 +402,24 @@ fn process_local_declaration(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     let mut all_annotations = leading_annotations.clone();
     all_annotations.extend(inline_annotations);
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
+    let binding_kind = declaration_binding_kind(node, source, PromptBindingKind::Local);
 
     // Find variable_declarator nodes
     let mut cursor = node.walk();
@@ -236,6 +437,7 @@ fn process_local_declaration(
                                 &var_child,
                                 has_prompt_annotation,
                                 &all_annotations,
+                                binding_kind.clone(),
                                 stmt_start,
                                 stmt_end,
                                 source,
@@ -243,6 +445,7 @@ fn process_local_declaration(
                                 comments,
                                 scopes,
                                 prompts,
+                                groups,
                             );
                         }
                         if !var_cursor.goto_next_sibling() {
@@ -266,17 +469,24 @@ fn process_field_declaration(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     let mut all_annotations = leading_annotations.clone();
     all_annotations.extend(inline_annotations);
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
+    let binding_kind = declaration_binding_kind(node, source, PromptBindingKind::Field);
 
     // Find variable_declarator nodes in variable_declaration
     let mut cursor = node.walk();
@@ -294,6 +504,7 @@ fn process_field_declaration(
                                 &var_child,
                                 has_prompt_annotation,
                                 &all_annotations,
+                                binding_kind.clone(),
                                 stmt_start,
                                 stmt_end,
                                 source,
@@ -301,6 +512,7 @@ fn process_field_declaration(
                                 comments,
                                 scopes,
                                 prompts,
+                                groups,
                             );
                         }
                         if !var_cursor.goto_next_sibling() {
@@ -328,6 +540,9 @@ fn process_assignment(
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Get left side (should be an identifier)
     let left_node = match node.child_by_field_name("left") {
         Some(n) => n,
@@ -341,11 +556,19 @@ fn process_assignment(
         return;
     };
 
-    // Check if this identifier is a prompt variable
-    if !scopes.is_prompt_ident(ident_name) {
+    // Opt-out annotations keep the identifier ignored in reassignments
+    if scopes.is_statement_ignored() {
+        scopes.mark_ignored_ident(ident_name);
+        return;
+    }
+
+    // Check if this identifier is a prompt variable or is in a prompt region
+    if !scopes.is_prompt_ident(ident_name) && !comments.in_prompt_region(stmt_start) {
         return;
     }
 
+    let binding = binding_from_node(&left_node, source, PromptBindingKind::Local);
+
     // Get right side (value being assigned)
     let right_node = match node.child_by_field_name("right") {
         Some(n) => n,
@@ -379,6 +602,7 @@ fn process_assignment(
         stmt_end,
         comments,
         &final_annotations,
+        &binding,
         prompts,
     );
 }
@@ -389,6 +613,7 @@ fn process_variable_declarator(
     node: &Node,
     has_prompt_annotation: bool,
     annotations: &[PromptAnnotation],
+    binding_kind: PromptBindingKind,
     stmt_start: u32,
     stmt_end: u32,
     source: &str,
@@ -396,6 +621,7 @@ fn process_variable_declarator(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Get identifier node
     let ident_node = match node.child_by_field_name("name") {
@@ -404,6 +630,10 @@ fn process_variable_declarator(
     };
 
     let ident_name = ident_node.utf8_text(source.as_bytes()).unwrap_or("");
+    let binding = binding_from_node(&ident_node, source, binding_kind);
+
+    // Declarations shadow the outer identifiers with the same name
+    scopes.declare(ident_name);
 
     // Get value node - in C# it's not a named field, it's the last child after '='
     // Pattern: identifier = value
@@ -429,8 +659,50 @@ fn process_variable_declarator(
         }
     };
 
-    // Determine if this is a prompt
-    let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
+    process_identifier_assignment(
+        ident_name,
+        &value_node,
+        has_prompt_annotation,
+        annotations,
+        &binding,
+        stmt_start,
+        stmt_end,
+        source,
+        filename,
+        comments,
+        scopes,
+        prompts,
+        groups,
+    );
+}
+
+/// Process a value assigned to an identifier: chat messages, a string,
+/// concatenation, `string.Join` or `string.Format` call or a string array.
+#[allow(clippy::too_many_arguments)]
+fn process_identifier_assignment(
+    ident_name: &str,
+    value_node: &Node,
+    has_prompt_annotation: bool,
+    annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
+    stmt_start: u32,
+    stmt_end: u32,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    // Check if it's a chat message object or a collection of them
+    let messages = extract_messages(value_node, source);
+
+    // Determine if this is a prompt. Chat messages are prompts by structure.
+    let is_prompt = is_prompt_variable(
+        ident_name,
+        has_prompt_annotation || !messages.is_empty(),
+        scopes,
+    );
 
     if is_prompt {
         // Mark as prompt identifier
@@ -441,8 +713,43 @@ fn process_variable_declarator(
             scopes.store_def_annotation(ident_name, annotations.to_vec());
         }
 
+        if !messages.is_empty() {
+            let final_annotations = if !annotations.is_empty() {
+                annotations.to_vec()
+            } else {
+                scopes.get_def_annotation(ident_name).unwrap_or_default()
+            };
+
+            // Handle messages: new[] { new ChatMessage { Role = ..., Content = "..." } }
+            let group_start = prompts.len();
+            for (role, content) in messages {
+                let prompts_len = prompts.len();
+                create_prompt_from_string(
+                    &content,
+                    source,
+                    filename,
+                    stmt_start,
+                    stmt_end,
+                    comments,
+                    &final_annotations,
+                    binding,
+                    prompts,
+                );
+                for prompt in &mut prompts[prompts_len..] {
+                    prompt.role = role;
+                }
+            }
+
+            // Group the messages list into a conversation
+            if extract_message(value_node, source).is_none() {
+                let span = (value_node.start_byte() as u32, value_node.end_byte() as u32);
+                if let Some(group) = prompt_group(filename, span, binding, prompts, group_start) {
+                    groups.push(group);
+                }
+            }
+        }
         // Check if it's a string or binary expression
-        if is_string_like(&value_node) {
+        else if is_string_like(value_node) {
             // Annotations from comment tracker are already validated to contain @prompt
             // Get annotations (from current statement or from definition)
             let final_annotations = if !annotations.is_empty() {
@@ -453,13 +760,14 @@ fn process_variable_declarator(
 
             // Create prompt
             create_prompt_from_string(
-                &value_node,
+                value_node,
                 source,
                 filename,
                 stmt_start,
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
                 prompts,
             );
         } else if value_node.kind() == "binary_expression" {
@@ -472,13 +780,14 @@ fn process_variable_declarator(
 
             // Try to process as concatenation
             if let Some(prompt) = process_concatenation(
-                &value_node,
+                value_node,
                 source,
                 filename,
                 stmt_start,
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -492,24 +801,26 @@ fn process_variable_declarator(
 
             // Try String.Join first
             if let Some(prompt) = process_string_join(
-                &value_node,
+                value_node,
                 source,
                 filename,
                 stmt_start,
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             // Then try String.Format
             } else if let Some(prompt) = process_string_format(
-                &value_node,
+                value_node,
                 source,
                 filename,
                 stmt_start,
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -521,13 +832,14 @@ fn process_variable_declarator(
             };
 
             if let Some(prompt) = process_array(
-                &value_node,
+                value_node,
                 source,
                 filename,
                 stmt_start,
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -535,13 +847,311 @@ fn process_variable_declarator(
     }
 }
 
-/// Check if a variable should be treated as a prompt.
-fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &ScopeTracker) -> bool {
-    ident_name.to_lowercase().contains("prompt")
+/// Extract chat message roles and string contents from a message object, e.g.,
+/// `new ChatMessage { Role = ChatRole.System, Content = "..." }` or
+/// `new { role = "system", content = "..." }`, or from an array or a collection
+/// initializer of message objects.
+fn extract_messages<'a>(node: &Node<'a>, source: &str) -> Vec<(Option<PromptRole>, Node<'a>)> {
+    if let Some(message) = extract_message(node, source) {
+        return vec![message];
+    }
+    let mut cursor = node.walk();
+    let initializer = match node.kind() {
+        "object_creation_expression" => node.child_by_field_name("initializer"),
+        "array_creation_expression" | "implicit_array_creation_expression" => node
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "initializer_expression"),
+        _ => None,
+    };
+    let Some(initializer) = initializer else {
+        return Vec::new();
+    };
+    let mut cursor = initializer.walk();
+    initializer
+        .named_children(&mut cursor)
+        .filter_map(|element| extract_message(&element, source))
+        .collect()
+}
+
+/// Extract the role and the string content from a message object. Objects
+/// without the role member aren't messages.
+fn extract_message<'a>(node: &Node<'a>, source: &str) -> Option<(Option<PromptRole>, Node<'a>)> {
+    let mut role = None;
+    let mut content = None;
+    for (key, value) in object_members(node)? {
+        let key_text = key.utf8_text(source.as_bytes()).unwrap_or_default();
+        if is_role_key(key_text) {
+            role = Some(parse_role_value(
+                value.utf8_text(source.as_bytes()).unwrap_or_default(),
+            ));
+        } else if is_content_key(key_text) && is_string_like(&value) {
+            content = Some(value);
+        }
+    }
+    Some((role?, content?))
+}
+
+/// Collect member name and value pairs from an anonymous object or an object
+/// initializer, e.g., `new { role = "user" }` or `new Message { Role = ... }`.
+fn object_members<'a>(node: &Node<'a>) -> Option<Vec<(Node<'a>, Node<'a>)>> {
+    let mut members = Vec::new();
+    match node.kind() {
+        "anonymous_object_creation_expression" => {
+            // Members are flattened into `name = value` child sequences
+            let mut cursor = node.walk();
+            let children: Vec<Node<'a>> = node.children(&mut cursor).collect();
+            for window in children.windows(3) {
+                if window[0].kind() == "identifier" && window[1].kind() == "=" {
+                    members.push((window[0], window[2]));
+                }
+            }
+        }
+        "object_creation_expression" => {
+            let initializer = node.child_by_field_name("initializer")?;
+            let mut cursor = initializer.walk();
+            for member in initializer.named_children(&mut cursor) {
+                if member.kind() != "assignment_expression" {
+                    continue;
+                }
+                if let (Some(left), Some(right)) = (
+                    member.child_by_field_name("left"),
+                    member.child_by_field_name("right"),
+                ) {
+                    members.push((left, right));
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(members)
+}
+
+/// Create a prompt binding from an identifier node.
+fn binding_from_node(node: &Node, source: &str, kind: PromptBindingKind) -> PromptBinding {
+    PromptBinding {
+        name: node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+        span: (node.start_byte() as u32, node.end_byte() as u32),
+        kind,
+    }
+}
+
+/// Resolve the binding kind of a local or field declaration: `const`
+/// declarations are constants, anything else falls back to the default kind.
+fn declaration_binding_kind(
+    node: &Node,
+    source: &str,
+    default: PromptBindingKind,
+) -> PromptBindingKind {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "modifier" && child.utf8_text(source.as_bytes()) == Ok("const") {
+            return PromptBindingKind::Const;
+        }
+    }
+    default
+}
+
+/// Check if a variable should be treated as a prompt. Variables in statements
+/// with an opt-out annotation are marked as ignored instead.
+fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &mut ScopeTracker) -> bool {
+    if scopes.is_statement_ignored() {
+        scopes.mark_ignored_ident(ident_name);
+        return false;
+    }
+    scopes.is_prompt_name(ident_name)
         || has_annotation
         || scopes.is_prompt_ident(ident_name)
 }
 
+/// C# LLM SDK call site syntax, e.g., `new SystemChatMessage("...")`.
+struct CallSites<'a> {
+    comments: &'a CommentTracker,
+}
+
+impl<'tree> CallSiteSyntax<'tree> for CallSites<'_> {
+    fn call(&self, node: &Node<'tree>, _source: &str) -> Option<(Span, Node<'tree>)> {
+        let callee = match node.kind() {
+            "invocation_expression" => node.child_by_field_name("function")?,
+            // Constructors are matched by the class, e.g., `new SystemChatMessage(...)`
+            "object_creation_expression" => node.child_by_field_name("type")?,
+            _ => return None,
+        };
+        let arguments = node.child_by_field_name("arguments")?;
+        Some((
+            (callee.start_byte() as u32, callee.end_byte() as u32),
+            arguments,
+        ))
+    }
+
+    /// Named arguments and members of anonymous object or initializer
+    /// arguments are named.
+    fn arguments(&self, arguments: &Node<'tree>, source: &str) -> Vec<CallArgument<'tree>> {
+        let mut members = Vec::new();
+        let mut values = Vec::new();
+        let mut cursor = arguments.walk();
+        let arguments = arguments
+            .named_children(&mut cursor)
+            .filter(|argument| argument.kind() == "argument");
+        for (position, argument) in arguments.enumerate() {
+            let mut argument_cursor = argument.walk();
+            let Some(value) = argument.named_children(&mut argument_cursor).last() else {
+                continue;
+            };
+            if let Some(name) = argument.child_by_field_name("name") {
+                members.push((name, value));
+            } else if let Some(object_members) =
+                object_members(&value).filter(|_| extract_message(&value, source).is_none())
+            {
+                members.extend(object_members);
+            } else {
+                values.push(CallArgument::Positional(position, value));
+            }
+        }
+        values.extend(
+            members
+                .into_iter()
+                .map(|(key, value)| CallArgument::Named(key, value)),
+        );
+        values
+    }
+
+    fn messages(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+    ) -> Vec<(Option<PromptRole>, Node<'tree>)> {
+        extract_messages(value, source)
+    }
+
+    fn is_conversation(&self, value: &Node<'tree>, source: &str) -> bool {
+        extract_message(value, source).is_none()
+    }
+
+    fn is_string_like(&self, value: &Node<'tree>) -> bool {
+        is_string_like(value)
+    }
+
+    fn create_prompt(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+        filename: &str,
+        call: Span,
+        binding: &PromptBinding,
+        prompts: &mut Vec<Prompt>,
+    ) {
+        create_prompt_from_string(
+            value,
+            source,
+            filename,
+            call.0,
+            call.1,
+            self.comments,
+            &[],
+            binding,
+            prompts,
+        );
+    }
+}
+
+/// Recursively resolve the identifiers referencing prompt bindings, e.g.,
+/// `Send(userPrompt)`, tracking the method, lambda and class scopes.
+fn process_references(node: &Node, source: &str, references: &mut PromptReferences) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
+    let kind = node.kind();
+    let is_scope = matches!(
+        kind,
+        "method_declaration"
+            | "constructor_declaration"
+            | "local_function_statement"
+            | "lambda_expression"
+            | "class_declaration"
+            | "struct_declaration"
+    );
+    if is_scope {
+        references.enter_scope();
+        // Parameters shadow the outer bindings
+        if let Some(parameters) = node.child_by_field_name("parameters") {
+            for name in lambda_or_function_parameters(&parameters, source) {
+                references.shadow(&name);
+            }
+        }
+    }
+
+    match kind {
+        "variable_declarator" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                if let Ok(text) = name.utf8_text(source.as_bytes()) {
+                    references
+                        .declaration(text, (name.start_byte() as u32, name.end_byte() as u32));
+                }
+            }
+        }
+        "identifier" => {
+            if let (Some(reference_kind), Ok(name)) =
+                (reference_kind(node), node.utf8_text(source.as_bytes()))
+            {
+                references.identifier(
+                    name,
+                    (node.start_byte() as u32, node.end_byte() as u32),
+                    reference_kind,
+                );
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        process_references(&child, source, references);
+    }
+
+    if is_scope {
+        references.exit_scope();
+    }
+}
+
+/// Collect the parameter names including the implicit lambda parameter,
+/// e.g., `p` for `p => p.Trim()`.
+fn lambda_or_function_parameters(node: &Node, source: &str) -> Vec<String> {
+    if node.kind() == "implicit_parameter" {
+        return node
+            .utf8_text(source.as_bytes())
+            .map(str::to_string)
+            .into_iter()
+            .collect();
+    }
+    function_parameters(node, source)
+}
+
+/// Get the reference kind by the identifier position. Member, type and
+/// named argument names aren't references.
+fn reference_kind(node: &Node) -> Option<PromptReferenceKind> {
+    let parent = node.parent()?;
+    let field = |name: &str| parent.child_by_field_name(name) == Some(*node);
+    match parent.kind() {
+        "method_declaration"
+        | "constructor_declaration"
+        | "local_function_statement"
+        | "class_declaration"
+        | "struct_declaration"
+        | "parameter"
+        | "member_access_expression"
+        | "argument"
+            if field("name") =>
+        {
+            None
+        }
+        "assignment_expression" if field("left") => Some(PromptReferenceKind::Reassign),
+        "argument" => Some(PromptReferenceKind::Argument),
+        _ => Some(PromptReferenceKind::Read),
+    }
+}
+
 /// Check if a node represents a string literal or interpolated string.
 fn is_string_like(node: &Node) -> bool {
     matches!(
@@ -551,6 +1161,7 @@ fn is_string_like(node: &Node) -> bool {
 }
 
 /// Create a prompt from a string node.
+#[allow(clippy::too_many_arguments)]
 fn create_prompt_from_string(
     string_node: &Node,
     source: &str,
@@ -559,6 +1170,7 @@ fn create_prompt_from_string(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Calculate spans
@@ -582,28 +1194,19 @@ fn create_prompt_from_string(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
-/// Represents a segment in a string concatenation expression
-#[derive(Debug, Clone)]
-enum ConcatSegment {
-    /// String literal segment with its span
-    String(SpanShape),
-    /// Variable identifier segment with its span
-    Variable(SpanShape),
-    /// Primitive value (number, boolean) that should be stringified
-    Primitive(SpanShape),
-    /// Other expression types (objects, arrays, etc.) that we don't handle
-    Other,
-}
-
 /// Process a binary expression as concatenation
+#[allow(clippy::too_many_arguments)]
 fn process_concatenation(
     binary_node: &Node,
     source: &str,
@@ -612,265 +1215,78 @@ fn process_concatenation(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
-    // Check if this is an addition binary expression (+ operator)
+    // Check if this is a concatenation binary expression (+ operator)
     let operator_node = binary_node.child_by_field_name("operator")?;
     let operator_text = operator_node.utf8_text(source.as_bytes()).ok()?;
     if operator_text != "+" {
         return None;
     }
 
-    // Extract segments from the binary expression tree
     let segments = extract_concat_segments(binary_node, source);
+    let outer = (
+        binary_node.start_byte() as u32,
+        binary_node.end_byte() as u32,
+    );
+    let ConcatContent {
+        span,
+        vars,
+        content,
+    } = concat_content(source, outer, "+", &segments)?;
 
-    // Check if we have any non-string/non-identifier segments (objects, arrays, etc.)
-    // In that case, don't treat this as a prompt
-    if segments.iter().any(|s| matches!(s, ConcatSegment::Other)) {
-        return None;
-    }
-
-    if segments.is_empty() {
-        return None;
-    }
-
-    // Build synthetic span for the concatenated result
-    let outer = (binary_node.start_byte() as u32, binary_node.end_byte() as u32);
-    let inner_start = match segments.first() {
-        Some(ConcatSegment::String(span)) => span.inner.0,
-        Some(ConcatSegment::Variable(span) | ConcatSegment::Primitive(span)) => span.outer.0,
-        _ => outer.0,
-    };
-    let inner_end = match segments.last() {
-        Some(ConcatSegment::String(span)) => span.inner.1,
-        Some(ConcatSegment::Variable(span) | ConcatSegment::Primitive(span)) => span.outer.1,
-        _ => outer.1,
-    };
-    let inner = (inner_start, inner_end);
-    let span = SpanShape { outer, inner };
-
-    // Extract vars (variables only, not primitives)
-    let vars: Vec<PromptVar> = segments
-        .iter()
-        .filter_map(|seg| match seg {
-            ConcatSegment::Variable(var_span) => Some(PromptVar {
-                span: var_span.clone(),
-            }),
-            _ => None,
-        })
-        .collect();
-
-    // Build content tokens from segments
-    let content = build_concat_content_tokens(&segments);
-
-    // Calculate enclosure
-    let enclosure_start = comments.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
-    let enclosure = (enclosure_start, stmt_end);
-
+    let enclosure_start = comments
+        .get_any_leading_start(stmt_start)
+        .unwrap_or(stmt_start);
     Some(Prompt {
         file: filename.to_string(),
         span,
-        enclosure,
+        enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
 /// Extract concatenation segments from a binary expression
 fn extract_concat_segments(node: &Node, source: &str) -> Vec<ConcatSegment> {
-    if node.kind() == "binary_expression" {
-        // Check if it's a + operator
-        if let Some(operator_node) = node.child_by_field_name("operator") {
-            if let Ok(operator_text) = operator_node.utf8_text(source.as_bytes()) {
-                if operator_text == "+" {
-                    let mut segments = Vec::new();
-                    if let Some(left) = node.child_by_field_name("left") {
-                        segments.extend(extract_concat_segments(&left, source));
-                    }
-                    if let Some(right) = node.child_by_field_name("right") {
-                        segments.extend(extract_concat_segments(&right, source));
-                    }
-                    return segments;
-                }
-            }
+    let operator = node
+        .child_by_field_name("operator")
+        .and_then(|operator| operator.utf8_text(source.as_bytes()).ok());
+    if node.kind() == "binary_expression" && operator == Some("+") {
+        let mut segments = Vec::new();
+        if let Some(left) = node.child_by_field_name("left") {
+            segments.extend(extract_concat_segments(&left, source));
+        }
+        if let Some(right) = node.child_by_field_name("right") {
+            segments.extend(extract_concat_segments(&right, source));
         }
-        return vec![];
+        return segments;
     }
 
+    let span = (node.start_byte() as u32, node.end_byte() as u32);
     match node.kind() {
         "string_literal" | "verbatim_string_literal" | "interpolated_string_expression" => {
-            let span = span_shape_string_like(node, source);
-            vec![ConcatSegment::String(span)]
-        }
-        "identifier" | "invocation_expression" | "member_access_expression" | "element_access_expression" => {
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            let outer_expanded = expand_to_operators(outer, source);
-            vec![ConcatSegment::Variable(SpanShape {
-                outer: outer_expanded,
-                inner,
-            })]
-        }
-        "integer_literal" | "real_literal" | "boolean_literal" | "true_literal" | "false_literal" => {
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            let outer_expanded = expand_to_operators(outer, source);
-            vec![ConcatSegment::Primitive(SpanShape {
-                outer: outer_expanded,
-                inner,
-            })]
-        }
-        // Objects, arrays, new expressions, etc. - mark as "other" to skip prompt detection
-        "object_creation_expression" | "array_creation_expression" | "implicit_array_creation_expression"
-        | "initializer_expression" | "anonymous_object_creation_expression" => {
-            vec![ConcatSegment::Other]
+            vec![ConcatSegment::String(span_shape_string_like(node, source))]
         }
-        _ => vec![],
+        "identifier"
+        | "invocation_expression"
+        | "member_access_expression"
+        | "element_access_expression" => vec![ConcatSegment::Variable(span)],
+        "integer_literal" | "real_literal" | "boolean_literal" | "true_literal"
+        | "false_literal" => vec![ConcatSegment::Primitive(span)],
+        // Objects, arrays and other expressions can't be stringified
+        _ => vec![ConcatSegment::Other],
     }
 }
 
-/// Expand span to include surrounding operators and spaces
-fn expand_to_operators(span: (u32, u32), source: &str) -> (u32, u32) {
-    let (start, end) = span;
-    let mut new_start = start;
-    let mut new_end = end;
-
-    let code_bytes = source.as_bytes();
-
-    // Expand left to include " + "
-    let mut pos = start.saturating_sub(1) as usize;
-    while pos > 0 {
-        match code_bytes.get(pos) {
-            Some(b' ') | Some(b'\t') => pos -= 1,
-            Some(b'+') => {
-                new_start = pos as u32;
-                // Also consume space before +
-                if pos > 0 && matches!(code_bytes.get(pos - 1), Some(b' ') | Some(b'\t')) {
-                    new_start = (pos - 1) as u32;
-                }
-                break;
-            }
-            _ => break,
-        }
-    }
-
-    // Expand right to include " + "
-    let mut pos = end as usize;
-    while pos < code_bytes.len() {
-        match code_bytes.get(pos) {
-            Some(b' ') | Some(b'\t') => pos += 1,
-            Some(b'+') => {
-                new_end = (pos + 1) as u32;
-                // Also consume space after +
-                if pos + 1 < code_bytes.len()
-                    && matches!(code_bytes.get(pos + 1), Some(b' ') | Some(b'\t'))
-                {
-                    new_end = (pos + 2) as u32;
-                }
-                break;
-            }
-            _ => break,
-        }
-    }
-
-    (new_start, new_end)
-}
-
-/// Build content tokens from concatenation segments
-fn build_concat_content_tokens(segments: &[ConcatSegment]) -> Vec<PromptContentToken> {
-    let mut var_idx = 0u32;
-    segments
-        .iter()
-        .map(|seg| match seg {
-            ConcatSegment::String(span) => {
-                PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                })
-            }
-            ConcatSegment::Variable(span) => {
-                let token = PromptContentToken::PromptContentTokenVar(PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: span.inner,
-                    index: var_idx,
-                });
-                var_idx += 1;
-                token
-            }
-            ConcatSegment::Primitive(span) => {
-                PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                })
-            }
-            ConcatSegment::Other => {
-                // This shouldn't happen as we filter these out earlier
-                PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (0, 0),
-                })
-            }
-        })
-        .collect()
-}
-
-/// Build content tokens from span and variables.
-/// For prompts without variables, returns a single str token.
-/// For prompts with variables, returns interleaved str and var tokens.
-fn build_content_tokens(span: &SpanShape, vars: &[PromptVar]) -> Vec<PromptContentToken> {
-    if vars.is_empty() {
-        // Simple case: single str token
-        return vec![PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: span.inner,
-            },
-        )];
-    }
-
-    let mut tokens = Vec::new();
-    let mut pos = span.inner.0;
-
-    for (var_idx, var) in vars.iter().enumerate() {
-        // Add str token before variable (if any content)
-        if pos < var.span.outer.0 {
-            tokens.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (pos, var.span.outer.0),
-                },
-            ));
-        }
-
-        // Add var token
-        tokens.push(PromptContentToken::PromptContentTokenVar(
-            PromptContentTokenVar {
-                r#type: PromptContentTokenVarTypeVar,
-                span: var.span.outer,
-                index: var_idx as u32,
-            },
-        ));
-
-        pos = var.span.outer.1;
-    }
-
-    // Add trailing str token (if any content)
-    if pos < span.inner.1 {
-        tokens.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (pos, span.inner.1),
-            },
-        ));
-    }
-
-    tokens
-}
-
 // Format function support
 
 /// Process String.Format call: String.Format("Hello {0}", name)
@@ -883,6 +1299,7 @@ fn process_string_format(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Get the member access (String.Format part)
     let func_node = node.child_by_field_name("function")?;
@@ -928,62 +1345,14 @@ fn process_string_format(
         return None;
     }
     
-    // Build vars from remaining arguments
-    let mut vars = Vec::new();
-    for (arg_idx, arg_node) in arg_nodes.iter().skip(1).enumerate() {
-        if arg_idx >= placeholders.len() {
-            break;
-        }
-        
-        let arg_start = arg_node.start_byte() as u32;
-        let arg_end = arg_node.end_byte() as u32;
-        vars.push(PromptVar {
-            span: SpanShape {
-                outer: (arg_start, arg_end),
-                inner: (arg_start, arg_end),
-            },
-        });
-    }
-    
-    // Build content tokens
-    let mut content = Vec::new();
-    let mut pos = 0usize;
-    let format_inner_start = format_str_span.inner.0;
-    
-    for (placeholder_idx, (start, end)) in placeholders.iter().enumerate() {
-        // Add string token before placeholder
-        if pos < *start {
-            content.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (format_inner_start + pos as u32, format_inner_start + *start as u32),
-                },
-            ));
-        }
-        
-        // Add var token for placeholder (if we have a corresponding arg)
-        if placeholder_idx < vars.len() {
-            content.push(PromptContentToken::PromptContentTokenVar(
-                PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: (format_inner_start + *start as u32, format_inner_start + *end as u32),
-                    index: placeholder_idx as u32,
-                },
-            ));
-        }
-        
-        pos = *end;
-    }
-    
-    // Add trailing string token
-    if pos < format_str_content.len() {
-        content.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (format_inner_start + pos as u32, format_str_span.inner.1),
-            },
-        ));
-    }
+    // Build vars and content tokens from the arguments
+    let arguments: Vec<_> = arg_nodes
+        .iter()
+        .skip(1)
+        .map(|arg_node| (arg_node.start_byte() as u32, arg_node.end_byte() as u32))
+        .collect();
+    let (vars, content) =
+        format_content(source, format_str_span.inner, &placeholders, &arguments);
     
     let enclosure_start = comments.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
     
@@ -996,24 +1365,27 @@ fn process_string_format(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
-/// Parse C# format placeholders: {0}, {1}, {2}, etc.
+/// Parse C# format placeholders: {0}, {1}, {2}, etc. Returns the byte ranges.
 fn parse_csharp_placeholders(format_str: &str) -> Vec<(usize, usize)> {
     let mut placeholders = Vec::new();
-    let chars: Vec<char> = format_str.chars().collect();
+    let chars = format_str.as_bytes();
     let mut i = 0;
     
     while i < chars.len() {
-        if chars[i] == '{' {
+        if chars[i] == b'{' {
             // Check for escaped {{
-            if i + 1 < chars.len() && chars[i + 1] == '{' {
+            if i + 1 < chars.len() && chars[i + 1] == b'{' {
                 i += 2;
                 continue;
             }
@@ -1022,13 +1394,13 @@ fn parse_csharp_placeholders(format_str: &str) -> Vec<(usize, usize)> {
             i += 1;
             
             // Find closing }
-            while i < chars.len() && chars[i] != '}' {
+            while i < chars.len() && chars[i] != b'}' {
                 i += 1;
             }
             
-            if i < chars.len() && chars[i] == '}' {
+            if i < chars.len() && chars[i] == b'}' {
                 // Check for escaped }}
-                if i + 1 < chars.len() && chars[i + 1] == '}' {
+                if i + 1 < chars.len() && chars[i + 1] == b'}' {
                     i += 2;
                     continue;
                 }
@@ -1054,6 +1426,7 @@ fn process_array(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     let mut vars = Vec::new();
     let mut content = Vec::new();
@@ -1087,14 +1460,13 @@ fn process_array(
                             PromptContentTokenStr {
                                 r#type: PromptContentTokenStrTypeStr,
                                 span: span.inner,
+                                escapes: None,
                             },
                         ));
                     }
                     "identifier" => {
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape { outer, inner: outer },
-                        });
+                        vars.push(prompt_var(source, SpanShape { outer, inner: outer }, outer));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1106,9 +1478,7 @@ fn process_array(
                     }
                     "invocation_expression" | "member_access_expression" => {
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape { outer, inner: outer },
-                        });
+                        vars.push(prompt_var(source, SpanShape { outer, inner: outer }, outer));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1153,11 +1523,14 @@ fn process_array(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -1171,6 +1544,7 @@ fn process_string_join(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if it's a member_access_expression (String.Join)
     let mut member_node = None;
@@ -1291,6 +1665,7 @@ fn process_string_join(
                             content.push(PromptContentToken::PromptContentTokenJoint(
                                 PromptContentTokenJoint {
                                     r#type: PromptContentTokenJointTypeJoint,
+                                    escapes: None,
                                 },
                             ));
                         }
@@ -1300,6 +1675,7 @@ fn process_string_join(
                             PromptContentTokenStr {
                                 r#type: PromptContentTokenStrTypeStr,
                                 span: span.inner,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1308,14 +1684,13 @@ fn process_string_join(
                             content.push(PromptContentToken::PromptContentTokenJoint(
                                 PromptContentTokenJoint {
                                     r#type: PromptContentTokenJointTypeJoint,
+                                    escapes: None,
                                 },
                             ));
                         }
                         first = false;
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape { outer, inner: outer },
-                        });
+                        vars.push(prompt_var(source, SpanShape { outer, inner: outer }, outer));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1361,7 +1736,10 @@ fn process_string_join(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint,
+        role: None,
+        references: None,
     })
 }
@@ -1,5 +1,6 @@
 use tree_sitter::Node;
-use volumen_types::{PromptVar, SpanShape};
+use volumen_parser_core::{EscapeStyle, interpolation_var};
+use volumen_types::{PromptVar, Span, SpanShape};
 
 /// Calculate outer and inner spans for a string-like node.
 /// For C#, this handles string literals, interpolated strings, and verbatim strings.
@@ -82,12 +83,31 @@ pub fn extract_interpolation_vars(node: &Node, source: &str) -> Vec<PromptVar> {
                 let inner_start = outer_start + 1; // Skip {
                 let inner_end = outer_end - 1; // Skip }
 
-                vars.push(PromptVar {
-                    span: SpanShape {
+                // Expression is followed by optional alignment and format
+                // clauses, e.g., `{price,10:N2}`
+                let mut expr_cursor = child.walk();
+                let expression = child
+                    .named_children(&mut expr_cursor)
+                    .find(|node| {
+                        !matches!(
+                            node.kind(),
+                            "interpolation_brace"
+                                | "interpolation_alignment_clause"
+                                | "interpolation_format_clause"
+                        )
+                    })
+                    .map_or((inner_start, inner_end), |node| {
+                        (node.start_byte() as u32, node.end_byte() as u32)
+                    });
+
+                vars.push(interpolation_var(
+                    source,
+                    SpanShape {
                         outer: (outer_start, outer_end),
                         inner: (inner_start, inner_end),
                     },
-                });
+                    expression,
+                ));
             }
 
             if !cursor.goto_next_sibling() {
@@ -98,3 +118,37 @@ pub fn extract_interpolation_vars(node: &Node, source: &str) -> Vec<PromptVar> {
 
     vars
 }
+
+/// Resolve escaping rules of the string literal containing the span. C#
+/// verbatim strings (`@"..."`) double quotes instead of backslash escapes, and
+/// interpolated strings (`$"..."`) double braces.
+pub fn escape_style(root: &Node, source: &str, span: Span) -> EscapeStyle {
+    let mut node = root.descendant_for_byte_range(span.0 as usize, span.1 as usize);
+    while let Some(current) = node {
+        match current.kind() {
+            "string_literal" => {
+                return EscapeStyle::Cs {
+                    verbatim: false,
+                    interpolated: false,
+                };
+            }
+            "verbatim_string_literal" => {
+                return EscapeStyle::Cs {
+                    verbatim: true,
+                    interpolated: false,
+                };
+            }
+            "interpolated_string_expression" => {
+                let text = &source[current.start_byte()..current.end_byte()];
+                let prefix = &text[..text.find('"').unwrap_or(0)];
+                return EscapeStyle::Cs {
+                    verbatim: prefix.contains('@'),
+                    interpolated: true,
+                };
+            }
+            "raw_string_literal" => return EscapeStyle::None,
+            _ => node = current.parent(),
+        }
+    }
+    EscapeStyle::None
+}
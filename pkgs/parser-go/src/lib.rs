@@ -1,51 +1,87 @@
 mod comments;
-mod scope;
 mod spans;
 
 use comments::CommentTracker;
-use scope::ScopeTracker;
-use spans::span_shape_string_like;
+use spans::{escape_style, span_shape_string_like};
 use tree_sitter::{Node, Parser, Tree};
 pub use volumen_parser_core::VolumenParser;
+use volumen_parser_core::{
+    collect_diagnostics, concat_content, error_diagnostic, file_size_error, format_content,
+    is_content_key, is_role_key, parse_result, parse_role_value, printf_placeholders,
+    process_call_sites, prompt_group, prompt_var, resolve_escapes, CallArgument, CallSiteSyntax,
+    ConcatContent, ConcatSegment, PromptDetector, PromptFunction, PromptReferences, ScopeTracker,
+};
+
 use volumen_types::*;
 
-pub struct ParserGo {}
+pub struct ParserGo {
+    parser: Parser,
+}
 
-impl VolumenParser for ParserGo {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        // Initialize Tree-sitter parser
+impl ParserGo {
+    pub fn new() -> Self {
         let mut parser = Parser::new();
         parser
             .set_language(&tree_sitter_go::LANGUAGE.into())
             .expect("Failed to load Go grammar");
+        Self { parser }
+    }
+}
+
+impl Default for ParserGo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VolumenParser for ParserGo {
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        // Skip sources exceeding the maximum file size
+        if let Some(result) =
+            file_size_error(source, options, ParseLanguage::Go, None, "tree-sitter")
+        {
+            return result;
+        }
 
         // Parse source
-        let tree = match parser.parse(source, None) {
+        let tree = match self.parser.parse(source, None) {
             Some(tree) => tree,
             None => {
                 return ParseResult::ParseResultError(ParseResultError {
                     state: ParseResultErrorStateError,
                     error: "Failed to parse source".to_string(),
+                    diagnostics: vec![error_diagnostic(
+                        "tree-sitter",
+                        "parse-failed",
+                        "Failed to parse source".to_string(),
+                        (0, source.len() as u32),
+                    )],
+                    language: Some(ParseLanguage::Go),
+                    dialect: None,
+                    detection: None,
+                    parser: Some("tree-sitter".to_string()),
                 });
             }
         };
 
-        // Check for parse errors
+        // Collect parse errors. Tree-sitter recovers from errors, so the tree
+        // is still processed, skipping error nodes.
         let root = tree.root_node();
+        let mut diagnostics = Vec::new();
         if root.has_error() {
-            let error_msg = format_tree_sitter_error(&root, source);
-            return ParseResult::ParseResultError(ParseResultError {
-                state: ParseResultErrorStateError,
-                error: error_msg,
-            });
+            collect_diagnostics(&root, source, &mut diagnostics);
         }
 
+        // Resolve prompt detection rules
+        let detector = PromptDetector::new(options);
+
         // Extract comments
-        let comment_tracker = CommentTracker::new(&tree, source);
+        let comment_tracker = CommentTracker::new(&tree, source, detector.clone());
 
         // Initialize state
         let mut prompts = Vec::new();
-        let mut scope_tracker = ScopeTracker::new();
+        let mut groups = Vec::new();
+        let mut scope_tracker = ScopeTracker::new(detector.clone());
 
         // Process tree
         process_tree(
@@ -56,55 +92,48 @@ impl VolumenParser for ParserGo {
             &comment_tracker,
             &mut scope_tracker,
             &mut prompts,
+            &mut groups,
         );
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
-            prompts,
-        })
-    }
-}
+        // Detect prompts passed to LLM SDK call sites
+        let call_sites = CallSites {
+            comments: &comment_tracker,
+        };
+        process_call_sites(
+            &root,
+            source,
+            filename,
+            &detector,
+            &call_sites,
+            &mut prompts,
+            &mut groups,
+        );
 
-/// Format tree-sitter parse errors into a readable message.
-fn format_tree_sitter_error(node: &Node, source: &str) -> String {
-    let mut errors = Vec::new();
-    collect_errors(node, source, &mut errors);
-    if errors.is_empty() {
-        "Parse error: unknown".to_string()
-    } else {
-        format!("Parse errors:\n{}", errors.join("\n"))
-    }
-}
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = PromptReferences::new(&prompts);
+        process_references(&root, source, &mut references);
+        references.apply(&mut prompts);
 
-fn collect_errors(node: &Node, source: &str, errors: &mut Vec<String>) {
-    if node.is_error() || node.is_missing() {
-        let start = node.start_position();
-        let error_text = node
-            .utf8_text(source.as_bytes())
-            .unwrap_or("<unknown>")
-            .chars()
-            .take(50)
-            .collect::<String>();
-        errors.push(format!(
-            "  Line {}, Column {}: {}",
-            start.row + 1,
-            start.column + 1,
-            error_text
-        ));
-    }
+        // Decode escape sequences in string tokens
+        resolve_escapes(&mut prompts, source, |span| escape_style(&root, span));
 
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            collect_errors(&cursor.node(), source, errors);
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
+        // Assign role hints from annotations, e.g., `// @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        parse_result(
+            ParseLanguage::Go,
+            None,
+            "tree-sitter",
+            prompts,
+            groups,
+            Vec::new(),
+            diagnostics,
+        )
     }
 }
 
 /// Process the entire syntax tree to extract prompts.
+#[allow(clippy::too_many_arguments)]
 fn process_tree(
     _tree: &Tree,
     root: &Node,
@@ -113,9 +142,10 @@ fn process_tree(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Recursively traverse the tree
-    traverse_node(root, source, filename, comments, scopes, prompts);
+    traverse_node(root, source, filename, comments, scopes, prompts, groups);
 }
 
 /// Recursively traverse nodes to find assignments and manage scopes.
@@ -126,11 +156,24 @@ fn traverse_node(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
     let kind = node.kind();
 
-    // Handle scope boundaries (function, method)
-    if kind == "function_declaration" || kind == "method_declaration" {
+    // Handle function scopes, tracking prompt functions returning prompts
+    if kind == "function_declaration" || kind == "method_declaration" || kind == "func_literal" {
+        process_function(node, source, filename, comments, scopes, prompts, groups);
+        return;
+    }
+
+    // Handle block scopes, e.g., `{ prompt := "..." }`, and `if`/`for`
+    // statement variables
+    if matches!(kind, "block" | "if_statement" | "for_statement") {
         scopes.enter_scope();
 
         // Process children
@@ -138,7 +181,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             loop {
                 let child = cursor.node();
-                traverse_node(&child, source, filename, comments, scopes, prompts);
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -151,7 +194,7 @@ fn traverse_node(
 
     // Handle short variable declarations (x := value)
     if kind == "short_var_declaration" {
-        process_short_var_declaration(node, source, filename, comments, scopes, prompts);
+        process_short_var_declaration(node, source, filename, comments, scopes, prompts, groups);
         return;
     }
 
@@ -167,12 +210,22 @@ fn traverse_node(
         return;
     }
 
+    // Handle prompt function returns: `return "Hello, " + name + "!"`. The
+    // first value is the prompt, e.g., for `return prompt, nil`.
+    if kind == "return_statement" {
+        if let Some(value) = node.named_child(0).and_then(|values| values.named_child(0)) {
+            process_function_return(
+                &value, node, source, filename, comments, scopes, prompts, groups,
+            );
+        }
+    }
+
     // Recursively process children
     let mut cursor = node.walk();
     if cursor.goto_first_child() {
         loop {
             let child = cursor.node();
-            traverse_node(&child, source, filename, comments, scopes, prompts);
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
             if !cursor.goto_next_sibling() {
                 break;
             }
@@ -180,6 +233,124 @@ fn traverse_node(
     }
 }
 
+/// Process a function or method declaration scope. Declarations with
+/// prompt-like names or leading `@prompt` annotations are prompt functions,
+/// so their returned values are detected as prompts. Function literals
+/// never are, so their returns don't leak into the enclosing function.
+fn process_function(
+    node: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let stmt_start = node.start_byte() as u32;
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|parameters| function_parameters(&parameters, source))
+        .unwrap_or_default();
+    let function = node.child_by_field_name("name").and_then(|name| {
+        // Opt-out annotations exclude the function from prompt functions
+        if comments.has_ignore_annotation(stmt_start, name.end_byte() as u32) {
+            return None;
+        }
+        PromptFunction::new(
+            scopes.detector(),
+            source,
+            (name.start_byte() as u32, name.end_byte() as u32),
+            parameters.clone(),
+            comments.collect_adjacent_leading(stmt_start),
+        )
+    });
+    scopes.enter_function(function);
+
+    // Parameters shadow the outer identifiers with the same names
+    for parameter in &parameters {
+        scopes.declare(parameter);
+    }
+
+    // Process children
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    scopes.exit_function();
+}
+
+/// Collect the function parameter names, e.g., `user`, `a` and `b` for
+/// `func buildPrompt(user User, a, b string)`.
+fn function_parameters(node: &Node, source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut cursor = node.walk();
+    for parameter in node.named_children(&mut cursor) {
+        let mut name_cursor = parameter.walk();
+        for name in parameter.children_by_field_name("name", &mut name_cursor) {
+            if let Ok(name) = name.utf8_text(source.as_bytes()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Process a value returned from the enclosing prompt function. The value is
+/// processed like a prompt assignment bound to the function name, marking
+/// the variables that come from the function parameters.
+#[allow(clippy::too_many_arguments)]
+fn process_function_return(
+    value: &Node,
+    stmt: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let Some(function) = scopes.current_function().cloned() else {
+        return;
+    };
+    let stmt_start = stmt.start_byte() as u32;
+    let stmt_end = stmt.end_byte() as u32;
+
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
+    // Statement annotations take precedence over the function annotations
+    let mut annotations = comments.collect_adjacent_leading(stmt_start);
+    annotations.extend(comments.collect_inline_prompt(stmt_start, stmt_end));
+    if annotations.is_empty() {
+        annotations = function.annotations.clone();
+    }
+
+    let prompts_len = prompts.len();
+    process_identifier_assignment(
+        &function.binding.name,
+        value,
+        true,
+        &annotations,
+        &function.binding,
+        stmt_start,
+        stmt_end,
+        source,
+        filename,
+        comments,
+        scopes,
+        prompts,
+        groups,
+    );
+    function.mark_parameter_vars(prompts, prompts_len);
+}
+
 /// Process a short variable declaration (x := value).
 fn process_short_var_declaration(
     node: &Node,
@@ -188,17 +359,23 @@ fn process_short_var_declaration(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     let mut all_annotations = leading_annotations.clone();
     all_annotations.extend(inline_annotations);
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
 
     // Get left and right sides
     let left = match node.child_by_field_name("left") {
@@ -215,6 +392,12 @@ fn process_short_var_declaration(
     let mut identifiers = Vec::new();
     extract_identifiers(&left, source, &mut identifiers);
 
+    // Declarations shadow the outer identifiers with the same names, while
+    // the already declared ones are reassigned
+    for (ident_name, _) in &identifiers {
+        scopes.declare(ident_name);
+    }
+
     // Unwrap expression_list if present (Go wraps right side in expression_list)
     let actual_right = if right.kind() == "expression_list" {
         // Get first child of expression_list
@@ -223,138 +406,175 @@ fn process_short_var_declaration(
         right
     };
 
-    // Process the right side - if it's a single string, match with first identifier
-    if is_string_like(&actual_right) && !identifiers.is_empty() {
-        let ident_name = &identifiers[0];
-        let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
+    // Process the right side matched with the first identifier
+    if let Some((ident_name, ident_node)) = identifiers.first() {
+        let binding = binding_from_node(ident_node, source, PromptBindingKind::Local);
+        process_identifier_assignment(
+            ident_name,
+            &actual_right,
+            has_prompt_annotation,
+            &all_annotations,
+            &binding,
+            stmt_start,
+            stmt_end,
+            source,
+            filename,
+            comments,
+            scopes,
+            prompts,
+            groups,
+        );
+    }
+}
+
+/// Process a value assigned to an identifier: a string, concatenation,
+/// `strings.Join` or `fmt.Sprintf` call, chat messages or a string slice.
+#[allow(clippy::too_many_arguments)]
+fn process_identifier_assignment(
+    ident_name: &str,
+    value: &Node,
+    has_prompt_annotation: bool,
+    annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
+    stmt_start: u32,
+    stmt_end: u32,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let is_literal = value.kind() == "composite_literal" || value.kind() == "slice_literal";
+    if !is_string_like(value)
+        && !is_literal
+        && value.kind() != "binary_expression"
+        && value.kind() != "call_expression"
+    {
+        return;
+    }
+
+    // Chat messages are prompts by structure
+    let messages = if is_literal {
+        extract_messages(value, source)
+    } else {
+        Vec::new()
+    };
+    let is_prompt = is_prompt_variable(
+        ident_name,
+        has_prompt_annotation || !messages.is_empty(),
+        scopes,
+    );
+    if !is_prompt {
+        return;
+    }
 
-        if is_prompt {
-            scopes.mark_prompt_ident(ident_name);
+    scopes.mark_prompt_ident(ident_name);
 
-            if has_prompt_annotation {
-                scopes.store_def_annotation(ident_name, all_annotations.clone());
-            }
+    if has_prompt_annotation {
+        scopes.store_def_annotation(ident_name, annotations.to_vec());
+    }
 
-            // Annotations from comment tracker are already validated to contain @prompt
-            // Get annotations (from current statement or from definition)
-            let final_annotations = if !all_annotations.is_empty() {
-                all_annotations.clone()
-            } else {
-                scopes.get_def_annotation(ident_name).unwrap_or_default()
-            };
+    // Annotations from comment tracker are already validated to contain @prompt
+    // Get annotations (from current statement or from definition)
+    let final_annotations = if !annotations.is_empty() {
+        annotations.to_vec()
+    } else {
+        scopes.get_def_annotation(ident_name).unwrap_or_default()
+    };
 
+    if is_string_like(value) {
+        create_prompt_from_string(
+            value,
+            source,
+            filename,
+            stmt_start,
+            stmt_end,
+            comments,
+            &final_annotations,
+            binding,
+            prompts,
+        );
+    } else if value.kind() == "binary_expression" {
+        // Try to process as concatenation
+        if let Some(prompt) = process_concatenation(
+            value,
+            source,
+            filename,
+            stmt_start,
+            stmt_end,
+            comments,
+            &final_annotations,
+            binding,
+        ) {
+            prompts.push(prompt);
+        }
+    } else if value.kind() == "call_expression" {
+        // Try strings.Join first
+        if let Some(prompt) = process_strings_join(
+            value,
+            source,
+            filename,
+            stmt_start,
+            stmt_end,
+            comments,
+            &final_annotations,
+            binding,
+        ) {
+            prompts.push(prompt);
+        // Then try fmt.Sprintf
+        } else if let Some(prompt) = process_sprintf_call(
+            value,
+            source,
+            filename,
+            stmt_start,
+            stmt_end,
+            comments,
+            &final_annotations,
+            binding,
+        ) {
+            prompts.push(prompt);
+        }
+    // Handle messages: []openai.ChatCompletionMessage{{Role: ..., Content: "..."}}
+    } else if !messages.is_empty() {
+        let group_start = prompts.len();
+        for (role, content) in messages {
+            let prompts_len = prompts.len();
             create_prompt_from_string(
-                &actual_right,
+                &content,
                 source,
                 filename,
                 stmt_start,
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
                 prompts,
             );
-        }
-    } else if actual_right.kind() == "binary_expression" && !identifiers.is_empty() {
-        let ident_name = &identifiers[0];
-        let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
-
-        if is_prompt {
-            scopes.mark_prompt_ident(ident_name);
-
-            if has_prompt_annotation {
-                scopes.store_def_annotation(ident_name, all_annotations.clone());
-            }
-
-            let final_annotations = if !all_annotations.is_empty() {
-                all_annotations.clone()
-            } else {
-                scopes.get_def_annotation(ident_name).unwrap_or_default()
-            };
-
-            // Try to process as concatenation
-            if let Some(prompt) = process_concatenation(
-                &actual_right,
-                source,
-                filename,
-                stmt_start,
-                stmt_end,
-                comments,
-                &final_annotations,
-            ) {
-                prompts.push(prompt);
+            for prompt in &mut prompts[prompts_len..] {
+                prompt.role = role;
             }
         }
-    } else if actual_right.kind() == "call_expression" && !identifiers.is_empty() {
-        let ident_name = &identifiers[0];
-        let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
-
-        if is_prompt {
-            scopes.mark_prompt_ident(ident_name);
-
-            if has_prompt_annotation {
-                scopes.store_def_annotation(ident_name, all_annotations.clone());
-            }
 
-            let final_annotations = if !all_annotations.is_empty() {
-                all_annotations.clone()
-            } else {
-                scopes.get_def_annotation(ident_name).unwrap_or_default()
-            };
-
-            // Try strings.Join first
-            if let Some(prompt) = process_strings_join(
-                &actual_right,
-                source,
-                filename,
-                stmt_start,
-                stmt_end,
-                comments,
-                &final_annotations,
-            ) {
-                prompts.push(prompt);
-            // Then try fmt.Sprintf
-            } else if let Some(prompt) = process_sprintf_call(
-                &actual_right,
-                source,
-                filename,
-                stmt_start,
-                stmt_end,
-                comments,
-                &final_annotations,
-            ) {
-                prompts.push(prompt);
-            }
-        }
-    } else if (actual_right.kind() == "composite_literal" || actual_right.kind() == "slice_literal") && !identifiers.is_empty() {
-        let ident_name = &identifiers[0];
-        let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
-
-        if is_prompt {
-            scopes.mark_prompt_ident(ident_name);
-
-            if has_prompt_annotation {
-                scopes.store_def_annotation(ident_name, all_annotations.clone());
-            }
-
-            let final_annotations = if !all_annotations.is_empty() {
-                all_annotations.clone()
-            } else {
-                scopes.get_def_annotation(ident_name).unwrap_or_default()
-            };
-
-            // Try to process as array
-            if let Some(prompt) = process_array(
-                &actual_right,
-                source,
-                filename,
-                stmt_start,
-                stmt_end,
-                comments,
-                &final_annotations,
-            ) {
-                prompts.push(prompt);
+        // Group the messages slice into a conversation
+        if extract_message(value, source).is_none() {
+            let span = (value.start_byte() as u32, value.end_byte() as u32);
+            if let Some(group) = prompt_group(filename, span, binding, prompts, group_start) {
+                groups.push(group);
             }
         }
+    // Otherwise try to process as array
+    } else if let Some(prompt) = process_array(
+        value,
+        source,
+        filename,
+        stmt_start,
+        stmt_end,
+        comments,
+        &final_annotations,
+        binding,
+    ) {
+        prompts.push(prompt);
     }
 }
 
@@ -370,13 +590,18 @@ fn process_var_declaration(
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     let mut all_annotations = leading_annotations.clone();
     all_annotations.extend(inline_annotations);
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
 
     // Find var_spec nodes
     let mut cursor = node.walk();
@@ -416,6 +641,9 @@ fn process_assignment(
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
@@ -475,8 +703,14 @@ fn process_assignment(
     }
 
     // Process each left identifier that's a prompt variable
-    for (idx, (ident_name, _ident_node)) in left_idents.iter().enumerate() {
-        if scopes.is_prompt_ident(ident_name) {
+    for (idx, (ident_name, ident_node)) in left_idents.iter().enumerate() {
+        // Opt-out annotations keep the identifier ignored in reassignments
+        if scopes.is_statement_ignored() {
+            scopes.mark_ignored_ident(ident_name);
+            continue;
+        }
+
+        if scopes.is_prompt_ident(ident_name) || comments.in_prompt_region(stmt_start) {
             // Get corresponding right value (or last if fewer rights than lefts)
             let right_value = if idx < right_values.len() {
                 right_values[idx]
@@ -495,6 +729,8 @@ fn process_assignment(
                     scopes.get_def_annotation(ident_name).unwrap_or_default()
                 };
 
+                let binding = binding_from_node(ident_node, source, PromptBindingKind::Local);
+
                 // Create prompt from the string
                 create_prompt_from_string(
                     &right_value,
@@ -504,6 +740,7 @@ fn process_assignment(
                     stmt_end,
                     comments,
                     &final_annotations,
+                    &binding,
                     prompts,
                 );
             }
@@ -533,6 +770,9 @@ fn process_var_spec(
 
     let ident_name = name_node.utf8_text(source.as_bytes()).unwrap_or("");
 
+    // Declarations shadow the outer identifiers with the same name
+    scopes.declare(ident_name);
+
     // Get value node
     let value_node = match node.child_by_field_name("value") {
         Some(n) => n,
@@ -557,6 +797,8 @@ fn process_var_spec(
             scopes.get_def_annotation(ident_name).unwrap_or_default()
         };
 
+        let binding = binding_from_node(&name_node, source, PromptBindingKind::Local);
+
         if is_string_like(&value_node) {
             create_prompt_from_string(
                 &value_node,
@@ -566,6 +808,7 @@ fn process_var_spec(
                 stmt_end,
                 comments,
                 &final_annotations,
+                &binding,
                 prompts,
             );
         } else if value_node.kind() == "binary_expression" {
@@ -578,6 +821,7 @@ fn process_var_spec(
                 stmt_end,
                 comments,
                 &final_annotations,
+                &binding,
             ) {
                 prompts.push(prompt);
             }
@@ -585,13 +829,200 @@ fn process_var_spec(
     }
 }
 
-/// Check if a variable should be treated as a prompt.
-fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &ScopeTracker) -> bool {
-    ident_name.to_lowercase().contains("prompt")
+/// Check if a variable should be treated as a prompt. Variables in statements
+/// with an opt-out annotation are marked as ignored instead.
+fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &mut ScopeTracker) -> bool {
+    if scopes.is_statement_ignored() {
+        scopes.mark_ignored_ident(ident_name);
+        return false;
+    }
+    scopes.is_prompt_name(ident_name)
         || has_annotation
         || scopes.is_prompt_ident(ident_name)
 }
 
+/// Go LLM SDK call site syntax, e.g., `openai.SystemMessage("...")`.
+struct CallSites<'a> {
+    comments: &'a CommentTracker,
+}
+
+impl<'tree> CallSiteSyntax<'tree> for CallSites<'_> {
+    fn call(&self, node: &Node<'tree>, _source: &str) -> Option<(Span, Node<'tree>)> {
+        if node.kind() != "call_expression" {
+            return None;
+        }
+        let function = node.child_by_field_name("function")?;
+        let arguments = node.child_by_field_name("arguments")?;
+        Some((
+            (function.start_byte() as u32, function.end_byte() as u32),
+            arguments,
+        ))
+    }
+
+    /// Keyed elements of struct and map literal arguments are named.
+    fn arguments(&self, arguments: &Node<'tree>, source: &str) -> Vec<CallArgument<'tree>> {
+        let mut elements = Vec::new();
+        let mut values = Vec::new();
+        let mut cursor = arguments.walk();
+        let arguments = arguments
+            .named_children(&mut cursor)
+            .filter(|argument| argument.kind() != "comment");
+        for (position, argument) in arguments.enumerate() {
+            // Unwrap pointers to literals, e.g., `&openai.ChatCompletionRequest{...}`
+            let value = match argument.kind() {
+                "unary_expression" => argument.child_by_field_name("operand").unwrap_or(argument),
+                _ => argument,
+            };
+            let body = literal_body(&value).filter(|_| {
+                value.kind() == "composite_literal" && extract_message(&value, source).is_none()
+            });
+            if let Some(body) = body {
+                let mut body_cursor = body.walk();
+                elements.extend(
+                    body.named_children(&mut body_cursor)
+                        .filter(|element| element.kind() == "keyed_element"),
+                );
+            } else {
+                values.push(CallArgument::Positional(position, argument));
+            }
+        }
+        values.extend(elements.into_iter().filter_map(|element| {
+            Some(CallArgument::Named(
+                element
+                    .child_by_field_name("key")
+                    .and_then(|key| key.named_child(0))?,
+                element
+                    .child_by_field_name("value")
+                    .and_then(|value| value.named_child(0))?,
+            ))
+        }));
+        values
+    }
+
+    fn messages(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+    ) -> Vec<(Option<PromptRole>, Node<'tree>)> {
+        extract_messages(value, source)
+    }
+
+    fn is_conversation(&self, value: &Node<'tree>, source: &str) -> bool {
+        extract_message(value, source).is_none()
+    }
+
+    fn is_string_like(&self, value: &Node<'tree>) -> bool {
+        is_string_like(value)
+    }
+
+    fn create_prompt(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+        filename: &str,
+        call: Span,
+        binding: &PromptBinding,
+        prompts: &mut Vec<Prompt>,
+    ) {
+        create_prompt_from_string(
+            value,
+            source,
+            filename,
+            call.0,
+            call.1,
+            self.comments,
+            &[],
+            binding,
+            prompts,
+        );
+    }
+}
+
+/// Recursively resolve the identifiers referencing prompt bindings, e.g.,
+/// `send(userPrompt)`, tracking the function scopes.
+fn process_references(node: &Node, source: &str, references: &mut PromptReferences) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
+    let kind = node.kind();
+    let is_scope = matches!(
+        kind,
+        "function_declaration" | "method_declaration" | "func_literal"
+    );
+    if is_scope {
+        references.enter_scope();
+        // Parameters shadow the outer bindings
+        if let Some(parameters) = node.child_by_field_name("parameters") {
+            for name in function_parameters(&parameters, source) {
+                references.shadow(&name);
+            }
+        }
+    }
+
+    match kind {
+        // Declared names, e.g., `userPrompt := "Hi!"` or `var userPrompt = "Hi!"`
+        "short_var_declaration" | "var_spec" | "const_spec" => {
+            let mut names = Vec::new();
+            let mut cursor = node.walk();
+            match node.child_by_field_name("left") {
+                Some(left) => names.extend(left.named_children(&mut cursor)),
+                None => names.extend(node.children_by_field_name("name", &mut cursor)),
+            }
+            for name in names {
+                if let Ok(text) = name.utf8_text(source.as_bytes()) {
+                    references
+                        .declaration(text, (name.start_byte() as u32, name.end_byte() as u32));
+                }
+            }
+        }
+        "identifier" => {
+            if let (Some(reference_kind), Ok(name)) =
+                (reference_kind(node), node.utf8_text(source.as_bytes()))
+            {
+                references.identifier(
+                    name,
+                    (node.start_byte() as u32, node.end_byte() as u32),
+                    reference_kind,
+                );
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        process_references(&child, source, references);
+    }
+
+    if is_scope {
+        references.exit_scope();
+    }
+}
+
+/// Get the reference kind by the identifier position. Function names
+/// aren't references.
+fn reference_kind(node: &Node) -> Option<PromptReferenceKind> {
+    let parent = node.parent()?;
+    match parent.kind() {
+        "function_declaration" => None,
+        "argument_list" => Some(PromptReferenceKind::Argument),
+        "expression_list" => {
+            let is_assigned = parent.parent().is_some_and(|stmt| {
+                stmt.kind() == "assignment_statement"
+                    && stmt.child_by_field_name("left") == Some(parent)
+            });
+            Some(if is_assigned {
+                PromptReferenceKind::Reassign
+            } else {
+                PromptReferenceKind::Read
+            })
+        }
+        _ => Some(PromptReferenceKind::Read),
+    }
+}
+
 /// Check if a node represents a string literal.
 fn is_string_like(node: &Node) -> bool {
     matches!(
@@ -600,11 +1031,76 @@ fn is_string_like(node: &Node) -> bool {
     )
 }
 
+/// Extract chat message roles and string contents from a message struct or
+/// map literal, e.g., `openai.ChatCompletionMessage{Role: "system", Content: "..."}`,
+/// or from a slice of message literals.
+fn extract_messages<'a>(node: &Node<'a>, source: &str) -> Vec<(Option<PromptRole>, Node<'a>)> {
+    let Some(body) = literal_body(node) else {
+        return Vec::new();
+    };
+    if let Some(message) = extract_message(&body, source) {
+        return vec![message];
+    }
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter(|child| child.kind() == "literal_element")
+        .filter_map(|element| element.named_child(0))
+        .filter_map(|value| extract_message(&value, source))
+        .collect()
+}
+
+/// Extract the role and the string content from a message literal. Literals
+/// without the role key aren't messages.
+fn extract_message<'a>(node: &Node<'a>, source: &str) -> Option<(Option<PromptRole>, Node<'a>)> {
+    let body = literal_body(node)?;
+    let mut role = None;
+    let mut content = None;
+    let mut cursor = body.walk();
+    for element in body.named_children(&mut cursor) {
+        if element.kind() != "keyed_element" {
+            continue;
+        }
+        let (Some(key), Some(value)) = (
+            element
+                .child_by_field_name("key")
+                .and_then(|key| key.named_child(0)),
+            element
+                .child_by_field_name("value")
+                .and_then(|value| value.named_child(0)),
+        ) else {
+            continue;
+        };
+        let key_text = key.utf8_text(source.as_bytes()).unwrap_or_default();
+        if is_role_key(key_text) {
+            role = Some(parse_role_value(
+                value.utf8_text(source.as_bytes()).unwrap_or_default(),
+            ));
+        } else if is_content_key(key_text) && is_string_like(&value) {
+            content = Some(value);
+        }
+    }
+    Some((role?, content?))
+}
+
+/// Get the elements body of a composite literal. Elements of slice literals
+/// can omit the type, e.g., `{Role: "user"}`, so the body is the value itself.
+fn literal_body<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    match node.kind() {
+        "composite_literal" => node.child_by_field_name("body"),
+        "literal_value" => Some(*node),
+        _ => None,
+    }
+}
+
 /// Extract identifiers from an expression_list or identifier.
-fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>) {
+fn extract_identifiers<'a>(
+    node: &Node<'a>,
+    source: &str,
+    identifiers: &mut Vec<(String, Node<'a>)>,
+) {
     if node.kind() == "identifier" {
         if let Ok(name) = node.utf8_text(source.as_bytes()) {
-            identifiers.push(name.to_string());
+            identifiers.push((name.to_string(), *node));
         }
         return;
     }
@@ -616,7 +1112,7 @@ fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>)
             let child = cursor.node();
             if child.kind() == "identifier" {
                 if let Ok(name) = child.utf8_text(source.as_bytes()) {
-                    identifiers.push(name.to_string());
+                    identifiers.push((name.to_string(), child));
                 }
             } else {
                 extract_identifiers(&child, source, identifiers);
@@ -628,7 +1124,17 @@ fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>)
     }
 }
 
+/// Create a prompt binding from an identifier node.
+fn binding_from_node(node: &Node, source: &str, kind: PromptBindingKind) -> PromptBinding {
+    PromptBinding {
+        name: node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+        span: (node.start_byte() as u32, node.end_byte() as u32),
+        kind,
+    }
+}
+
 /// Create a prompt from a string node.
+#[allow(clippy::too_many_arguments)]
 fn create_prompt_from_string(
     string_node: &Node,
     source: &str,
@@ -637,6 +1143,7 @@ fn create_prompt_from_string(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Calculate spans
@@ -658,28 +1165,24 @@ fn create_prompt_from_string(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content: vec![PromptContentToken::PromptContentTokenStr(
             PromptContentTokenStr {
                 r#type: PromptContentTokenStrTypeStr,
                 span: content_span,
+                escapes: None,
             }
         )],
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
-/// Represents a segment in a string concatenation expression
-#[derive(Debug, Clone)]
-enum ConcatSegment {
-    String(SpanShape),
-    Variable(SpanShape),
-    Primitive(SpanShape),
-    Other,
-}
-
+#[allow(clippy::too_many_arguments)]
 fn process_concatenation(
     binary_node: &Node,
     source: &str,
@@ -688,7 +1191,9 @@ fn process_concatenation(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
+    // Check if this is a concatenation binary expression (+ operator)
     let operator_node = binary_node.child_by_field_name("operator")?;
     let operator_text = operator_node.utf8_text(source.as_bytes()).ok()?;
     if operator_text != "+" {
@@ -696,162 +1201,65 @@ fn process_concatenation(
     }
 
     let segments = extract_concat_segments(binary_node, source);
-    if segments.iter().any(|s| matches!(s, ConcatSegment::Other)) {
-        return None;
-    }
-    if segments.is_empty() {
-        return None;
-    }
-
-    let outer = (binary_node.start_byte() as u32, binary_node.end_byte() as u32);
-    let inner_start = match segments.first() {
-        Some(ConcatSegment::String(span)) => span.inner.0,
-        Some(ConcatSegment::Variable(span) | ConcatSegment::Primitive(span)) => span.outer.0,
-        _ => outer.0,
-    };
-    let inner_end = match segments.last() {
-        Some(ConcatSegment::String(span)) => span.inner.1,
-        Some(ConcatSegment::Variable(span) | ConcatSegment::Primitive(span)) => span.outer.1,
-        _ => outer.1,
-    };
-    let span = SpanShape { outer, inner: (inner_start, inner_end) };
-
-    let vars: Vec<PromptVar> = segments
-        .iter()
-        .filter_map(|seg| match seg {
-            ConcatSegment::Variable(var_span) => Some(PromptVar { span: var_span.clone() }),
-            _ => None,
-        })
-        .collect();
-
-    let mut var_idx = 0u32;
-    let content = segments
-        .iter()
-        .map(|seg| match seg {
-            ConcatSegment::String(span) => PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                }
-            ),
-            ConcatSegment::Variable(span) => {
-                let token = PromptContentToken::PromptContentTokenVar(
-                    PromptContentTokenVar {
-                        r#type: PromptContentTokenVarTypeVar,
-                        span: span.inner,
-                        index: var_idx,
-                    }
-                );
-                var_idx += 1;
-                token
-            },
-            ConcatSegment::Primitive(span) => PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                }
-            ),
-            ConcatSegment::Other => PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (0, 0),
-                }
-            ),
-        })
-        .collect();
+    let outer = (
+        binary_node.start_byte() as u32,
+        binary_node.end_byte() as u32,
+    );
+    let ConcatContent {
+        span,
+        vars,
+        content,
+    } = concat_content(source, outer, "+", &segments)?;
 
-    let enclosure_start = comments.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
+    let enclosure_start = comments
+        .get_any_leading_start(stmt_start)
+        .unwrap_or(stmt_start);
     Some(Prompt {
         file: filename.to_string(),
         span,
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
-        joint: SpanShape { outer: (0, 0), inner: (0, 0) },
+        joint: SpanShape {
+            outer: (0, 0),
+            inner: (0, 0),
+        },
+        role: None,
+        references: None,
     })
 }
 
 fn extract_concat_segments(node: &Node, source: &str) -> Vec<ConcatSegment> {
-    if node.kind() == "binary_expression" {
-        if let Some(operator_node) = node.child_by_field_name("operator") {
-            if let Ok(operator_text) = operator_node.utf8_text(source.as_bytes()) {
-                if operator_text == "+" {
-                    let mut segments = Vec::new();
-                    if let Some(left) = node.child_by_field_name("left") {
-                        segments.extend(extract_concat_segments(&left, source));
-                    }
-                    if let Some(right) = node.child_by_field_name("right") {
-                        segments.extend(extract_concat_segments(&right, source));
-                    }
-                    return segments;
-                }
-            }
+    let operator = node
+        .child_by_field_name("operator")
+        .and_then(|operator| operator.utf8_text(source.as_bytes()).ok());
+    if node.kind() == "binary_expression" && operator == Some("+") {
+        let mut segments = Vec::new();
+        if let Some(left) = node.child_by_field_name("left") {
+            segments.extend(extract_concat_segments(&left, source));
+        }
+        if let Some(right) = node.child_by_field_name("right") {
+            segments.extend(extract_concat_segments(&right, source));
         }
-        return vec![];
+        return segments;
     }
 
+    let span = (node.start_byte() as u32, node.end_byte() as u32);
     match node.kind() {
         "interpreted_string_literal" | "raw_string_literal" => {
-            let span = span_shape_string_like(node, source);
-            vec![ConcatSegment::String(span)]
+            vec![ConcatSegment::String(span_shape_string_like(node, source))]
         }
         "identifier" | "call_expression" | "selector_expression" => {
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let outer_expanded = expand_to_operators(outer, source);
-            vec![ConcatSegment::Variable(SpanShape { outer: outer_expanded, inner: outer })]
-        }
-        "int_literal" | "float_literal" | "true" | "false" => {
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let outer_expanded = expand_to_operators(outer, source);
-            vec![ConcatSegment::Primitive(SpanShape { outer: outer_expanded, inner: outer })]
+            vec![ConcatSegment::Variable(span)]
         }
-        "slice_literal" | "composite_literal" => vec![ConcatSegment::Other],
-        _ => vec![],
+        "int_literal" | "float_literal" | "true" | "false" => vec![ConcatSegment::Primitive(span)],
+        // Objects, arrays and other expressions can't be stringified
+        _ => vec![ConcatSegment::Other],
     }
 }
 
-fn expand_to_operators(span: (u32, u32), source: &str) -> (u32, u32) {
-    let (start, end) = span;
-    let mut new_start = start;
-    let mut new_end = end;
-    let code_bytes = source.as_bytes();
-
-    let mut pos = start.saturating_sub(1) as usize;
-    while pos > 0 {
-        match code_bytes.get(pos) {
-            Some(b' ') | Some(b'\t') => pos -= 1,
-            Some(b'+') => {
-                new_start = pos as u32;
-                if pos > 0 && matches!(code_bytes.get(pos - 1), Some(b' ') | Some(b'\t')) {
-                    new_start = (pos - 1) as u32;
-                }
-                break;
-            }
-            _ => break,
-        }
-    }
-
-    let mut pos = end as usize;
-    while pos < code_bytes.len() {
-        match code_bytes.get(pos) {
-            Some(b' ') | Some(b'\t') => pos += 1,
-            Some(b'+') => {
-                new_end = (pos + 1) as u32;
-                if pos + 1 < code_bytes.len()
-                    && matches!(code_bytes.get(pos + 1), Some(b' ') | Some(b'\t'))
-                {
-                    new_end = (pos + 2) as u32;
-                }
-                break;
-            }
-            _ => break,
-        }
-    }
-
-    (new_start, new_end)
-}
-
 // Format function support
 
 /// Process fmt.Sprintf call: fmt.Sprintf("Hello %s", name)
@@ -864,6 +1272,7 @@ fn process_sprintf_call(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Get function being called
     let func_node = node.child_by_field_name("function")?;
@@ -897,68 +1306,20 @@ fn process_sprintf_call(
     // Parse format string
     let format_str_span = span_shape_string_like(&format_str_node, source);
     let format_str_content = &source[format_str_span.inner.0 as usize..format_str_span.inner.1 as usize];
-    let placeholders = parse_printf_placeholders(format_str_content);
+    let placeholders = printf_placeholders(format_str_content);
     
     if placeholders.is_empty() {
         return None;
     }
     
-    // Build vars from remaining arguments
-    let mut vars = Vec::new();
-    for (arg_idx, arg_node) in arg_nodes.iter().skip(1).enumerate() {
-        if arg_idx >= placeholders.len() {
-            break;
-        }
-        
-        let arg_start = arg_node.start_byte() as u32;
-        let arg_end = arg_node.end_byte() as u32;
-        vars.push(PromptVar {
-            span: SpanShape {
-                outer: (arg_start, arg_end),
-                inner: (arg_start, arg_end),
-            },
-        });
-    }
-    
-    // Build content tokens
-    let mut content = Vec::new();
-    let mut pos = 0usize;
-    let format_inner_start = format_str_span.inner.0;
-    
-    for (placeholder_idx, (start, end)) in placeholders.iter().enumerate() {
-        // Add string token before placeholder
-        if pos < *start {
-            content.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (format_inner_start + pos as u32, format_inner_start + *start as u32),
-                },
-            ));
-        }
-        
-        // Add var token for placeholder (if we have a corresponding arg)
-        if placeholder_idx < vars.len() {
-            content.push(PromptContentToken::PromptContentTokenVar(
-                PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: (format_inner_start + *start as u32, format_inner_start + *end as u32),
-                    index: placeholder_idx as u32,
-                },
-            ));
-        }
-        
-        pos = *end;
-    }
-    
-    // Add trailing string token
-    if pos < format_str_content.len() {
-        content.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (format_inner_start + pos as u32, format_str_span.inner.1),
-            },
-        ));
-    }
+    // Build vars and content tokens from the arguments
+    let arguments: Vec<_> = arg_nodes
+        .iter()
+        .skip(1)
+        .map(|arg_node| (arg_node.start_byte() as u32, arg_node.end_byte() as u32))
+        .collect();
+    let (vars, content) =
+        format_content(source, format_str_span.inner, &placeholders, &arguments);
     
     let enclosure_start = comments.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
     
@@ -971,49 +1332,17 @@ fn process_sprintf_call(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
-/// Parse printf-style placeholders: %s, %d, %v, %f, etc.
-fn parse_printf_placeholders(format_str: &str) -> Vec<(usize, usize)> {
-    let mut placeholders = Vec::new();
-    let chars: Vec<char> = format_str.chars().collect();
-    let mut i = 0;
-    
-    while i < chars.len() {
-        if chars[i] == '%' {
-            // Check for escaped %%
-            if i + 1 < chars.len() && chars[i + 1] == '%' {
-                i += 2;
-                continue;
-            }
-            
-            let start = i;
-            i += 1;
-            
-            // Skip flags, width, precision
-            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-' || chars[i] == '+' || chars[i] == ' ' || chars[i] == '#' || chars[i] == '.' || chars[i] == '*') {
-                i += 1;
-            }
-            
-            // Get format specifier
-            if i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '%') {
-                i += 1;
-                placeholders.push((start, i));
-                continue;
-            }
-        }
-        i += 1;
-    }
-    
-    placeholders
-}
-
 /// Process array/slice: []string{"Hello ", user, "!"}
 #[allow(clippy::too_many_arguments)]
 fn process_array(
@@ -1024,6 +1353,7 @@ fn process_array(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Extract array elements using recursive helper (like strings.Join)
     fn extract_elements(
@@ -1044,14 +1374,13 @@ fn process_array(
                             PromptContentTokenStr {
                                 r#type: PromptContentTokenStrTypeStr,
                                 span: span.inner,
+                                escapes: None,
                             },
                         ));
                     }
                     "identifier" => {
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape { outer, inner: outer },
-                        });
+                        vars.push(prompt_var(source, SpanShape { outer, inner: outer }, outer));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1063,9 +1392,7 @@ fn process_array(
                     }
                     "call_expression" => {
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape { outer, inner: outer },
-                        });
+                        vars.push(prompt_var(source, SpanShape { outer, inner: outer }, outer));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1133,11 +1460,14 @@ fn process_array(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -1151,6 +1481,7 @@ fn process_strings_join(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if it's a selector_expression (package.function)
     let mut func_node = None;
@@ -1253,6 +1584,7 @@ fn process_strings_join(
                             content.push(PromptContentToken::PromptContentTokenJoint(
                                 PromptContentTokenJoint {
                                     r#type: PromptContentTokenJointTypeJoint,
+                                    escapes: None,
                                 },
                             ));
                         }
@@ -1262,6 +1594,7 @@ fn process_strings_join(
                             PromptContentTokenStr {
                                 r#type: PromptContentTokenStrTypeStr,
                                 span: span.inner,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1271,14 +1604,13 @@ fn process_strings_join(
                             content.push(PromptContentToken::PromptContentTokenJoint(
                                 PromptContentTokenJoint {
                                     r#type: PromptContentTokenJointTypeJoint,
+                                    escapes: None,
                                 },
                             ));
                         }
                         *first = false;
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape { outer, inner: outer },
-                        });
+                        vars.push(prompt_var(source, SpanShape { outer, inner: outer }, outer));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1348,7 +1680,10 @@ fn process_strings_join(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint,
+        role: None,
+        references: None,
     })
 }
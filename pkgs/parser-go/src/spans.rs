@@ -1,5 +1,6 @@
 use tree_sitter::Node;
-use volumen_types::SpanShape;
+use volumen_parser_core::EscapeStyle;
+use volumen_types::{Span, SpanShape};
 
 /// Calculate outer and inner spans for a string-like node.
 /// For Go, this handles interpreted strings ("...") and raw strings (`...`).
@@ -36,3 +37,17 @@ pub fn span_shape_string_like(node: &Node, source: &str) -> SpanShape {
 
     SpanShape { outer, inner }
 }
+
+/// Resolve escaping rules of the string literal containing the span. Go raw
+/// strings (`` `...` ``) have no escapes.
+pub fn escape_style(root: &Node, span: Span) -> EscapeStyle {
+    let mut node = root.descendant_for_byte_range(span.0 as usize, span.1 as usize);
+    while let Some(current) = node {
+        match current.kind() {
+            "interpreted_string_literal" => return EscapeStyle::Go,
+            "raw_string_literal" => return EscapeStyle::None,
+            _ => node = current.parent(),
+        }
+    }
+    EscapeStyle::None
+}
@@ -1,5 +1,5 @@
 use tree_sitter::{Node, Tree};
-use volumen_parser_core::{compute_comment_inner_offsets, parse_annotation};
+use volumen_parser_core::{PromptDetector, PromptRegions, compute_comment_inner_offsets};
 use volumen_types::{PromptAnnotation, SpanShape};
 
 /// Recursively extract all comment nodes from the tree.
@@ -37,11 +37,13 @@ pub struct CommentNode {
 pub struct CommentTracker {
     source: String,
     comments: Vec<CommentNode>,
+    regions: PromptRegions,
+    detector: PromptDetector,
 }
 
 impl CommentTracker {
     /// Create a new CommentTracker by extracting all comments from the tree.
-    pub fn new(tree: &Tree, source: &str) -> Self {
+    pub fn new(tree: &Tree, source: &str, detector: PromptDetector) -> Self {
         let root = tree.root_node();
         let mut comments = Vec::new();
 
@@ -51,12 +53,27 @@ impl CommentTracker {
         // Sort comments by start position for efficient searching
         comments.sort_by_key(|c| c.start);
 
+        // Resolve file-level and region markers once for all statements
+        let regions = PromptRegions::new(
+            source,
+            comments.iter().map(|c| (c.start, c.end, c.text.as_str())),
+            &detector,
+        );
+
         Self {
             source: source.to_string(),
             comments,
+            regions,
+            detector,
         }
     }
 
+    /// Check if a statement starts inside a region marked with `@prompts` or
+    /// `@prompt-begin`/`@prompt-end`.
+    pub fn in_prompt_region(&self, stmt_start: u32) -> bool {
+        self.regions.contains(stmt_start)
+    }
+
     /// Collect adjacent leading comments for a statement.
     /// Finds contiguous comment blocks immediately before the statement (only whitespace between).
     pub fn collect_adjacent_leading(&self, stmt_start: u32) -> Vec<PromptAnnotation> {
@@ -110,7 +127,7 @@ impl CommentTracker {
         // Check if any comment in the block contains @prompt
         let has_prompt = block_ranges
             .iter()
-            .any(|c| parse_annotation(&c.text).unwrap_or(false));
+            .any(|c| self.detector.annotation(&c.text).unwrap_or(false));
         if !has_prompt {
             return Vec::new();
         }
@@ -133,9 +150,8 @@ impl CommentTracker {
             })
             .collect();
 
-        vec![PromptAnnotation {
-            spans,
-        }]
+        let attributes = self.detector.annotation_attributes(&self.source, &spans);
+        vec![PromptAnnotation { spans, attributes }]
     }
 
     /// Collect inline @prompt comments within a statement's range.
@@ -143,19 +159,33 @@ impl CommentTracker {
         self.comments
             .iter()
             .filter(|c| c.start >= stmt_start && c.start < stmt_end)
-            .filter(|c| parse_annotation(&c.text).unwrap_or(false))
+            .filter(|c| self.detector.annotation(&c.text).unwrap_or(false))
             .map(|c| {
                 let (inner_start_offset, inner_end_offset) = compute_comment_inner_offsets(&c.text);
-                PromptAnnotation {
-                    spans: vec![SpanShape {
-                        outer: (c.start, c.end),
-                        inner: (c.start + inner_start_offset, c.start + inner_end_offset),
-                    }],
-                }
+                let spans = vec![SpanShape {
+                    outer: (c.start, c.end),
+                    inner: (c.start + inner_start_offset, c.start + inner_end_offset),
+                }];
+                let attributes = self.detector.annotation_attributes(&self.source, &spans);
+                PromptAnnotation { spans, attributes }
             })
             .collect()
     }
 
+    /// Check if a statement has an opt-out annotation (e.g., `@prompt-ignore`)
+    /// in the adjacent leading comments, inline or trailing on its last line.
+    pub fn has_ignore_annotation(&self, stmt_start: u32, stmt_end: u32) -> bool {
+        let start = self.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
+        let end = self.source[stmt_end as usize..]
+            .find('\n')
+            .map_or(self.source.len(), |offset| stmt_end as usize + offset)
+            as u32;
+        self.comments
+            .iter()
+            .filter(|c| c.start >= start && c.start < end)
+            .any(|c| self.detector.ignore_annotation(&c.text))
+    }
+
     /// Get the start position of any adjacent leading comment, regardless of whether it's valid.
     /// Used for enclosure calculation when we want to include all leading comments.
     pub fn get_any_leading_start(&self, stmt_start: u32) -> Option<u32> {
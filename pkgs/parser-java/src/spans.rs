@@ -1,5 +1,6 @@
 use tree_sitter::Node;
-use volumen_types::SpanShape;
+use volumen_parser_core::EscapeStyle;
+use volumen_types::{Span, SpanShape};
 
 /// Information about text block incidental whitespace stripping
 #[derive(Debug, Clone)]
@@ -108,4 +109,15 @@ pub fn get_text_block_info(node: &Node, source: &str) -> Option<TextBlockInfo> {
     })
 }
 
-
+/// Resolve escaping rules of the string literal containing the span. Java
+/// strings and text blocks share the escaping rules.
+pub fn escape_style(root: &Node, span: Span) -> EscapeStyle {
+    let mut node = root.descendant_for_byte_range(span.0 as usize, span.1 as usize);
+    while let Some(current) = node {
+        match current.kind() {
+            "string_literal" => return EscapeStyle::Java,
+            _ => node = current.parent(),
+        }
+    }
+    EscapeStyle::None
+}
@@ -1,51 +1,88 @@
 mod comments;
-mod scope;
 mod spans;
 
 use comments::CommentTracker;
-use scope::ScopeTracker;
-use spans::span_shape_string_like;
+use spans::{escape_style, span_shape_string_like};
 use tree_sitter::{Node, Parser, Tree};
 pub use volumen_parser_core::VolumenParser;
+use volumen_parser_core::{
+    build_content_tokens, collect_diagnostics, concat_content, error_diagnostic, file_size_error,
+    format_content, is_content_key, is_role_key, parse_result, parse_role_value,
+    printf_placeholders, process_call_sites, prompt_group, prompt_var, resolve_escapes,
+    CallArgument, CallSiteSyntax, ConcatContent, ConcatSegment, PromptDetector, PromptFunction,
+    PromptReferences, ScopeTracker,
+};
+
 use volumen_types::*;
 
-pub struct ParserPhp {}
+pub struct ParserPhp {
+    parser: Parser,
+}
 
-impl VolumenParser for ParserPhp {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        // Initialize Tree-sitter parser
+impl ParserPhp {
+    pub fn new() -> Self {
         let mut parser = Parser::new();
         parser
             .set_language(&tree_sitter_php::LANGUAGE_PHP.into())
             .expect("Failed to load PHP grammar");
+        Self { parser }
+    }
+}
+
+impl Default for ParserPhp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VolumenParser for ParserPhp {
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        // Skip sources exceeding the maximum file size
+        if let Some(result) =
+            file_size_error(source, options, ParseLanguage::Php, None, "tree-sitter")
+        {
+            return result;
+        }
 
         // Parse source
-        let tree = match parser.parse(source, None) {
+        let tree = match self.parser.parse(source, None) {
             Some(tree) => tree,
             None => {
                 return ParseResult::ParseResultError(ParseResultError {
                     state: ParseResultErrorStateError,
                     error: "Failed to parse source".to_string(),
+                    diagnostics: vec![error_diagnostic(
+                        "tree-sitter",
+                        "parse-failed",
+                        "Failed to parse source".to_string(),
+                        (0, source.len() as u32),
+                    )],
+                    language: Some(ParseLanguage::Php),
+                    dialect: None,
+                    detection: None,
+                    parser: Some("tree-sitter".to_string()),
                 });
             }
         };
 
-        // Check for parse errors
+        // Collect parse errors. Tree-sitter recovers from errors, so the tree
+        // is still processed, skipping error nodes.
         let root = tree.root_node();
+        let mut diagnostics = Vec::new();
         if root.has_error() {
-            let error_msg = format_tree_sitter_error(&root, source);
-            return ParseResult::ParseResultError(ParseResultError {
-                state: ParseResultErrorStateError,
-                error: error_msg,
-            });
+            collect_diagnostics(&root, source, &mut diagnostics);
         }
 
+        // Resolve prompt detection rules
+        let detector = PromptDetector::new(options);
+
         // Extract comments
-        let comment_tracker = CommentTracker::new(&tree, source);
+        let comment_tracker = CommentTracker::new(&tree, source, detector.clone());
 
         // Initialize state
         let mut prompts = Vec::new();
-        let mut scope_tracker = ScopeTracker::new();
+        let mut groups = Vec::new();
+        let mut scope_tracker = ScopeTracker::new(detector.clone());
 
         // Process tree
         process_tree(
@@ -56,55 +93,48 @@ impl VolumenParser for ParserPhp {
             &comment_tracker,
             &mut scope_tracker,
             &mut prompts,
+            &mut groups,
         );
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
-            prompts,
-        })
-    }
-}
+        // Detect prompts passed to LLM SDK call sites
+        let call_sites = CallSites {
+            comments: &comment_tracker,
+        };
+        process_call_sites(
+            &root,
+            source,
+            filename,
+            &detector,
+            &call_sites,
+            &mut prompts,
+            &mut groups,
+        );
 
-/// Format tree-sitter parse errors into a readable message.
-fn format_tree_sitter_error(node: &Node, source: &str) -> String {
-    let mut errors = Vec::new();
-    collect_errors(node, source, &mut errors);
-    if errors.is_empty() {
-        "Parse error: unknown".to_string()
-    } else {
-        format!("Parse errors:\n{}", errors.join("\n"))
-    }
-}
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = PromptReferences::new(&prompts);
+        process_references(&root, source, &mut references);
+        references.apply(&mut prompts);
 
-fn collect_errors(node: &Node, source: &str, errors: &mut Vec<String>) {
-    if node.is_error() || node.is_missing() {
-        let start = node.start_position();
-        let error_text = node
-            .utf8_text(source.as_bytes())
-            .unwrap_or("<unknown>")
-            .chars()
-            .take(50)
-            .collect::<String>();
-        errors.push(format!(
-            "  Line {}, Column {}: {}",
-            start.row + 1,
-            start.column + 1,
-            error_text
-        ));
-    }
+        // Decode escape sequences in string tokens
+        resolve_escapes(&mut prompts, source, |span| escape_style(&root, span));
 
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            collect_errors(&cursor.node(), source, errors);
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
+        // Assign role hints from annotations, e.g., `// @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        parse_result(
+            ParseLanguage::Php,
+            None,
+            "tree-sitter",
+            prompts,
+            groups,
+            Vec::new(),
+            diagnostics,
+        )
     }
 }
 
 /// Process the entire syntax tree to extract prompts.
+#[allow(clippy::too_many_arguments)]
 fn process_tree(
     _tree: &Tree,
     root: &Node,
@@ -113,9 +143,10 @@ fn process_tree(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Recursively traverse the tree
-    traverse_node(root, source, filename, comments, scopes, prompts);
+    traverse_node(root, source, filename, comments, scopes, prompts, groups);
 }
 
 /// Recursively traverse nodes to find assignments and manage scopes.
@@ -126,12 +157,26 @@ fn traverse_node(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
     let kind = node.kind();
 
-    // Handle scope boundaries (function, class, method)
-    if kind == "function_definition" || kind == "class_declaration" || kind == "method_declaration"
-    {
+    // Handle function scopes, tracking prompt functions returning prompts
+    if matches!(
+        kind,
+        "function_definition" | "method_declaration" | "anonymous_function" | "arrow_function"
+    ) {
+        process_function(node, source, filename, comments, scopes, prompts, groups);
+        return;
+    }
+
+    // Handle scope boundaries (class)
+    if kind == "class_declaration" {
         scopes.enter_scope();
 
         // Process children
@@ -139,7 +184,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             loop {
                 let child = cursor.node();
-                traverse_node(&child, source, filename, comments, scopes, prompts);
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -156,7 +201,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             let child = cursor.node();
             if child.kind() == "assignment_expression" {
-                process_assignment(&child, source, filename, comments, scopes, prompts);
+                process_assignment(&child, source, filename, comments, scopes, prompts, groups);
                 return;
             }
         }
@@ -164,21 +209,143 @@ fn traverse_node(
 
     // Handle assignments directly
     if kind == "assignment_expression" {
-        process_assignment(node, source, filename, comments, scopes, prompts);
+        process_assignment(node, source, filename, comments, scopes, prompts, groups);
         return;
     }
 
+    // Handle prompt function returns: `return "Hello, {$user}!";`
+    if kind == "return_statement" {
+        if let Some(value) = node.named_child(0) {
+            process_function_return(
+                &value, node, source, filename, comments, scopes, prompts, groups,
+            );
+        }
+    }
+
     // Recursively process children
     let mut cursor = node.walk();
     if cursor.goto_first_child() {
         loop {
             let child = cursor.node();
-            traverse_node(&child, source, filename, comments, scopes, prompts);
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Process a function or method definition scope. Named functions with
+/// prompt-like names or leading `@prompt` annotations are prompt functions,
+/// so their returned values are detected as prompts. Anonymous functions
+/// never are, so their returns don't leak into the enclosing function.
+fn process_function(
+    node: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let stmt_start = node.start_byte() as u32;
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|parameters| function_parameters(&parameters, source))
+        .unwrap_or_default();
+    let function = node.child_by_field_name("name").and_then(|name| {
+        // Opt-out annotations exclude the function from prompt functions
+        if comments.has_ignore_annotation(stmt_start, name.end_byte() as u32) {
+            return None;
+        }
+        PromptFunction::new(
+            scopes.detector(),
+            source,
+            (name.start_byte() as u32, name.end_byte() as u32),
+            parameters.clone(),
+            comments.collect_adjacent_leading(stmt_start),
+        )
+    });
+    scopes.enter_function(function);
+
+    // Parameters shadow the outer identifiers with the same names
+    for parameter in &parameters {
+        scopes.declare(parameter);
+    }
+
+    // Process children
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
             if !cursor.goto_next_sibling() {
                 break;
             }
         }
     }
+
+    scopes.exit_function();
+}
+
+/// Collect the function parameter names, e.g., `$user` and `$options` for
+/// `function buildPrompt($user, array $options = [])`.
+fn function_parameters(node: &Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter_map(|parameter| parameter.child_by_field_name("name"))
+        .filter_map(|name| name.utf8_text(source.as_bytes()).ok())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Process a value returned from the enclosing prompt function. The value is
+/// processed like a prompt assignment bound to the function name, marking
+/// the variables that come from the function parameters.
+#[allow(clippy::too_many_arguments)]
+fn process_function_return(
+    value: &Node,
+    stmt: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let Some(function) = scopes.current_function().cloned() else {
+        return;
+    };
+    let stmt_start = stmt.start_byte() as u32;
+    let stmt_end = stmt.end_byte() as u32;
+
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
+    // Statement annotations take precedence over the function annotations
+    let mut annotations = comments.collect_adjacent_leading(stmt_start);
+    annotations.extend(comments.collect_inline_prompt(stmt_start, stmt_end));
+    if annotations.is_empty() {
+        annotations = function.annotations.clone();
+    }
+
+    let prompts_len = prompts.len();
+    process_identifier_assignment(
+        &function.binding.name,
+        value,
+        true,
+        &annotations,
+        &function.binding,
+        stmt_start,
+        stmt_end,
+        source,
+        filename,
+        comments,
+        scopes,
+        prompts,
+        groups,
+    );
+    function.mark_parameter_vars(prompts, prompts_len);
 }
 
 /// Process an assignment node to extract prompts.
@@ -189,17 +356,23 @@ fn process_assignment(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     let mut all_annotations = leading_annotations.clone();
     all_annotations.extend(inline_annotations);
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
 
     // Get left and right sides
     let left = match node.child_by_field_name("left") {
@@ -217,11 +390,13 @@ fn process_assignment(
         "variable_name" | "simple_variable" => {
             // Simple assignment: $var = value
             let ident_name = left.utf8_text(source.as_bytes()).unwrap_or("");
+            let binding = binding_from_node(&left, source, PromptBindingKind::Local);
             process_identifier_assignment(
                 ident_name,
                 &right,
                 has_prompt_annotation,
                 &all_annotations,
+                &binding,
                 stmt_start,
                 stmt_end,
                 source,
@@ -229,6 +404,7 @@ fn process_assignment(
                 comments,
                 scopes,
                 prompts,
+                groups,
             );
         }
         "list_literal" => {
@@ -258,6 +434,7 @@ fn process_identifier_assignment(
     right: &Node,
     has_prompt_annotation: bool,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     stmt_start: u32,
     stmt_end: u32,
     source: &str,
@@ -265,9 +442,17 @@ fn process_identifier_assignment(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
-    // Determine if this is a prompt
-    let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
+    // Extract chat messages to create a prompt per message content
+    let messages = extract_messages(right, source);
+
+    // Determine if this is a prompt. Chat messages are prompts by structure.
+    let is_prompt = is_prompt_variable(
+        ident_name,
+        has_prompt_annotation || !messages.is_empty(),
+        scopes,
+    );
 
     if is_prompt {
         // Mark as prompt identifier
@@ -283,6 +468,7 @@ fn process_identifier_assignment(
             // Get the left side of the chained assignment to mark it as prompt too
             if let Some(chained_left) = right.child_by_field_name("left") {
                 let chained_ident = chained_left.utf8_text(source.as_bytes()).unwrap_or("");
+                scopes.declare(chained_ident);
                 scopes.mark_prompt_ident(chained_ident);
                 
                 // Store definition annotations for the chained variable too
@@ -299,6 +485,7 @@ fn process_identifier_assignment(
                     &chained_right,
                     has_prompt_annotation,
                     annotations,
+                    binding,
                     stmt_start,
                     stmt_end,
                     source,
@@ -306,16 +493,20 @@ fn process_identifier_assignment(
                     comments,
                     scopes,
                     prompts,
+                    groups,
                 );
                 
                 // Also process the middle variable(s) in the chain
                 if let Some(chained_left) = right.child_by_field_name("left") {
                     let chained_ident = chained_left.utf8_text(source.as_bytes()).unwrap_or("");
+                    let chained_binding =
+                        binding_from_node(&chained_left, source, PromptBindingKind::Local);
                     process_identifier_assignment(
                         chained_ident,
                         &chained_right,
                         has_prompt_annotation,
                         annotations,
+                        &chained_binding,
                         stmt_start,
                         stmt_end,
                         source,
@@ -323,10 +514,47 @@ fn process_identifier_assignment(
                         comments,
                         scopes,
                         prompts,
+                        groups,
                     );
                 }
             }
-        } 
+        }
+        // Check if it's a chat message array or an array of them
+        else if !messages.is_empty() {
+            let final_annotations = if !annotations.is_empty() {
+                annotations.to_vec()
+            } else {
+                scopes.get_def_annotation(ident_name).unwrap_or_default()
+            };
+
+            // Handle messages: [['role' => 'system', 'content' => '...']]
+            let group_start = prompts.len();
+            for (role, content) in messages {
+                let prompts_len = prompts.len();
+                create_prompt_from_string(
+                    &content,
+                    source,
+                    filename,
+                    stmt_start,
+                    stmt_end,
+                    comments,
+                    &final_annotations,
+                    binding,
+                    prompts,
+                );
+                for prompt in &mut prompts[prompts_len..] {
+                    prompt.role = role;
+                }
+            }
+
+            // Group the messages array into a conversation
+            if extract_message(right, source).is_none() {
+                let span = (right.start_byte() as u32, right.end_byte() as u32);
+                if let Some(group) = prompt_group(filename, span, binding, prompts, group_start) {
+                    groups.push(group);
+                }
+            }
+        }
         // Check if it's a string or binary expression
         else if is_string_like(right) {
             // Annotations from comment tracker are already validated to contain @prompt
@@ -346,6 +574,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
                 prompts,
             );
         } else if right.kind() == "binary_expression" {
@@ -365,6 +594,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -387,6 +617,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             // Then try sprintf/printf
@@ -398,6 +629,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -418,6 +650,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -449,12 +682,14 @@ fn process_multi_assignment(
     extract_value_ranges(right, &mut value_ranges);
 
     // Match identifiers with values
-    for (i, ident_name) in identifiers.iter().enumerate() {
+    for (i, (ident_name, ident_node)) in identifiers.iter().enumerate() {
         if let Some((start, end, kind)) = value_ranges.get(i) {
             let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
 
             if is_prompt && (*kind == "string" || *kind == "encapsed_string") {
                 scopes.mark_prompt_ident(ident_name);
+                let binding =
+                    binding_from_node(ident_node, source, PromptBindingKind::Destructured);
 
                 // Create prompt from range
                 create_prompt_from_range(
@@ -466,6 +701,7 @@ fn process_multi_assignment(
                     stmt_end,
                     comments,
                     annotations,
+                    &binding,
                     prompts,
                 );
             }
@@ -473,9 +709,17 @@ fn process_multi_assignment(
     }
 }
 
-/// Check if a variable should be treated as a prompt.
-fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &ScopeTracker) -> bool {
-    ident_name.to_lowercase().contains("prompt")
+/// Check if a variable should be treated as a prompt. Assignments declare
+/// the variable in the current scope, shadowing the outer variables with the
+/// name. Variables in statements with an opt-out annotation are marked as
+/// ignored instead.
+fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &mut ScopeTracker) -> bool {
+    scopes.declare(ident_name);
+    if scopes.is_statement_ignored() {
+        scopes.mark_ignored_ident(ident_name);
+        return false;
+    }
+    scopes.is_prompt_name(ident_name)
         || has_annotation
         || scopes.is_prompt_ident(ident_name)
 }
@@ -488,13 +732,248 @@ fn is_string_like(node: &Node) -> bool {
     )
 }
 
+/// Extract chat message roles and string contents from a message array, e.g.,
+/// `['role' => 'system', 'content' => '...']`, or from an array of message
+/// arrays.
+fn extract_messages<'a>(node: &Node<'a>, source: &str) -> Vec<(Option<PromptRole>, Node<'a>)> {
+    if node.kind() != "array_creation_expression" {
+        return Vec::new();
+    }
+    if let Some(message) = extract_message(node, source) {
+        return vec![message];
+    }
+    array_elements(node)
+        .into_iter()
+        .filter_map(|(_, value)| extract_message(&value, source))
+        .collect()
+}
+
+/// Extract the role and the string content from a message array. Arrays
+/// without the role key aren't messages.
+fn extract_message<'a>(node: &Node<'a>, source: &str) -> Option<(Option<PromptRole>, Node<'a>)> {
+    if node.kind() != "array_creation_expression" {
+        return None;
+    }
+    let mut role = None;
+    let mut content = None;
+    for (key, value) in array_elements(node) {
+        let Some(key) = key else {
+            continue;
+        };
+        let key_text = key.utf8_text(source.as_bytes()).unwrap_or_default();
+        if is_role_key(key_text) {
+            role = Some(parse_role_value(
+                value.utf8_text(source.as_bytes()).unwrap_or_default(),
+            ));
+        } else if is_content_key(key_text) && is_string_like(&value) {
+            content = Some(value);
+        }
+    }
+    Some((role?, content?))
+}
+
+/// Collect the optional keys and the values of the array elements.
+fn array_elements<'a>(node: &Node<'a>) -> Vec<(Option<Node<'a>>, Node<'a>)> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == "array_element_initializer")
+        .filter_map(|element| {
+            let mut cursor = element.walk();
+            let children: Vec<Node<'a>> = element.named_children(&mut cursor).collect();
+            match children.as_slice() {
+                [key, value] => Some((Some(*key), *value)),
+                [value] => Some((None, *value)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// PHP LLM SDK call site syntax, e.g.,
+/// `$client->messages()->create(['system' => '...'])`.
+struct CallSites<'a> {
+    comments: &'a CommentTracker,
+}
+
+impl<'tree> CallSiteSyntax<'tree> for CallSites<'_> {
+    fn call(&self, node: &Node<'tree>, _source: &str) -> Option<(Span, Node<'tree>)> {
+        // Callee span, e.g., `$client->chat()->create`
+        let (callee_start, callee_end) = match node.kind() {
+            "function_call_expression" => node
+                .child_by_field_name("function")
+                .map(|function| (node.start_byte(), function.end_byte()))?,
+            "member_call_expression"
+            | "nullsafe_member_call_expression"
+            | "scoped_call_expression" => node
+                .child_by_field_name("name")
+                .map(|name| (node.start_byte(), name.end_byte()))?,
+            // Constructors are matched by the class, e.g., `new SystemMessage(...)`
+            "object_creation_expression" => {
+                let mut cursor = node.walk();
+                let class = node
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() != "arguments");
+                class.map(|class| (class.start_byte(), class.end_byte()))?
+            }
+            _ => return None,
+        };
+        let mut cursor = node.walk();
+        let arguments = node
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "arguments")?;
+        Some(((callee_start as u32, callee_end as u32), arguments))
+    }
+
+    /// Named arguments and keyed array arguments are named.
+    fn arguments(&self, arguments: &Node<'tree>, source: &str) -> Vec<CallArgument<'tree>> {
+        let mut named = Vec::new();
+        let mut values = Vec::new();
+        let mut cursor = arguments.walk();
+        let arguments = arguments
+            .named_children(&mut cursor)
+            .filter(|argument| argument.kind() == "argument");
+        for (position, argument) in arguments.enumerate() {
+            let name = argument.child_by_field_name("name");
+            let mut argument_cursor = argument.walk();
+            let Some(value) = argument.named_children(&mut argument_cursor).last() else {
+                continue;
+            };
+            if let Some(name) = name {
+                named.push(CallArgument::Named(name, value));
+            } else if value.kind() == "array_creation_expression"
+                && extract_message(&value, source).is_none()
+                && array_elements(&value).iter().any(|(key, _)| key.is_some())
+            {
+                named.extend(
+                    array_elements(&value)
+                        .into_iter()
+                        .filter_map(|(key, value)| Some(CallArgument::Named(key?, value))),
+                );
+            } else {
+                values.push(CallArgument::Positional(position, value));
+            }
+        }
+        values.extend(named);
+        values
+    }
+
+    fn messages(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+    ) -> Vec<(Option<PromptRole>, Node<'tree>)> {
+        extract_messages(value, source)
+    }
+
+    fn is_conversation(&self, value: &Node<'tree>, source: &str) -> bool {
+        extract_message(value, source).is_none()
+    }
+
+    fn is_string_like(&self, value: &Node<'tree>) -> bool {
+        is_string_like(value)
+    }
+
+    fn create_prompt(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+        filename: &str,
+        call: Span,
+        binding: &PromptBinding,
+        prompts: &mut Vec<Prompt>,
+    ) {
+        create_prompt_from_string(
+            value,
+            source,
+            filename,
+            call.0,
+            call.1,
+            self.comments,
+            &[],
+            binding,
+            prompts,
+        );
+    }
+}
+
+/// Recursively resolve the variables referencing prompt bindings, e.g.,
+/// `send($userPrompt)`, tracking the function and class scopes.
+fn process_references(node: &Node, source: &str, references: &mut PromptReferences) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
+    let kind = node.kind();
+    let is_scope = matches!(
+        kind,
+        "function_definition"
+            | "method_declaration"
+            | "anonymous_function"
+            | "arrow_function"
+            | "class_declaration"
+    );
+    if is_scope {
+        references.enter_scope();
+        // Parameters shadow the outer bindings
+        if let Some(parameters) = node.child_by_field_name("parameters") {
+            for name in function_parameters(&parameters, source) {
+                references.shadow(&name);
+            }
+        }
+    }
+
+    if kind == "variable_name" {
+        if let (Some(reference_kind), Ok(name)) =
+            (reference_kind(node), node.utf8_text(source.as_bytes()))
+        {
+            let span = (node.start_byte() as u32, node.end_byte() as u32);
+            // Assignments declare the variables in the current scope
+            if reference_kind == PromptReferenceKind::Reassign {
+                references.assignment(name, span);
+            } else {
+                references.identifier(name, span, reference_kind);
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        process_references(&child, source, references);
+    }
+
+    if is_scope {
+        references.exit_scope();
+    }
+}
+
+/// Get the reference kind by the variable position.
+fn reference_kind(node: &Node) -> Option<PromptReferenceKind> {
+    let parent = node.parent()?;
+    match parent.kind() {
+        "assignment_expression"
+        | "augmented_assignment_expression"
+        | "reference_assignment_expression"
+            if parent.child_by_field_name("left") == Some(*node) =>
+        {
+            Some(PromptReferenceKind::Reassign)
+        }
+        "argument" => Some(PromptReferenceKind::Argument),
+        _ => Some(PromptReferenceKind::Read),
+    }
+}
+
 /// Extract identifiers from a list_literal or similar pattern.
-fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>) {
+fn extract_identifiers<'a>(
+    node: &Node<'a>,
+    source: &str,
+    identifiers: &mut Vec<(String, Node<'a>)>,
+) {
     let kind = node.kind();
 
     if kind == "variable_name" || kind == "simple_variable" {
         if let Ok(name) = node.utf8_text(source.as_bytes()) {
-            identifiers.push(name.to_string());
+            identifiers.push((name.to_string(), *node));
         }
         return;
     }
@@ -512,6 +991,15 @@ fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>)
     }
 }
 
+/// Create a prompt binding from an identifier node.
+fn binding_from_node(node: &Node, source: &str, kind: PromptBindingKind) -> PromptBinding {
+    PromptBinding {
+        name: node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+        span: (node.start_byte() as u32, node.end_byte() as u32),
+        kind,
+    }
+}
+
 /// Extract value ranges from the right side of an assignment.
 fn extract_value_ranges(node: &Node, values: &mut Vec<(u32, u32, &'static str)>) {
     let kind = node.kind();
@@ -552,6 +1040,7 @@ fn extract_value_ranges(node: &Node, values: &mut Vec<(u32, u32, &'static str)>)
 }
 
 /// Create a prompt from a string node.
+#[allow(clippy::too_many_arguments)]
 fn create_prompt_from_string(
     string_node: &Node,
     source: &str,
@@ -560,6 +1049,7 @@ fn create_prompt_from_string(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Calculate spans
@@ -583,67 +1073,17 @@ fn create_prompt_from_string(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
-/// Build content tokens from span and variables.
-/// For prompts without variables, returns a single str token.
-/// For prompts with variables, returns interleaved str and var tokens.
-fn build_content_tokens(span: &SpanShape, vars: &[PromptVar]) -> Vec<PromptContentToken> {
-    if vars.is_empty() {
-        // Simple case: single str token
-        return vec![PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: span.inner,
-            },
-        )];
-    }
-
-    let mut tokens = Vec::new();
-    let mut pos = span.inner.0;
-
-    for (var_idx, var) in vars.iter().enumerate() {
-        // Add str token before variable (if any content)
-        if pos < var.span.outer.0 {
-            tokens.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (pos, var.span.outer.0),
-                },
-            ));
-        }
-
-        // Add var token
-        tokens.push(PromptContentToken::PromptContentTokenVar(
-            PromptContentTokenVar {
-                r#type: PromptContentTokenVarTypeVar,
-                span: var.span.outer,
-                index: var_idx as u32,
-            },
-        ));
-
-        pos = var.span.outer.1;
-    }
-
-    // Add trailing str token (if any content)
-    if pos < span.inner.1 {
-        tokens.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (pos, span.inner.1),
-            },
-        ));
-    }
-
-    tokens
-}
-
 /// Create a prompt from a byte range.
 #[allow(clippy::too_many_arguments)]
 fn create_prompt_from_range(
@@ -655,6 +1095,7 @@ fn create_prompt_from_range(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Create a temporary node-like structure for span calculation
@@ -687,23 +1128,18 @@ fn create_prompt_from_range(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
-/// Represents a segment in a string concatenation expression
-#[derive(Debug, Clone)]
-enum ConcatSegment {
-    String(SpanShape),
-    Variable(SpanShape),
-    Primitive(SpanShape),
-    Other,
-}
-
+#[allow(clippy::too_many_arguments)]
 fn process_concatenation(
     binary_node: &Node,
     source: &str,
@@ -712,172 +1148,79 @@ fn process_concatenation(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
-    // Check if this is a concatenation binary expression (. operator in PHP)
-    let operator = binary_node.child_by_field_name("operator")?;
-    let operator_text = operator.utf8_text(source.as_bytes()).ok()?;
+    // Check if this is a concatenation binary expression (. operator)
+    let operator_node = binary_node.child_by_field_name("operator")?;
+    let operator_text = operator_node.utf8_text(source.as_bytes()).ok()?;
     if operator_text != "." {
         return None;
     }
 
     let segments = extract_concat_segments(binary_node, source);
-    if segments.iter().any(|s| matches!(s, ConcatSegment::Other)) {
-        return None;
-    }
-    if segments.is_empty() {
-        return None;
-    }
-
-    let outer = (binary_node.start_byte() as u32, binary_node.end_byte() as u32);
-    let inner_start = match segments.first() {
-        Some(ConcatSegment::String(span)) => span.inner.0,
-        Some(ConcatSegment::Variable(span) | ConcatSegment::Primitive(span)) => span.outer.0,
-        _ => outer.0,
-    };
-    let inner_end = match segments.last() {
-        Some(ConcatSegment::String(span)) => span.inner.1,
-        Some(ConcatSegment::Variable(span) | ConcatSegment::Primitive(span)) => span.outer.1,
-        _ => outer.1,
-    };
-    let span = SpanShape { outer, inner: (inner_start, inner_end) };
-
-    let vars: Vec<PromptVar> = segments
-        .iter()
-        .filter_map(|seg| match seg {
-            ConcatSegment::Variable(var_span) => Some(PromptVar { span: var_span.clone() }),
-            _ => None,
-        })
-        .collect();
-
-    let mut var_idx = 0u32;
-    let content = segments
-        .iter()
-        .map(|seg| match seg {
-            ConcatSegment::String(span) => PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                }
-            ),
-            ConcatSegment::Variable(span) => {
-                let token = PromptContentToken::PromptContentTokenVar(
-                    PromptContentTokenVar {
-                        r#type: PromptContentTokenVarTypeVar,
-                        span: span.inner,
-                        index: var_idx,
-                    }
-                );
-                var_idx += 1;
-                token
-            },
-            ConcatSegment::Primitive(span) => PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                }
-            ),
-            ConcatSegment::Other => PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (0, 0),
-                }
-            ),
-        })
-        .collect();
+    let outer = (
+        binary_node.start_byte() as u32,
+        binary_node.end_byte() as u32,
+    );
+    let ConcatContent {
+        span,
+        vars,
+        content,
+    } = concat_content(source, outer, ".", &segments)?;
 
-    let enclosure_start = comments.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
+    let enclosure_start = comments
+        .get_any_leading_start(stmt_start)
+        .unwrap_or(stmt_start);
     Some(Prompt {
         file: filename.to_string(),
         span,
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
-        joint: SpanShape { outer: (0, 0), inner: (0, 0) },
+        joint: SpanShape {
+            outer: (0, 0),
+            inner: (0, 0),
+        },
+        role: None,
+        references: None,
     })
 }
 
 fn extract_concat_segments(node: &Node, source: &str) -> Vec<ConcatSegment> {
-    if node.kind() == "binary_expression" {
-        if let Some(operator) = node.child_by_field_name("operator") {
-            if let Ok(operator_text) = operator.utf8_text(source.as_bytes()) {
-                if operator_text == "." {
-                    let mut segments = Vec::new();
-                    if let Some(left) = node.child_by_field_name("left") {
-                        segments.extend(extract_concat_segments(&left, source));
-                    }
-                    if let Some(right) = node.child_by_field_name("right") {
-                        segments.extend(extract_concat_segments(&right, source));
-                    }
-                    return segments;
-                }
-            }
+    let operator = node
+        .child_by_field_name("operator")
+        .and_then(|operator| operator.utf8_text(source.as_bytes()).ok());
+    if node.kind() == "binary_expression" && operator == Some(".") {
+        let mut segments = Vec::new();
+        if let Some(left) = node.child_by_field_name("left") {
+            segments.extend(extract_concat_segments(&left, source));
+        }
+        if let Some(right) = node.child_by_field_name("right") {
+            segments.extend(extract_concat_segments(&right, source));
         }
-        return vec![];
+        return segments;
     }
 
+    let span = (node.start_byte() as u32, node.end_byte() as u32);
     match node.kind() {
         "string" | "encapsed_string" | "heredoc" | "nowdoc" => {
-            let span = span_shape_string_like(node, source);
-            vec![ConcatSegment::String(span)]
+            vec![ConcatSegment::String(span_shape_string_like(node, source))]
         }
-        "variable_name" | "name" | "member_access_expression" | "scoped_property_access_expression"
-        | "function_call_expression" | "member_call_expression" | "scoped_call_expression" => {
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let outer_expanded = expand_to_operators(outer, source);
-            vec![ConcatSegment::Variable(SpanShape { outer: outer_expanded, inner: outer })]
-        }
-        "integer" | "float" | "boolean" | "true" | "false" => {
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let outer_expanded = expand_to_operators(outer, source);
-            vec![ConcatSegment::Primitive(SpanShape { outer: outer_expanded, inner: outer })]
-        }
-        "array_creation_expression" | "object_creation_expression" => vec![ConcatSegment::Other],
-        _ => vec![],
+        "variable_name"
+        | "name"
+        | "member_access_expression"
+        | "scoped_property_access_expression"
+        | "function_call_expression"
+        | "member_call_expression"
+        | "scoped_call_expression" => vec![ConcatSegment::Variable(span)],
+        "integer" | "float" | "boolean" | "true" | "false" => vec![ConcatSegment::Primitive(span)],
+        // Objects, arrays and other expressions can't be stringified
+        _ => vec![ConcatSegment::Other],
     }
 }
 
-fn expand_to_operators(span: (u32, u32), source: &str) -> (u32, u32) {
-    let (start, end) = span;
-    let mut new_start = start;
-    let mut new_end = end;
-    let code_bytes = source.as_bytes();
-
-    let mut pos = start.saturating_sub(1) as usize;
-    while pos > 0 {
-        match code_bytes.get(pos) {
-            Some(b' ') | Some(b'\t') => pos -= 1,
-            Some(b'.') => {
-                new_start = pos as u32;
-                if pos > 0 && matches!(code_bytes.get(pos - 1), Some(b' ') | Some(b'\t')) {
-                    new_start = (pos - 1) as u32;
-                }
-                break;
-            }
-            _ => break,
-        }
-    }
-
-    let mut pos = end as usize;
-    while pos < code_bytes.len() {
-        match code_bytes.get(pos) {
-            Some(b' ') | Some(b'\t') => pos += 1,
-            Some(b'.') => {
-                new_end = (pos + 1) as u32;
-                if pos + 1 < code_bytes.len()
-                    && matches!(code_bytes.get(pos + 1), Some(b' ') | Some(b'\t'))
-                {
-                    new_end = (pos + 2) as u32;
-                }
-                break;
-            }
-            _ => break,
-        }
-    }
-
-    (new_start, new_end)
-}
-
 // Format function support
 
 /// Process sprintf/printf function call: sprintf("Hello %s", $name)
@@ -890,6 +1233,7 @@ fn process_sprintf_call(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Get function name (try both "function" and "name" fields)
     let name_node = node.child_by_field_name("function")
@@ -937,68 +1281,20 @@ fn process_sprintf_call(
     // Parse format string
     let format_str_span = span_shape_string_like(&actual_string_node, source);
     let format_str_content = &source[format_str_span.inner.0 as usize..format_str_span.inner.1 as usize];
-    let placeholders = parse_printf_placeholders(format_str_content);
+    let placeholders = printf_placeholders(format_str_content);
     
     if placeholders.is_empty() {
         return None;
     }
     
-    // Build vars from remaining arguments
-    let mut vars = Vec::new();
-    for (arg_idx, arg_node) in arg_nodes.iter().skip(1).enumerate() {
-        if arg_idx >= placeholders.len() {
-            break;
-        }
-        
-        let arg_start = arg_node.start_byte() as u32;
-        let arg_end = arg_node.end_byte() as u32;
-        vars.push(PromptVar {
-            span: SpanShape {
-                outer: (arg_start, arg_end),
-                inner: (arg_start, arg_end),
-            },
-        });
-    }
-    
-    // Build content tokens
-    let mut content = Vec::new();
-    let mut pos = 0usize;
-    let format_inner_start = format_str_span.inner.0;
-    
-    for (placeholder_idx, (start, end)) in placeholders.iter().enumerate() {
-        // Add string token before placeholder
-        if pos < *start {
-            content.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (format_inner_start + pos as u32, format_inner_start + *start as u32),
-                },
-            ));
-        }
-        
-        // Add var token for placeholder (if we have a corresponding arg)
-        if placeholder_idx < vars.len() {
-            content.push(PromptContentToken::PromptContentTokenVar(
-                PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: (format_inner_start + *start as u32, format_inner_start + *end as u32),
-                    index: placeholder_idx as u32,
-                },
-            ));
-        }
-        
-        pos = *end;
-    }
-    
-    // Add trailing string token
-    if pos < format_str_content.len() {
-        content.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (format_inner_start + pos as u32, format_str_span.inner.1),
-            },
-        ));
-    }
+    // Build vars and content tokens from the arguments
+    let arguments: Vec<_> = arg_nodes
+        .iter()
+        .skip(1)
+        .map(|arg_node| (arg_node.start_byte() as u32, arg_node.end_byte() as u32))
+        .collect();
+    let (vars, content) =
+        format_content(source, format_str_span.inner, &placeholders, &arguments);
     
     let enclosure_start = comments.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
     
@@ -1011,49 +1307,17 @@ fn process_sprintf_call(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
-/// Parse printf-style placeholders: %s, %d, %f, etc.
-fn parse_printf_placeholders(format_str: &str) -> Vec<(usize, usize)> {
-    let mut placeholders = Vec::new();
-    let chars: Vec<char> = format_str.chars().collect();
-    let mut i = 0;
-    
-    while i < chars.len() {
-        if chars[i] == '%' {
-            // Check for escaped %%
-            if i + 1 < chars.len() && chars[i + 1] == '%' {
-                i += 2;
-                continue;
-            }
-            
-            let start = i;
-            i += 1;
-            
-            // Skip flags, width, precision
-            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-' || chars[i] == '+' || chars[i] == ' ' || chars[i] == '#' || chars[i] == '.' || chars[i] == '*') {
-                i += 1;
-            }
-            
-            // Get format specifier
-            if i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '%') {
-                i += 1;
-                placeholders.push((start, i));
-                continue;
-            }
-        }
-        i += 1;
-    }
-    
-    placeholders
-}
-
 /// Process an array: ["Hello ", $user, "!"]
 #[allow(clippy::too_many_arguments)]
 fn process_array(
@@ -1064,6 +1328,7 @@ fn process_array(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // For PHP, the array_creation_expression might have the structure:
     // array_creation_expression -> [ -> array_initializer -> elements
@@ -1088,17 +1353,20 @@ fn process_array(
                             PromptContentTokenStr {
                                 r#type: PromptContentTokenStrTypeStr,
                                 span: span.inner,
+                                escapes: None,
                             },
                         ));
                     }
                     "variable_name" | "simple_variable" => {
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape {
+                        vars.push(prompt_var(
+                            source,
+                            SpanShape {
                                 outer,
                                 inner: outer,
                             },
-                        });
+                            outer,
+                        ));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1110,12 +1378,14 @@ fn process_array(
                     }
                     "function_call_expression" | "member_call_expression" => {
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape {
+                        vars.push(prompt_var(
+                            source,
+                            SpanShape {
                                 outer,
                                 inner: outer,
                             },
-                        });
+                            outer,
+                        ));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1161,11 +1431,14 @@ fn process_array(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -1179,6 +1452,7 @@ fn process_implode_call(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Get function name (try both "function" and "name" fields)
     let name_node = node.child_by_field_name("function")
@@ -1270,6 +1544,7 @@ fn process_implode_call(
                             content.push(PromptContentToken::PromptContentTokenJoint(
                                 PromptContentTokenJoint {
                                     r#type: PromptContentTokenJointTypeJoint,
+                                    escapes: None,
                                 },
                             ));
                         }
@@ -1279,6 +1554,7 @@ fn process_implode_call(
                             PromptContentTokenStr {
                                 r#type: PromptContentTokenStrTypeStr,
                                 span: span.inner,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1288,17 +1564,20 @@ fn process_implode_call(
                             content.push(PromptContentToken::PromptContentTokenJoint(
                                 PromptContentTokenJoint {
                                     r#type: PromptContentTokenJointTypeJoint,
+                                    escapes: None,
                                 },
                             ));
                         }
                         *first = false;
                         let outer = (child.start_byte() as u32, child.end_byte() as u32);
-                        vars.push(PromptVar {
-                            span: SpanShape {
+                        vars.push(prompt_var(
+                            source,
+                            SpanShape {
                                 outer,
                                 inner: outer,
                             },
-                        });
+                            outer,
+                        ));
                         content.push(PromptContentToken::PromptContentTokenVar(
                             PromptContentTokenVar {
                                 r#type: PromptContentTokenVarTypeVar,
@@ -1346,7 +1625,10 @@ fn process_implode_call(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint,
+        role: None,
+        references: None,
     })
 }
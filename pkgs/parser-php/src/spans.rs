@@ -1,5 +1,6 @@
 use tree_sitter::Node;
-use volumen_types::{PromptVar, SpanShape};
+use volumen_parser_core::{EscapeStyle, prompt_var};
+use volumen_types::{PromptVar, Span, SpanShape};
 
 /// Information about heredoc whitespace stripping behavior for PHP
 #[derive(Debug, Clone)]
@@ -234,12 +235,14 @@ pub fn extract_interpolation_vars(node: &Node, source: &str) -> Vec<PromptVar> {
                 let inner_start = (i + 1) as u32;  // Skip the opening {
                 let inner_end = (j - 1) as u32;     // Skip the closing }
                 
-                vars.push(PromptVar {
-                    span: SpanShape {
+                vars.push(prompt_var(
+                    source,
+                    SpanShape {
                         outer: (outer_start, outer_end),
                         inner: (inner_start, inner_end),
                     },
-                });
+                    (inner_start, inner_end),
+                ));
                 
                 i = j;
             } else {
@@ -290,3 +293,19 @@ pub fn is_string_like(node: &Node) -> bool {
         "string" | "string_content" | "encapsed_string" | "heredoc" | "nowdoc" | "heredoc_body"
     )
 }
+
+/// Resolve escaping rules of the string literal containing the span. PHP
+/// single-quoted strings only escape `\\` and `\'`, while nowdocs have no
+/// escapes at all.
+pub fn escape_style(root: &Node, span: Span) -> EscapeStyle {
+    let mut node = root.descendant_for_byte_range(span.0 as usize, span.1 as usize);
+    while let Some(current) = node {
+        match current.kind() {
+            "encapsed_string" | "heredoc" => return EscapeStyle::Php,
+            "string" => return EscapeStyle::SingleQuoted,
+            "nowdoc" => return EscapeStyle::None,
+            _ => node = current.parent(),
+        }
+    }
+    EscapeStyle::None
+}
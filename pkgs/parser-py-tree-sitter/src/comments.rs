@@ -1,5 +1,5 @@
 use tree_sitter::{Node, Tree};
-use volumen_parser_core::{compute_comment_inner_offsets, parse_annotation};
+use volumen_parser_core::{PromptDetector, PromptRegions, compute_comment_inner_offsets};
 use volumen_types::{PromptAnnotation, SpanShape};
 
 /// Recursively extract all comment nodes from the tree.
@@ -36,11 +36,13 @@ pub struct CommentNode {
 pub struct CommentTracker<'a> {
     source: &'a str,
     comments: Vec<CommentNode>,
+    regions: PromptRegions,
+    detector: PromptDetector,
 }
 
 impl<'a> CommentTracker<'a> {
     /// Create a new CommentTracker by extracting all comments from the tree.
-    pub fn new(tree: &Tree, source: &'a str) -> Self {
+    pub fn new(tree: &Tree, source: &'a str, detector: PromptDetector) -> Self {
         let root = tree.root_node();
         let mut comments = Vec::new();
 
@@ -50,7 +52,25 @@ impl<'a> CommentTracker<'a> {
         // Sort comments by start position for efficient searching
         comments.sort_by_key(|c| c.start);
 
-        Self { source, comments }
+        // Resolve file-level and region markers once for all statements
+        let regions = PromptRegions::new(
+            source,
+            comments.iter().map(|c| (c.start, c.end, c.text.as_str())),
+            &detector,
+        );
+
+        Self {
+            source,
+            comments,
+            regions,
+            detector,
+        }
+    }
+
+    /// Check if a statement starts inside a region marked with `@prompts` or
+    /// `@prompt-begin`/`@prompt-end`.
+    pub fn in_prompt_region(&self, stmt_start: u32) -> bool {
+        self.regions.contains(stmt_start)
     }
 
     /// Collect adjacent leading comments for a statement.
@@ -106,7 +126,7 @@ impl<'a> CommentTracker<'a> {
         // Check if any comment in the block contains @prompt
         let has_prompt = block_ranges
             .iter()
-            .any(|c| parse_annotation(&c.text).unwrap_or(false));
+            .any(|c| self.detector.annotation(&c.text).unwrap_or(false));
         if !has_prompt {
             return Vec::new();
         }
@@ -129,9 +149,8 @@ impl<'a> CommentTracker<'a> {
             })
             .collect();
 
-        vec![PromptAnnotation {
-            spans,
-        }]
+        let attributes = self.detector.annotation_attributes(self.source, &spans);
+        vec![PromptAnnotation { spans, attributes }]
     }
 
     /// Collect inline @prompt comments within a statement's range.
@@ -139,15 +158,15 @@ impl<'a> CommentTracker<'a> {
         self.comments
             .iter()
             .filter(|c| c.start >= stmt_start && c.start < stmt_end)
-            .filter(|c| parse_annotation(&c.text).unwrap_or(false))
+            .filter(|c| self.detector.annotation(&c.text).unwrap_or(false))
             .map(|c| {
                 let (inner_start_offset, inner_end_offset) = compute_comment_inner_offsets(&c.text);
-                PromptAnnotation {
-                    spans: vec![SpanShape {
-                        outer: (c.start, c.end),
-                        inner: (c.start + inner_start_offset, c.start + inner_end_offset),
-                    }],
-                }
+                let spans = vec![SpanShape {
+                    outer: (c.start, c.end),
+                    inner: (c.start + inner_start_offset, c.start + inner_end_offset),
+                }];
+                let attributes = self.detector.annotation_attributes(self.source, &spans);
+                PromptAnnotation { spans, attributes }
             })
             .collect()
     }
@@ -158,6 +177,20 @@ impl<'a> CommentTracker<'a> {
         annotations.first().and_then(|a| a.spans.first()).map(|s| s.outer.0)
     }
 
+    /// Check if a statement has an opt-out annotation (e.g., `@prompt-ignore`)
+    /// in the adjacent leading comments, inline or trailing on its last line.
+    pub fn has_ignore_annotation(&self, stmt_start: u32, stmt_end: u32) -> bool {
+        let start = self.get_any_leading_start(stmt_start).unwrap_or(stmt_start);
+        let end = self.source[stmt_end as usize..]
+            .find('\n')
+            .map_or(self.source.len(), |offset| stmt_end as usize + offset)
+            as u32;
+        self.comments
+            .iter()
+            .filter(|c| c.start >= start && c.start < end)
+            .any(|c| self.detector.ignore_annotation(&c.text))
+    }
+
     /// Get the start position of any adjacent leading comment, regardless of whether it's valid.
     /// Used for enclosure calculation when we want to include all leading comments.
     pub fn get_any_leading_start(&self, stmt_start: u32) -> Option<u32> {
@@ -244,7 +277,7 @@ mod tests {
 x = 1"#;
 
         let tree = parser.parse(source, None).unwrap();
-        let tracker = CommentTracker::new(&tree, source);
+        let tracker = CommentTracker::new(&tree, source, PromptDetector::default());
 
         assert_eq!(tracker.comments.len(), 2);
         assert_eq!(tracker.comments[0].text, "# Comment 1");
@@ -263,7 +296,7 @@ x = 1"#;
 x = "hello""#;
 
         let tree = parser.parse(source, None).unwrap();
-        let tracker = CommentTracker::new(&tree, source);
+        let tracker = CommentTracker::new(&tree, source, PromptDetector::default());
 
         // Find the statement start - need to skip comment nodes
         let root = tree.root_node();
@@ -286,7 +319,7 @@ x = "hello""#;
         let source = r#"x = "hello"  # @prompt"#;
 
         let tree = parser.parse(source, None).unwrap();
-        let tracker = CommentTracker::new(&tree, source);
+        let tracker = CommentTracker::new(&tree, source, PromptDetector::default());
 
         // Use the line start/end instead of the statement byte range
         // because inline comments appear after the statement
@@ -311,7 +344,7 @@ x = "hello""#;
 x = "hello""#;
 
         let tree = parser.parse(source, None).unwrap();
-        let tracker = CommentTracker::new(&tree, source);
+        let tracker = CommentTracker::new(&tree, source, PromptDetector::default());
 
         let root = tree.root_node();
         let assignment = root.child(0).unwrap();
@@ -1,52 +1,108 @@
 mod comments;
 mod queries;
-mod scope;
 mod spans;
 
 use comments::CommentTracker;
-use scope::ScopeTracker;
-use spans::{extract_fstring_vars, span_shape_string_like};
+use spans::{escape_style, extract_fstring_vars, span_shape_string_like};
 use tree_sitter::{Node, Parser, Tree};
 pub use volumen_parser_core::VolumenParser;
+use volumen_parser_core::{
+    CallArgument, CallSiteSyntax, ConcatContent, ConcatSegment, PromptDetector, PromptFunction,
+    PromptReferences, ScopeTracker, build_content_tokens, collect_diagnostics, concat_content,
+    error_diagnostic, file_language, file_size_error, format_var, is_content_key, is_role_key,
+    parse_result, parse_role_value, process_call_sites, prompt_group, prompt_var, resolve_escapes,
+};
+
 use volumen_types::*;
 
-pub struct ParserPy {}
+pub struct ParserPy {
+    parser: Parser,
+}
 
-impl VolumenParser for ParserPy {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        // Initialize Tree-sitter parser
+impl ParserPy {
+    pub fn new() -> Self {
         let mut parser = Parser::new();
         parser
             .set_language(&tree_sitter_python::LANGUAGE.into())
             .expect("Failed to load Python grammar");
+        Self { parser }
+    }
+}
+
+impl Default for ParserPy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VolumenParser for ParserPy {
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        // Stub files are parsed the same way, but reported as a dialect
+        let dialect = match file_language(filename) {
+            Some((ParseLanguage::Py, dialect)) => dialect,
+            _ => None,
+        };
+        self.parse_as(source, filename, ParseLanguage::Py, dialect, options)
+    }
+
+    fn parse_as(
+        &mut self,
+        source: &str,
+        filename: &str,
+        _language: ParseLanguage,
+        dialect: Option<ParseDialect>,
+        options: &ParseOptions,
+    ) -> ParseResult {
+        // Skip sources exceeding the maximum file size
+        if let Some(result) = file_size_error(
+            source,
+            options,
+            ParseLanguage::Py,
+            dialect.clone(),
+            "tree-sitter",
+        ) {
+            return result;
+        }
 
         // Parse source
-        let tree = match parser.parse(source, None) {
+        let tree = match self.parser.parse(source, None) {
             Some(tree) => tree,
             None => {
                 return ParseResult::ParseResultError(ParseResultError {
                     state: ParseResultErrorStateError,
                     error: "Failed to parse source".to_string(),
+                    diagnostics: vec![error_diagnostic(
+                        "tree-sitter",
+                        "parse-failed",
+                        "Failed to parse source".to_string(),
+                        (0, source.len() as u32),
+                    )],
+                    language: Some(ParseLanguage::Py),
+                    dialect,
+                    detection: None,
+                    parser: Some("tree-sitter".to_string()),
                 });
             }
         };
 
-        // Check for parse errors
+        // Collect parse errors. Tree-sitter recovers from errors, so the tree
+        // is still processed, skipping error nodes.
         let root = tree.root_node();
+        let mut diagnostics = Vec::new();
         if root.has_error() {
-            let error_msg = format_tree_sitter_error(&root, source);
-            return ParseResult::ParseResultError(ParseResultError {
-                state: ParseResultErrorStateError,
-                error: error_msg,
-            });
+            collect_diagnostics(&root, source, &mut diagnostics);
         }
 
+        // Resolve prompt detection rules
+        let detector = PromptDetector::new(options);
+
         // Extract comments
-        let comment_tracker = CommentTracker::new(&tree, source);
+        let comment_tracker = CommentTracker::new(&tree, source, detector.clone());
 
         // Initialize state
         let mut prompts = Vec::new();
-        let mut scope_tracker = ScopeTracker::new();
+        let mut groups = Vec::new();
+        let mut scope_tracker = ScopeTracker::new(detector.clone());
 
         // Process tree
         process_tree(
@@ -57,16 +113,51 @@ impl VolumenParser for ParserPy {
             &comment_tracker,
             &mut scope_tracker,
             &mut prompts,
+            &mut groups,
         );
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
+        // Detect prompts passed to LLM SDK call sites
+        let call_sites = CallSites {
+            comments: &comment_tracker,
+        };
+        process_call_sites(
+            &root,
+            source,
+            filename,
+            &detector,
+            &call_sites,
+            &mut prompts,
+            &mut groups,
+        );
+
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = PromptReferences::new(&prompts);
+        process_references(&root, source, &mut references);
+        references.apply(&mut prompts);
+
+        // Collect the module imports for the project analysis
+        let imports = module_imports(&root, source);
+
+        // Decode escape sequences in string tokens
+        resolve_escapes(&mut prompts, source, |span| escape_style(&root, source, span));
+
+        // Assign role hints from annotations, e.g., `# @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        parse_result(
+            ParseLanguage::Py,
+            dialect,
+            "tree-sitter",
             prompts,
-        })
+            groups,
+            imports,
+            diagnostics,
+        )
     }
 }
 
 /// Process the entire syntax tree to extract prompts.
+#[allow(clippy::too_many_arguments)]
 fn process_tree(
     _tree: &Tree,
     root: &Node,
@@ -75,9 +166,10 @@ fn process_tree(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Recursively traverse the tree
-    traverse_node(root, source, filename, comments, scopes, prompts);
+    traverse_node(root, source, filename, comments, scopes, prompts, groups);
 }
 
 /// Recursively traverse nodes to find assignments and manage scopes.
@@ -88,11 +180,23 @@ fn traverse_node(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
     let kind = node.kind();
 
+    // Handle function scopes, tracking prompt functions returning prompts
+    if kind == "function_definition" {
+        process_function(node, source, filename, comments, scopes, prompts, groups);
+        return;
+    }
+
     // Handle scope boundaries
-    if kind == "function_definition" || kind == "class_definition" {
+    if kind == "class_definition" {
         scopes.enter_scope();
 
         // Process children
@@ -100,7 +204,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             loop {
                 let child = cursor.node();
-                traverse_node(&child, source, filename, comments, scopes, prompts);
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -118,7 +222,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             let child = cursor.node();
             if child.kind() == "assignment" {
-                process_assignment(&child, source, filename, comments, scopes, prompts);
+                process_assignment(&child, source, filename, comments, scopes, prompts, groups);
                 return; // Don't process children again
             }
         }
@@ -126,21 +230,162 @@ fn traverse_node(
 
     // Handle assignments directly
     if kind == "assignment" {
-        process_assignment(node, source, filename, comments, scopes, prompts);
+        process_assignment(node, source, filename, comments, scopes, prompts, groups);
         return; // Don't process children of assignments
     }
 
+    // Handle prompt function returns: `return f"Hello, {name}!"`
+    if kind == "return_statement"
+        && let Some(value) = node.named_child(0)
+    {
+        process_function_return(
+            &value, node, source, filename, comments, scopes, prompts, groups,
+        );
+    }
+
     // Recursively process children
     let mut cursor = node.walk();
     if cursor.goto_first_child() {
         loop {
             let child = cursor.node();
-            traverse_node(&child, source, filename, comments, scopes, prompts);
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Process a function definition scope. Functions with prompt-like names or
+/// leading `@prompt` annotations are prompt functions, so their returned
+/// values are detected as prompts.
+fn process_function(
+    node: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    // Decorated functions are annotated before the decorators
+    let stmt_start = node
+        .parent()
+        .filter(|parent| parent.kind() == "decorated_definition")
+        .map_or(node.start_byte(), |parent| parent.start_byte()) as u32;
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|parameters| function_parameters(&parameters, source))
+        .unwrap_or_default();
+    let function = node.child_by_field_name("name").and_then(|name| {
+        // Opt-out annotations exclude the function from prompt functions
+        if comments.has_ignore_annotation(stmt_start, name.end_byte() as u32) {
+            return None;
+        }
+        PromptFunction::new(
+            scopes.detector(),
+            source,
+            (name.start_byte() as u32, name.end_byte() as u32),
+            parameters.clone(),
+            comments.collect_adjacent_leading(stmt_start),
+        )
+    });
+    scopes.enter_function(function);
+
+    // Parameters shadow the outer identifiers with the same names
+    for parameter in &parameters {
+        scopes.declare(parameter);
+    }
+
+    // Process children
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
             if !cursor.goto_next_sibling() {
                 break;
             }
         }
     }
+
+    scopes.exit_function();
+}
+
+/// Collect the function parameter names, e.g., `user`, `args` and `kwargs`
+/// for `def build_prompt(user, *args, **kwargs)`.
+fn function_parameters(node: &Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter_map(parameter_name)
+        .filter_map(|name| name.utf8_text(source.as_bytes()).ok())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Get the name identifier of a function parameter, e.g., `name` for
+/// `name: str = "World"` or `kwargs` for `**kwargs`.
+fn parameter_name(node: Node) -> Option<Node> {
+    match node.kind() {
+        "identifier" => Some(node),
+        "default_parameter" | "typed_default_parameter" => {
+            node.child_by_field_name("name").and_then(parameter_name)
+        }
+        "typed_parameter" | "list_splat_pattern" | "dictionary_splat_pattern" => {
+            node.named_child(0).and_then(parameter_name)
+        }
+        _ => None,
+    }
+}
+
+/// Process a value returned from the enclosing prompt function. The value is
+/// processed like a prompt assignment bound to the function name, marking
+/// the variables that come from the function parameters.
+#[allow(clippy::too_many_arguments)]
+fn process_function_return(
+    value: &Node,
+    stmt: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let Some(function) = scopes.current_function().cloned() else {
+        return;
+    };
+    let stmt_start = stmt.start_byte() as u32;
+    let stmt_end = stmt.end_byte() as u32;
+
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
+    // Statement annotations take precedence over the function annotations
+    let mut annotations = comments.collect_adjacent_leading(stmt_start);
+    annotations.extend(comments.collect_inline_prompt(stmt_start, stmt_end));
+    if annotations.is_empty() {
+        annotations = function.annotations.clone();
+    }
+
+    let prompts_len = prompts.len();
+    process_identifier_assignment(
+        &function.binding.name,
+        Some(*value),
+        false,
+        true,
+        &annotations,
+        &function.binding,
+        stmt_start,
+        stmt_end,
+        source,
+        filename,
+        comments,
+        scopes,
+        prompts,
+        groups,
+    );
+    function.mark_parameter_vars(prompts, prompts_len);
 }
 
 /// Process an assignment node to extract prompts.
@@ -151,13 +396,15 @@ fn process_assignment(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     process_assignment_with_annotations(
-        node, source, filename, comments, scopes, prompts, None, None,
+        node, source, filename, comments, scopes, prompts, groups, None, None,
     );
 }
 
 /// Process an assignment node with inherited annotations (for chained assignments).
+#[allow(clippy::too_many_arguments)]
 fn process_assignment_with_annotations(
     node: &Node,
     source: &str,
@@ -165,12 +412,16 @@ fn process_assignment_with_annotations(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
     inherited_annotations: Option<Vec<PromptAnnotation>>,
     parent_stmt_bounds: Option<(u32, u32)>,
 ) {
     let (stmt_start, stmt_end) =
         parent_stmt_bounds.unwrap_or_else(|| (node.start_byte() as u32, node.end_byte() as u32));
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations - use inherited if provided, otherwise collect from comments
     let all_annotations = if let Some(inherited) = inherited_annotations {
         inherited
@@ -182,7 +433,9 @@ fn process_assignment_with_annotations(
         all_annotations
     };
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
 
     // Get assignment components using field names
     let left = match node.child_by_field_name("left") {
@@ -198,12 +451,14 @@ fn process_assignment_with_annotations(
         "identifier" => {
             // Simple assignment: var = value
             let ident_name = left.utf8_text(source.as_bytes()).unwrap_or("");
+            let binding = binding_from_node(&left, source, PromptBindingKind::Local);
             process_identifier_assignment(
                 ident_name,
                 right_node,
                 has_type_annotation,
                 has_prompt_annotation,
                 &all_annotations,
+                &binding,
                 stmt_start,
                 stmt_end,
                 source,
@@ -211,6 +466,7 @@ fn process_assignment_with_annotations(
                 comments,
                 scopes,
                 prompts,
+                groups,
             );
 
             // Handle chained assignments: a = b = value
@@ -246,6 +502,7 @@ fn process_assignment_with_annotations(
                                     stmt_end,
                                     comments,
                                     &all_annotations,
+                                    &binding,
                                     prompts,
                                 );
                             }
@@ -261,6 +518,7 @@ fn process_assignment_with_annotations(
                         comments,
                         scopes,
                         prompts,
+                        groups,
                         Some(all_annotations.clone()),
                         Some((stmt_start, stmt_end)),
                     );
@@ -295,6 +553,7 @@ fn process_identifier_assignment(
     has_type_annotation: bool,
     has_prompt_annotation: bool,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     stmt_start: u32,
     stmt_end: u32,
     source: &str,
@@ -302,11 +561,13 @@ fn process_identifier_assignment(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Check if it's a type-only annotation (no value)
     if right_node.is_none() {
         if has_type_annotation && has_prompt_annotation {
             // Store the annotation for later use
+            scopes.declare(ident_name);
             scopes.store_def_annotation(ident_name, annotations.to_vec());
             scopes.mark_annotated(ident_name);
             scopes.mark_prompt_ident(ident_name);
@@ -319,8 +580,15 @@ fn process_identifier_assignment(
         None => return,
     };
 
-    // Determine if this is a prompt
-    let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
+    // Check if it's a chat message dict or a list of them
+    let messages = extract_messages(&right, source);
+
+    // Determine if this is a prompt. Chat messages are prompts by structure.
+    let is_prompt = is_prompt_variable(
+        ident_name,
+        has_prompt_annotation || !messages.is_empty(),
+        scopes,
+    );
 
     if is_prompt {
         // Mark as prompt identifier (even if value isn't a string yet)
@@ -332,8 +600,41 @@ fn process_identifier_assignment(
             scopes.mark_annotated(ident_name);
         }
 
-        // Check if it's a string or f-string
-        if is_string_like(&right) {
+        if !messages.is_empty() {
+            let final_annotations = if !annotations.is_empty() {
+                annotations.to_vec()
+            } else {
+                scopes.get_def_annotation(ident_name).unwrap_or_default()
+            };
+
+            // Handle messages: [{"role": "system", "content": "..."}]
+            let group_start = prompts.len();
+            for (role, content) in messages {
+                let prompts_len = prompts.len();
+                create_prompt_from_string(
+                    &content,
+                    source,
+                    filename,
+                    stmt_start,
+                    stmt_end,
+                    comments,
+                    &final_annotations,
+                    binding,
+                    prompts,
+                );
+                for prompt in &mut prompts[prompts_len..] {
+                    prompt.role = role;
+                }
+            }
+
+            // Group the messages list into a conversation
+            if matches!(right.kind(), "list" | "tuple") {
+                let span = (right.start_byte() as u32, right.end_byte() as u32);
+                if let Some(group) = prompt_group(filename, span, binding, prompts, group_start) {
+                    groups.push(group);
+                }
+            }
+        } else if is_string_like(&right) {
             // Annotations from comment tracker are already validated to contain @prompt
             // Get annotations (from current statement or from definition)
             let final_annotations = if !annotations.is_empty() {
@@ -351,6 +652,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
                 prompts,
             );
         } else if right.kind() == "binary_operator" {
@@ -363,6 +665,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -376,6 +679,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -390,6 +694,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             } else if let Some(prompt) = process_format_call(
@@ -400,6 +705,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -436,12 +742,14 @@ fn process_multi_assignment(
     extract_value_ranges(&right, &mut value_ranges);
 
     // Match identifiers with values
-    for (i, ident_name) in identifiers.iter().enumerate() {
+    for (i, (ident_name, ident_node)) in identifiers.iter().enumerate() {
         if let Some((start, end, kind)) = value_ranges.get(i) {
             let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
 
             if is_prompt && (kind == "string" || kind == "concatenated_string") {
                 scopes.mark_prompt_ident(ident_name);
+                let binding =
+                    binding_from_node(ident_node, source, PromptBindingKind::Destructured);
 
                 // Create a temporary node-like structure using ranges
                 create_prompt_from_range(
@@ -453,6 +761,7 @@ fn process_multi_assignment(
                     stmt_end,
                     comments,
                     annotations,
+                    &binding,
                     prompts,
                 );
             }
@@ -461,10 +770,14 @@ fn process_multi_assignment(
 }
 
 /// Extract identifiers from a pattern (tuple/list).
-fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>) {
+fn extract_identifiers<'a>(
+    node: &Node<'a>,
+    source: &str,
+    identifiers: &mut Vec<(String, Node<'a>)>,
+) {
     if node.kind() == "identifier" {
         if let Ok(name) = node.utf8_text(source.as_bytes()) {
-            identifiers.push(name.to_string());
+            identifiers.push((name.to_string(), *node));
         }
         return;
     }
@@ -481,6 +794,15 @@ fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>)
     }
 }
 
+/// Create a prompt binding from an identifier node.
+fn binding_from_node(node: &Node, source: &str, kind: PromptBindingKind) -> PromptBinding {
+    PromptBinding {
+        name: node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+        span: (node.start_byte() as u32, node.end_byte() as u32),
+        kind,
+    }
+}
+
 /// Extract value ranges from a tuple/list.
 /// Returns (start_byte, end_byte, kind) for each value.
 fn extract_value_ranges(node: &Node, ranges: &mut Vec<(usize, usize, String)>) {
@@ -513,6 +835,7 @@ fn create_prompt_from_range(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Calculate spans manually
@@ -593,18 +916,31 @@ fn create_prompt_from_range(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
-/// Check if a variable should be considered a prompt.
-fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &ScopeTracker) -> bool {
-    // Name contains "prompt" (case-insensitive)
-    if ident_name.to_lowercase().contains("prompt") {
+/// Check if a variable should be considered a prompt. Assignments declare
+/// the variable in the current scope, shadowing the outer variables with the
+/// name.
+fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &mut ScopeTracker) -> bool {
+    scopes.declare(ident_name);
+
+    // Has @prompt-ignore annotation, so the identifier stays ignored
+    if scopes.is_statement_ignored() {
+        scopes.mark_ignored_ident(ident_name);
+        return false;
+    }
+
+    // Name matches a prompt name pattern
+    if scopes.is_prompt_name(ident_name) {
         return true;
     }
 
@@ -621,62 +957,278 @@ fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &ScopeTrac
     false
 }
 
-/// Check if a node is a string-like value.
-fn is_string_like(node: &Node) -> bool {
-    node.kind() == "string" || node.kind() == "concatenated_string"
+/// Python LLM SDK call site syntax, e.g.,
+/// `client.messages.create(system="...")`.
+struct CallSites<'a> {
+    comments: &'a CommentTracker<'a>,
 }
 
-/// Build content tokens from span and variables.
-/// For prompts without variables, returns a single str token.
-/// For prompts with variables, returns interleaved str and var tokens.
-fn build_content_tokens(span: &SpanShape, vars: &[PromptVar]) -> Vec<PromptContentToken> {
-    if vars.is_empty() {
-        // Simple case: single str token
-        return vec![PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: span.inner,
-            },
-        )];
+impl<'tree> CallSiteSyntax<'tree> for CallSites<'_> {
+    fn call(&self, node: &Node<'tree>, _source: &str) -> Option<(Span, Node<'tree>)> {
+        if node.kind() != "call" {
+            return None;
+        }
+        let function = node.child_by_field_name("function")?;
+        let arguments = node.child_by_field_name("arguments")?;
+        Some((
+            (function.start_byte() as u32, function.end_byte() as u32),
+            arguments,
+        ))
+    }
+
+    /// Keyword arguments and dict literal arguments are named.
+    fn arguments(&self, arguments: &Node<'tree>, source: &str) -> Vec<CallArgument<'tree>> {
+        let mut pairs = Vec::new();
+        let mut values = Vec::new();
+        let mut cursor = arguments.walk();
+        let arguments = arguments
+            .named_children(&mut cursor)
+            .filter(|argument| argument.kind() != "comment");
+        for (position, argument) in arguments.enumerate() {
+            match argument.kind() {
+                "keyword_argument" => {
+                    if let (Some(name), Some(value)) = (
+                        argument.child_by_field_name("name"),
+                        argument.child_by_field_name("value"),
+                    ) {
+                        pairs.push(CallArgument::Named(name, value));
+                    }
+                }
+                "dictionary" if extract_message(&argument, source).is_none() => {
+                    let mut dict_cursor = argument.walk();
+                    pairs.extend(
+                        argument
+                            .named_children(&mut dict_cursor)
+                            .filter(|pair| pair.kind() == "pair")
+                            .filter_map(|pair| {
+                                Some(CallArgument::Named(
+                                    pair.child_by_field_name("key")?,
+                                    pair.child_by_field_name("value")?,
+                                ))
+                            }),
+                    );
+                }
+                _ => values.push(CallArgument::Positional(position, argument)),
+            }
+        }
+        values.extend(pairs);
+        values
     }
 
-    let mut tokens = Vec::new();
-    let mut pos = span.inner.0;
+    fn messages(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+    ) -> Vec<(Option<PromptRole>, Node<'tree>)> {
+        extract_messages(value, source)
+    }
 
-    for (var_idx, var) in vars.iter().enumerate() {
-        // Add str token before variable (if any content)
-        if pos < var.span.outer.0 {
-            tokens.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (pos, var.span.outer.0),
-                },
-            ));
+    fn is_conversation(&self, value: &Node<'tree>, _source: &str) -> bool {
+        matches!(value.kind(), "list" | "tuple")
+    }
+
+    fn is_string_like(&self, value: &Node<'tree>) -> bool {
+        is_string_like(value)
+    }
+
+    fn create_prompt(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+        filename: &str,
+        call: Span,
+        binding: &PromptBinding,
+        prompts: &mut Vec<Prompt>,
+    ) {
+        create_prompt_from_string(
+            value,
+            source,
+            filename,
+            call.0,
+            call.1,
+            self.comments,
+            &[],
+            binding,
+            prompts,
+        );
+    }
+}
+
+/// Recursively resolve the identifiers referencing prompt bindings, e.g.,
+/// `send(user_prompt)`, tracking the function, lambda and class scopes.
+fn process_references(node: &Node, source: &str, references: &mut PromptReferences) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
+    let kind = node.kind();
+    let is_scope = matches!(kind, "function_definition" | "lambda" | "class_definition");
+    if is_scope {
+        references.enter_scope();
+        // Parameters shadow the outer bindings
+        if let Some(parameters) = node.child_by_field_name("parameters") {
+            for name in function_parameters(&parameters, source) {
+                references.shadow(&name);
+            }
         }
+    }
 
-        // Add var token
-        tokens.push(PromptContentToken::PromptContentTokenVar(
-            PromptContentTokenVar {
-                r#type: PromptContentTokenVarTypeVar,
-                span: var.span.outer,
-                index: var_idx as u32,
-            },
-        ));
+    if kind == "identifier"
+        && let (Some(reference_kind), Ok(name)) =
+            (reference_kind(node), node.utf8_text(source.as_bytes()))
+    {
+        let span = (node.start_byte() as u32, node.end_byte() as u32);
+        // Assignments declare the variables in the current scope
+        if reference_kind == PromptReferenceKind::Reassign {
+            references.assignment(name, span);
+        } else {
+            references.identifier(name, span, reference_kind);
+        }
+    }
 
-        pos = var.span.outer.1;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        process_references(&child, source, references);
     }
 
-    // Add trailing str token (if any content)
-    if pos < span.inner.1 {
-        tokens.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (pos, span.inner.1),
-            },
-        ));
+    if is_scope {
+        references.exit_scope();
     }
+}
 
-    tokens
+/// Get the reference kind by the identifier position. Function, class,
+/// parameter, attribute and keyword names aren't references.
+fn reference_kind(node: &Node) -> Option<PromptReferenceKind> {
+    let parent = node.parent()?;
+    let field = |name: &str| parent.child_by_field_name(name) == Some(*node);
+    match parent.kind() {
+        "function_definition" | "class_definition" | "keyword_argument" if field("name") => None,
+        "default_parameter" | "typed_default_parameter" if field("name") => None,
+        "parameters" | "lambda_parameters" | "typed_parameter" => None,
+        "list_splat_pattern" | "dictionary_splat_pattern"
+            if parent.parent().is_some_and(|parameters| {
+                matches!(parameters.kind(), "parameters" | "lambda_parameters")
+            }) =>
+        {
+            None
+        }
+        "attribute" if field("attribute") => None,
+        "assignment" | "augmented_assignment" if field("left") => {
+            Some(PromptReferenceKind::Reassign)
+        }
+        "argument_list" | "keyword_argument" => Some(PromptReferenceKind::Argument),
+        _ => Some(PromptReferenceKind::Read),
+    }
+}
+
+/// Collect the top-level `from` imports, e.g., `SYSTEM` for
+/// `from .prompts import SYSTEM`.
+fn module_imports(root: &Node, source: &str) -> Vec<ParseImport> {
+    let mut imports = Vec::new();
+    let mut cursor = root.walk();
+    for statement in root.children(&mut cursor) {
+        if statement.kind() != "import_from_statement" {
+            continue;
+        }
+        let Some(module) = statement.child_by_field_name("module_name") else {
+            continue;
+        };
+
+        let mut names_cursor = statement.walk();
+        let mut names: Vec<Node> = statement
+            .children_by_field_name("name", &mut names_cursor)
+            .collect();
+        // Wildcard imports, e.g., `from .prompts import *`
+        names.extend(
+            statement
+                .named_children(&mut names_cursor)
+                .filter(|child| child.kind() == "wildcard_import"),
+        );
+
+        for name in names {
+            // Aliased imports, e.g., `from .prompts import SYSTEM as system`
+            let (imported, local) = if name.kind() == "aliased_import" {
+                match (
+                    name.child_by_field_name("name"),
+                    name.child_by_field_name("alias"),
+                ) {
+                    (Some(imported), Some(alias)) => (imported, alias),
+                    _ => continue,
+                }
+            } else {
+                (name, name)
+            };
+            imports.push(ParseImport {
+                source: source[module.byte_range()].to_string(),
+                name: source[imported.byte_range()].to_string(),
+                local: source[local.byte_range()].to_string(),
+                span: (imported.start_byte() as u32, imported.end_byte() as u32),
+            });
+        }
+    }
+    imports
+}
+
+/// Check if a node is a string-like value.
+fn is_string_like(node: &Node) -> bool {
+    node.kind() == "string" || node.kind() == "concatenated_string"
+}
+
+/// Extract chat message roles and string contents from a message dict, e.g.,
+/// `{"role": "system", "content": "..."}` or `dict(role="system", content="...")`,
+/// or from a list or a tuple of message dicts.
+fn extract_messages<'a>(node: &Node<'a>, source: &str) -> Vec<(Option<PromptRole>, Node<'a>)> {
+    match node.kind() {
+        "dictionary" | "call" => extract_message(node, source).into_iter().collect(),
+        "list" | "tuple" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .filter_map(|child| extract_message(&child, source))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Extract the role and the string content from a message dict. Dicts
+/// without the role key aren't messages.
+fn extract_message<'a>(node: &Node<'a>, source: &str) -> Option<(Option<PromptRole>, Node<'a>)> {
+    let (pairs, key_field, value_field) = match node.kind() {
+        "dictionary" => (*node, "key", "value"),
+        "call" => {
+            let function = node.child_by_field_name("function")?;
+            if function.utf8_text(source.as_bytes()).ok()? != "dict" {
+                return None;
+            }
+            (node.child_by_field_name("arguments")?, "name", "value")
+        }
+        _ => return None,
+    };
+
+    let mut role = None;
+    let mut content = None;
+    let mut cursor = pairs.walk();
+    for pair in pairs.named_children(&mut cursor) {
+        if !matches!(pair.kind(), "pair" | "keyword_argument") {
+            continue;
+        }
+        let (Some(key), Some(value)) = (
+            pair.child_by_field_name(key_field),
+            pair.child_by_field_name(value_field),
+        ) else {
+            continue;
+        };
+        let key_text = key.utf8_text(source.as_bytes()).unwrap_or_default();
+        if is_role_key(key_text) {
+            role = Some(parse_role_value(
+                value.utf8_text(source.as_bytes()).unwrap_or_default(),
+            ));
+        } else if is_content_key(key_text) && is_string_like(&value) {
+            content = Some(value);
+        }
+    }
+    Some((role?, content?))
 }
 
 /// Create a prompt from a string node.
@@ -689,6 +1241,7 @@ fn create_prompt_from_string(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Calculate spans
@@ -719,11 +1272,14 @@ fn create_prompt_from_string(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
@@ -733,54 +1289,8 @@ fn is_fstring(node: &Node, source: &str) -> bool {
     text.starts_with('f') || text.starts_with("fr") || text.starts_with("rf")
 }
 
-/// Format Tree-sitter error message.
-fn format_tree_sitter_error(root: &Node, source: &str) -> String {
-    if !root.has_error() {
-        return "Unknown syntax error".to_string();
-    }
-
-    // Find first ERROR node
-    find_error_node(root, source)
-}
-
-/// Find and format error node.
-fn find_error_node(node: &Node, source: &str) -> String {
-    if node.is_error() || node.is_missing() {
-        let pos = node.start_position();
-        return format!(
-            "Syntax error at line {}, column {}",
-            pos.row + 1,
-            pos.column + 1
-        );
-    }
-
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            let child = cursor.node();
-            let result = find_error_node(&child, source);
-            if result != "Unknown syntax error" {
-                return result;
-            }
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
-    }
-
-    "Unknown syntax error".to_string()
-}
-
 // Concatenation support
 
-#[derive(Debug)]
-enum ConcatSegment {
-    String(SpanShape),
-    Variable(SpanShape),
-    Primitive(SpanShape),
-    Other,
-}
-
 /// Process a binary operator for string concatenation
 #[allow(clippy::too_many_arguments)]
 fn process_concatenation(
@@ -791,96 +1301,26 @@ fn process_concatenation(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if it's a + operator
-    let operator_node = node.child_by_field_name("operator")?;
-    let operator = operator_node.utf8_text(source.as_bytes()).ok()?;
-    if operator != "+" {
+    let operator = node.child_by_field_name("operator")?;
+    let op_text = operator.utf8_text(source.as_bytes()).ok()?;
+    if op_text != "+" {
         return None;
     }
 
     // Extract segments recursively
     let segments = extract_concat_segments(node, source);
-
-    // Reject if no strings or contains complex objects
-    let has_string = segments.iter().any(|s| matches!(s, ConcatSegment::String(_)));
-    let has_other = segments.iter().any(|s| matches!(s, ConcatSegment::Other));
-
-    if !has_string || has_other {
-        return None;
-    }
-
-    // Build prompt outer span (entire concatenation expression)
-    let prompt_outer = (node.start_byte() as u32, node.end_byte() as u32);
-
-    // Find first and last string segments to determine inner span
-    let first_string_pos = segments.iter().find_map(|s| match s {
-        ConcatSegment::String(span) => Some(span.inner.0),
-        _ => None,
-    })?;
-
-    let last_string_end = segments.iter().rev().find_map(|s| match s {
-        ConcatSegment::String(span) => Some(span.inner.1),
-        _ => None,
-    })?;
-
-    let prompt_inner = (first_string_pos, last_string_end);
-    let span = SpanShape {
-        outer: prompt_outer,
-        inner: prompt_inner,
-    };
-
-    // Build vars and content tokens
-    let mut vars = Vec::new();
-    let mut content = Vec::new();
-    let mut current_pos = prompt_inner.0;
-    let mut var_idx = 0u32;
-
-    for segment in &segments {
-        match segment {
-            ConcatSegment::String(s_span) => {
-                // Add string token
-                content.push(PromptContentToken::PromptContentTokenStr(
-                    PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: (s_span.inner.0, s_span.inner.1),
-                    },
-                ));
-                current_pos = s_span.inner.1;
-            }
-            ConcatSegment::Variable(v_span) | ConcatSegment::Primitive(v_span) => {
-                // Expand to include operators
-                let mut var_outer = v_span.clone();
-                expand_to_operators(&mut var_outer, source, prompt_outer);
-
-                let var = PromptVar {
-                    span: SpanShape {
-                        outer: var_outer.outer,
-                        inner: v_span.inner,
-                    },
-                };
-                
-                // Add variable token (before pushing var)
-                content.push(PromptContentToken::PromptContentTokenVar(
-                    PromptContentTokenVar {
-                        r#type: PromptContentTokenVarTypeVar,
-                        span: var.span.inner,
-                        index: var_idx,
-                    },
-                ));
-                
-                vars.push(var);
-                var_idx += 1;
-                current_pos = v_span.inner.1;
-            }
-            ConcatSegment::Other => {}
-        }
-    }
+    let outer = (node.start_byte() as u32, node.end_byte() as u32);
+    let ConcatContent {
+        span,
+        vars,
+        content,
+    } = concat_content(source, outer, "+", &segments)?;
 
     // Calculate enclosure
-    let enclosure_start = comments
-        .get_leading_start(stmt_start)
-        .unwrap_or(stmt_start);
+    let enclosure_start = comments.get_leading_start(stmt_start).unwrap_or(stmt_start);
     let enclosure = (enclosure_start, stmt_end);
 
     Some(Prompt {
@@ -889,11 +1329,14 @@ fn process_concatenation(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -929,43 +1372,17 @@ fn extract_concat_segments(node: &Node, source: &str) -> Vec<ConcatSegment> {
 
 /// Classify a single node as a segment
 fn classify_single_node(node: &Node, source: &str) -> Vec<ConcatSegment> {
-    let kind = node.kind();
-
-    match kind {
+    let span = (node.start_byte() as u32, node.end_byte() as u32);
+    match node.kind() {
+        // String literal
         "string" | "concatenated_string" => {
-            // String literal
-            let span = span_shape_string_like(node, source);
-            vec![ConcatSegment::String(span)]
-        }
-        "identifier" => {
-            // Variable
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Variable(SpanShape { outer, inner })]
-        }
-        "call" => {
-            // Function call - treat as variable
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Variable(SpanShape { outer, inner })]
-        }
-        "attribute" => {
-            // Member access (obj.prop or obj.method()) - treat as variable
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Variable(SpanShape { outer, inner })]
-        }
-        "integer" | "float" | "true" | "false" => {
-            // Primitives - Python requires str() conversion, so this would be a type error
-            // But we can still parse it as a variable for completeness
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Primitive(SpanShape { outer, inner })]
-        }
-        "list" | "tuple" | "dictionary" | "set" => {
-            // Complex objects - reject
-            vec![ConcatSegment::Other]
+            vec![ConcatSegment::String(span_shape_string_like(node, source))]
         }
+        // Variables, function calls and member access (obj.prop or obj.method())
+        "identifier" | "call" | "attribute" => vec![ConcatSegment::Variable(span)],
+        // Primitives - Python requires str() conversion, so this would be a type error
+        // But we can still parse it as a primitive for completeness
+        "integer" | "float" | "true" | "false" => vec![ConcatSegment::Primitive(span)],
         "parenthesized_expression" => {
             // Unwrap parentheses
             if let Some(child) = node.child(1) {
@@ -973,55 +1390,9 @@ fn classify_single_node(node: &Node, source: &str) -> Vec<ConcatSegment> {
             }
             vec![ConcatSegment::Other]
         }
-        _ => {
-            // Unknown - reject
-            vec![ConcatSegment::Other]
-        }
-    }
-}
-
-/// Expand variable span to include surrounding operators
-fn expand_to_operators(var_span: &mut SpanShape, source: &str, prompt_outer: (u32, u32)) {
-    let bytes = source.as_bytes();
-    let mut new_start = var_span.outer.0;
-    let mut new_end = var_span.outer.1;
-
-    // Expand left to include " + "
-    let mut i = new_start as usize;
-    while i > prompt_outer.0 as usize {
-        i -= 1;
-        let c = bytes[i];
-        if c == b'+' {
-            // Include the + and any spaces before it
-            while i > prompt_outer.0 as usize && (bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') {
-                i -= 1;
-            }
-            new_start = i as u32;
-            break;
-        } else if c != b' ' && c != b'\t' {
-            break;
-        }
-    }
-
-    // Expand right to include " + "
-    let mut i = new_end as usize;
-    while i < prompt_outer.1 as usize {
-        let c = bytes[i];
-        if c == b'+' {
-            // Include the + and any spaces after it
-            i += 1;
-            while i < prompt_outer.1 as usize && (bytes[i] == b' ' || bytes[i] == b'\t') {
-                i += 1;
-            }
-            new_end = i as u32;
-            break;
-        } else if c != b' ' && c != b'\t' {
-            break;
-        }
-        i += 1;
+        // Complex objects and unknown expressions - reject
+        _ => vec![ConcatSegment::Other],
     }
-
-    var_span.outer = (new_start, new_end);
 }
 
 /// Process an array/list assignment: ["Hello ", user, "!"]
@@ -1034,6 +1405,7 @@ fn process_array(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Extract array elements
     let mut vars = Vec::new();
@@ -1052,6 +1424,7 @@ fn process_array(
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -1059,9 +1432,7 @@ fn process_array(
                     // Variable
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1075,9 +1446,7 @@ fn process_array(
                     // Function call or member access - treat as variable
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1115,11 +1484,14 @@ fn process_array(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -1133,6 +1505,7 @@ fn process_join_call(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if this is a .join() call
     let function_node = node.child_by_field_name("function")?;
@@ -1209,6 +1582,7 @@ fn process_join_call(
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1219,6 +1593,7 @@ fn process_join_call(
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -1228,6 +1603,7 @@ fn process_join_call(
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1235,9 +1611,7 @@ fn process_join_call(
                     // Variable
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1253,6 +1627,7 @@ fn process_join_call(
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1260,9 +1635,7 @@ fn process_join_call(
                     // Function call or member access - treat as variable
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1300,8 +1673,11 @@ fn process_join_call(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint,
+        role: None,
+        references: None,
     })
 }
 
@@ -1315,6 +1691,7 @@ fn process_format_call(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if this is a .format() call
     let function_node = node.child_by_field_name("function")?;
@@ -1421,6 +1798,7 @@ fn process_format_call(
                 PromptContentTokenStr {
                     r#type: PromptContentTokenStrTypeStr,
                     span: (current_pos, placeholder_span.0),
+                    escapes: None,
                 },
             ));
         }
@@ -1428,13 +1806,8 @@ fn process_format_call(
         // Add variable token
         if let Some(arg_node) = argument_nodes.get(idx) {
             let var_outer = (arg_node.start_byte() as u32, arg_node.end_byte() as u32);
-            let var_inner = var_outer;
-            vars.push(PromptVar {
-                span: SpanShape {
-                    outer: var_outer,
-                    inner: var_inner,
-                },
-            });
+            let placeholder = &source[placeholder_span.0 as usize..placeholder_span.1 as usize];
+            vars.push(format_var(source, placeholder, var_outer));
             content.push(PromptContentToken::PromptContentTokenVar(
                 PromptContentTokenVar {
                     r#type: PromptContentTokenVarTypeVar,
@@ -1453,6 +1826,7 @@ fn process_format_call(
             PromptContentTokenStr {
                 r#type: PromptContentTokenStrTypeStr,
                 span: (current_pos, format_str_span.inner.1),
+                escapes: None,
             },
         ));
     }
@@ -1476,10 +1850,13 @@ fn process_format_call(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
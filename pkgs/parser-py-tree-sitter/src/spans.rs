@@ -1,4 +1,5 @@
 use tree_sitter::Node;
+use volumen_parser_core::{EscapeStyle, interpolation_var};
 use volumen_types::{PromptVar, Span, SpanShape};
 
 /// Calculate outer and inner spans for Python string literals.
@@ -89,9 +90,15 @@ pub fn extract_fstring_vars(node: &Node, source: &str) -> Vec<PromptVar> {
 
             let exp = &source[outer_start..outer_end];
 
-            vars.push(PromptVar {
-                span: SpanShape { outer, inner },
-            });
+            let expression = child
+                .child_by_field_name("expression")
+                .map_or(inner, |expr| (expr.start_byte() as u32, expr.end_byte() as u32));
+
+            vars.push(interpolation_var(
+                source,
+                SpanShape { outer, inner },
+                expression,
+            ));
         }
 
         if !cursor.goto_next_sibling() {
@@ -193,16 +200,19 @@ mod tests {
 
         let vars = extract_fstring_vars(&string_node, source);
 
-        assert_ron_snapshot!(vars, @"
+        assert_ron_snapshot!(vars, @r#"
         [
           PromptVar(
             span: SpanShape(
               outer: (8, 14),
               inner: (9, 13),
             ),
+            expression: "name",
+            kind: identifier,
+            root: Some("name"),
           ),
         ]
-        ");
+        "#);
     }
 
     #[test]
@@ -221,21 +231,47 @@ mod tests {
 
         let vars = extract_fstring_vars(&string_node, source);
 
-        assert_ron_snapshot!(vars, @"
+        assert_ron_snapshot!(vars, @r#"
         [
           PromptVar(
             span: SpanShape(
               outer: (8, 15),
               inner: (9, 14),
             ),
+            expression: "first",
+            kind: identifier,
+            root: Some("first"),
           ),
           PromptVar(
             span: SpanShape(
               outer: (16, 22),
               inner: (17, 21),
             ),
+            expression: "last",
+            kind: identifier,
+            root: Some("last"),
           ),
         ]
-        ");
+        "#);
+    }
+}
+
+/// Resolve escaping rules of the string literal containing the span. Python
+/// string prefix defines if it is raw (`r"..."`) or f-string (`f"..."`).
+pub fn escape_style(root: &Node, source: &str, span: Span) -> EscapeStyle {
+    let mut node = root.descendant_for_byte_range(span.0 as usize, span.1 as usize);
+    while let Some(current) = node {
+        match current.kind() {
+            "string" => {
+                let text = &source[current.start_byte()..current.end_byte()];
+                let prefix = &text[..text.find(['\'', '"']).unwrap_or(0)];
+                return EscapeStyle::Py {
+                    raw: prefix.contains(['r', 'R']),
+                    fstring: prefix.contains(['f', 'F', 't', 'T']),
+                };
+            }
+            _ => node = current.parent(),
+        }
     }
+    EscapeStyle::None
 }
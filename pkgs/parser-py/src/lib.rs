@@ -1,5 +1,3 @@
-use std::collections::{HashMap, HashSet};
-
 use ruff_python_ast as ast;
 use ruff_python_ast::visitor::{self, Visitor};
 use ruff_python_parser::{self as parser, TokenKind};
@@ -8,29 +6,126 @@ pub use volumen_parser_core::VolumenParser;
 use volumen_parser_core::*;
 use volumen_types::*;
 
+/// Ruff parser has no state to reuse, so the instances are interchangeable.
+#[derive(Default)]
 pub struct ParserPy {}
 
+impl ParserPy {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
 impl VolumenParser for ParserPy {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        let parsed = match parser::parse_module(source) {
-            Ok(parsed) => parsed,
-            Err(err) => {
-                return ParseResult::ParseResultError(ParseResultError {
-                    state: ParseResultErrorStateError,
-                    error: format!("{}", err),
-                });
-            }
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        let dialect = match file_language(filename) {
+            Some((ParseLanguage::Py, dialect)) => dialect,
+            _ => None,
         };
+        self.parse_as(source, filename, ParseLanguage::Py, dialect, options)
+    }
+
+    fn parse_as(
+        &mut self,
+        source: &str,
+        filename: &str,
+        _language: ParseLanguage,
+        dialect: Option<ParseDialect>,
+        options: &ParseOptions,
+    ) -> ParseResult {
+        // Skip sources exceeding the maximum file size
+        if let Some(result) =
+            file_size_error(source, options, ParseLanguage::Py, dialect.clone(), "ruff")
+        {
+            return result;
+        }
+
+        // Stub files are parsed with the stub syntax rules
+        let source_type = match dialect {
+            Some(ParseDialect::Pyi) => ast::PySourceType::Stub,
+            _ => ast::PySourceType::Python,
+        };
+        // Ruff recovers from syntax errors, so the module is always available
+        let parsed = parser::parse_unchecked_source(source, source_type);
+        let diagnostics: Vec<ParseDiagnostic> = parsed
+            .errors()
+            .iter()
+            .map(|err| {
+                let span = (err.location.start().to_u32(), err.location.end().to_u32());
+                error_diagnostic("ruff", "syntax-error", err.error.to_string(), span)
+            })
+            .collect();
 
         let comments = ParserPy::parse_comments(source, &parsed);
 
-        let mut visitor = PyPromptVisitor::new(source, filename.to_string(), comments);
+        let detector = PromptDetector::new(options);
+        let mut visitor =
+            PyPromptVisitor::new(source, filename.to_string(), comments, detector.clone());
         visitor.visit_body(parsed.suite());
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
-            prompts: visitor.prompts,
-        })
+        // Detect prompts passed to LLM SDK call sites
+        PyCallSiteVisitor {
+            visitor: &mut visitor,
+            detector: detector.clone(),
+            stmt_start_stack: Vec::new(),
+        }
+        .visit_body(parsed.suite());
+
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = PyReferenceVisitor {
+            references: PromptReferences::new(&visitor.prompts),
+        };
+        references.visit_body(parsed.suite());
+        references.references.apply(&mut visitor.prompts);
+
+        // Collect the module imports for the project analysis
+        let imports = module_imports(parsed.suite());
+
+        // Decode escape sequences in string tokens
+        let strings = ParserPy::parse_strings(source, &parsed);
+        let mut prompts = visitor.prompts;
+        let mut groups = visitor.groups;
+        resolve_escapes(&mut prompts, source, |span| {
+            strings
+                .iter()
+                .find(|(range, _)| {
+                    range.start().to_u32() <= span.0 && span.1 <= range.end().to_u32()
+                })
+                .map_or(EscapeStyle::None, |(_, style)| *style)
+        });
+
+        // Assign role hints from annotations, e.g., `# @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        if diagnostics.is_empty() {
+            ParseResult::ParseResultSuccess(ParseResultSuccess {
+                state: ParseResultSuccessStateSuccess,
+                language: ParseLanguage::Py,
+                dialect,
+                detection: None,
+                parser: "ruff".to_string(),
+                prompts,
+                groups: (!groups.is_empty()).then_some(groups),
+                imports: (!imports.is_empty()).then_some(imports),
+                exports: None,
+            })
+        } else {
+            retain_prompts(&mut prompts, &mut groups, |prompt| {
+                !overlaps_diagnostics(prompt.enclosure, &diagnostics)
+            });
+            ParseResult::ParseResultPartial(ParseResultPartial {
+                state: ParseResultPartialStatePartial,
+                language: ParseLanguage::Py,
+                dialect,
+                detection: None,
+                parser: "ruff".to_string(),
+                prompts,
+                groups: (!groups.is_empty()).then_some(groups),
+                imports: (!imports.is_empty()).then_some(imports),
+                exports: None,
+                diagnostics,
+            })
+        }
     }
 }
 
@@ -51,6 +146,44 @@ impl ParserPy {
         }
         comments
     }
+
+    /// Collect string literal token ranges with their escaping rules. String
+    /// prefixes define if a string is raw (`r"..."`) or f-string (`f"..."`).
+    fn parse_strings(
+        source: &str,
+        parsed: &parser::Parsed<ast::ModModule>,
+    ) -> Vec<(TextRange, EscapeStyle)> {
+        let mut strings = Vec::new();
+        // Raw flags of the enclosing f-strings and t-strings
+        let mut interpolated: Vec<bool> = Vec::new();
+
+        for token in parsed.tokens() {
+            let range = token.range();
+            let text = &source[range];
+            let raw = text[..text.find(['\'', '"']).unwrap_or(0)].contains(['r', 'R']);
+            match token.kind() {
+                TokenKind::String => {
+                    strings.push((
+                        range,
+                        EscapeStyle::Py {
+                            raw,
+                            fstring: false,
+                        },
+                    ));
+                }
+                TokenKind::FStringStart | TokenKind::TStringStart => interpolated.push(raw),
+                TokenKind::FStringMiddle | TokenKind::TStringMiddle => {
+                    let raw = interpolated.last().copied().unwrap_or(false);
+                    strings.push((range, EscapeStyle::Py { raw, fstring: true }));
+                }
+                TokenKind::FStringEnd | TokenKind::TStringEnd => {
+                    interpolated.pop();
+                }
+                _ => {}
+            }
+        }
+        strings
+    }
 }
 
 struct PyPromptVisitor<'a> {
@@ -60,38 +193,56 @@ struct PyPromptVisitor<'a> {
     file: String,
     /// Collected prompts.
     prompts: Vec<Prompt>,
-    /// Stack of identifiers that have prompt annotations.
-    prompt_idents_stack: Vec<HashSet<String>>,
+    /// Collected chat message groups.
+    groups: Vec<PromptGroup>,
+    /// Identifier definitions across the scopes and the enclosing functions.
+    scopes: ScopeTracker,
     /// All comment markers sorted by start.
     comments: Vec<TextRange>,
+    /// Ranges marked with `@prompts` and `@prompt-begin`/`@prompt-end`.
+    regions: PromptRegions,
     /// Cursor to track position in comments array.
     comment_cursor: usize,
     /// Annotations for the current statement (leading comments + inline @prompt).
     stmt_annotations_stack: Vec<Vec<PromptAnnotation>>,
+    /// Whether the current statement has an opt-out annotation.
+    stmt_ignored_stack: Vec<bool>,
     /// Earliest leading annotation start for current statement.
     stmt_leading_start_stack: Vec<Option<u32>>,
     /// Current statement range stack.
     stmt_range_stack: Vec<TextRange>,
-    /// Annotations captured at definition time for annotated variables.
-    def_prompt_annotations: HashMap<String, Vec<PromptAnnotation>>,
-    /// Set of annotated identifiers.
-    annotated_idents: HashSet<String>,
+    /// Prompt detection rules.
+    detector: PromptDetector,
 }
 
 impl<'a> PyPromptVisitor<'a> {
-    fn new(code: &'a str, file: String, comments: Vec<TextRange>) -> Self {
+    fn new(
+        code: &'a str,
+        file: String,
+        comments: Vec<TextRange>,
+        detector: PromptDetector,
+    ) -> Self {
+        let regions = PromptRegions::new(
+            code,
+            comments
+                .iter()
+                .map(|r| (r.start().to_u32(), r.end().to_u32(), &code[*r])),
+            &detector,
+        );
         Self {
             code,
             file,
             prompts: Vec::new(),
-            prompt_idents_stack: vec![HashSet::new()],
+            groups: Vec::new(),
+            scopes: ScopeTracker::new(detector.clone()),
             comments,
+            regions,
             comment_cursor: 0,
             stmt_annotations_stack: Vec::new(),
+            stmt_ignored_stack: Vec::new(),
             stmt_leading_start_stack: Vec::new(),
             stmt_range_stack: Vec::new(),
-            def_prompt_annotations: HashMap::new(),
-            annotated_idents: HashSet::new(),
+            detector,
         }
     }
 
@@ -142,56 +293,6 @@ impl<'a> PyPromptVisitor<'a> {
         SpanShape { outer, inner }
     }
 
-    fn build_content_tokens(&self, span: &SpanShape, vars: &[PromptVar]) -> Vec<PromptContentToken> {
-        if vars.is_empty() {
-            // Simple case: single str token
-            return vec![PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                },
-            )];
-        }
-
-        let mut tokens = Vec::new();
-        let mut pos = span.inner.0;
-
-        for (var_idx, var) in vars.iter().enumerate() {
-            // Add str token before variable (if any content)
-            if pos < var.span.outer.0 {
-                tokens.push(PromptContentToken::PromptContentTokenStr(
-                    PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: (pos, var.span.outer.0),
-                    },
-                ));
-            }
-
-            // Add var token
-            tokens.push(PromptContentToken::PromptContentTokenVar(
-                PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: var.span.outer,
-                    index: var_idx as u32,
-                },
-            ));
-
-            pos = var.span.outer.1;
-        }
-
-        // Add trailing str token (if any content)
-        if pos < span.inner.1 {
-            tokens.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (pos, span.inner.1),
-                },
-            ));
-        }
-
-        tokens
-    }
-
     fn parse_fstr_vars(&self, fstr: &ast::ExprFString) -> Vec<PromptVar> {
         let mut vars: Vec<PromptVar> = Vec::new();
         for part in fstr.value.as_slice() {
@@ -199,15 +300,17 @@ impl<'a> PyPromptVisitor<'a> {
                 for element in &inner.elements {
                     if let ast::InterpolatedStringElement::Interpolation(interp) = element {
                         let range = interp.range();
-                        vars.push(PromptVar {
-                            span: SpanShape {
+                        vars.push(interpolation_var(
+                            self.code,
+                            SpanShape {
                                 outer: self.span(range),
                                 inner: (
                                     self.span(range).0 + 1,
                                     self.span(range).1.saturating_sub(1),
                                 ),
                             },
-                        });
+                            self.span(interp.expression.range()),
+                        ));
                     }
                 }
             }
@@ -220,12 +323,14 @@ impl<'a> PyPromptVisitor<'a> {
         for element in tstr.value.elements() {
             if let ast::InterpolatedStringElement::Interpolation(interp) = element {
                 let r = interp.range();
-                vars.push(PromptVar {
-                    span: SpanShape {
+                vars.push(interpolation_var(
+                    self.code,
+                    SpanShape {
                         outer: self.span(r),
                         inner: (self.span(r).0 + 1, self.span(r).1.saturating_sub(1)),
                     },
-                });
+                    self.span(interp.expression.range()),
+                ));
             }
         }
         vars
@@ -239,7 +344,7 @@ impl<'a> PyPromptVisitor<'a> {
     ) {
         match &target {
             ast::Expr::Name(name_expr) => {
-                self.process_assign(is_prompt, name_expr, val);
+                self.process_assign(is_prompt, name_expr, val, PromptBindingKind::Local);
             }
 
             ast::Expr::Tuple(ast::ExprTuple { elts: idents, .. })
@@ -269,69 +374,268 @@ impl<'a> PyPromptVisitor<'a> {
         let vals = vals.as_ref();
         for (i, ident) in idents.iter().enumerate() {
             if let ast::Expr::Name(ident) = ident {
-                self.process_assign(is_prompt, ident, vals.and_then(|v| v.get(i)));
+                self.process_assign(
+                    is_prompt,
+                    ident,
+                    vals.and_then(|v| v.get(i)),
+                    PromptBindingKind::Destructured,
+                );
             }
         }
     }
 
-    fn process_assign(&mut self, is_prompt: bool, name: &ast::ExprName, val: Option<&ast::Expr>) {
+    fn process_assign(
+        &mut self,
+        is_prompt: bool,
+        name: &ast::ExprName,
+        val: Option<&ast::Expr>,
+        kind: PromptBindingKind,
+    ) {
         let ident = name.id.as_str();
 
+        // Assignments declare the variables in the current scope, shadowing
+        // the outer variables with the name
+        self.scopes.declare(ident);
+
+        // Opt-out annotations keep the identifier ignored in reassignments
+        if self.stmt_ignored_stack.last().copied().unwrap_or(false) {
+            self.push_ignored_ident(ident);
+            return;
+        }
+
         if is_prompt {
             self.push_prompt_ident(ident);
         }
 
+        let binding = PromptBinding {
+            name: ident.to_string(),
+            span: self.span(name.range()),
+            kind,
+        };
+
         if let Some(val) = val {
-            match val {
-                ast::Expr::FString(expr) => self.process_fstr(ident, expr),
-                ast::Expr::StringLiteral(expr) => self.process_str_literal(ident, expr),
-                ast::Expr::TString(expr) => self.process_tstr(ident, expr),
-                ast::Expr::BinOp(expr) => {
-                    // Handle concatenation: "Hello, " + name + "!"
-                    if let Some(prompt) = self.process_concatenation(ident, expr) {
-                        self.prompts.push(prompt);
-                    }
+            self.process_value(&binding, val);
+        }
+    }
+
+    /// Process an assigned or returned value bound to the binding: chat
+    /// messages, a string, concatenation, join or format call or a list.
+    fn process_value(&mut self, binding: &PromptBinding, val: &ast::Expr) {
+        let ident = binding.name.as_str();
+
+        // Handle messages: [{"role": "system", "content": "..."}]
+        let messages = self.extract_messages(val);
+        if !messages.is_empty() {
+            // Chat messages are prompts by structure
+            self.push_prompt_ident(ident);
+
+            let group_start = self.prompts.len();
+            for (role, content) in messages {
+                let prompts_len = self.prompts.len();
+                match content {
+                    ast::Expr::FString(expr) => self.process_fstr(binding, expr),
+                    ast::Expr::StringLiteral(expr) => self.process_str_literal(binding, expr),
+                    ast::Expr::TString(expr) => self.process_tstr(binding, expr),
+                    _ => {}
+                }
+                for prompt in &mut self.prompts[prompts_len..] {
+                    prompt.role = role;
+                }
+            }
+
+            // Group the messages list into a conversation
+            if matches!(val, ast::Expr::List(_) | ast::Expr::Tuple(_))
+                && let Some(group) = prompt_group(
+                    &self.file,
+                    self.span(val.range()),
+                    binding,
+                    &self.prompts,
+                    group_start,
+                )
+            {
+                self.groups.push(group);
+            }
+            return;
+        }
+
+        match val {
+            ast::Expr::FString(expr) => self.process_fstr(binding, expr),
+            ast::Expr::StringLiteral(expr) => self.process_str_literal(binding, expr),
+            ast::Expr::TString(expr) => self.process_tstr(binding, expr),
+            ast::Expr::BinOp(expr) => {
+                // Handle concatenation: "Hello, " + name + "!"
+                if let Some(prompt) = self.process_concatenation(binding, expr) {
+                    self.prompts.push(prompt);
+                }
+            }
+            ast::Expr::Call(call_expr) => {
+                // First check if it's a join call: "\n".join([...])
+                if let Some(prompt) = self.process_join_call(binding, call_expr) {
+                    self.prompts.push(prompt);
+                // Otherwise check if it's a format method: "Hello {}".format(name)
+                } else if let Some(prompt) = self.process_format_call(binding, call_expr) {
+                    self.prompts.push(prompt);
                 }
-                ast::Expr::Call(call_expr) => {
-                    // First check if it's a join call: "\n".join([...])
-                    if let Some(prompt) = self.process_join_call(ident, call_expr) {
-                        self.prompts.push(prompt);
-                    // Otherwise check if it's a format method: "Hello {}".format(name)
-                    } else if let Some(prompt) = self.process_format_call(ident, call_expr) {
-                        self.prompts.push(prompt);
+            }
+            ast::Expr::List(list_expr) => {
+                // Handle array: ["Hello ", user, "!"]
+                if let Some(prompt) = self.process_array(binding, list_expr) {
+                    self.prompts.push(prompt);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Process a value returned from the enclosing prompt function like a
+    /// prompt assignment bound to the function name, marking the variables
+    /// that come from the function parameters.
+    fn process_return(&mut self, function: &PromptFunction, value: &ast::Expr) {
+        // Opt-out annotations suppress prompt detection for the statement
+        if self.stmt_ignored_stack.last().copied().unwrap_or(false) {
+            return;
+        }
+
+        // Statement annotations take precedence over the function annotations
+        if let Some(annotations) = self.stmt_annotations_stack.last_mut()
+            && annotations.is_empty()
+        {
+            *annotations = function.annotations.clone();
+        }
+
+        let prompts_len = self.prompts.len();
+        self.push_prompt_ident(&function.binding.name);
+        self.process_value(&function.binding, value);
+        function.mark_parameter_vars(&mut self.prompts, prompts_len);
+    }
+
+    /// Creates the prompt function for a function definition with a prompt
+    /// name or a leading `@prompt` annotation. Opt-out annotations in the
+    /// leading comments or on the definition line exclude the function.
+    fn prompt_function(
+        &self,
+        stmt: &'a ast::Stmt,
+        func: &ast::StmtFunctionDef,
+    ) -> Option<PromptFunction> {
+        let name_end = func.name.range().end().to_usize();
+        let line_end = self.code[name_end..]
+            .find('\n')
+            .map_or(self.code.len(), |offset| name_end + offset);
+        let inline = self
+            .comments
+            .iter()
+            .filter(|cr| cr.start() >= stmt.range().start() && cr.start().to_usize() < line_end);
+        if self
+            .leading_comment_block(stmt)
+            .iter()
+            .chain(inline)
+            .any(|cr| self.detector.ignore_annotation(&self.code[*cr]))
+        {
+            return None;
+        }
+
+        let parameters = func
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name().as_str().to_string())
+            .collect();
+        PromptFunction::new(
+            &self.detector,
+            self.code,
+            self.span(func.name.range()),
+            parameters,
+            self.collect_adjacent_leading_comments(stmt),
+        )
+    }
+
+    /// Extract chat message roles and string contents from a message dict, e.g.,
+    /// `{"role": "system", "content": "..."}` or `dict(role="system", content="...")`,
+    /// or from a list or a tuple of message dicts.
+    fn extract_messages<'b>(
+        &self,
+        expr: &'b ast::Expr,
+    ) -> Vec<(Option<PromptRole>, &'b ast::Expr)> {
+        match expr {
+            ast::Expr::Dict(_) | ast::Expr::Call(_) => {
+                self.extract_message(expr).into_iter().collect()
+            }
+            ast::Expr::List(ast::ExprList { elts, .. })
+            | ast::Expr::Tuple(ast::ExprTuple { elts, .. }) => elts
+                .iter()
+                .filter_map(|elt| self.extract_message(elt))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Extract the role and the string content from a message dict. Dicts
+    /// without the role key aren't messages.
+    fn extract_message<'b>(
+        &self,
+        expr: &'b ast::Expr,
+    ) -> Option<(Option<PromptRole>, &'b ast::Expr)> {
+        let mut role = None;
+        let mut content = None;
+        let mut visit_field = |key: &str, value: &'b ast::Expr| {
+            if is_role_key(key) {
+                role = Some(parse_role_value(&self.code[value.range()]));
+            } else if is_content_key(key)
+                && matches!(
+                    value,
+                    ast::Expr::StringLiteral(_) | ast::Expr::FString(_) | ast::Expr::TString(_)
+                )
+            {
+                content = Some(value);
+            }
+        };
+
+        match expr {
+            ast::Expr::Dict(dict) => {
+                for item in &dict.items {
+                    if let Some(key) = &item.key {
+                        visit_field(&self.code[key.range()], &item.value);
                     }
                 }
-                ast::Expr::List(list_expr) => {
-                    // Handle array: ["Hello ", user, "!"]
-                    if let Some(prompt) = self.process_array(ident, list_expr) {
-                        self.prompts.push(prompt);
+            }
+            ast::Expr::Call(call) => {
+                let func = call.func.as_name_expr()?;
+                if func.id.as_str() != "dict" {
+                    return None;
+                }
+                for keyword in &call.arguments.keywords {
+                    if let Some(arg) = &keyword.arg {
+                        visit_field(arg.as_str(), &keyword.value);
                     }
                 }
-                _ => {}
             }
+            _ => return None,
         }
+
+        Some((role?, content?))
     }
 
-    fn process_str_literal(&mut self, ident_name: &str, str: &ast::ExprStringLiteral) {
-        self.process_range(ident_name, str.range(), Vec::new());
+    fn process_str_literal(&mut self, binding: &PromptBinding, str: &ast::ExprStringLiteral) {
+        self.process_range(binding, str.range(), Vec::new());
     }
 
-    fn process_tstr(&mut self, ident_name: &str, tstr: &ast::ExprTString) {
+    fn process_tstr(&mut self, binding: &PromptBinding, tstr: &ast::ExprTString) {
         let vars = self.parse_tstr_vars(tstr);
-        self.process_range(ident_name, tstr.range(), vars);
+        self.process_range(binding, tstr.range(), vars);
     }
 
-    fn process_fstr(&mut self, ident_name: &str, fstr: &ast::ExprFString) {
+    fn process_fstr(&mut self, binding: &PromptBinding, fstr: &ast::ExprFString) {
         let vars = self.parse_fstr_vars(fstr);
-        self.process_range(ident_name, fstr.range(), vars);
+        self.process_range(binding, fstr.range(), vars);
     }
 
-    fn process_range(&mut self, ident: &str, node_range: TextRange, vars: Vec<PromptVar>) {
-        let in_prompt_ident = self
-            .prompt_idents_stack
-            .iter()
-            .rev()
-            .any(|s| s.contains(ident));
+    fn process_range(
+        &mut self,
+        binding: &PromptBinding,
+        node_range: TextRange,
+        vars: Vec<PromptVar>,
+    ) {
+        let ident = binding.name.as_str();
+        let in_prompt_ident = self.in_prompt_ident(ident);
         let mut annotations: Vec<PromptAnnotation> = self
             .stmt_annotations_stack
             .last()
@@ -341,15 +645,14 @@ impl<'a> PyPromptVisitor<'a> {
         // Annotations from comment tracker are already validated to contain @prompt
         // If no annotations in current statement, use stored definition annotations
         if annotations.is_empty()
-            && self.annotated_idents.contains(ident)
-            && let Some(def) = self.def_prompt_annotations.get(ident)
+            && self.scopes.is_annotated(ident)
+            && let Some(def) = self.scopes.get_def_annotation(ident)
         {
-            annotations = def.clone();
+            annotations = def;
         }
         // Annotations are already validated to contain @prompt
         let has_prompt_annotation = !annotations.is_empty();
-        let is_prompt =
-            ident.to_lowercase().contains("prompt") || in_prompt_ident || has_prompt_annotation;
+        let is_prompt = self.is_prompt_name(ident) || in_prompt_ident || has_prompt_annotation;
         if !is_prompt {
             return;
         }
@@ -364,7 +667,7 @@ impl<'a> PyPromptVisitor<'a> {
         let enclosure = (leading_start, self.span(stmt_range).1);
 
         let span = self.span_shape_string_like(node_range);
-        let content = self.build_content_tokens(&span, &vars);
+        let content = build_content_tokens(&span, &vars);
 
         let prompt = Prompt {
             file: self.file.clone(),
@@ -372,15 +675,18 @@ impl<'a> PyPromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint: SpanShape {
                 outer: (0, 0),
                 inner: (0, 0),
             },
+            role: None,
+            references: None,
         };
         self.prompts.push(prompt);
     }
-    fn collect_adjacent_leading_comments(&self, stmt: &'a ast::Stmt) -> Vec<PromptAnnotation> {
+    fn leading_comment_block(&self, stmt: &'a ast::Stmt) -> Vec<TextRange> {
         let stmt_start = stmt.range().start();
         let mut block_ranges: Vec<TextRange> = Vec::new();
         let mut idx: isize = (self.comments.len() as isize) - 1;
@@ -410,7 +716,11 @@ impl<'a> PyPromptVisitor<'a> {
             }
             idx -= 1;
         }
+        block_ranges
+    }
 
+    fn collect_adjacent_leading_comments(&self, stmt: &'a ast::Stmt) -> Vec<PromptAnnotation> {
+        let block_ranges = self.leading_comment_block(stmt);
         if block_ranges.is_empty() {
             return Vec::new();
         }
@@ -420,7 +730,7 @@ impl<'a> PyPromptVisitor<'a> {
             .iter()
             .any(|cr| {
                 let text = &self.code[*cr];
-                parse_annotation(text).unwrap_or(false)
+                self.detector.annotation(text).unwrap_or(false)
             });
         if !has_prompt {
             return Vec::new();
@@ -447,9 +757,8 @@ impl<'a> PyPromptVisitor<'a> {
             })
             .collect();
 
-        vec![PromptAnnotation {
-            spans,
-        }]
+        let attributes = self.detector.annotation_attributes(self.code, &spans);
+        vec![PromptAnnotation { spans, attributes }]
     }
 
     fn collect_inline_prompt_comments(&self, stmt: &'a ast::Stmt) -> Vec<PromptAnnotation> {
@@ -458,26 +767,52 @@ impl<'a> PyPromptVisitor<'a> {
         for &cr in &self.comments {
             if cr.start() >= r.start() && cr.start() < r.end() {
                 let text = self.code[cr].to_string();
-                if parse_annotation(&text).unwrap_or(false) {
+                if self.detector.annotation(&text).unwrap_or(false) {
                     let s = cr.start().to_u32();
                     let e = cr.end().to_u32();
                     let (inner_start_offset, inner_end_offset) = compute_comment_inner_offsets(&text);
-                    out.push(PromptAnnotation {
-                        spans: vec![SpanShape {
-                            outer: (s, e),
-                            inner: (s + inner_start_offset, s + inner_end_offset),
-                        }],
-                    });
+                    let spans = vec![SpanShape {
+                        outer: (s, e),
+                        inner: (s + inner_start_offset, s + inner_end_offset),
+                    }];
+                    let attributes = self.detector.annotation_attributes(self.code, &spans);
+                    out.push(PromptAnnotation { spans, attributes });
                 }
             }
         }
         out
     }
 
+    fn has_ignore_annotation(&self, stmt: &'a ast::Stmt) -> bool {
+        let r = stmt.range();
+        // Include comments trailing on the statement's last line
+        let end = self.code[r.end().to_usize()..]
+            .find('\n')
+            .map_or(self.code.len(), |offset| r.end().to_usize() + offset);
+        let inline = self
+            .comments
+            .iter()
+            .filter(|cr| cr.start() >= r.start() && cr.start().to_usize() < end);
+        self.leading_comment_block(stmt)
+            .iter()
+            .chain(inline)
+            .any(|cr| self.detector.ignore_annotation(&self.code[*cr]))
+    }
+
     fn push_prompt_ident(&mut self, ident: &str) {
-        if let Some(scope) = self.prompt_idents_stack.last_mut() {
-            scope.insert(ident.to_string());
-        }
+        self.scopes.mark_prompt_ident(ident);
+    }
+
+    fn push_ignored_ident(&mut self, ident: &str) {
+        self.scopes.mark_ignored_ident(ident);
+    }
+
+    fn in_prompt_ident(&self, ident: &str) -> bool {
+        self.scopes.is_prompt_ident(ident)
+    }
+
+    fn is_prompt_name(&self, ident: &str) -> bool {
+        self.scopes.is_prompt_name(ident)
     }
 }
 
@@ -490,9 +825,12 @@ impl<'a> Visitor<'a> for PyPromptVisitor<'a> {
         for a in leading.into_iter().chain(inline.into_iter()) {
             annotations.push(a);
         }
-        // Annotations are already validated to contain @prompt
-        let is_prompt = !annotations.is_empty();
+        // Annotations are already validated to contain @prompt, and statements
+        // in `@prompts` files and `@prompt-begin` regions are annotated too
+        let is_prompt =
+            !annotations.is_empty() || self.regions.contains(stmt.range().start().to_u32());
         self.stmt_annotations_stack.push(annotations);
+        self.stmt_ignored_stack.push(self.has_ignore_annotation(stmt));
         self.stmt_leading_start_stack.push(leading_start);
         self.stmt_range_stack.push(stmt.range());
 
@@ -507,65 +845,353 @@ impl<'a> Visitor<'a> for PyPromptVisitor<'a> {
             ast::Stmt::AnnAssign(assign) => {
                 // Record annotated identifiers
                 if let ast::Expr::Name(name) = &*assign.target {
-                    self.annotated_idents.insert(name.id.to_string());
+                    let ident = name.id.as_str();
+                    self.scopes.declare(ident);
+                    self.scopes.mark_annotated(ident);
                     if is_prompt
                         && let Some(ann) = self.stmt_annotations_stack.last()
                         && !ann.is_empty()
                     {
-                        self.def_prompt_annotations
-                            .insert(name.id.to_string(), ann.clone());
+                        self.scopes.store_def_annotation(ident, ann.clone());
                     }
                 }
                 self.process_assign_target(is_prompt, &assign.target, assign.value.as_deref());
             }
 
+            // Handle prompt function returns: `return f"Hello, {name}!"`
+            ast::Stmt::Return(ast::StmtReturn {
+                value: Some(value), ..
+            }) => {
+                if let Some(function) = self.scopes.current_function().cloned() {
+                    self.process_return(&function, value);
+                }
+            }
+
             _ => {}
         }
 
-        // Check if we are entering a new scope.
-        let new_scope = matches!(stmt, ast::Stmt::FunctionDef(_) | ast::Stmt::ClassDef(_));
-        if new_scope {
-            self.prompt_idents_stack.push(HashSet::new());
+        // Enter the function and class scopes, tracking prompt functions
+        // returning prompts
+        match stmt {
+            ast::Stmt::FunctionDef(func) => {
+                let function = self.prompt_function(stmt, func);
+                self.scopes.enter_function(function);
+                // Parameters shadow the outer identifiers with the same names
+                for parameter in func.parameters.iter() {
+                    self.scopes.declare(parameter.name().as_str());
+                }
+            }
+            ast::Stmt::ClassDef(_) => self.scopes.enter_scope(),
+            _ => {}
         }
 
         // Visit nested statements.
         visitor::walk_stmt(self, stmt);
 
-        // If we opened a scope, pop it off the stack.
-        if new_scope {
-            self.prompt_idents_stack.pop();
+        // Exit the scope opened above
+        match stmt {
+            ast::Stmt::FunctionDef(_) => self.scopes.exit_function(),
+            ast::Stmt::ClassDef(_) => self.scopes.exit_scope(),
+            _ => {}
         }
 
         self.stmt_annotations_stack.pop();
+        self.stmt_ignored_stack.pop();
         self.stmt_leading_start_stack.pop();
         self.stmt_range_stack.pop();
     }
 }
 
-// Concatenation support
+// Call site support
+
+/// Visitor detecting prompts passed to LLM SDK call sites, e.g.,
+/// `client.messages.create(system="...")`. It runs after the prompt visitor,
+/// so the call site prompts follow the assignment prompts.
+struct PyCallSiteVisitor<'v, 'a> {
+    /// Prompt visitor collecting the prompts and the groups.
+    visitor: &'v mut PyPromptVisitor<'a>,
+    /// Prompt detection rules.
+    detector: PromptDetector,
+    /// Stack of statement starts with the leading annotation starts.
+    stmt_start_stack: Vec<(u32, Option<u32>)>,
+}
+
+impl<'a> Visitor<'a> for PyCallSiteVisitor<'_, 'a> {
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        let leading_start = self
+            .visitor
+            .collect_adjacent_leading_comments(stmt)
+            .first()
+            .and_then(|a| a.spans.first())
+            .map(|s| s.outer.0);
+        self.stmt_start_stack
+            .push((stmt.range().start().to_u32(), leading_start));
+        visitor::walk_stmt(self, stmt);
+        self.stmt_start_stack.pop();
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        let code = self.visitor.code;
+        if let ast::Expr::Call(call) = expr
+            && let Some(call_site) = self.detector.call_site(&code[call.func.range()])
+        {
+            // Leading annotations only extend calls starting the statement
+            let call_span = self.visitor.span(call.range());
+            let enclosure_start = match self.stmt_start_stack.last() {
+                Some((stmt_start, Some(leading_start))) if *stmt_start == call_span.0 => {
+                    *leading_start
+                }
+                _ => call_span.0,
+            };
+            let enclosure = (enclosure_start, call_span.1);
+
+            let mut pairs = Vec::new();
+            for (position, arg) in call.arguments.args.iter().enumerate() {
+                match arg {
+                    ast::Expr::Dict(dict) if self.visitor.extract_message(arg).is_none() => {
+                        pairs.extend(
+                            dict.items
+                                .iter()
+                                .filter_map(|item| Some((item.key.as_ref()?.range(), &item.value))),
+                        );
+                    }
+                    _ => {
+                        if let Some(signature) = call_site.positional(position) {
+                            let binding =
+                                argument_binding(code, self.visitor.span(call.func.range()));
+                            self.visitor.process_call_argument(
+                                arg,
+                                signature.role,
+                                &binding,
+                                enclosure,
+                            );
+                        }
+                    }
+                }
+            }
+            pairs.extend(
+                call.arguments
+                    .keywords
+                    .iter()
+                    .filter_map(|keyword| Some((keyword.arg.as_ref()?.range(), &keyword.value))),
+            );
+
+            for (key_range, value) in pairs {
+                if let Some(signature) = call_site.named(&code[key_range]) {
+                    let binding = argument_binding(code, self.visitor.span(key_range));
+                    self.visitor
+                        .process_call_argument(value, signature.role, &binding, enclosure);
+                }
+            }
+        }
+
+        visitor::walk_expr(self, expr);
+    }
+}
 
-#[derive(Debug)]
-enum ConcatSegment {
-    String(SpanShape),
-    Variable(SpanShape),
-    Primitive(SpanShape),
-    Other,
+// References support
+
+/// Visitor resolving the identifiers referencing prompt bindings, e.g.,
+/// `send(user_prompt)`, tracking the function, lambda and class scopes.
+struct PyReferenceVisitor {
+    /// Prompt references resolver.
+    references: PromptReferences,
+}
+
+impl<'a> Visitor<'a> for PyReferenceVisitor {
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        let new_scope = matches!(stmt, ast::Stmt::FunctionDef(_) | ast::Stmt::ClassDef(_));
+        if new_scope {
+            self.references.enter_scope();
+        }
+        // Parameters shadow the outer bindings
+        if let ast::Stmt::FunctionDef(func) = stmt {
+            for parameter in func.parameters.iter() {
+                self.references.shadow(parameter.name().as_str());
+            }
+        }
+
+        visitor::walk_stmt(self, stmt);
+
+        if new_scope {
+            self.references.exit_scope();
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match expr {
+            ast::Expr::Name(name) => {
+                let kind = match name.ctx {
+                    ast::ExprContext::Store => PromptReferenceKind::Reassign,
+                    _ => PromptReferenceKind::Read,
+                };
+                self.identifier(name, kind);
+            }
+
+            // Names passed directly as call arguments, e.g., `send(user_prompt)`
+            ast::Expr::Call(call) => {
+                let keywords = call.arguments.keywords.iter().map(|keyword| &keyword.value);
+                for arg in call.arguments.args.iter().chain(keywords) {
+                    if let ast::Expr::Name(name) = arg {
+                        self.identifier(name, PromptReferenceKind::Argument);
+                    }
+                }
+            }
+
+            ast::Expr::Lambda(lambda) => {
+                self.references.enter_scope();
+                for parameter in lambda
+                    .parameters
+                    .iter()
+                    .flat_map(|parameters| parameters.iter())
+                {
+                    self.references.shadow(parameter.name().as_str());
+                }
+                visitor::walk_expr(self, expr);
+                self.references.exit_scope();
+                return;
+            }
+
+            _ => {}
+        }
+
+        visitor::walk_expr(self, expr);
+    }
+}
+
+impl PyReferenceVisitor {
+    /// Resolve the name occurrence referencing a prompt binding. Assignments
+    /// declare the variables in the current scope.
+    fn identifier(&mut self, name: &ast::ExprName, kind: PromptReferenceKind) {
+        let span = (name.range().start().to_u32(), name.range().end().to_u32());
+        if kind == PromptReferenceKind::Reassign {
+            self.references.assignment(name.id.as_str(), span);
+        } else {
+            self.references.identifier(name.id.as_str(), span, kind);
+        }
+    }
+}
+
+/// Collect the top-level `from` imports, e.g., `SYSTEM` for
+/// `from .prompts import SYSTEM`.
+fn module_imports(suite: &[ast::Stmt]) -> Vec<ParseImport> {
+    let mut imports = Vec::new();
+    for stmt in suite {
+        let ast::Stmt::ImportFrom(import) = stmt else {
+            continue;
+        };
+        // Relative imports are prefixed with dots, e.g., `.prompts`
+        let source = format!(
+            "{}{}",
+            ".".repeat(import.level as usize),
+            import.module.as_ref().map_or("", |module| module.as_str())
+        );
+        for alias in &import.names {
+            let local = alias.asname.as_ref().unwrap_or(&alias.name);
+            imports.push(ParseImport {
+                source: source.clone(),
+                name: alias.name.to_string(),
+                local: local.to_string(),
+                span: (
+                    alias.name.range().start().to_u32(),
+                    alias.name.range().end().to_u32(),
+                ),
+            });
+        }
+    }
+    imports
 }
 
+impl<'a> PyPromptVisitor<'a> {
+    /// Process a call argument value as a prompt, either a string or chat
+    /// messages. Messages lists are grouped into conversations.
+    fn process_call_argument(
+        &mut self,
+        value: &ast::Expr,
+        role: Option<PromptRole>,
+        binding: &PromptBinding,
+        enclosure: Span,
+    ) {
+        let prompts_len = self.prompts.len();
+        let messages = self.extract_messages(value);
+        if messages.is_empty() {
+            self.push_call_argument(value, binding, enclosure);
+            for prompt in &mut self.prompts[prompts_len..] {
+                prompt.role = role;
+            }
+            return;
+        }
+
+        for (message_role, content) in messages {
+            let message_len = self.prompts.len();
+            self.push_call_argument(content, binding, enclosure);
+            for prompt in &mut self.prompts[message_len..] {
+                prompt.role = message_role.or(role);
+            }
+        }
+
+        // Group the messages list into a conversation
+        if matches!(value, ast::Expr::List(_) | ast::Expr::Tuple(_))
+            && let Some(group) = prompt_group(
+                &self.file,
+                self.span(value.range()),
+                binding,
+                &self.prompts,
+                prompts_len,
+            )
+        {
+            self.groups.push(group);
+        }
+    }
+
+    /// Push a string call argument as a prompt. Call arguments are prompts by
+    /// the call site signature, so unlike `process_range`, the binding isn't
+    /// checked.
+    fn push_call_argument(&mut self, value: &ast::Expr, binding: &PromptBinding, enclosure: Span) {
+        let vars = match value {
+            ast::Expr::StringLiteral(_) => Vec::new(),
+            ast::Expr::FString(expr) => self.parse_fstr_vars(expr),
+            ast::Expr::TString(expr) => self.parse_tstr_vars(expr),
+            _ => return,
+        };
+        let span = self.span_shape_string_like(value.range());
+        let content = build_content_tokens(&span, &vars);
+        self.prompts.push(Prompt {
+            file: self.file.clone(),
+            span,
+            enclosure,
+            vars,
+            annotations: Vec::new(),
+            binding: binding.clone(),
+            content,
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            role: None,
+            references: None,
+        });
+    }
+}
+
+// Concatenation support
+
 impl<'a> PyPromptVisitor<'a> {
     /// Process a binary operator for string concatenation
-    fn process_concatenation(&mut self, ident_name: &str, binop: &ast::ExprBinOp) -> Option<Prompt> {
+    fn process_concatenation(
+        &mut self,
+        binding: &PromptBinding,
+        binop: &ast::ExprBinOp,
+    ) -> Option<Prompt> {
+        let ident_name = binding.name.as_str();
+
         // Check if it's an Add operator
         if !matches!(binop.op, ast::Operator::Add) {
             return None;
         }
 
         // Check if ident is a prompt variable
-        let in_prompt_ident = self
-            .prompt_idents_stack
-            .iter()
-            .rev()
-            .any(|s| s.contains(ident_name));
+        let in_prompt_ident = self.in_prompt_ident(ident_name);
         let mut annotations: Vec<PromptAnnotation> = self
             .stmt_annotations_stack
             .last()
@@ -573,92 +1199,25 @@ impl<'a> PyPromptVisitor<'a> {
             .unwrap_or_default();
 
         if annotations.is_empty()
-            && self.annotated_idents.contains(ident_name)
-            && let Some(def) = self.def_prompt_annotations.get(ident_name)
+            && self.scopes.is_annotated(ident_name)
+            && let Some(def) = self.scopes.get_def_annotation(ident_name)
         {
-            annotations = def.clone();
+            annotations = def;
         }
         let has_prompt_annotation = !annotations.is_empty();
-        let is_prompt =
-            ident_name.to_lowercase().contains("prompt") || in_prompt_ident || has_prompt_annotation;
+        let is_prompt = self.is_prompt_name(ident_name) || in_prompt_ident || has_prompt_annotation;
         if !is_prompt {
             return None;
         }
 
         // Extract segments recursively
         let segments = self.extract_concat_segments(&ast::Expr::BinOp(binop.clone()));
-
-        // Reject if no strings or contains complex objects
-        let has_string = segments.iter().any(|s| matches!(s, ConcatSegment::String(_)));
-        let has_other = segments.iter().any(|s| matches!(s, ConcatSegment::Other));
-
-        if !has_string || has_other {
-            return None;
-        }
-
-        // Build prompt outer span (entire concatenation expression)
-        let prompt_outer = self.span(binop.range());
-
-        // Find first and last string segments to determine inner span
-        let first_string_pos = segments.iter().find_map(|s| match s {
-            ConcatSegment::String(span) => Some(span.inner.0),
-            _ => None,
-        })?;
-
-        let last_string_end = segments.iter().rev().find_map(|s| match s {
-            ConcatSegment::String(span) => Some(span.inner.1),
-            _ => None,
-        })?;
-
-        let prompt_inner = (first_string_pos, last_string_end);
-        let span = SpanShape {
-            outer: prompt_outer,
-            inner: prompt_inner,
-        };
-
-        // Build vars and content tokens
-        let mut vars = Vec::new();
-        let mut content = Vec::new();
-        let mut var_idx = 0u32;
-
-        for segment in &segments {
-            match segment {
-                ConcatSegment::String(s_span) => {
-                    // Add string token
-                    content.push(PromptContentToken::PromptContentTokenStr(
-                        PromptContentTokenStr {
-                            r#type: PromptContentTokenStrTypeStr,
-                            span: (s_span.inner.0, s_span.inner.1),
-                        },
-                    ));
-                }
-                ConcatSegment::Variable(v_span) | ConcatSegment::Primitive(v_span) => {
-                    // Expand to include operators
-                    let mut var_outer = v_span.clone();
-                    self.expand_to_operators(&mut var_outer, prompt_outer);
-
-                    let var = PromptVar {
-                        span: SpanShape {
-                            outer: var_outer.outer,
-                            inner: v_span.inner,
-                        },
-                    };
-
-                    // Add variable token (before pushing var)
-                    content.push(PromptContentToken::PromptContentTokenVar(
-                        PromptContentTokenVar {
-                            r#type: PromptContentTokenVarTypeVar,
-                            span: var.span.inner,
-                            index: var_idx,
-                        },
-                    ));
-
-                    vars.push(var);
-                    var_idx += 1;
-                }
-                ConcatSegment::Other => {}
-            }
-        }
+        let outer = self.span(binop.range());
+        let ConcatContent {
+            span,
+            vars,
+            content,
+        } = concat_content(self.code, outer, "+", &segments)?;
 
         // Calculate enclosure
         let stmt_range = self.stmt_range_stack.last().copied().unwrap_or(binop.range());
@@ -676,11 +1235,14 @@ impl<'a> PyPromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint: SpanShape {
                 outer: (0, 0),
                 inner: (0, 0),
             },
+            role: None,
+            references: None,
         })
     }
 
@@ -700,94 +1262,31 @@ impl<'a> PyPromptVisitor<'a> {
 
     /// Classify a single node as a segment
     fn classify_single_node(&self, expr: &ast::Expr) -> Vec<ConcatSegment> {
+        let span = self.span(expr.range());
         match expr {
+            // String literal
             ast::Expr::StringLiteral(_) | ast::Expr::FString(_) | ast::Expr::TString(_) => {
-                // String literal
-                let span = self.span_shape_string_like(expr.range());
-                vec![ConcatSegment::String(span)]
-            }
-            ast::Expr::Name(_) => {
-                // Variable
-                let outer = self.span(expr.range());
-                let inner = outer;
-                vec![ConcatSegment::Variable(SpanShape { outer, inner })]
-            }
-            ast::Expr::Call(_) => {
-                // Function call - treat as variable
-                let outer = self.span(expr.range());
-                let inner = outer;
-                vec![ConcatSegment::Variable(SpanShape { outer, inner })]
+                vec![ConcatSegment::String(
+                    self.span_shape_string_like(expr.range()),
+                )]
             }
-            ast::Expr::Attribute(_) => {
-                // Member access (obj.prop or obj.method()) - treat as variable
-                let outer = self.span(expr.range());
-                let inner = outer;
-                vec![ConcatSegment::Variable(SpanShape { outer, inner })]
+            // Variables, function calls and member access (obj.prop or obj.method())
+            ast::Expr::Name(_) | ast::Expr::Call(_) | ast::Expr::Attribute(_) => {
+                vec![ConcatSegment::Variable(span)]
             }
+            // Primitives - Python requires str() conversion, so this would be a type error
+            // But we can still parse it as a primitive for completeness
             ast::Expr::NumberLiteral(_) | ast::Expr::BooleanLiteral(_) => {
-                // Primitives - Python requires str() conversion, so this would be a type error
-                // But we can still parse it as a primitive for completeness
-                let outer = self.span(expr.range());
-                let inner = outer;
-                vec![ConcatSegment::Primitive(SpanShape { outer, inner })]
-            }
-            ast::Expr::List(_) | ast::Expr::Tuple(_) | ast::Expr::Dict(_) | ast::Expr::Set(_) => {
-                // Complex objects - reject
-                vec![ConcatSegment::Other]
-            }
-            _ => {
-                // Unknown - reject
-                vec![ConcatSegment::Other]
+                vec![ConcatSegment::Primitive(span)]
             }
+            // Complex objects and unknown expressions - reject
+            _ => vec![ConcatSegment::Other],
         }
     }
 
-    /// Expand variable span to include surrounding operators
-    fn expand_to_operators(&self, var_span: &mut SpanShape, prompt_outer: (u32, u32)) {
-        let bytes = self.code.as_bytes();
-        let mut new_start = var_span.outer.0;
-        let mut new_end = var_span.outer.1;
-
-        // Expand left to include " + "
-        let mut i = new_start as usize;
-        while i > prompt_outer.0 as usize {
-            i -= 1;
-            let c = bytes[i];
-            if c == b'+' {
-                // Include the + and any spaces before it
-                while i > prompt_outer.0 as usize && (bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') {
-                    i -= 1;
-                }
-                new_start = i as u32;
-                break;
-            } else if c != b' ' && c != b'\t' {
-                break;
-            }
-        }
-
-        // Expand right to include " + "
-        let mut i = new_end as usize;
-        while i < prompt_outer.1 as usize {
-            let c = bytes[i];
-            if c == b'+' {
-                // Include the + and any spaces after it
-                i += 1;
-                while i < prompt_outer.1 as usize && (bytes[i] == b' ' || bytes[i] == b'\t') {
-                    i += 1;
-                }
-                new_end = i as u32;
-                break;
-            } else if c != b' ' && c != b'\t' {
-                break;
-            }
-            i += 1;
-        }
-
-        var_span.outer = (new_start, new_end);
-    }
-
     /// Process format method call: "Hello {}".format(name)
-    fn process_format_call(&mut self, ident: &str, call: &ast::ExprCall) -> Option<Prompt> {
+    fn process_format_call(&mut self, binding: &PromptBinding, call: &ast::ExprCall) -> Option<Prompt> {
+        let ident = binding.name.as_str();
         // Check if this is a .format() method call on a string
         let attr = call.func.as_attribute_expr()?;
         if attr.attr.as_str() != "format" {
@@ -798,18 +1297,14 @@ impl<'a> PyPromptVisitor<'a> {
         let format_str = attr.value.as_string_literal_expr()?;
         
         // Check if this should be treated as a prompt
-        let in_prompt_ident = self
-            .prompt_idents_stack
-            .iter()
-            .rev()
-            .any(|s| s.contains(ident));
+        let in_prompt_ident = self.in_prompt_ident(ident);
         let annotations: Vec<PromptAnnotation> = self
             .stmt_annotations_stack
             .last()
             .cloned()
             .unwrap_or_default();
         
-        if !in_prompt_ident && annotations.is_empty() && !ident.to_lowercase().contains("prompt") {
+        if !in_prompt_ident && annotations.is_empty() && !self.is_prompt_name(ident) {
             return None;
         }
 
@@ -829,12 +1324,10 @@ impl<'a> PyPromptVisitor<'a> {
             }
             
             let arg_range = arg.range();
-            vars.push(PromptVar {
-                span: SpanShape {
-                    outer: self.span(arg_range),
-                    inner: self.span(arg_range),
-                },
-            });
+            // Placeholder positions are in characters
+            let (start, end) = placeholders[arg_idx];
+            let placeholder: String = format_str_value.chars().skip(start).take(end - start).collect();
+            vars.push(format_var(self.code, &placeholder, self.span(arg_range)));
         }
         
         // Build content tokens with placeholders as var positions
@@ -850,6 +1343,7 @@ impl<'a> PyPromptVisitor<'a> {
                     PromptContentTokenStr {
                         r#type: PromptContentTokenStrTypeStr,
                         span: (format_inner_start + pos as u32, format_inner_start + *start as u32),
+                        escapes: None,
                     },
                 ));
             }
@@ -874,6 +1368,7 @@ impl<'a> PyPromptVisitor<'a> {
                 PromptContentTokenStr {
                     r#type: PromptContentTokenStrTypeStr,
                     span: (format_inner_start + pos as u32, format_inner_end),
+                    escapes: None,
                 },
             ));
         }
@@ -896,11 +1391,14 @@ impl<'a> PyPromptVisitor<'a> {
             enclosure: (leading_start, self.span(stmt_range).1),
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint: SpanShape {
                 outer: (0, 0),
                 inner: (0, 0),
             },
+            role: None,
+            references: None,
         })
     }
     
@@ -946,20 +1444,17 @@ impl<'a> PyPromptVisitor<'a> {
     }
 
     /// Process an array/list assignment: ["Hello ", user, "!"]
-    fn process_array(&mut self, ident: &str, list: &ast::ExprList) -> Option<Prompt> {
+    fn process_array(&mut self, binding: &PromptBinding, list: &ast::ExprList) -> Option<Prompt> {
+        let ident = binding.name.as_str();
         // Check if this should be treated as a prompt
-        let in_prompt_ident = self
-            .prompt_idents_stack
-            .iter()
-            .rev()
-            .any(|s| s.contains(ident));
+        let in_prompt_ident = self.in_prompt_ident(ident);
         let annotations: Vec<PromptAnnotation> = self
             .stmt_annotations_stack
             .last()
             .cloned()
             .unwrap_or_default();
         
-        if !in_prompt_ident && annotations.is_empty() && !ident.to_lowercase().contains("prompt") {
+        if !in_prompt_ident && annotations.is_empty() && !self.is_prompt_name(ident) {
             return None;
         }
 
@@ -977,18 +1472,21 @@ impl<'a> PyPromptVisitor<'a> {
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
                 _ => {
                     // Variables and other expressions
                     let elem_range = element.range();
-                    vars.push(PromptVar {
-                        span: SpanShape {
+                    vars.push(prompt_var(
+                        self.code,
+                        SpanShape {
                             outer: self.span(elem_range),
                             inner: self.span(elem_range),
                         },
-                    });
+                        self.span(elem_range),
+                    ));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1023,16 +1521,20 @@ impl<'a> PyPromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint: SpanShape {
                 outer: (0, 0),
                 inner: (0, 0),
             },
+            role: None,
+            references: None,
         })
     }
 
     /// Process a join call: "\n".join(["Hello", user, "!"])
-    fn process_join_call(&mut self, ident: &str, call: &ast::ExprCall) -> Option<Prompt> {
+    fn process_join_call(&mut self, binding: &PromptBinding, call: &ast::ExprCall) -> Option<Prompt> {
+        let ident = binding.name.as_str();
         // Check if this is a .join() method call
         let attr = call.func.as_attribute_expr()?;
         if attr.attr.as_str() != "join" {
@@ -1047,18 +1549,14 @@ impl<'a> PyPromptVisitor<'a> {
         let list = list_arg.as_list_expr()?;
 
         // Check if this should be treated as a prompt
-        let in_prompt_ident = self
-            .prompt_idents_stack
-            .iter()
-            .rev()
-            .any(|s| s.contains(ident));
+        let in_prompt_ident = self.in_prompt_ident(ident);
         let annotations: Vec<PromptAnnotation> = self
             .stmt_annotations_stack
             .last()
             .cloned()
             .unwrap_or_default();
         
-        if !in_prompt_ident && annotations.is_empty() && !ident.to_lowercase().contains("prompt") {
+        if !in_prompt_ident && annotations.is_empty() && !self.is_prompt_name(ident) {
             return None;
         }
 
@@ -1079,6 +1577,7 @@ impl<'a> PyPromptVisitor<'a> {
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1089,6 +1588,7 @@ impl<'a> PyPromptVisitor<'a> {
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -1098,18 +1598,21 @@ impl<'a> PyPromptVisitor<'a> {
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
                     first = false;
                     // Variables and other expressions
                     let elem_range = element.range();
-                    vars.push(PromptVar {
-                        span: SpanShape {
+                    vars.push(prompt_var(
+                        self.code,
+                        SpanShape {
                             outer: self.span(elem_range),
                             inner: self.span(elem_range),
                         },
-                    });
+                        self.span(elem_range),
+                    ));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1146,8 +1649,11 @@ impl<'a> PyPromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint,
+            role: None,
+            references: None,
         })
     }
 }
@@ -1,51 +1,87 @@
 mod comments;
-mod scope;
 mod spans;
 
 use comments::CommentTracker;
-use scope::ScopeTracker;
-use spans::span_shape_string_like;
+use spans::{escape_style, span_shape_string_like};
 use tree_sitter::{Node, Parser, Tree};
 pub use volumen_parser_core::VolumenParser;
+use volumen_parser_core::{
+    collect_diagnostics, concat_content, error_diagnostic, file_size_error, format_content,
+    is_content_key, is_role_key, parse_result, parse_role_value, printf_placeholders,
+    process_call_sites, prompt_group, prompt_var, resolve_escapes, CallArgument, CallSiteSyntax,
+    ConcatContent, ConcatSegment, PromptDetector, PromptFunction, PromptReferences, ScopeTracker,
+};
+
 use volumen_types::*;
 
-pub struct ParserRb {}
+pub struct ParserRb {
+    parser: Parser,
+}
 
-impl VolumenParser for ParserRb {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        // Initialize Tree-sitter parser
+impl ParserRb {
+    pub fn new() -> Self {
         let mut parser = Parser::new();
         parser
             .set_language(&tree_sitter_ruby::LANGUAGE.into())
             .expect("Failed to load Ruby grammar");
+        Self { parser }
+    }
+}
+
+impl Default for ParserRb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VolumenParser for ParserRb {
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        // Skip sources exceeding the maximum file size
+        if let Some(result) =
+            file_size_error(source, options, ParseLanguage::Rb, None, "tree-sitter")
+        {
+            return result;
+        }
 
         // Parse source
-        let tree = match parser.parse(source, None) {
+        let tree = match self.parser.parse(source, None) {
             Some(tree) => tree,
             None => {
                 return ParseResult::ParseResultError(ParseResultError {
                     state: ParseResultErrorStateError,
                     error: "Failed to parse source".to_string(),
+                    diagnostics: vec![error_diagnostic(
+                        "tree-sitter",
+                        "parse-failed",
+                        "Failed to parse source".to_string(),
+                        (0, source.len() as u32),
+                    )],
+                    language: Some(ParseLanguage::Rb),
+                    dialect: None,
+                    detection: None,
+                    parser: Some("tree-sitter".to_string()),
                 });
             }
         };
 
-        // Check for parse errors
+        // Collect parse errors. Tree-sitter recovers from errors, so the tree
+        // is still processed, skipping error nodes.
         let root = tree.root_node();
+        let mut diagnostics = Vec::new();
         if root.has_error() {
-            let error_msg = format_tree_sitter_error(&root, source);
-            return ParseResult::ParseResultError(ParseResultError {
-                state: ParseResultErrorStateError,
-                error: error_msg,
-            });
+            collect_diagnostics(&root, source, &mut diagnostics);
         }
 
+        // Resolve prompt detection rules
+        let detector = PromptDetector::new(options);
+
         // Extract comments
-        let comment_tracker = CommentTracker::new(&tree, source);
+        let comment_tracker = CommentTracker::new(&tree, source, detector.clone());
 
         // Initialize state
         let mut prompts = Vec::new();
-        let mut scope_tracker = ScopeTracker::new();
+        let mut groups = Vec::new();
+        let mut scope_tracker = ScopeTracker::new(detector.clone());
 
         // Process tree
         process_tree(
@@ -56,55 +92,48 @@ impl VolumenParser for ParserRb {
             &comment_tracker,
             &mut scope_tracker,
             &mut prompts,
+            &mut groups,
         );
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
-            prompts,
-        })
-    }
-}
+        // Detect prompts passed to LLM SDK call sites
+        let call_sites = CallSites {
+            comments: &comment_tracker,
+        };
+        process_call_sites(
+            &root,
+            source,
+            filename,
+            &detector,
+            &call_sites,
+            &mut prompts,
+            &mut groups,
+        );
 
-/// Format tree-sitter parse errors into a readable message.
-fn format_tree_sitter_error(node: &Node, source: &str) -> String {
-    let mut errors = Vec::new();
-    collect_errors(node, source, &mut errors);
-    if errors.is_empty() {
-        "Parse error: unknown".to_string()
-    } else {
-        format!("Parse errors:\n{}", errors.join("\n"))
-    }
-}
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = PromptReferences::new(&prompts);
+        process_references(&root, source, &mut references);
+        references.apply(&mut prompts);
 
-fn collect_errors(node: &Node, source: &str, errors: &mut Vec<String>) {
-    if node.is_error() || node.is_missing() {
-        let start = node.start_position();
-        let error_text = node
-            .utf8_text(source.as_bytes())
-            .unwrap_or("<unknown>")
-            .chars()
-            .take(50)
-            .collect::<String>();
-        errors.push(format!(
-            "  Line {}, Column {}: {}",
-            start.row + 1,
-            start.column + 1,
-            error_text
-        ));
-    }
+        // Decode escape sequences in string tokens
+        resolve_escapes(&mut prompts, source, |span| escape_style(&root, source, span));
 
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            collect_errors(&cursor.node(), source, errors);
-            if !cursor.goto_next_sibling() {
-                break;
-            }
-        }
+        // Assign role hints from annotations, e.g., `# @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        parse_result(
+            ParseLanguage::Rb,
+            None,
+            "tree-sitter",
+            prompts,
+            groups,
+            Vec::new(),
+            diagnostics,
+        )
     }
 }
 
 /// Process the entire syntax tree to extract prompts.
+#[allow(clippy::too_many_arguments)]
 fn process_tree(
     _tree: &Tree,
     root: &Node,
@@ -113,9 +142,10 @@ fn process_tree(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     // Recursively traverse the tree
-    traverse_node(root, source, filename, comments, scopes, prompts);
+    traverse_node(root, source, filename, comments, scopes, prompts, groups);
 }
 
 /// Recursively traverse nodes to find assignments and manage scopes.
@@ -126,11 +156,23 @@ fn traverse_node(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
     let kind = node.kind();
 
-    // Handle scope boundaries (method, class, module, block)
-    if kind == "method" || kind == "class" || kind == "module" {
+    // Handle method scopes, tracking prompt functions returning prompts
+    if kind == "method" || kind == "singleton_method" {
+        process_method(node, source, filename, comments, scopes, prompts, groups);
+        return;
+    }
+
+    // Handle scope boundaries (class, module)
+    if kind == "class" || kind == "module" {
         scopes.enter_scope();
 
         // Process children
@@ -138,7 +180,7 @@ fn traverse_node(
         if cursor.goto_first_child() {
             loop {
                 let child = cursor.node();
-                traverse_node(&child, source, filename, comments, scopes, prompts);
+                traverse_node(&child, source, filename, comments, scopes, prompts, groups);
                 if !cursor.goto_next_sibling() {
                     break;
                 }
@@ -156,16 +198,25 @@ fn traverse_node(
 
     // Handle assignments
     if kind == "assignment" {
-        process_assignment(node, source, filename, comments, scopes, prompts);
+        process_assignment(node, source, filename, comments, scopes, prompts, groups);
         return; // Don't process children of assignments
     }
 
+    // Handle prompt function returns: `return "Hello, #{user}!"`
+    if kind == "return" {
+        if let Some(value) = return_value(node) {
+            process_function_return(
+                &value, node, source, filename, comments, scopes, prompts, groups,
+            );
+        }
+    }
+
     // Recursively process children
     let mut cursor = node.walk();
     if cursor.goto_first_child() {
         loop {
             let child = cursor.node();
-            traverse_node(&child, source, filename, comments, scopes, prompts);
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
             if !cursor.goto_next_sibling() {
                 break;
             }
@@ -173,6 +224,156 @@ fn traverse_node(
     }
 }
 
+/// Process a method definition scope. Methods with prompt-like names or
+/// leading `@prompt` annotations are prompt functions, so their returned
+/// values, including the implicit last expression, are detected as prompts.
+fn process_method(
+    node: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let stmt_start = node.start_byte() as u32;
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|parameters| method_parameters(&parameters, source))
+        .unwrap_or_default();
+    let function = node.child_by_field_name("name").and_then(|name| {
+        // Opt-out annotations exclude the method from prompt functions
+        if comments.has_ignore_annotation(stmt_start, name.end_byte() as u32) {
+            return None;
+        }
+        PromptFunction::new(
+            scopes.detector(),
+            source,
+            (name.start_byte() as u32, name.end_byte() as u32),
+            parameters.clone(),
+            comments.collect_adjacent_leading(stmt_start),
+        )
+    });
+    scopes.enter_function(function);
+
+    // Parameters shadow the outer identifiers with the same names
+    for parameter in &parameters {
+        scopes.declare(parameter);
+    }
+
+    // Process children
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            traverse_node(&child, source, filename, comments, scopes, prompts, groups);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    // Methods implicitly return the last expression
+    if scopes.current_function().is_some() {
+        if let Some(value) = node
+            .child_by_field_name("body")
+            .and_then(|body| last_expression(&body))
+        {
+            if !matches!(value.kind(), "return" | "assignment") {
+                process_function_return(
+                    &value, &value, source, filename, comments, scopes, prompts, groups,
+                );
+            }
+        }
+    }
+
+    scopes.exit_function();
+}
+
+/// Collect the method parameter names, e.g., `user` and `options` for
+/// `def build_prompt(user, options = {})`.
+fn method_parameters(node: &Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter_map(|parameter| match parameter.kind() {
+            "identifier" => Some(parameter),
+            _ => parameter.child_by_field_name("name"),
+        })
+        .filter_map(|name| name.utf8_text(source.as_bytes()).ok())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Get the last expression of a method body. Endless methods, e.g.,
+/// `def prompt = "Hi!"`, have the expression as the body.
+fn last_expression<'a>(body: &Node<'a>) -> Option<Node<'a>> {
+    if body.kind() != "body_statement" {
+        return Some(*body);
+    }
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter(|child| !matches!(child.kind(), "comment" | "heredoc_body"))
+        .last()
+}
+
+/// Get the value of a `return` statement returning a single value.
+fn return_value<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let arguments = node.named_child(0)?;
+    if arguments.named_child_count() != 1 {
+        return None;
+    }
+    arguments.named_child(0)
+}
+
+/// Process a value returned from the enclosing prompt function. The value is
+/// processed like a prompt assignment bound to the function name, marking
+/// the variables that come from the function parameters.
+#[allow(clippy::too_many_arguments)]
+fn process_function_return(
+    value: &Node,
+    stmt: &Node,
+    source: &str,
+    filename: &str,
+    comments: &CommentTracker,
+    scopes: &mut ScopeTracker,
+    prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
+) {
+    let Some(function) = scopes.current_function().cloned() else {
+        return;
+    };
+    let stmt_start = stmt.start_byte() as u32;
+    let stmt_end = stmt.end_byte() as u32;
+
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
+    // Statement annotations take precedence over the function annotations
+    let mut annotations = comments.collect_adjacent_leading(stmt_start);
+    annotations.extend(comments.collect_inline_prompt(stmt_start, stmt_end));
+    if annotations.is_empty() {
+        annotations = function.annotations.clone();
+    }
+
+    let prompts_len = prompts.len();
+    process_identifier_assignment(
+        &function.binding.name,
+        value,
+        true,
+        &annotations,
+        &function.binding,
+        stmt_start,
+        stmt_end,
+        source,
+        filename,
+        comments,
+        scopes,
+        prompts,
+        groups,
+    );
+    function.mark_parameter_vars(prompts, prompts_len);
+}
+
 /// Mark annotated standalone identifiers as prompt definitions so later assignments inherit them.
 fn process_identifier_declaration(
     node: &Node,
@@ -183,6 +384,14 @@ fn process_identifier_declaration(
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations mark the identifier as ignored instead
+    if comments.has_ignore_annotation(stmt_start, stmt_end) {
+        if let Ok(name) = node.utf8_text(source.as_bytes()) {
+            scopes.mark_ignored_ident(name);
+        }
+        return;
+    }
+
     let mut annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     annotations.extend(inline_annotations);
@@ -205,17 +414,23 @@ fn process_assignment(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
     let stmt_start = node.start_byte() as u32;
     let stmt_end = node.end_byte() as u32;
 
+    // Opt-out annotations suppress prompt detection for the statement
+    scopes.set_statement_ignored(comments.has_ignore_annotation(stmt_start, stmt_end));
+
     // Collect annotations
     let leading_annotations = comments.collect_adjacent_leading(stmt_start);
     let inline_annotations = comments.collect_inline_prompt(stmt_start, stmt_end);
     let mut all_annotations = leading_annotations.clone();
     all_annotations.extend(inline_annotations);
 
-    let has_prompt_annotation = !all_annotations.is_empty();
+    // Statements in `@prompts` files and `@prompt-begin` regions are annotated
+    let has_prompt_annotation =
+        !all_annotations.is_empty() || comments.in_prompt_region(stmt_start);
 
     // Get left and right sides
     let left = match node.child_by_field_name("left") {
@@ -250,11 +465,13 @@ fn process_assignment(
         "identifier" => {
             // Simple assignment: var = value
             let ident_name = left.utf8_text(source.as_bytes()).unwrap_or("");
+            let binding = binding_from_node(&left, source, PromptBindingKind::Local);
             process_identifier_assignment(
                 ident_name,
                 &right,
                 has_prompt_annotation,
                 &all_annotations,
+                &binding,
                 stmt_start,
                 stmt_end,
                 source,
@@ -262,6 +479,7 @@ fn process_assignment(
                 comments,
                 scopes,
                 prompts,
+                groups,
             );
         }
         "left_assignment_list" => {
@@ -325,9 +543,10 @@ fn process_chained_assignment(
         return;
     }
 
-    for ident in idents.iter() {
+    for (ident, ident_node) in idents.iter() {
         if is_prompt_variable(ident, has_prompt_annotation, scopes) {
             scopes.mark_prompt_ident(ident);
+            let binding = binding_from_node(ident_node, source, PromptBindingKind::Local);
             create_prompt_from_string(
                 &value,
                 source,
@@ -336,6 +555,7 @@ fn process_chained_assignment(
                 stmt_end,
                 comments,
                 annotations,
+                &binding,
                 prompts,
             );
         }
@@ -349,6 +569,7 @@ fn process_identifier_assignment(
     right: &Node,
     has_prompt_annotation: bool,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     stmt_start: u32,
     stmt_end: u32,
     source: &str,
@@ -356,9 +577,17 @@ fn process_identifier_assignment(
     comments: &CommentTracker,
     scopes: &mut ScopeTracker,
     prompts: &mut Vec<Prompt>,
+    groups: &mut Vec<PromptGroup>,
 ) {
-    // Determine if this is a prompt
-    let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
+    // Check if it's a chat message hash or an array of them
+    let messages = extract_messages(right, source);
+
+    // Determine if this is a prompt. Chat messages are prompts by structure.
+    let is_prompt = is_prompt_variable(
+        ident_name,
+        has_prompt_annotation || !messages.is_empty(),
+        scopes,
+    );
 
     if is_prompt {
         // Mark as prompt identifier
@@ -369,8 +598,41 @@ fn process_identifier_assignment(
             scopes.store_def_annotation(ident_name, annotations.to_vec());
         }
 
-        // Check if it's a string
-        if is_string_like(right) {
+        if !messages.is_empty() {
+            let final_annotations = if !annotations.is_empty() {
+                annotations.to_vec()
+            } else {
+                scopes.get_def_annotation(ident_name).unwrap_or_default()
+            };
+
+            // Handle messages: [{ role: "system", content: "..." }]
+            let group_start = prompts.len();
+            for (role, content) in messages {
+                let prompts_len = prompts.len();
+                create_prompt_from_string(
+                    &content,
+                    source,
+                    filename,
+                    stmt_start,
+                    stmt_end,
+                    comments,
+                    &final_annotations,
+                    binding,
+                    prompts,
+                );
+                for prompt in &mut prompts[prompts_len..] {
+                    prompt.role = role;
+                }
+            }
+
+            // Group the messages array into a conversation
+            if right.kind() == "array" {
+                let span = (right.start_byte() as u32, right.end_byte() as u32);
+                if let Some(group) = prompt_group(filename, span, binding, prompts, group_start) {
+                    groups.push(group);
+                }
+            }
+        } else if is_string_like(right) {
             // Annotations from comment tracker are already validated to contain @prompt
             // Get annotations (from current statement or from definition)
             let final_annotations = if !annotations.is_empty() {
@@ -388,6 +650,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
                 prompts,
             );
         } else if right.kind() == "binary" {
@@ -410,6 +673,7 @@ fn process_identifier_assignment(
                             stmt_end,
                             comments,
                             &final_annotations,
+                            binding,
                         ) {
                             prompts.push(prompt);
                         }
@@ -423,6 +687,7 @@ fn process_identifier_assignment(
                             stmt_end,
                             comments,
                             &final_annotations,
+                            binding,
                         ) {
                             prompts.push(prompt);
                         }
@@ -445,6 +710,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -464,6 +730,7 @@ fn process_identifier_assignment(
                 stmt_end,
                 comments,
                 &final_annotations,
+                binding,
             ) {
                 prompts.push(prompt);
             }
@@ -495,12 +762,14 @@ fn process_multi_assignment(
     extract_value_ranges(right, &mut value_ranges);
 
     // Match identifiers with values
-    for (i, ident_name) in identifiers.iter().enumerate() {
+    for (i, (ident_name, ident_node)) in identifiers.iter().enumerate() {
         if let Some((start, end, kind)) = value_ranges.get(i) {
             let is_prompt = is_prompt_variable(ident_name, has_prompt_annotation, scopes);
 
             if is_prompt && (*kind == "string" || *kind == "string_content") {
                 scopes.mark_prompt_ident(ident_name);
+                let binding =
+                    binding_from_node(ident_node, source, PromptBindingKind::Destructured);
 
                 // Create prompt from range
                 create_prompt_from_range(
@@ -512,6 +781,7 @@ fn process_multi_assignment(
                     stmt_end,
                     comments,
                     annotations,
+                    &binding,
                     prompts,
                 );
             }
@@ -519,9 +789,17 @@ fn process_multi_assignment(
     }
 }
 
-/// Check if a variable should be treated as a prompt.
-fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &ScopeTracker) -> bool {
-    ident_name.to_lowercase().contains("prompt")
+/// Check if a variable should be treated as a prompt. Assignments declare
+/// the variable in the current scope, shadowing the outer variables with the
+/// name. Variables in statements with an opt-out annotation are marked as
+/// ignored instead.
+fn is_prompt_variable(ident_name: &str, has_annotation: bool, scopes: &mut ScopeTracker) -> bool {
+    scopes.declare(ident_name);
+    if scopes.is_statement_ignored() {
+        scopes.mark_ignored_ident(ident_name);
+        return false;
+    }
+    scopes.is_prompt_name(ident_name)
         || has_annotation
         || scopes.is_prompt_ident(ident_name)
 }
@@ -534,11 +812,222 @@ fn is_string_like(node: &Node) -> bool {
     )
 }
 
+/// Extract chat message roles and string contents from a message hash, e.g.,
+/// `{ role: "system", content: "..." }`, or from an array of message hashes.
+fn extract_messages<'a>(node: &Node<'a>, source: &str) -> Vec<(Option<PromptRole>, Node<'a>)> {
+    match node.kind() {
+        "hash" => extract_message(node, source).into_iter().collect(),
+        "array" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .filter(|child| child.kind() == "hash")
+                .filter_map(|child| extract_message(&child, source))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Extract the role and the string content from a message hash. Hashes
+/// without the role key aren't messages.
+fn extract_message<'a>(node: &Node<'a>, source: &str) -> Option<(Option<PromptRole>, Node<'a>)> {
+    let mut role = None;
+    let mut content = None;
+    let mut cursor = node.walk();
+    for pair in node.named_children(&mut cursor) {
+        if pair.kind() != "pair" {
+            continue;
+        }
+        let (Some(key), Some(value)) = (
+            pair.child_by_field_name("key"),
+            pair.child_by_field_name("value"),
+        ) else {
+            continue;
+        };
+        let key_text = key.utf8_text(source.as_bytes()).unwrap_or_default();
+        if is_role_key(key_text) {
+            role = Some(parse_role_value(
+                value.utf8_text(source.as_bytes()).unwrap_or_default(),
+            ));
+        } else if is_content_key(key_text) && is_string_like(&value) {
+            content = Some(value);
+        }
+    }
+    Some((role?, content?))
+}
+
+/// Ruby LLM SDK call site syntax, e.g., `client.messages.create(system: "...")`.
+struct CallSites<'a> {
+    comments: &'a CommentTracker,
+}
+
+impl<'tree> CallSiteSyntax<'tree> for CallSites<'_> {
+    fn call(&self, node: &Node<'tree>, source: &str) -> Option<(Span, Node<'tree>)> {
+        if node.kind() != "call" {
+            return None;
+        }
+        let method = node.child_by_field_name("method")?;
+        let arguments = node.child_by_field_name("arguments")?;
+        // Constructors are matched by the class, e.g., `SystemMessage.new(...)`
+        let callee_end = match node.child_by_field_name("receiver") {
+            Some(receiver) if method.utf8_text(source.as_bytes()) == Ok("new") => {
+                receiver.end_byte()
+            }
+            _ => method.end_byte(),
+        };
+        Some(((node.start_byte() as u32, callee_end as u32), arguments))
+    }
+
+    /// Keyword arguments and hash literal arguments are named.
+    fn arguments(&self, arguments: &Node<'tree>, _source: &str) -> Vec<CallArgument<'tree>> {
+        let mut pairs = Vec::new();
+        let mut values = Vec::new();
+        let mut cursor = arguments.walk();
+        let arguments = arguments
+            .named_children(&mut cursor)
+            .filter(|argument| argument.kind() != "comment");
+        for (position, argument) in arguments.enumerate() {
+            match argument.kind() {
+                "pair" => pairs.push(argument),
+                "hash" => {
+                    let mut hash_cursor = argument.walk();
+                    pairs.extend(
+                        argument
+                            .named_children(&mut hash_cursor)
+                            .filter(|pair| pair.kind() == "pair"),
+                    );
+                }
+                _ => values.push(CallArgument::Positional(position, argument)),
+            }
+        }
+        values.extend(pairs.into_iter().filter_map(|pair| {
+            Some(CallArgument::Named(
+                pair.child_by_field_name("key")?,
+                pair.child_by_field_name("value")?,
+            ))
+        }));
+        values
+    }
+
+    fn messages(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+    ) -> Vec<(Option<PromptRole>, Node<'tree>)> {
+        extract_messages(value, source)
+    }
+
+    fn is_conversation(&self, value: &Node<'tree>, _source: &str) -> bool {
+        value.kind() == "array"
+    }
+
+    fn is_string_like(&self, value: &Node<'tree>) -> bool {
+        is_string_like(value)
+    }
+
+    fn create_prompt(
+        &self,
+        value: &Node<'tree>,
+        source: &str,
+        filename: &str,
+        call: Span,
+        binding: &PromptBinding,
+        prompts: &mut Vec<Prompt>,
+    ) {
+        create_prompt_from_string(
+            value,
+            source,
+            filename,
+            call.0,
+            call.1,
+            self.comments,
+            &[],
+            binding,
+            prompts,
+        );
+    }
+}
+
+/// Recursively resolve the identifiers referencing prompt bindings, e.g.,
+/// `send(user_prompt)`, tracking the method, class and module scopes.
+fn process_references(node: &Node, source: &str, references: &mut PromptReferences) {
+    // Skip code affected by syntax errors
+    if node.is_error() {
+        return;
+    }
+
+    let kind = node.kind();
+    let is_scope = matches!(kind, "method" | "singleton_method" | "class" | "module");
+    if is_scope {
+        references.enter_scope();
+        // Parameters shadow the outer bindings
+        if let Some(parameters) = node.child_by_field_name("parameters") {
+            for name in method_parameters(&parameters, source) {
+                references.shadow(&name);
+            }
+        }
+    }
+
+    if matches!(
+        kind,
+        "identifier" | "constant" | "instance_variable" | "class_variable" | "global_variable"
+    ) {
+        if let (Some(reference_kind), Ok(name)) =
+            (reference_kind(node), node.utf8_text(source.as_bytes()))
+        {
+            let span = (node.start_byte() as u32, node.end_byte() as u32);
+            // Assignments declare the variables in the current scope
+            if reference_kind == PromptReferenceKind::Reassign {
+                references.assignment(name, span);
+            } else {
+                references.identifier(name, span, reference_kind);
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        process_references(&child, source, references);
+    }
+
+    if is_scope {
+        references.exit_scope();
+    }
+}
+
+/// Get the reference kind by the identifier position. Method names aren't
+/// references.
+fn reference_kind(node: &Node) -> Option<PromptReferenceKind> {
+    let parent = node.parent()?;
+    let field = |name: &str| parent.child_by_field_name(name) == Some(*node);
+    match parent.kind() {
+        "call" if field("method") => None,
+        "method" | "singleton_method" | "class" | "module" if field("name") => None,
+        "assignment" | "operator_assignment" if field("left") => {
+            Some(PromptReferenceKind::Reassign)
+        }
+        "argument_list" => Some(PromptReferenceKind::Argument),
+        "pair"
+            if field("value")
+                && parent
+                    .parent()
+                    .is_some_and(|list| list.kind() == "argument_list") =>
+        {
+            Some(PromptReferenceKind::Argument)
+        }
+        _ => Some(PromptReferenceKind::Read),
+    }
+}
+
 /// Extract identifiers from a left_assignment_list or similar pattern.
-fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>) {
+fn extract_identifiers<'a>(
+    node: &Node<'a>,
+    source: &str,
+    identifiers: &mut Vec<(String, Node<'a>)>,
+) {
     if node.kind() == "identifier" {
         if let Ok(name) = node.utf8_text(source.as_bytes()) {
-            identifiers.push(name.to_string());
+            identifiers.push((name.to_string(), *node));
         }
         return;
     }
@@ -556,6 +1045,15 @@ fn extract_identifiers(node: &Node, source: &str, identifiers: &mut Vec<String>)
     }
 }
 
+/// Create a prompt binding from an identifier node.
+fn binding_from_node(node: &Node, source: &str, kind: PromptBindingKind) -> PromptBinding {
+    PromptBinding {
+        name: node.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+        span: (node.start_byte() as u32, node.end_byte() as u32),
+        kind,
+    }
+}
+
 /// Extract value ranges from the right side of an assignment.
 fn extract_value_ranges(node: &Node, values: &mut Vec<(u32, u32, &'static str)>) {
     let kind = node.kind();
@@ -596,8 +1094,6 @@ fn extract_value_ranges(node: &Node, values: &mut Vec<(u32, u32, &'static str)>)
 }
 
 /// Build content tokens from span and variables.
-/// For prompts without variables, returns a single str token.
-/// For prompts with variables, returns interleaved str and var tokens.
 /// For squiggly heredocs, creates tokens starting after stripped whitespace on each line.
 fn build_content_tokens(
     span: &SpanShape,
@@ -613,53 +1109,7 @@ fn build_content_tokens(
     }
 
     // Standard token building (non-squiggly heredocs and regular strings)
-    if vars.is_empty() {
-        // Simple case: single str token
-        return vec![PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: span.inner,
-            },
-        )];
-    }
-
-    let mut tokens = Vec::new();
-    let mut pos = span.inner.0;
-
-    for (var_idx, var) in vars.iter().enumerate() {
-        // Add str token before variable (if any content)
-        if pos < var.span.outer.0 {
-            tokens.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (pos, var.span.outer.0),
-                },
-            ));
-        }
-
-        // Add var token
-        tokens.push(PromptContentToken::PromptContentTokenVar(
-            PromptContentTokenVar {
-                r#type: PromptContentTokenVarTypeVar,
-                span: var.span.outer,
-                index: var_idx as u32,
-            },
-        ));
-
-        pos = var.span.outer.1;
-    }
-
-    // Add trailing str token (if any content)
-    if pos < span.inner.1 {
-        tokens.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (pos, span.inner.1),
-            },
-        ));
-    }
-
-    tokens
+    volumen_parser_core::build_content_tokens(span, vars)
 }
 
 /// Build content tokens for squiggly heredocs with whitespace stripping.
@@ -710,6 +1160,7 @@ fn build_heredoc_tokens(
                     PromptContentTokenStr {
                         r#type: PromptContentTokenStrTypeStr,
                         span: (token_start as u32, token_end as u32),
+                        escapes: None,
                     },
                 ));
             }
@@ -723,6 +1174,7 @@ fn build_heredoc_tokens(
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: (pos, var.span.outer.0),
+                            escapes: None,
                         },
                     ));
                 }
@@ -745,6 +1197,7 @@ fn build_heredoc_tokens(
                     PromptContentTokenStr {
                         r#type: PromptContentTokenStrTypeStr,
                         span: (pos, token_end as u32),
+                        escapes: None,
                     },
                 ));
             }
@@ -780,6 +1233,7 @@ fn calculate_min_heredoc_indent(body_text: &str) -> usize {
 }
 
 /// Create a prompt from a string node.
+#[allow(clippy::too_many_arguments)]
 fn create_prompt_from_string(
     string_node: &Node,
     source: &str,
@@ -788,6 +1242,7 @@ fn create_prompt_from_string(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // Normalize heredoc nodes to the nearest enclosing "string" node so spans cover the body
@@ -827,11 +1282,14 @@ fn create_prompt_from_string(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
@@ -846,6 +1304,7 @@ fn create_prompt_from_range(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
     prompts: &mut Vec<Prompt>,
 ) {
     // For simple strings, outer and inner are close
@@ -875,24 +1334,19 @@ fn create_prompt_from_range(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     });
 }
 
 // Concatenation support
 
-#[derive(Debug)]
-enum ConcatSegment {
-    String(SpanShape),
-    Variable(SpanShape),
-    Primitive(SpanShape),
-    Other,
-}
-
 /// Process a binary expression for string concatenation
 #[allow(clippy::too_many_arguments)]
 fn process_concatenation(
@@ -903,6 +1357,7 @@ fn process_concatenation(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if it's a + operator
     let operator = node.child_by_field_name("operator")?;
@@ -913,78 +1368,12 @@ fn process_concatenation(
 
     // Extract segments recursively
     let segments = extract_concat_segments(node, source);
-
-    // Reject if no strings or contains complex objects
-    let has_string = segments.iter().any(|s| matches!(s, ConcatSegment::String(_)));
-    let has_other = segments.iter().any(|s| matches!(s, ConcatSegment::Other));
-
-    if !has_string || has_other {
-        return None;
-    }
-
-    // Build prompt outer span (entire concatenation expression)
-    let prompt_outer = (node.start_byte() as u32, node.end_byte() as u32);
-
-    // Find first and last string segments to determine inner span
-    let first_string_pos = segments.iter().find_map(|s| match s {
-        ConcatSegment::String(span) => Some(span.inner.0),
-        _ => None,
-    })?;
-
-    let last_string_end = segments.iter().rev().find_map(|s| match s {
-        ConcatSegment::String(span) => Some(span.inner.1),
-        _ => None,
-    })?;
-
-    let prompt_inner = (first_string_pos, last_string_end);
-    let span = SpanShape {
-        outer: prompt_outer,
-        inner: prompt_inner,
-    };
-
-    // Build vars and content tokens
-    let mut vars = Vec::new();
-    let mut content = Vec::new();
-    let mut var_idx = 0u32;
-
-    for segment in &segments {
-        match segment {
-            ConcatSegment::String(s_span) => {
-                // Add string token
-                content.push(PromptContentToken::PromptContentTokenStr(
-                    PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: (s_span.inner.0, s_span.inner.1),
-                    },
-                ));
-            }
-            ConcatSegment::Variable(v_span) | ConcatSegment::Primitive(v_span) => {
-                // Expand to include operators
-                let mut var_outer = v_span.clone();
-                expand_to_operators(&mut var_outer, source, prompt_outer);
-
-                let var = PromptVar {
-                    span: SpanShape {
-                        outer: var_outer.outer,
-                        inner: v_span.inner,
-                    },
-                };
-
-                // Add variable token (before pushing var)
-                content.push(PromptContentToken::PromptContentTokenVar(
-                    PromptContentTokenVar {
-                        r#type: PromptContentTokenVarTypeVar,
-                        span: var.span.inner,
-                        index: var_idx,
-                    },
-                ));
-
-                vars.push(var);
-                var_idx += 1;
-            }
-            ConcatSegment::Other => {}
-        }
-    }
+    let outer = (node.start_byte() as u32, node.end_byte() as u32);
+    let ConcatContent {
+        span,
+        vars,
+        content,
+    } = concat_content(source, outer, "+", &segments)?;
 
     // Calculate enclosure
     let enclosure_start = comments
@@ -998,11 +1387,14 @@ fn process_concatenation(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -1038,37 +1430,18 @@ fn extract_concat_segments(node: &Node, source: &str) -> Vec<ConcatSegment> {
 
 /// Classify a single node as a segment
 fn classify_single_node(node: &Node, source: &str) -> Vec<ConcatSegment> {
-    let kind = node.kind();
-
-    match kind {
+    let span = (node.start_byte() as u32, node.end_byte() as u32);
+    match node.kind() {
+        // String literal
         "string" | "string_content" | "heredoc_body" => {
-            // String literal
-            let span = span_shape_string_like(node, source);
-            vec![ConcatSegment::String(span)]
-        }
-        "identifier" | "constant" | "instance_variable" | "class_variable" | "global_variable" => {
-            // Variable
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Variable(SpanShape { outer, inner })]
-        }
-        "call" | "method_call" => {
-            // Function/method call - treat as variable
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Variable(SpanShape { outer, inner })]
-        }
-        "integer" | "float" | "true" | "false" => {
-            // Primitives - Ruby requires .to_s conversion, so this would be a type error
-            // But we can still parse it as a primitive for completeness
-            let outer = (node.start_byte() as u32, node.end_byte() as u32);
-            let inner = outer;
-            vec![ConcatSegment::Primitive(SpanShape { outer, inner })]
-        }
-        "array" | "hash" => {
-            // Complex objects - reject
-            vec![ConcatSegment::Other]
+            vec![ConcatSegment::String(span_shape_string_like(node, source))]
         }
+        // Variables and function/method calls
+        "identifier" | "constant" | "instance_variable" | "class_variable" | "global_variable"
+        | "call" | "method_call" => vec![ConcatSegment::Variable(span)],
+        // Primitives - Ruby requires .to_s conversion, so this would be a type error
+        // But we can still parse it as a primitive for completeness
+        "integer" | "float" | "true" | "false" => vec![ConcatSegment::Primitive(span)],
         "parenthesized_statements" => {
             // Unwrap parentheses
             let mut cursor = node.walk();
@@ -1085,55 +1458,9 @@ fn classify_single_node(node: &Node, source: &str) -> Vec<ConcatSegment> {
             }
             vec![ConcatSegment::Other]
         }
-        _ => {
-            // Unknown - reject
-            vec![ConcatSegment::Other]
-        }
-    }
-}
-
-/// Expand variable span to include surrounding operators
-fn expand_to_operators(var_span: &mut SpanShape, source: &str, prompt_outer: (u32, u32)) {
-    let bytes = source.as_bytes();
-    let mut new_start = var_span.outer.0;
-    let mut new_end = var_span.outer.1;
-
-    // Expand left to include " + "
-    let mut i = new_start as usize;
-    while i > prompt_outer.0 as usize {
-        i -= 1;
-        let c = bytes[i];
-        if c == b'+' {
-            // Include the + and any spaces before it
-            while i > prompt_outer.0 as usize && (bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') {
-                i -= 1;
-            }
-            new_start = i as u32;
-            break;
-        } else if c != b' ' && c != b'\t' {
-            break;
-        }
-    }
-
-    // Expand right to include " + "
-    let mut i = new_end as usize;
-    while i < prompt_outer.1 as usize {
-        let c = bytes[i];
-        if c == b'+' {
-            // Include the + and any spaces after it
-            i += 1;
-            while i < prompt_outer.1 as usize && (bytes[i] == b' ' || bytes[i] == b'\t') {
-                i += 1;
-            }
-            new_end = i as u32;
-            break;
-        } else if c != b' ' && c != b'\t' {
-            break;
-        }
-        i += 1;
+        // Complex objects and unknown expressions - reject
+        _ => vec![ConcatSegment::Other],
     }
-
-    var_span.outer = (new_start, new_end);
 }
 
 // Format operator support
@@ -1148,6 +1475,7 @@ fn process_format_operator(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Get left (format string) and right (arguments)
     let left = node.child_by_field_name("left")?;
@@ -1161,7 +1489,7 @@ fn process_format_operator(
     // Parse format string to find placeholders
     let format_str_span = span_shape_string_like(&left, source);
     let format_str_content = &source[format_str_span.inner.0 as usize..format_str_span.inner.1 as usize];
-    let placeholders = parse_printf_placeholders(format_str_content);
+    let placeholders = printf_placeholders(format_str_content);
     
     if placeholders.is_empty() {
         return None;
@@ -1185,62 +1513,13 @@ fn process_format_operator(
         arg_nodes.push(right);
     }
     
-    // Build vars from arguments
-    let mut vars = Vec::new();
-    for (arg_idx, arg_node) in arg_nodes.iter().enumerate() {
-        if arg_idx >= placeholders.len() {
-            break; // More args than placeholders
-        }
-        
-        let arg_start = arg_node.start_byte() as u32;
-        let arg_end = arg_node.end_byte() as u32;
-        vars.push(PromptVar {
-            span: SpanShape {
-                outer: (arg_start, arg_end),
-                inner: (arg_start, arg_end),
-            },
-        });
-    }
-    
-    // Build content tokens
-    let mut content = Vec::new();
-    let mut pos = 0usize;
-    let format_inner_start = format_str_span.inner.0;
-    
-    for (placeholder_idx, (start, end)) in placeholders.iter().enumerate() {
-        // Add string token before placeholder
-        if pos < *start {
-            content.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (format_inner_start + pos as u32, format_inner_start + *start as u32),
-                },
-            ));
-        }
-        
-        // Add var token for placeholder (if we have a corresponding arg)
-        if placeholder_idx < vars.len() {
-            content.push(PromptContentToken::PromptContentTokenVar(
-                PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: (format_inner_start + *start as u32, format_inner_start + *end as u32),
-                    index: placeholder_idx as u32,
-                },
-            ));
-        }
-        
-        pos = *end;
-    }
-    
-    // Add trailing string token
-    if pos < format_str_content.len() {
-        content.push(PromptContentToken::PromptContentTokenStr(
-            PromptContentTokenStr {
-                r#type: PromptContentTokenStrTypeStr,
-                span: (format_inner_start + pos as u32, format_str_span.inner.1),
-            },
-        ));
-    }
+    // Build vars and content tokens from the arguments
+    let arguments: Vec<_> = arg_nodes
+        .iter()
+        .map(|arg_node| (arg_node.start_byte() as u32, arg_node.end_byte() as u32))
+        .collect();
+    let (vars, content) =
+        format_content(source, format_str_span.inner, &placeholders, &arguments);
     
     let enclosure_start = comments
         .get_any_leading_start(stmt_start)
@@ -1255,50 +1534,17 @@ fn process_format_operator(
         enclosure: (enclosure_start, stmt_end),
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
-/// Parse printf-style placeholders: %s, %d, %f, etc.
-/// Returns vec of (start, end) positions in the string
-fn parse_printf_placeholders(format_str: &str) -> Vec<(usize, usize)> {
-    let mut placeholders = Vec::new();
-    let chars: Vec<char> = format_str.chars().collect();
-    let mut i = 0;
-    
-    while i < chars.len() {
-        if chars[i] == '%' {
-            // Check for escaped %%
-            if i + 1 < chars.len() && chars[i + 1] == '%' {
-                i += 2; // Skip escaped %%
-                continue;
-            }
-            
-            let start = i;
-            i += 1;
-            
-            // Skip flags, width, precision
-            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-' || chars[i] == '+' || chars[i] == ' ' || chars[i] == '#' || chars[i] == '.' || chars[i] == '*') {
-                i += 1;
-            }
-            
-            // Get format specifier
-            if i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '%') {
-                i += 1;
-                placeholders.push((start, i));
-                continue;
-            }
-        }
-        i += 1;
-    }
-    
-    placeholders
-}
-
 /// Process an array: ["Hello ", user, "!"]
 #[allow(clippy::too_many_arguments)]
 fn process_array(
@@ -1309,6 +1555,7 @@ fn process_array(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Extract array elements
     let mut vars = Vec::new();
@@ -1327,6 +1574,7 @@ fn process_array(
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -1334,9 +1582,7 @@ fn process_array(
                     // Variable
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1350,9 +1596,7 @@ fn process_array(
                     // Function/method call - treat as variable
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1390,11 +1634,14 @@ fn process_array(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint: SpanShape {
             outer: (0, 0),
             inner: (0, 0),
         },
+        role: None,
+        references: None,
     })
 }
 
@@ -1408,6 +1655,7 @@ fn process_join_call(
     stmt_end: u32,
     comments: &CommentTracker,
     annotations: &[PromptAnnotation],
+    binding: &PromptBinding,
 ) -> Option<Prompt> {
     // Check if method name is "join"
     let method_node = node.child_by_field_name("method")?;
@@ -1462,6 +1710,7 @@ fn process_join_call(
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -1471,6 +1720,7 @@ fn process_join_call(
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -1480,15 +1730,14 @@ fn process_join_call(
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
                     first = false;
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1504,15 +1753,14 @@ fn process_join_call(
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
                     first = false;
                     let outer = (child.start_byte() as u32, child.end_byte() as u32);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(source, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -1551,7 +1799,10 @@ fn process_join_call(
         enclosure,
         vars,
         annotations: annotations.to_vec(),
+        binding: binding.clone(),
         content,
         joint,
+        role: None,
+        references: None,
     })
 }
@@ -1,5 +1,6 @@
 use tree_sitter::Node;
-use volumen_types::{PromptVar, SpanShape};
+use volumen_parser_core::{EscapeStyle, prompt_var};
+use volumen_types::{PromptVar, Span, SpanShape};
 
 /// Information about heredoc whitespace stripping behavior
 #[derive(Debug, Clone)]
@@ -259,12 +260,14 @@ pub fn extract_interpolation_vars(node: &Node, source: &str) -> Vec<PromptVar> {
             let inner_start = outer_start + 2; // Skip #{
             let inner_end = outer_end.saturating_sub(1); // Skip }
 
-            vars.push(PromptVar {
-                span: SpanShape {
+            vars.push(prompt_var(
+                source,
+                SpanShape {
                     outer: (outer_start, outer_end),
                     inner: (inner_start, inner_end),
                 },
-            });
+                (inner_start, inner_end),
+            ));
             return;
         }
 
@@ -315,3 +318,25 @@ pub fn is_string_like(node: &Node) -> bool {
         "string" | "string_content" | "heredoc_body" | "heredoc_beginning"
     )
 }
+
+/// Resolve escaping rules of the string literal containing the span. Ruby
+/// strings are single-quoted (`'...'`, `%q(...)`) or double-quoted (`"..."`,
+/// heredocs).
+pub fn escape_style(root: &Node, source: &str, span: Span) -> EscapeStyle {
+    let mut node = root.descendant_for_byte_range(span.0 as usize, span.1 as usize);
+    while let Some(current) = node {
+        match current.kind() {
+            "string" => {
+                let text = &source[current.start_byte()..current.end_byte()];
+                return if text.starts_with('\'') || text.starts_with("%q") {
+                    EscapeStyle::SingleQuoted
+                } else {
+                    EscapeStyle::Rb
+                };
+            }
+            "heredoc_body" => return EscapeStyle::Rb,
+            _ => node = current.parent(),
+        }
+    }
+    EscapeStyle::None
+}
@@ -9,6 +9,9 @@ pub fn assert_prompts_size(result: ParseResult, expected_size: usize) {
         ParseResult::ParseResultError(ParseResultError { error, .. }) => {
             panic!("Parsing failed: {}", error)
         }
+        ParseResult::ParseResultPartial(ParseResultPartial { diagnostics, .. }) => {
+            panic!("Parsing partially failed: {:?}", diagnostics)
+        }
     }
 }
 
@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -35,6 +37,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -88,6 +95,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -108,6 +117,11 @@ fn nested() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "helloPrompt",
+                        span: (48, 59),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.cs",
@@ -135,8 +149,14 @@ fn nested() {
                               inner: (90, 98),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "alsoPrompt",
+                        span: (114, 124),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -186,3 +206,113 @@ fn nested() {
         },
     );
 }
+
+#[test]
+fn fields() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            class User
+            {
+                private string userPrompt = "You are a helpful assistant.";
+                private const string SystemPrompt = "Be concise.";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (17, 76),
+                      span: SpanShape(
+                        outer: (45, 75),
+                        inner: (46, 74),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (46, 74),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (32, 42),
+                        kind: field,
+                      ),
+                    ),
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (81, 131),
+                      span: SpanShape(
+                        outer: (117, 130),
+                        inner: (118, 129),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (118, 129),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "SystemPrompt",
+                        span: (102, 114),
+                        kind: const,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "private string userPrompt = \"You are a helpful assistant.\";",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "private const string SystemPrompt = \"Be concise.\";",
+                    "outer": "\"Be concise.\"",
+                    "inner": "Be concise.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Be concise."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -35,6 +37,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (0, 10),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -86,6 +93,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -106,6 +115,11 @@ fn nested() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "helloPrompt",
+                        span: (19, 30),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.go",
@@ -133,8 +147,14 @@ fn nested() {
                               inner: (57, 65),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "alsoPrompt",
+                        span: (70, 80),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
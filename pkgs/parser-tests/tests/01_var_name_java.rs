@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -35,6 +37,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -88,6 +95,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -108,6 +117,11 @@ fn nested() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "helloPrompt",
+                        span: (48, 59),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.java",
@@ -135,8 +149,14 @@ fn nested() {
                               inner: (90, 98),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "alsoPrompt",
+                        span: (114, 124),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -186,3 +206,112 @@ fn nested() {
         },
     );
 }
+
+#[test]
+fn fields() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            class User {
+                private String userPrompt = "You are a helpful assistant.";
+                private static final String SYSTEM_PROMPT = "Be concise.";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (17, 76),
+                      span: SpanShape(
+                        outer: (45, 75),
+                        inner: (46, 74),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (46, 74),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (32, 42),
+                        kind: field,
+                      ),
+                    ),
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (81, 139),
+                      span: SpanShape(
+                        outer: (125, 138),
+                        inner: (126, 137),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (126, 137),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "SYSTEM_PROMPT",
+                        span: (109, 122),
+                        kind: const,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "private String userPrompt = \"You are a helpful assistant.\";",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "private static final String SYSTEM_PROMPT = \"Be concise.\";",
+                    "outer": "\"Be concise.\"",
+                    "inner": "Be concise.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Be concise."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -36,6 +38,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -93,6 +100,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -126,9 +135,17 @@ fn nested() {
                             outer: (69, 76),
                             inner: (70, 75),
                           ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$hello_prompt",
+                        span: (45, 58),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -156,8 +173,14 @@ fn nested() {
                               inner: (87, 95),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$also_prompt",
+                        span: (100, 112),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -35,6 +37,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -91,6 +98,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -124,9 +133,17 @@ fn nested() {
                             outer: (88, 94),
                             inner: (89, 93),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "hello_prompt",
+                        span: (64, 76),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -154,8 +171,14 @@ fn nested() {
                               inner: (111, 119),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "also_prmpt",
+                        span: (132, 142),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -35,6 +37,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -94,6 +101,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -127,9 +136,17 @@ fn nested() {
                             outer: (64, 71),
                             inner: (66, 70),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "hello_prompt",
+                        span: (41, 53),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -157,8 +174,14 @@ fn nested() {
                               inner: (82, 90),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "also_prompt",
+                        span: (97, 108),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
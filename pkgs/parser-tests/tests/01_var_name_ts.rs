@@ -17,6 +17,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -37,6 +39,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -85,6 +92,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -105,6 +114,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (4, 14),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -153,6 +167,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -173,6 +189,11 @@ fn simple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (4, 14),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -233,6 +254,8 @@ fn nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -266,9 +289,17 @@ fn nested() {
                             outer: (99, 106),
                             inner: (101, 105),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "helloPrompt",
+                        span: (77, 88),
+                        kind: const,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -296,8 +327,14 @@ fn nested() {
                               inner: (125, 133),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "alsoPrmpt",
+                        span: (152, 161),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
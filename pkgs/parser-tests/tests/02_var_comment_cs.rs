@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -43,8 +45,14 @@ fn simple() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (18, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -99,6 +107,8 @@ fn inline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -126,8 +136,14 @@ fn inline() {
                               inner: (2, 11),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (21, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -182,6 +198,8 @@ fn doc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -209,8 +227,14 @@ fn doc() {
                               inner: (3, 11),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (19, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -266,6 +290,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -296,6 +322,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -326,6 +354,8 @@ fn reassigned_strings() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -353,8 +383,20 @@ fn reassigned_strings() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (18, 28),
+                        kind: local,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (40, 50),
+                          kind: reassign,
+                        ),
+                      ]),
                     ),
                     Prompt(
                       file: "Prompts.cs",
@@ -382,8 +424,14 @@ fn reassigned_strings() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (40, 50),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -453,6 +501,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -483,6 +533,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -523,6 +575,8 @@ fn mixed_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -550,8 +604,14 @@ fn mixed_nested() {
                               inner: (87, 95),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (126, 128),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -705,6 +765,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -736,6 +798,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -772,6 +836,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -799,8 +865,14 @@ fn spaced() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (20, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -855,6 +927,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -882,8 +956,15 @@ fn dirty() {
                               inner: (2, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (25, 31),
+                        kind: local,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -938,6 +1019,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -965,8 +1048,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (18, 23),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.cs",
@@ -994,8 +1083,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (35, 40),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1052,3 +1147,157 @@ fn multi() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            class Hello {
+                // @prompt
+                string greeting = "Hello!";
+
+                void World() {
+                    string greeting = "Hi!";
+                    greeting = "Bye!";
+
+                    {
+                        // @prompt
+                        string farewell = "Bye!";
+                    }
+                    string farewell = "See you!";
+                }
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (18, 60),
+                      span: SpanShape(
+                        outer: (51, 59),
+                        inner: (52, 58),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (52, 58),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (18, 28),
+                              inner: (20, 28),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (40, 48),
+                        kind: field,
+                      ),
+                    ),
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (164, 212),
+                      span: SpanShape(
+                        outer: (205, 211),
+                        inner: (206, 210),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (206, 210),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (164, 174),
+                              inner: (166, 174),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (194, 202),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n    string greeting = \"Hello!\";",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\n            string farewell = \"Bye!\";",
+                    "outer": "\"Bye!\"",
+                    "inner": "Bye!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!",
+                  "Bye!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
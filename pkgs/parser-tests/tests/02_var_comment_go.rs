@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -43,8 +45,14 @@ fn simple() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (11, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -99,6 +107,8 @@ fn inline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -126,8 +136,14 @@ fn inline() {
                               inner: (2, 11),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (14, 19),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -183,6 +199,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -214,6 +232,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -245,6 +265,8 @@ fn reassigned_strings() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -272,8 +294,20 @@ fn reassigned_strings() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (11, 21),
+                        kind: local,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (33, 43),
+                          kind: reassign,
+                        ),
+                      ]),
                     ),
                     Prompt(
                       file: "prompts.go",
@@ -301,8 +335,14 @@ fn reassigned_strings() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (33, 43),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -372,6 +412,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -402,6 +444,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -442,6 +486,8 @@ fn mixed_nested_strings() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -469,8 +515,20 @@ fn mixed_nested_strings() {
                               inner: (81, 89),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (94, 96),
+                        kind: local,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (110, 112),
+                          kind: reassign,
+                        ),
+                      ]),
                     ),
                     Prompt(
                       file: "prompts.go",
@@ -498,8 +556,14 @@ fn mixed_nested_strings() {
                               inner: (81, 89),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (110, 112),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -572,6 +636,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -605,6 +671,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -641,6 +709,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -668,8 +738,14 @@ fn spaced() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (13, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -724,6 +800,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -751,8 +829,15 @@ fn dirty() {
                               inner: (2, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (18, 24),
+                        kind: local,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -808,6 +893,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -835,8 +922,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (11, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -878,3 +971,159 @@ fn multi() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            package main
+
+            func world() {
+            	// @prompt
+            	greeting := "Hello!"
+
+            	if true {
+            		greeting := "Hi!"
+            		greeting = "Bye!"
+            	}
+
+            	{
+            		// @prompt
+            		farewell := "Bye!"
+            	}
+            	farewell := "See you!"
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (30, 62),
+                      span: SpanShape(
+                        outer: (54, 62),
+                        inner: (55, 61),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (55, 61),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (30, 40),
+                              inner: (32, 40),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (42, 50),
+                        kind: local,
+                      ),
+                    ),
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (124, 155),
+                      span: SpanShape(
+                        outer: (149, 155),
+                        inner: (150, 154),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (150, 154),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (124, 134),
+                              inner: (126, 134),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (137, 145),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n\tgreeting := \"Hello!\"",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\n\t\tfarewell := \"Bye!\"",
+                    "outer": "\"Bye!\"",
+                    "inner": "Bye!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!",
+                  "Bye!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -43,8 +45,14 @@ fn simple() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (18, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -99,6 +107,8 @@ fn inline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -126,8 +136,14 @@ fn inline() {
                               inner: (2, 11),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (21, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -184,6 +200,8 @@ fn doc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -211,8 +229,14 @@ fn doc() {
                               inner: (3, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (26, 31),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -268,6 +292,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -298,6 +324,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -328,6 +356,8 @@ fn reassigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -355,8 +385,20 @@ fn reassigned() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (18, 28),
+                        kind: local,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (40, 50),
+                          kind: reassign,
+                        ),
+                      ]),
                     ),
                     Prompt(
                       file: "Prompts.java",
@@ -384,8 +426,14 @@ fn reassigned() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (40, 50),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -455,6 +503,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -485,6 +535,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -525,6 +577,8 @@ fn mixed_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -552,8 +606,14 @@ fn mixed_nested() {
                               inner: (87, 95),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (126, 128),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -612,6 +672,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -643,6 +705,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -679,6 +743,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -706,8 +772,14 @@ fn spaced() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (20, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -762,6 +834,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -789,8 +863,15 @@ fn dirty() {
                               inner: (2, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (25, 31),
+                        kind: local,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -845,6 +926,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -872,8 +955,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (18, 23),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.java",
@@ -901,8 +990,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (35, 40),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -959,3 +1054,157 @@ fn multi() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            class Hello {
+                // @prompt
+                String greeting = "Hello!";
+
+                void world() {
+                    String greeting = "Hi!";
+                    greeting = "Bye!";
+
+                    {
+                        // @prompt
+                        String farewell = "Bye!";
+                    }
+                    String farewell = "See you!";
+                }
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (18, 60),
+                      span: SpanShape(
+                        outer: (51, 59),
+                        inner: (52, 58),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (52, 58),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (18, 28),
+                              inner: (20, 28),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (40, 48),
+                        kind: field,
+                      ),
+                    ),
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (164, 212),
+                      span: SpanShape(
+                        outer: (205, 211),
+                        inner: (206, 210),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (206, 210),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (164, 174),
+                              inner: (166, 174),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (194, 202),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n    String greeting = \"Hello!\";",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\n            String farewell = \"Bye!\";",
+                    "outer": "\"Bye!\"",
+                    "inner": "Bye!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!",
+                  "Bye!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
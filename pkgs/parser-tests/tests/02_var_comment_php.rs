@@ -17,6 +17,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -44,8 +46,14 @@ fn simple() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$system",
+                        span: (17, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -104,6 +112,8 @@ fn inline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -131,8 +141,14 @@ fn inline() {
                               inner: (8, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (20, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -190,6 +206,8 @@ fn doc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -217,8 +235,14 @@ fn doc() {
                               inner: (9, 22),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (25, 31),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -274,6 +298,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -303,6 +329,9 @@ fn assigned() {
                             outer: (39, 47),
                             inner: (40, 46),
                           ),
+                          expression: "$value",
+                          kind: identifier,
+                          root: Some("$value"),
                         ),
                       ],
                       annotations: [
@@ -313,8 +342,14 @@ fn assigned() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$assigned",
+                        span: (17, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -379,6 +414,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -406,8 +443,14 @@ fn assigned_late_comment() {
                               inner: (32, 40),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$assigned",
+                        span: (41, 50),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -467,6 +510,8 @@ fn reassigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -494,8 +539,20 @@ fn reassigned() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$reassigned",
+                        span: (17, 28),
+                        kind: local,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (40, 51),
+                          kind: reassign,
+                        ),
+                      ]),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -523,8 +580,14 @@ fn reassigned() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$reassigned",
+                        span: (40, 51),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -605,6 +668,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -632,8 +697,14 @@ fn spaced() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (19, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -690,6 +761,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -720,6 +793,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -747,8 +822,15 @@ fn dirty() {
                               inner: (8, 23),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$system",
+                        span: (24, 31),
+                        kind: local,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -804,6 +886,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -839,6 +923,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -866,8 +952,14 @@ fn mixed_assign() {
                               inner: (38, 52),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (53, 59),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -927,6 +1019,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -968,6 +1062,8 @@ fn mixed_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -995,8 +1091,14 @@ fn mixed_nested() {
                               inner: (94, 102),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hi",
+                        span: (130, 133),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1054,6 +1156,8 @@ fn destructuring() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -1081,8 +1185,14 @@ fn destructuring() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello1",
+                        span: (22, 29),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -1110,8 +1220,14 @@ fn destructuring() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$world1",
+                        span: (31, 38),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -1139,8 +1255,14 @@ fn destructuring() {
                               inner: (64, 72),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello2",
+                        span: (74, 81),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -1168,8 +1290,14 @@ fn destructuring() {
                               inner: (64, 72),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$world2",
+                        span: (83, 90),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1273,6 +1401,8 @@ fn chained() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -1300,8 +1430,14 @@ fn chained() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (17, 23),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -1329,8 +1465,14 @@ fn chained() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$world",
+                        span: (26, 32),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1407,6 +1549,8 @@ fn mixed_reassign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -1434,8 +1578,14 @@ fn mixed_reassign() {
                               inner: (8, 20),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (63, 69),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1494,6 +1644,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -1521,8 +1673,14 @@ fn multi() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (18, 24),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -1550,8 +1708,14 @@ fn multi() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$world",
+                        span: (26, 32),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1611,3 +1775,100 @@ fn multi() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt
+            $greeting = "Hello!";
+
+            function world() {
+                $greeting = "Hi!";
+                $greeting = "Bye!";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 37),
+                      span: SpanShape(
+                        outer: (29, 37),
+                        inner: (30, 36),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (30, 36),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (6, 16),
+                              inner: (8, 16),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "$greeting",
+                        span: (17, 26),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n$greeting = \"Hello!\"",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
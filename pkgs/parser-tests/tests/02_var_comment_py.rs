@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -43,8 +45,14 @@ fn simple() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (10, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -103,6 +111,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -132,6 +142,9 @@ fn assigned() {
                             outer: (47, 54),
                             inner: (48, 53),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -142,8 +155,14 @@ fn assigned() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "assigned",
+                        span: (25, 33),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -207,6 +226,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -236,6 +257,9 @@ fn assigned_late_comment() {
                             outer: (47, 54),
                             inner: (48, 53),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -246,8 +270,14 @@ fn assigned_late_comment() {
                               inner: (16, 24),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "assigned",
+                        span: (25, 33),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -312,6 +342,8 @@ fn reassigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -341,6 +373,9 @@ fn reassigned() {
                             outer: (88, 95),
                             inner: (89, 94),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -351,8 +386,14 @@ fn reassigned() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (62, 72),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -415,6 +456,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [],
                 )
                 "#);
@@ -448,6 +491,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [],
                 )
                 "#);
@@ -487,6 +532,8 @@ fn mixed_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -507,6 +554,11 @@ fn mixed_nested() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (113, 115),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -561,6 +613,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [],
                 )
                 "#);
@@ -595,6 +649,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -622,8 +678,14 @@ fn mixed_assign() {
                               inner: (26, 40),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (41, 46),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -778,6 +840,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -805,8 +869,14 @@ fn spaced() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (12, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -864,6 +934,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -891,8 +963,15 @@ fn dirty() {
                               inner: (1, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (17, 23),
+                        kind: local,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -950,6 +1029,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -977,8 +1058,14 @@ fn multi() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (10, 15),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1006,8 +1093,14 @@ fn multi() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (17, 22),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1090,6 +1183,8 @@ fn destructuring() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -1117,8 +1212,14 @@ fn destructuring() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello1",
+                        span: (10, 16),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1146,8 +1247,14 @@ fn destructuring() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world1",
+                        span: (18, 24),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1175,8 +1282,14 @@ fn destructuring() {
                               inner: (47, 55),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello2",
+                        span: (57, 63),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1204,8 +1317,14 @@ fn destructuring() {
                               inner: (47, 55),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world2",
+                        span: (65, 71),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1233,8 +1352,14 @@ fn destructuring() {
                               inner: (95, 103),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello3",
+                        span: (105, 111),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1262,8 +1387,14 @@ fn destructuring() {
                               inner: (95, 103),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world3",
+                        span: (113, 119),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1291,8 +1422,14 @@ fn destructuring() {
                               inner: (143, 151),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello4",
+                        span: (152, 158),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1320,8 +1457,14 @@ fn destructuring() {
                               inner: (143, 151),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world4",
+                        span: (160, 166),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1349,8 +1492,14 @@ fn destructuring() {
                               inner: (189, 197),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello5",
+                        span: (199, 205),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1378,8 +1527,14 @@ fn destructuring() {
                               inner: (189, 197),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world5",
+                        span: (207, 213),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1407,8 +1562,14 @@ fn destructuring() {
                               inner: (237, 245),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello6",
+                        span: (247, 253),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1436,8 +1597,14 @@ fn destructuring() {
                               inner: (237, 245),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world6",
+                        span: (255, 261),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1660,6 +1827,8 @@ fn chained() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -1687,8 +1856,14 @@ fn chained() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (10, 15),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -1716,8 +1891,14 @@ fn chained() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (18, 23),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1777,3 +1958,98 @@ fn chained() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            # @prompt
+            greeting = "Hello!"
+
+            def world():
+                greeting = "Hi!"
+                greeting = "Bye!"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 29),
+                      span: SpanShape(
+                        outer: (21, 29),
+                        inner: (22, 28),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 28),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (10, 18),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "# @prompt\ngreeting = \"Hello!\"",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -43,8 +45,14 @@ fn simple() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (10, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -100,6 +108,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -129,6 +139,9 @@ fn assigned() {
                             outer: (41, 49),
                             inner: (43, 48),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -139,8 +152,14 @@ fn assigned() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "assigned",
+                        span: (20, 28),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -204,6 +223,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -231,8 +252,14 @@ fn assigned_late_comment() {
                               inner: (11, 19),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "assigned",
+                        span: (20, 28),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -292,6 +319,8 @@ fn reassigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -319,8 +348,14 @@ fn reassigned() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (44, 54),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -378,6 +413,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -408,6 +445,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -451,6 +490,8 @@ fn mixed_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -478,8 +519,14 @@ fn mixed_nested() {
                               inner: (59, 67),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (85, 87),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -538,6 +585,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -569,6 +618,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -596,8 +647,14 @@ fn mixed_assign() {
                               inner: (27, 41),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (42, 47),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -655,6 +712,8 @@ fn mixed_reassign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -682,8 +741,14 @@ fn mixed_reassign() {
                               inner: (1, 13),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (51, 56),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -748,6 +813,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -775,8 +842,14 @@ fn spaced() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (12, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -834,6 +907,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -861,8 +936,15 @@ fn dirty() {
                               inner: (1, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (17, 23),
+                        kind: local,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -917,6 +999,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -944,8 +1028,14 @@ fn multi() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (10, 15),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -973,8 +1063,14 @@ fn multi() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (17, 22),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1049,6 +1145,8 @@ fn destructuring() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -1076,8 +1174,14 @@ fn destructuring() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello1",
+                        span: (10, 16),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -1105,8 +1209,14 @@ fn destructuring() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world1",
+                        span: (18, 24),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -1134,8 +1244,14 @@ fn destructuring() {
                               inner: (47, 55),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello2",
+                        span: (56, 62),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -1163,8 +1279,14 @@ fn destructuring() {
                               inner: (47, 55),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world2",
+                        span: (64, 70),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1267,6 +1389,8 @@ fn chained() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -1294,8 +1418,14 @@ fn chained() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (10, 15),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -1323,8 +1453,14 @@ fn chained() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (18, 23),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1384,3 +1520,99 @@ fn chained() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::rb(indoc! {r#"
+            # @prompt
+            greeting = "Hello!"
+
+            def world
+              greeting = "Hi!"
+              greeting = "Bye!"
+            end
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (0, 29),
+                      span: SpanShape(
+                        outer: (21, 29),
+                        inner: (22, 28),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 28),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (10, 18),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "# @prompt\ngreeting = \"Hello!\"",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -43,8 +45,14 @@ fn simple() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (17, 23),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -102,6 +110,8 @@ fn inline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -129,8 +139,14 @@ fn inline() {
                               inner: (2, 11),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (20, 25),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -190,6 +206,8 @@ fn doc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -217,8 +235,14 @@ fn doc() {
                               inner: (3, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (25, 30),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -277,6 +301,8 @@ fn assigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -306,6 +332,9 @@ fn assigned() {
                             outer: (46, 54),
                             inner: (48, 53),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -316,8 +345,14 @@ fn assigned() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "assigned",
+                        span: (25, 33),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -381,6 +416,8 @@ fn assigned_late_comment() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -410,6 +447,9 @@ fn assigned_late_comment() {
                             outer: (46, 54),
                             inner: (48, 53),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -420,8 +460,14 @@ fn assigned_late_comment() {
                               inner: (16, 24),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "assigned",
+                        span: (25, 33),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -486,6 +532,8 @@ fn reassigned() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -515,6 +563,9 @@ fn reassigned() {
                             outer: (76, 84),
                             inner: (78, 83),
                           ),
+                          expression: "value",
+                          kind: identifier,
+                          root: Some("value"),
                         ),
                       ],
                       annotations: [
@@ -525,8 +576,14 @@ fn reassigned() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "reassigned",
+                        span: (51, 61),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -589,6 +646,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [],
                 )
                 "#);
@@ -622,6 +681,8 @@ fn mixed() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [],
                 )
                 "#);
@@ -665,6 +726,8 @@ fn mixed_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -692,8 +755,14 @@ fn mixed_nested() {
                               inner: (86, 94),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hi",
+                        span: (125, 127),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -755,6 +824,8 @@ fn mixed_none() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [],
                 )
                 "#);
@@ -789,6 +860,8 @@ fn mixed_assign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -816,8 +889,14 @@ fn mixed_assign() {
                               inner: (28, 42),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (43, 48),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -879,6 +958,8 @@ fn mixed_reassign() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -906,8 +987,14 @@ fn mixed_reassign() {
                               inner: (2, 14),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (59, 64),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -968,6 +1055,8 @@ fn mixed_reassign_inline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -995,8 +1084,14 @@ fn mixed_reassign_inline() {
                               inner: (51, 66),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (41, 46),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1061,6 +1156,8 @@ fn spaced() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -1088,8 +1185,14 @@ fn spaced() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (19, 24),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -1147,6 +1250,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -1174,8 +1279,15 @@ fn dirty() {
                               inner: (2, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (24, 30),
+                        kind: const,
+                      ),
+                      role: Some(system),
                     ),
                   ],
                 )
@@ -1233,6 +1345,8 @@ fn multi() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -1260,8 +1374,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (17, 22),
+                        kind: const,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1289,8 +1409,14 @@ fn multi() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (34, 39),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -1370,6 +1496,8 @@ fn destructuring() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -1397,8 +1525,14 @@ fn destructuring() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello1",
+                        span: (18, 24),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1426,8 +1560,14 @@ fn destructuring() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world1",
+                        span: (26, 32),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1455,8 +1595,14 @@ fn destructuring() {
                               inner: (58, 66),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello2",
+                        span: (75, 81),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1484,8 +1630,14 @@ fn destructuring() {
                               inner: (58, 66),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world2",
+                        span: (83, 89),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1513,8 +1665,14 @@ fn destructuring() {
                               inner: (134, 142),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello3",
+                        span: (152, 158),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1542,8 +1700,14 @@ fn destructuring() {
                               inner: (134, 142),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world3",
+                        span: (160, 166),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1571,8 +1735,14 @@ fn destructuring() {
                               inner: (214, 222),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello4",
+                        span: (237, 243),
+                        kind: destructured,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1600,8 +1770,14 @@ fn destructuring() {
                               inner: (214, 222),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world4",
+                        span: (248, 254),
+                        kind: destructured,
+                      ),
                     ),
                   ],
                 )
@@ -1765,6 +1941,8 @@ fn chained() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -1792,8 +1970,14 @@ fn chained() {
                               inner: (14, 22),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (30, 35),
+                        kind: const,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -1821,8 +2005,14 @@ fn chained() {
                               inner: (14, 22),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "world",
+                        span: (38, 43),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1882,3 +2072,418 @@ fn chained() {
         },
     );
 }
+
+#[test]
+fn shadowed() {
+    ParseTest::test(
+        &ParseTestLang::ts(indoc! {r#"
+            // @prompt
+            let greeting = "Hello!";
+
+            function world() {
+              let greeting = "Hi!";
+              greeting = "Bye!";
+
+              {
+                // @prompt
+                const farewell = "Bye!";
+              }
+              let farewell = "See you!";
+
+              if (ready) {
+                // @prompt
+                var welcome = "Welcome!";
+              }
+              welcome = "Welcome back!";
+
+              for (let greeting of greetings) {
+                greeting = "Hey!";
+              }
+            }
+
+            greeting = "Hello again!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: js,
+                  parser: "oxc",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (0, 35),
+                      span: SpanShape(
+                        outer: (26, 34),
+                        inner: (27, 33),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (27, 33),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                    ),
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (110, 149),
+                      span: SpanShape(
+                        outer: (142, 148),
+                        inner: (143, 147),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (143, 147),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (110, 120),
+                              inner: (112, 120),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (131, 139),
+                        kind: const,
+                      ),
+                    ),
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (203, 243),
+                      span: SpanShape(
+                        outer: (232, 242),
+                        inner: (233, 241),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (233, 241),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (203, 213),
+                              inner: (205, 213),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "welcome",
+                        span: (222, 229),
+                        kind: local,
+                      ),
+                    ),
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (250, 276),
+                      span: SpanShape(
+                        outer: (260, 275),
+                        inner: (261, 274),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (261, 274),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (203, 213),
+                              inner: (205, 213),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "welcome",
+                        span: (250, 257),
+                        kind: local,
+                      ),
+                    ),
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (344, 370),
+                      span: SpanShape(
+                        outer: (355, 369),
+                        inner: (356, 368),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (356, 368),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (344, 352),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nlet greeting = \"Hello!\";",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\n    const farewell = \"Bye!\";",
+                    "outer": "\"Bye!\"",
+                    "inner": "Bye!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\n    var welcome = \"Welcome!\";",
+                    "outer": "\"Welcome!\"",
+                    "inner": "Welcome!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "welcome = \"Welcome back!\";",
+                    "outer": "\"Welcome back!\"",
+                    "inner": "Welcome back!",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "greeting = \"Hello again!\";",
+                    "outer": "\"Hello again!\"",
+                    "inner": "Hello again!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello!",
+                  "Bye!",
+                  "Welcome!",
+                  "Welcome back!",
+                  "Hello again!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
+
+#[test]
+fn shadowed_param() {
+    ParseTest::test(
+        &ParseTestLang::ts(indoc! {r#"
+            // @prompt
+            const greeting = "Hello!";
+
+            function world(greeting) {
+              greeting = "Hi!";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: js,
+                  parser: "oxc",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (0, 37),
+                      span: SpanShape(
+                        outer: (28, 36),
+                        inner: (29, 35),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 35),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (17, 25),
+                        kind: const,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nconst greeting = \"Hello!\";",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -74,6 +74,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -42,8 +44,14 @@ fn simple() {
                               inner: (14, 23),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (0, 8),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -98,6 +106,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -126,6 +136,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -153,8 +165,14 @@ fn dirty() {
                               inner: (14, 32),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (0, 8),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -42,8 +44,14 @@ fn simple() {
                               inner: (20, 29),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (7, 15),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -97,6 +105,8 @@ fn javadoc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -124,8 +134,14 @@ fn javadoc() {
                               inner: (18, 27),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (7, 12),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -180,6 +196,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -208,6 +226,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -235,8 +255,14 @@ fn dirty() {
                               inner: (20, 38),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (7, 15),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
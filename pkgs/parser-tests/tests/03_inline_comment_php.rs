@@ -16,6 +16,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -49,6 +51,9 @@ fn simple() {
                             outer: (42, 49),
                             inner: (43, 48),
                           ),
+                          expression: "$user",
+                          kind: identifier,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [
@@ -59,8 +64,14 @@ fn simple() {
                               inner: (20, 29),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$greeting",
+                        span: (6, 15),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -123,6 +134,8 @@ fn phpdoc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -150,8 +163,14 @@ fn phpdoc() {
                               inner: (18, 27),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (6, 12),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -210,6 +229,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -242,6 +263,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -275,6 +298,9 @@ fn dirty() {
                             outer: (51, 58),
                             inner: (52, 57),
                           ),
+                          expression: "$user",
+                          kind: identifier,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [
@@ -285,8 +311,14 @@ fn dirty() {
                               inner: (20, 38),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$greeting",
+                        span: (6, 15),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
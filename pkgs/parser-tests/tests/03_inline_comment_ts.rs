@@ -15,6 +15,8 @@ fn simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -48,6 +50,9 @@ fn simple() {
                             outer: (41, 48),
                             inner: (43, 47),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -58,8 +63,14 @@ fn simple() {
                               inner: (19, 28),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (6, 14),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -121,6 +132,8 @@ fn doc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -148,8 +161,14 @@ fn doc() {
                               inner: (17, 26),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (6, 11),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -207,6 +226,8 @@ fn inexact() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [],
                 )
                 "#);
@@ -238,6 +259,8 @@ fn dirty() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -271,6 +294,9 @@ fn dirty() {
                             outer: (50, 57),
                             inner: (52, 56),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -281,8 +307,14 @@ fn dirty() {
                               inner: (19, 37),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (6, 14),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -15,6 +15,8 @@ fn single_var() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -48,9 +50,17 @@ fn single_var() {
                             outer: (31, 37),
                             inner: (32, 36),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -102,6 +112,8 @@ fn multiple_vars() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -144,15 +156,26 @@ fn multiple_vars() {
                             outer: (29, 35),
                             inner: (30, 34),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (65, 71),
                             inner: (66, 70),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -208,6 +231,8 @@ fn exp() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -250,15 +275,26 @@ fn exp() {
                             outer: (29, 40),
                             inner: (30, 39),
                           ),
+                          expression: "user.Name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (70, 90),
                             inner: (71, 89),
                           ),
+                          expression: "user.Location.City",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -314,6 +350,8 @@ fn exp_complex() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -347,9 +385,16 @@ fn exp_complex() {
                             outer: (35, 74),
                             inner: (36, 73),
                           ),
+                          expression: "(price > 100 ? \"expensive\" : \"cheap\")",
+                          kind: other,
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -389,3 +434,119 @@ fn exp_complex() {
         },
     );
 }
+
+#[test]
+fn format_spec() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            string userPrompt = $"Total: {order.Total(),10:N2} for {items[0]}";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 67),
+                      span: SpanShape(
+                        outer: (20, 66),
+                        inner: (22, 65),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 29),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (29, 50),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (50, 55),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (55, 65),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (29, 50),
+                            inner: (30, 49),
+                          ),
+                          expression: "order.Total()",
+                          kind: call,
+                          root: Some("order"),
+                          format: Some(",10:N2"),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (55, 65),
+                            inner: (56, 64),
+                          ),
+                          expression: "items[0]",
+                          kind: index,
+                          root: Some("items"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "string userPrompt = $\"Total: {order.Total(),10:N2} for {items[0]}\";",
+                    "outer": "$\"Total: {order.Total(),10:N2} for {items[0]}\"",
+                    "inner": "Total: {order.Total(),10:N2} for {items[0]}",
+                    "vars": [
+                      {
+                        "outer": "{order.Total(),10:N2}",
+                        "inner": "order.Total(),10:N2"
+                      },
+                      {
+                        "outer": "{items[0]}",
+                        "inner": "items[0]"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Total: {0} for {1}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -16,6 +16,8 @@ fn single_var() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -49,9 +51,17 @@ fn single_var() {
                             outer: (31, 38),
                             inner: (32, 37),
                           ),
+                          expression: "$user",
+                          kind: identifier,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -107,6 +117,8 @@ fn multiple_vars() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -149,15 +161,26 @@ fn multiple_vars() {
                             outer: (29, 36),
                             inner: (30, 35),
                           ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (66, 73),
                             inner: (67, 72),
                           ),
+                          expression: "$city",
+                          kind: identifier,
+                          root: Some("$city"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -217,6 +240,8 @@ fn exp() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -259,15 +284,26 @@ fn exp() {
                             outer: (29, 42),
                             inner: (30, 41),
                           ),
+                          expression: "$user->name",
+                          kind: member,
+                          root: Some("$user"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (72, 95),
                             inner: (73, 94),
                           ),
+                          expression: "$user->location->city",
+                          kind: member,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -327,6 +363,8 @@ fn exp_complex() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -360,9 +398,16 @@ fn exp_complex() {
                             outer: (35, 73),
                             inner: (36, 72),
                           ),
+                          expression: "$price > 100 ? \'expensive\' : \'cheap\'",
+                          kind: other,
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -15,6 +15,8 @@ fn single_var() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -48,9 +50,17 @@ fn single_var() {
                             outer: (29, 35),
                             inner: (30, 34),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "greeting_prompt",
+                        span: (0, 15),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -105,6 +115,8 @@ fn multiple_vars() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -147,15 +159,26 @@ fn multiple_vars() {
                             outer: (23, 29),
                             inner: (24, 28),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (59, 65),
                             inner: (60, 64),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -214,6 +237,8 @@ fn exp() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -256,15 +281,26 @@ fn exp() {
                             outer: (23, 34),
                             inner: (24, 33),
                           ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (64, 84),
                             inner: (65, 83),
                           ),
+                          expression: "user.location.city",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -323,6 +359,8 @@ fn exp_complex() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -356,9 +394,16 @@ fn exp_complex() {
                             outer: (29, 72),
                             inner: (30, 71),
                           ),
+                          expression: "(\'expensive\' if price > 100 else \'cheap\')",
+                          kind: other,
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -401,3 +446,125 @@ fn exp_complex() {
         },
     );
 }
+
+#[test]
+fn format_spec() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            user_prompt = f"Hello {user.name!r:>10}, you have {items[0]:.2f}!"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 66),
+                      span: SpanShape(
+                        outer: (14, 66),
+                        inner: (16, 65),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (16, 22),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (22, 39),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (39, 50),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (50, 64),
+                          index: 1,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (64, 65),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (22, 39),
+                            inner: (23, 38),
+                          ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
+                          format: Some(">10"),
+                          conversion: Some("r"),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (50, 64),
+                            inner: (51, 63),
+                          ),
+                          expression: "items[0]",
+                          kind: index,
+                          root: Some("items"),
+                          format: Some(".2f"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "user_prompt = f\"Hello {user.name!r:>10}, you have {items[0]:.2f}!\"",
+                    "outer": "f\"Hello {user.name!r:>10}, you have {items[0]:.2f}!\"",
+                    "inner": "Hello {user.name!r:>10}, you have {items[0]:.2f}!",
+                    "vars": [
+                      {
+                        "outer": "{user.name!r:>10}",
+                        "inner": "user.name!r:>10"
+                      },
+                      {
+                        "outer": "{items[0]:.2f}",
+                        "inner": "items[0]:.2f"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello {0}, you have {1}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
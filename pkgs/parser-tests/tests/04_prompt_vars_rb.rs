@@ -15,6 +15,8 @@ fn single_var() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -48,9 +50,17 @@ fn single_var() {
                             outer: (24, 31),
                             inner: (26, 30),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -105,6 +115,8 @@ fn multiple_vars() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -147,15 +159,26 @@ fn multiple_vars() {
                             outer: (22, 29),
                             inner: (24, 28),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (59, 66),
                             inner: (61, 65),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -214,6 +237,8 @@ fn exp() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -256,15 +281,26 @@ fn exp() {
                             outer: (22, 34),
                             inner: (24, 33),
                           ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (64, 85),
                             inner: (66, 84),
                           ),
+                          expression: "user.location.city",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -323,6 +359,8 @@ fn exp_complex() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -356,9 +394,16 @@ fn exp_complex() {
                             outer: (28, 66),
                             inner: (30, 65),
                           ),
+                          expression: "price > 100 ? \'expensive\' : \'cheap\'",
+                          kind: other,
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
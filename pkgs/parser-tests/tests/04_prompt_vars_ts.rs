@@ -15,6 +15,8 @@ fn single_var() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -48,9 +50,17 @@ fn single_var() {
                             outer: (29, 36),
                             inner: (31, 35),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -105,6 +115,8 @@ fn multiple_vars() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -147,15 +159,26 @@ fn multiple_vars() {
                             outer: (27, 34),
                             inner: (29, 33),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (64, 71),
                             inner: (66, 70),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -214,6 +237,8 @@ fn exp() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -256,15 +281,26 @@ fn exp() {
                             outer: (27, 39),
                             inner: (29, 38),
                           ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (69, 90),
                             inner: (71, 89),
                           ),
+                          expression: "user.location.city",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -323,6 +359,8 @@ fn exp_complex() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -356,9 +394,16 @@ fn exp_complex() {
                             outer: (33, 71),
                             inner: (35, 70),
                           ),
+                          expression: "price > 100 ? \"expensive\" : \"cheap\"",
+                          kind: other,
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -21,6 +21,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -41,6 +43,11 @@ fn multiple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (0, 10),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.go",
@@ -68,8 +75,14 @@ fn multiple() {
                               inner: (43, 52),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (29, 37),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.go",
@@ -97,8 +110,14 @@ fn multiple() {
                               inner: (68, 76),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (77, 85),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.go",
@@ -126,8 +145,14 @@ fn multiple() {
                               inner: (103, 112),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (115, 121),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -21,6 +21,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -41,6 +43,11 @@ fn multiple() {
                       ),
                       vars: [],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.java",
@@ -68,8 +75,14 @@ fn multiple() {
                               inner: (56, 65),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (43, 51),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.java",
@@ -97,8 +110,14 @@ fn multiple() {
                               inner: (82, 90),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (98, 106),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "Prompts.java",
@@ -126,8 +145,14 @@ fn multiple() {
                               inner: (124, 133),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (143, 149),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
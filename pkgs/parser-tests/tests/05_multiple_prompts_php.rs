@@ -22,6 +22,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -55,9 +57,17 @@ fn multiple() {
                             outer: (29, 36),
                             inner: (30, 35),
                           ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -91,9 +101,17 @@ fn multiple() {
                             outer: (68, 75),
                             inner: (69, 74),
                           ),
+                          expression: "$user",
+                          kind: identifier,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "$greeting_prompt",
+                        span: (40, 56),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -127,6 +145,9 @@ fn multiple() {
                             outer: (111, 123),
                             inner: (112, 122),
                           ),
+                          expression: "$user_name",
+                          kind: identifier,
+                          root: Some("$user_name"),
                         ),
                       ],
                       annotations: [
@@ -137,8 +158,14 @@ fn multiple() {
                               inner: (81, 89),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$farewell",
+                        span: (90, 99),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.php",
@@ -166,8 +193,14 @@ fn multiple() {
                               inner: (129, 137),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$system",
+                        span: (138, 145),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
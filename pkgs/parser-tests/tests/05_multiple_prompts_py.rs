@@ -21,6 +21,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -54,9 +56,17 @@ fn multiple() {
                             outer: (23, 29),
                             inner: (24, 28),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -90,9 +100,17 @@ fn multiple() {
                             outer: (60, 66),
                             inner: (61, 65),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "greeting_prompt",
+                        span: (32, 47),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -126,6 +144,9 @@ fn multiple() {
                             outer: (100, 111),
                             inner: (101, 110),
                           ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -136,8 +157,14 @@ fn multiple() {
                               inner: (70, 78),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (79, 87),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.py",
@@ -165,8 +192,14 @@ fn multiple() {
                               inner: (115, 123),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (124, 130),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
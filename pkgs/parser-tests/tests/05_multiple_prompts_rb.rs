@@ -18,6 +18,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -51,9 +53,17 @@ fn multiple() {
                             outer: (22, 29),
                             inner: (24, 28),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.rb",
@@ -87,6 +97,9 @@ fn multiple() {
                             outer: (62, 74),
                             inner: (64, 73),
                           ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -97,8 +110,14 @@ fn multiple() {
                               inner: (33, 41),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (42, 50),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -21,6 +21,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -54,9 +56,17 @@ fn multiple() {
                             outer: (27, 34),
                             inner: (29, 33),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -90,6 +100,9 @@ fn multiple() {
                             outer: (78, 85),
                             inner: (80, 84),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -100,8 +113,14 @@ fn multiple() {
                               inner: (57, 66),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (44, 52),
+                        kind: const,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -135,6 +154,9 @@ fn multiple() {
                             outer: (126, 138),
                             inner: (128, 137),
                           ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -145,8 +167,14 @@ fn multiple() {
                               inner: (91, 99),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "farewell",
+                        span: (106, 114),
+                        kind: const,
+                      ),
                     ),
                     Prompt(
                       file: "prompts.js",
@@ -174,8 +202,14 @@ fn multiple() {
                               inner: (145, 154),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (163, 169),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
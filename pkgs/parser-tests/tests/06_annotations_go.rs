@@ -86,3 +86,105 @@ fn multiline_nested() {
         },
     );
 }
+
+#[test]
+fn attributes() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            // @prompt name=greeting model=gpt-4o
+            hello := "Hi!"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 52),
+                      span: SpanShape(
+                        outer: (47, 52),
+                        inner: (48, 51),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (48, 51),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 37),
+                              inner: (2, 37),
+                            ),
+                          ],
+                          attributes: [
+                            PromptAnnotationAttribute(
+                              key: "name",
+                              value: "greeting",
+                              span: (11, 24),
+                            ),
+                            PromptAnnotationAttribute(
+                              key: "model",
+                              value: "gpt-4o",
+                              span: (25, 37),
+                            ),
+                          ],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (38, 43),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt name=greeting model=gpt-4o\nhello := \"Hi!\"",
+                    "outer": "\"Hi!\"",
+                    "inner": "Hi!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hi!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt name=greeting model=gpt-4o",
+                        "inner": " @prompt name=greeting model=gpt-4o"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -180,6 +180,8 @@ fn multiline_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -215,8 +217,14 @@ fn multiline_nested() {
                               inner: (46, 52),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "msg",
+                        span: (64, 67),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -266,3 +274,105 @@ fn multiline_nested() {
         },
     );
 }
+
+#[test]
+fn attributes() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            // @prompt name=greeting model=gpt-4o
+            String hello = "Hi!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 59),
+                      span: SpanShape(
+                        outer: (53, 58),
+                        inner: (54, 57),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (54, 57),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 37),
+                              inner: (2, 37),
+                            ),
+                          ],
+                          attributes: [
+                            PromptAnnotationAttribute(
+                              key: "name",
+                              value: "greeting",
+                              span: (11, 24),
+                            ),
+                            PromptAnnotationAttribute(
+                              key: "model",
+                              value: "gpt-4o",
+                              span: (25, 37),
+                            ),
+                          ],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (45, 50),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt name=greeting model=gpt-4o\nString hello = \"Hi!\";",
+                    "outer": "\"Hi!\"",
+                    "inner": "Hi!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hi!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt name=greeting model=gpt-4o",
+                        "inner": " @prompt name=greeting model=gpt-4o"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
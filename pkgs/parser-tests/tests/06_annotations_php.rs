@@ -189,6 +189,8 @@ fn multiline_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -224,8 +226,14 @@ fn multiline_nested() {
                               inner: (57, 63),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$msg",
+                        span: (68, 72),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -278,3 +286,106 @@ fn multiline_nested() {
         },
     );
 }
+
+#[test]
+fn attributes() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt name=greeting model=gpt-4o
+            $hello = "Hi!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 58),
+                      span: SpanShape(
+                        outer: (53, 58),
+                        inner: (54, 57),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (54, 57),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (6, 43),
+                              inner: (8, 43),
+                            ),
+                          ],
+                          attributes: [
+                            PromptAnnotationAttribute(
+                              key: "name",
+                              value: "greeting",
+                              span: (17, 30),
+                            ),
+                            PromptAnnotationAttribute(
+                              key: "model",
+                              value: "gpt-4o",
+                              span: (31, 43),
+                            ),
+                          ],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "$hello",
+                        span: (44, 50),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt name=greeting model=gpt-4o\n$hello = \"Hi!\"",
+                    "outer": "\"Hi!\"",
+                    "inner": "Hi!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hi!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt name=greeting model=gpt-4o",
+                        "inner": " @prompt name=greeting model=gpt-4o"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -18,6 +18,8 @@ fn multiline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -53,8 +55,14 @@ fn multiline() {
                               inner: (19, 25),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "msg",
+                        span: (26, 29),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -123,6 +131,8 @@ fn multiline_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -158,8 +168,14 @@ fn multiline_nested() {
                               inner: (41, 47),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "msg",
+                        span: (52, 55),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -212,3 +228,105 @@ fn multiline_nested() {
         },
     );
 }
+
+#[test]
+fn attributes() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            # @prompt name=greeting model="gpt 4o"
+            hello = "Hi!"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 52),
+                      span: SpanShape(
+                        outer: (47, 52),
+                        inner: (48, 51),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (48, 51),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 38),
+                              inner: (1, 38),
+                            ),
+                          ],
+                          attributes: [
+                            PromptAnnotationAttribute(
+                              key: "name",
+                              value: "greeting",
+                              span: (10, 23),
+                            ),
+                            PromptAnnotationAttribute(
+                              key: "model",
+                              value: "gpt 4o",
+                              span: (24, 38),
+                            ),
+                          ],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (39, 44),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "# @prompt name=greeting model=\"gpt 4o\"\nhello = \"Hi!\"",
+                    "outer": "\"Hi!\"",
+                    "inner": "Hi!",
+                    "vars": []
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hi!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt name=greeting model=\"gpt 4o\"",
+                        "inner": " @prompt name=greeting model=\"gpt 4o\""
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
@@ -17,6 +17,8 @@ fn multiple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -44,6 +46,7 @@ fn multiple() {
                               inner: (2, 15),
                             ),
                           ],
+                          attributes: [],
                           exp: "// Hello, world",
                         ),
                         PromptAnnotation(
@@ -53,8 +56,14 @@ fn multiple() {
                               inner: (32, 41),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (22, 27),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -123,6 +132,8 @@ fn multiline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -150,6 +161,7 @@ fn multiline() {
                               inner: (2, 23),
                             ),
                           ],
+                          attributes: [],
                           exp: "/*\n Multi\n Line\n Block\n*/",
                         ),
                         PromptAnnotation(
@@ -159,8 +171,14 @@ fn multiline() {
                               inner: (42, 51),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (32, 37),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -228,6 +246,8 @@ fn multiline_nested() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -263,8 +283,14 @@ fn multiline_nested() {
                               inner: (50, 56),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "msg",
+                        span: (67, 70),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -317,3 +343,105 @@ fn multiline_nested() {
         },
     );
 }
+
+#[test]
+fn attributes() {
+    ParseTest::test(
+        &ParseTestLang::ts(indoc! {r#"
+            // @prompt name=greeting model=gpt-4o
+            const hello = "Hi!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: js,
+                  parser: "oxc",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (0, 58),
+                      span: SpanShape(
+                        outer: (52, 57),
+                        inner: (53, 56),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (53, 56),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 37),
+                              inner: (2, 37),
+                            ),
+                          ],
+                          attributes: [
+                            PromptAnnotationAttribute(
+                              key: "name",
+                              value: "greeting",
+                              span: (11, 24),
+                            ),
+                            PromptAnnotationAttribute(
+                              key: "model",
+                              value: "gpt-4o",
+                              span: (25, 37),
+                            ),
+                          ],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "hello",
+                        span: (44, 49),
+                        kind: const,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt name=greeting model=gpt-4o\nconst hello = \"Hi!\";",
+                    "outer": "\"Hi!\"",
+                    "inner": "Hi!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hi!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt name=greeting model=gpt-4o",
+                        "inner": " @prompt name=greeting model=gpt-4o"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -13,9 +13,20 @@ fn invalid() {
         ParseAssertions {
             result: Box::new(|result| {
                 assert_ron_snapshot!(result, @r#"
-                ParseResultError(
-                  state: "error",
-                  error: "<error>",
+                ParseResultPartial(
+                  state: "partial",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `string invalid = \"unclosed string`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (0, 33),
+                      parser: "tree-sitter",
+                    ),
+                  ],
                 )
                 "#);
             }),
@@ -32,6 +43,92 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            string userPrompt = "Hello, world!";
+            int x = (1 + ;
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 36),
+                      span: SpanShape(
+                        outer: (20, 35),
+                        inner: (21, 34),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (21, 34),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `= (1 +`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (43, 49),
+                      parser: "tree-sitter",
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "string userPrompt = \"Hello, world!\";",
+                    "outer": "\"Hello, world!\"",
+                    "inner": "Hello, world!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, world!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn verbatim_string() {
     ParseTest::test(
@@ -45,6 +142,8 @@ fn verbatim_string() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -72,8 +171,14 @@ fn verbatim_string() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (18, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -129,6 +234,8 @@ fn interpolated_verbatim() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -171,12 +278,18 @@ fn interpolated_verbatim() {
                             outer: (39, 45),
                             inner: (40, 44),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (58, 64),
                             inner: (59, 63),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [
@@ -187,8 +300,14 @@ fn interpolated_verbatim() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (18, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -252,6 +371,8 @@ fn multiline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -273,6 +394,12 @@ fn multiline() {
                         PromptContentTokenStr(
                           type: "str",
                           span: (40, 71),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (41, 43),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenVar(
                           type: "var",
@@ -282,6 +409,12 @@ fn multiline() {
                         PromptContentTokenStr(
                           type: "str",
                           span: (77, 80),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (78, 80),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                       ],
                       joint: SpanShape(
@@ -294,12 +427,18 @@ fn multiline() {
                             outer: (34, 40),
                             inner: (35, 39),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (71, 77),
                             inner: (72, 76),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [
@@ -310,8 +449,14 @@ fn multiline() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "user",
+                        span: (18, 22),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -13,9 +13,20 @@ fn invalid() {
         ParseAssertions {
             result: Box::new(|result| {
                 assert_ron_snapshot!(result, @r#"
-                ParseResultError(
-                  state: "error",
-                  error: "<error>",
+                ParseResultPartial(
+                  state: "partial",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `invalid := \"unclosed string`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (0, 27),
+                      parser: "tree-sitter",
+                    ),
+                  ],
                 )
                 "#);
             }),
@@ -32,6 +43,92 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            userPrompt := "Hello, world!"
+            x := (1 +
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 29),
+                      span: SpanShape(
+                        outer: (14, 29),
+                        inner: (15, 28),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (15, 28),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (0, 10),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `x := (1 +`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (30, 39),
+                      parser: "tree-sitter",
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "userPrompt := \"Hello, world!\"",
+                    "outer": "\"Hello, world!\"",
+                    "inner": "Hello, world!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, world!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn raw_string() {
     ParseTest::test(
@@ -45,6 +142,8 @@ fn raw_string() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -72,8 +171,14 @@ fn raw_string() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (11, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -13,9 +13,20 @@ fn invalid() {
         ParseAssertions {
             result: Box::new(|result| {
                 assert_ron_snapshot!(result, @r#"
-                ParseResultError(
-                  state: "error",
-                  error: "<error>",
+                ParseResultPartial(
+                  state: "partial",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `String invalid = \"unclosed string`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (0, 34),
+                      parser: "tree-sitter",
+                    ),
+                  ],
                 )
                 "#);
             }),
@@ -32,6 +43,92 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            String userPrompt = "Hello, world!";
+            int x = (1 + ;
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 36),
+                      span: SpanShape(
+                        outer: (20, 35),
+                        inner: (21, 34),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (21, 34),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `= (1 +`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (43, 49),
+                      parser: "tree-sitter",
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "String userPrompt = \"Hello, world!\";",
+                    "outer": "\"Hello, world!\"",
+                    "inner": "Hello, world!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, world!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn text_block() {
     ParseTest::test(
@@ -47,6 +144,8 @@ fn text_block() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -78,8 +177,14 @@ fn text_block() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (18, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
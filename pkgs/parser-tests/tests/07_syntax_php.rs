@@ -14,9 +14,20 @@ fn invalid() {
         ParseAssertions {
             result: Box::new(|result| {
                 assert_ron_snapshot!(result, @r#"
-                ParseResultError(
-                  state: "error",
-                  error: "<error>",
+                ParseResultPartial(
+                  state: "partial",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `$x = \"unclosed`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (6, 20),
+                      parser: "tree-sitter",
+                    ),
+                  ],
                 )
                 "#);
             }),
@@ -36,6 +47,117 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $user_prompt = "Hello, {$name}!";
+            $x = (1 + ;
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 38),
+                      span: SpanShape(
+                        outer: (21, 38),
+                        inner: (22, 37),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 29),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (29, 36),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (36, 37),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (29, 36),
+                            inner: (30, 35),
+                          ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `= (1 +`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (43, 49),
+                      parser: "tree-sitter",
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "$user_prompt = \"Hello, {$name}!\"",
+                    "outer": "\"Hello, {$name}!\"",
+                    "inner": "Hello, {$name}!",
+                    "vars": [
+                      {
+                        "outer": "{$name}",
+                        "inner": "$name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn heredoc() {
     ParseTest::test(
@@ -53,6 +175,8 @@ fn heredoc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -80,8 +204,14 @@ fn heredoc() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$system",
+                        span: (17, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -143,6 +273,8 @@ fn heredoc_interpolated() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -185,12 +317,18 @@ fn heredoc_interpolated() {
                             outer: (40, 47),
                             inner: (41, 46),
                           ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (77, 84),
                             inner: (78, 83),
                           ),
+                          expression: "$city",
+                          kind: identifier,
+                          root: Some("$city"),
                         ),
                       ],
                       annotations: [
@@ -201,8 +339,14 @@ fn heredoc_interpolated() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$user",
+                        span: (17, 22),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -13,9 +13,12 @@ fn invalid() {
         ParseAssertions {
             result: Box::new(|result| {
                 assert_ron_snapshot!(result, @r#"
-                ParseResultError(
-                  state: "error",
-                  error: "<error>",
+                ParseResultPartial(
+                  state: "partial",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [],
+                  diagnostics: "<diagnostics>",
                 )
                 "#);
             }),
@@ -35,6 +38,108 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            user_prompt = f"Hello, {name}!"
+            x = (1 +
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 31),
+                      span: SpanShape(
+                        outer: (14, 31),
+                        inner: (16, 30),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (16, 23),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (23, 29),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 30),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (23, 29),
+                            inner: (24, 28),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                  diagnostics: "<diagnostics>",
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "user_prompt = f\"Hello, {name}!\"",
+                    "outer": "f\"Hello, {name}!\"",
+                    "inner": "Hello, {name}!",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn multiline_str() {
     ParseTest::test(
@@ -49,6 +154,8 @@ fn multiline_str() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -76,8 +183,14 @@ fn multiline_str() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (10, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -137,6 +250,8 @@ fn multiline_fstr() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -179,12 +294,18 @@ fn multiline_fstr() {
                             outer: (28, 34),
                             inner: (29, 33),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (64, 70),
                             inner: (65, 69),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [
@@ -195,8 +316,14 @@ fn multiline_fstr() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "user",
+                        span: (10, 14),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
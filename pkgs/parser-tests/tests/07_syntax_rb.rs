@@ -13,9 +13,20 @@ fn invalid() {
         ParseAssertions {
             result: Box::new(|result| {
                 assert_ron_snapshot!(result, @r#"
-                ParseResultError(
-                  state: "error",
-                  error: "<error>",
+                ParseResultPartial(
+                  state: "partial",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `\"`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (4, 5),
+                      parser: "tree-sitter",
+                    ),
+                  ],
                 )
                 "#);
             }),
@@ -35,6 +46,116 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::rb(indoc! {r#"
+            user_prompt = "Hello, #{name}!"
+            x = (1 +
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (0, 31),
+                      span: SpanShape(
+                        outer: (14, 31),
+                        inner: (15, 30),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (15, 22),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (22, 29),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 30),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (22, 29),
+                            inner: (24, 28),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unexpected `= (1 +`",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (34, 40),
+                      parser: "tree-sitter",
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r##"
+                [
+                  {
+                    "enclosure": "user_prompt = \"Hello, #{name}!\"",
+                    "outer": "\"Hello, #{name}!\"",
+                    "inner": "Hello, #{name}!",
+                    "vars": [
+                      {
+                        "outer": "#{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "##);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn heredoc() {
     ParseTest::test(
@@ -51,6 +172,8 @@ fn heredoc() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -86,8 +209,14 @@ fn heredoc() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (10, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -148,6 +277,8 @@ fn heredoc_interpolated() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -179,8 +310,14 @@ fn heredoc_interpolated() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "user",
+                        span: (10, 14),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -238,6 +375,8 @@ fn single_quote() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -265,8 +404,14 @@ fn single_quote() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "single_quote",
+                        span: (10, 22),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -321,6 +466,8 @@ fn percent_q_paren() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -348,8 +495,14 @@ fn percent_q_paren() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "percent_q_paren",
+                        span: (10, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -404,6 +557,8 @@ fn percent_q_brace() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -431,8 +586,14 @@ fn percent_q_brace() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "percent_q_brace",
+                        span: (10, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -488,6 +649,8 @@ fn percent_q_upper() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -517,6 +680,9 @@ fn percent_q_upper() {
                             outer: (37, 44),
                             inner: (39, 43),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -527,8 +693,14 @@ fn percent_q_upper() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "percent_q_upper",
+                        span: (10, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -590,6 +762,8 @@ fn percent_q_pipe() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -619,6 +793,9 @@ fn percent_q_pipe() {
                             outer: (36, 43),
                             inner: (38, 42),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -629,8 +806,14 @@ fn percent_q_pipe() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "percent_q_pipe",
+                        span: (10, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -690,6 +873,8 @@ fn percent_q_angle() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -719,6 +904,9 @@ fn percent_q_angle() {
                             outer: (38, 45),
                             inner: (40, 44),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -729,8 +917,14 @@ fn percent_q_angle() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "percent_q_angle",
+                        span: (10, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -792,6 +986,8 @@ fn heredoc_plain() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -819,8 +1015,14 @@ fn heredoc_plain() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "heredoc",
+                        span: (10, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -877,6 +1079,8 @@ fn heredoc_squiggly() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -904,8 +1108,14 @@ fn heredoc_squiggly() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "squiggly_heredoc",
+                        span: (10, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -962,6 +1172,8 @@ fn heredoc_single() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -989,8 +1201,14 @@ fn heredoc_single() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "single_heredoc",
+                        span: (10, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -1047,6 +1265,8 @@ fn heredoc_double() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -1074,8 +1294,14 @@ fn heredoc_double() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "double_heredoc",
+                        span: (10, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -16,6 +16,17 @@ fn invalid() {
                 ParseResultError(
                   state: "error",
                   error: "<error>",
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Unterminated string",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (16, 35),
+                      parser: "oxc",
+                    ),
+                  ],
+                  language: Some(js),
+                  parser: Some("oxc"),
                 )
                 "#);
             }),
@@ -35,6 +46,92 @@ fn invalid() {
     );
 }
 
+#[test]
+fn partial() {
+    ParseTest::test(
+        &ParseTestLang::ts(indoc! {r#"
+            const userPrompt = "Hello, world!";
+            const broken;
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultPartial(
+                  state: "partial",
+                  language: js,
+                  parser: "oxc",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (0, 35),
+                      span: SpanShape(
+                        outer: (19, 34),
+                        inner: (20, 33),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (20, 33),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
+                    ),
+                  ],
+                  diagnostics: [
+                    ParseDiagnostic(
+                      message: "Missing initializer in const declaration",
+                      severity: error,
+                      code: "syntax-error",
+                      span: (42, 48),
+                      parser: "oxc",
+                    ),
+                  ],
+                )
+                "#);
+            }),
+
+            cuts: Box::new(|prompt_source_cuts| {
+                assert_json_snapshot!(prompt_source_cuts, @r#"
+                [
+                  {
+                    "enclosure": "const userPrompt = \"Hello, world!\";",
+                    "outer": "\"Hello, world!\"",
+                    "inner": "Hello, world!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Hello, world!"
+                ]
+                "#);
+            }),
+
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
 #[test]
 fn jsx() {
     ParseTest::test(
@@ -50,6 +147,9 @@ fn jsx() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: ts,
+                  dialect: Some(tsx),
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.tsx",
@@ -83,6 +183,9 @@ fn jsx() {
                             outer: (37, 45),
                             inner: (39, 44),
                           ),
+                          expression: "world",
+                          kind: identifier,
+                          root: Some("world"),
                         ),
                       ],
                       annotations: [
@@ -93,8 +196,20 @@ fn jsx() {
                               inner: (17, 26),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (6, 12),
+                        kind: const,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (71, 77),
+                          kind: read,
+                        ),
+                      ]),
                     ),
                   ],
                 )
@@ -159,6 +274,8 @@ fn ts() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: ts,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.ts",
@@ -192,6 +309,9 @@ fn ts() {
                             outer: (44, 52),
                             inner: (46, 51),
                           ),
+                          expression: "world",
+                          kind: identifier,
+                          root: Some("world"),
                         ),
                       ],
                       annotations: [
@@ -202,8 +322,14 @@ fn ts() {
                               inner: (25, 34),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (6, 12),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -269,6 +395,9 @@ fn tsx() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: ts,
+                  dialect: Some(tsx),
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.tsx",
@@ -302,6 +431,9 @@ fn tsx() {
                             outer: (44, 52),
                             inner: (46, 51),
                           ),
+                          expression: "world",
+                          kind: identifier,
+                          root: Some("world"),
                         ),
                       ],
                       annotations: [
@@ -312,8 +444,20 @@ fn tsx() {
                               inner: (25, 34),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (6, 12),
+                        kind: const,
+                      ),
+                      references: Some([
+                        PromptReference(
+                          span: (78, 84),
+                          kind: read,
+                        ),
+                      ]),
                     ),
                   ],
                 )
@@ -378,6 +522,8 @@ fn multiline() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: js,
+                  parser: "oxc",
                   prompts: [
                     Prompt(
                       file: "prompts.js",
@@ -420,12 +566,18 @@ fn multiline() {
                             outer: (32, 39),
                             inner: (34, 38),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                         PromptVar(
                           span: SpanShape(
                             outer: (69, 76),
                             inner: (71, 75),
                           ),
+                          expression: "city",
+                          kind: identifier,
+                          root: Some("city"),
                         ),
                       ],
                       annotations: [
@@ -436,8 +588,14 @@ fn multiline() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "user",
+                        span: (17, 21),
+                        kind: const,
+                      ),
                     ),
                   ],
                 )
@@ -16,6 +16,8 @@ fn concat() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -49,6 +51,9 @@ fn concat() {
                             outer: (38, 48),
                             inner: (41, 45),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -59,8 +64,14 @@ fn concat() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (18, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -120,6 +131,8 @@ fn concat_with_primitives() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -159,8 +172,14 @@ fn concat_with_primitives() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (18, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -215,6 +234,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -241,6 +262,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -270,6 +293,8 @@ fn concat_with_function_calls() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -303,6 +328,9 @@ fn concat_with_function_calls() {
                             outer: (36, 54),
                             inner: (39, 51),
                           ),
+                          expression: "Format(name)",
+                          kind: call,
+                          root: Some("Format"),
                         ),
                       ],
                       annotations: [
@@ -313,8 +341,14 @@ fn concat_with_function_calls() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (18, 25),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
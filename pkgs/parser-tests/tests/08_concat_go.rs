@@ -16,6 +16,8 @@ fn concat() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -49,6 +51,9 @@ fn concat() {
                             outer: (32, 42),
                             inner: (35, 39),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -59,8 +64,14 @@ fn concat() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (11, 19),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -108,6 +119,109 @@ fn concat() {
     );
 }
 
+#[test]
+fn concat_with_primitives() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            // @prompt
+            message := "Count: " + 42 + ", Active: " + true
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 58),
+                      span: SpanShape(
+                        outer: (22, 58),
+                        inner: (23, 58),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (23, 30),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (34, 36),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (40, 50),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (54, 58),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (11, 18),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nmessage := \"Count: \" + 42 + \", Active: \" + true",
+                    "outer": "\"Count: \" + 42 + \", Active: \" + true",
+                    "inner": "Count: \" + 42 + \", Active: \" + true",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Count: 42, Active: true"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
+
 #[test]
 fn concat_with_objects() {
     ParseTest::test(
@@ -120,6 +234,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -146,6 +262,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -175,6 +293,8 @@ fn concat_with_function_calls() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -208,6 +328,9 @@ fn concat_with_function_calls() {
                             outer: (30, 48),
                             inner: (33, 45),
                           ),
+                          expression: "format(name)",
+                          kind: call,
+                          root: Some("format"),
                         ),
                       ],
                       annotations: [
@@ -218,8 +341,14 @@ fn concat_with_function_calls() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (11, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -17,6 +17,8 @@ fn concat() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -50,6 +52,9 @@ fn concat() {
                             outer: (41, 52),
                             inner: (44, 49),
                           ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
                         ),
                       ],
                       annotations: [
@@ -60,8 +65,14 @@ fn concat() {
                               inner: (8, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$greeting",
+                        span: (20, 29),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -116,6 +127,7 @@ fn concat() {
 fn concat_with_primitives() {
     ParseTest::test(
         &ParseTestLang::php(indoc! {r#"
+            <?php
             // @prompt
             $message = "Count: " . 42 . ", Active: " . true;
         "#}),
@@ -124,18 +136,92 @@ fn concat_with_primitives() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
-                  prompts: [],
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 64),
+                      span: SpanShape(
+                        outer: (28, 64),
+                        inner: (29, 64),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 36),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (40, 42),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (46, 56),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (60, 64),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (6, 16),
+                              inner: (8, 16),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "$message",
+                        span: (17, 25),
+                        kind: local,
+                      ),
+                    ),
+                  ],
                 )
                 "#);
             }),
             cuts: Box::new(|cuts| {
-                assert_json_snapshot!(cuts, @"[]");
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n$message = \"Count: \" . 42 . \", Active: \" . true",
+                    "outer": "\"Count: \" . 42 . \", Active: \" . true",
+                    "inner": "Count: \" . 42 . \", Active: \" . true",
+                    "vars": []
+                  }
+                ]
+                "#);
             }),
             interpolate: Box::new(|interp| {
-                assert_json_snapshot!(interp, @"[]");
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Count: 42, Active: true"
+                ]
+                "#);
             }),
             annotations: Box::new(|annot| {
-                assert_json_snapshot!(annot, @"[]");
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
             }),
         },
     );
@@ -154,6 +240,8 @@ fn concat_with_function_calls() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -187,6 +275,9 @@ fn concat_with_function_calls() {
                             outer: (40, 59),
                             inner: (43, 56),
                           ),
+                          expression: "format($name)",
+                          kind: call,
+                          root: Some("format"),
                         ),
                       ],
                       annotations: [
@@ -197,8 +288,14 @@ fn concat_with_function_calls() {
                               inner: (8, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$greeting",
+                        span: (20, 29),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -259,6 +356,8 @@ fn concat_with_function_calls() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -292,6 +391,9 @@ fn concat_with_function_calls() {
                             outer: (39, 61),
                             inner: (42, 58),
                           ),
+                          expression: "$user->getName()",
+                          kind: call,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [
@@ -302,8 +404,14 @@ fn concat_with_function_calls() {
                               inner: (8, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$message",
+                        span: (20, 28),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -358,6 +466,7 @@ fn concat_with_function_calls() {
 fn concat_with_objects() {
     ParseTest::test(
         &ParseTestLang::php(indoc! {r#"
+            <?php
             // @prompt
             $message = "Items: " . [1, 2, 3] . "!";
         "#}),
@@ -366,6 +475,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
@@ -384,6 +495,7 @@ fn concat_with_objects() {
 
     ParseTest::test(
         &ParseTestLang::php(indoc! {r#"
+            <?php
             // @prompt
             $message = "Result: " . new stdClass() . "!";
         "#}),
@@ -392,6 +504,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [],
                 )
                 "#);
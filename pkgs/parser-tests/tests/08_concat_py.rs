@@ -16,6 +16,8 @@ fn concat() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -49,6 +51,9 @@ fn concat() {
                             outer: (30, 40),
                             inner: (33, 37),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -59,8 +64,14 @@ fn concat() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (10, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -108,6 +119,109 @@ fn concat() {
     );
 }
 
+#[test]
+fn concat_with_primitives() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            # @prompt
+            message = "Count: " + 42 + ", Active: " + True
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 56),
+                      span: SpanShape(
+                        outer: (20, 56),
+                        inner: (21, 56),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (21, 28),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (32, 34),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (38, 48),
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (52, 56),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (10, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "# @prompt\nmessage = \"Count: \" + 42 + \", Active: \" + True",
+                    "outer": "\"Count: \" + 42 + \", Active: \" + True",
+                    "inner": "Count: \" + 42 + \", Active: \" + True",
+                    "vars": []
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interpolations| {
+                assert_json_snapshot!(interpolations, @r#"
+                [
+                  "Count: 42, Active: True"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annotations| {
+                assert_json_snapshot!(annotations, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
+
 #[test]
 fn concat_with_function_calls() {
     ParseTest::test(
@@ -120,6 +234,8 @@ fn concat_with_function_calls() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -153,6 +269,9 @@ fn concat_with_function_calls() {
                             outer: (29, 47),
                             inner: (32, 44),
                           ),
+                          expression: "format(name)",
+                          kind: call,
+                          root: Some("format"),
                         ),
                       ],
                       annotations: [
@@ -163,8 +282,14 @@ fn concat_with_function_calls() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "greeting",
+                        span: (10, 18),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -221,6 +346,8 @@ fn concat_with_function_calls() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -254,6 +381,9 @@ fn concat_with_function_calls() {
                             outer: (28, 49),
                             inner: (31, 46),
                           ),
+                          expression: "user.get_name()",
+                          kind: call,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -264,8 +394,14 @@ fn concat_with_function_calls() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (10, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -325,6 +461,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [],
                 )
                 "#);
@@ -351,6 +489,8 @@ fn concat_with_objects() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [],
                 )
                 "#);
@@ -16,6 +16,8 @@ fn string_format() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.cs",
@@ -45,6 +47,9 @@ fn string_format() {
                             outer: (57, 61),
                             inner: (57, 61),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -55,8 +60,14 @@ fn string_format() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "formatted",
+                        span: (18, 27),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
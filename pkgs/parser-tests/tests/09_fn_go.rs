@@ -16,6 +16,8 @@ fn sprintf_fn() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -45,6 +47,10 @@ fn sprintf_fn() {
                             outer: (48, 52),
                             inner: (48, 52),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          format: Some("%s"),
                         ),
                       ],
                       annotations: [
@@ -55,8 +61,14 @@ fn sprintf_fn() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "formatted",
+                        span: (11, 20),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
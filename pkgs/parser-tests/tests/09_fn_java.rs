@@ -16,6 +16,8 @@ fn string_format() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -45,6 +47,10 @@ fn string_format() {
                             outer: (56, 60),
                             inner: (56, 60),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          format: Some("%s"),
                         ),
                       ],
                       annotations: [
@@ -55,8 +61,14 @@ fn string_format() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "formatted",
+                        span: (18, 27),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -17,6 +17,8 @@ fn sprintf_fn() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -46,6 +48,10 @@ fn sprintf_fn() {
                             outer: (50, 55),
                             inner: (50, 55),
                           ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
+                          format: Some("%s"),
                         ),
                       ],
                       annotations: [
@@ -56,8 +62,14 @@ fn sprintf_fn() {
                               inner: (8, 16),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$formatted",
+                        span: (17, 27),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -16,6 +16,8 @@ fn format_method() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -45,6 +47,9 @@ fn format_method() {
                             outer: (40, 44),
                             inner: (40, 44),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
                         ),
                       ],
                       annotations: [
@@ -55,8 +60,14 @@ fn format_method() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "formatted",
+                        span: (10, 19),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
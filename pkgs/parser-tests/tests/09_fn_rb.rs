@@ -16,6 +16,8 @@ fn format_method() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.rb",
@@ -45,6 +47,10 @@ fn format_method() {
                             outer: (35, 39),
                             inner: (35, 39),
                           ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          format: Some("%s"),
                         ),
                       ],
                       annotations: [
@@ -55,8 +61,14 @@ fn format_method() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "formatted",
+                        span: (10, 19),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
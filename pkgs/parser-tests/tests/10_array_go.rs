@@ -16,6 +16,8 @@ fn join_method() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -31,6 +33,12 @@ fn join_method() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (65, 67),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenVar(
                           type: "var",
@@ -39,6 +47,12 @@ fn join_method() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (65, 67),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenStr(
                           type: "str",
@@ -55,6 +69,9 @@ fn join_method() {
                             outer: (52, 56),
                             inner: (52, 56),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -65,8 +82,14 @@ fn join_method() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (11, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -126,6 +149,8 @@ fn array_simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: go,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.go",
@@ -159,6 +184,9 @@ fn array_simple() {
                             outer: (40, 44),
                             inner: (40, 44),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -169,8 +197,14 @@ fn array_simple() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (11, 17),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -16,6 +16,8 @@ fn join_method() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -31,6 +33,12 @@ fn join_method() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (40, 42),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenVar(
                           type: "var",
@@ -39,6 +47,12 @@ fn join_method() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (40, 42),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenStr(
                           type: "str",
@@ -55,6 +69,9 @@ fn join_method() {
                             outer: (67, 71),
                             inner: (67, 71),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -65,8 +82,14 @@ fn join_method() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (18, 24),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -126,6 +149,8 @@ fn array_simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: java,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "Prompts.java",
@@ -159,6 +184,9 @@ fn array_simple() {
                             outer: (52, 56),
                             inner: (52, 56),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -169,8 +197,14 @@ fn array_simple() {
                               inner: (2, 10),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (20, 26),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -17,6 +17,8 @@ fn implode_fn() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -32,6 +34,12 @@ fn implode_fn() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (39, 41),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenVar(
                           type: "var",
@@ -40,6 +48,12 @@ fn implode_fn() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (39, 41),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenStr(
                           type: "str",
@@ -56,6 +70,9 @@ fn implode_fn() {
                             outer: (54, 59),
                             inner: (54, 59),
                           ),
+                          expression: "$user",
+                          kind: identifier,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [
@@ -66,8 +83,14 @@ fn implode_fn() {
                               inner: (8, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$prompt",
+                        span: (20, 27),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -131,6 +154,8 @@ fn array_simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: php,
+                  parser: "tree-sitter",
                   prompts: [
                     Prompt(
                       file: "prompts.php",
@@ -164,6 +189,9 @@ fn array_simple() {
                             outer: (41, 46),
                             inner: (41, 46),
                           ),
+                          expression: "$user",
+                          kind: identifier,
+                          root: Some("$user"),
                         ),
                       ],
                       annotations: [
@@ -174,8 +202,14 @@ fn array_simple() {
                               inner: (8, 17),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "$prompt",
+                        span: (20, 27),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
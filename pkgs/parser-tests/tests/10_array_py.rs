@@ -16,6 +16,8 @@ fn join_method() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -31,6 +33,12 @@ fn join_method() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (20, 22),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenVar(
                           type: "var",
@@ -39,6 +47,12 @@ fn join_method() {
                         ),
                         PromptContentTokenJoint(
                           type: "joint",
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (20, 22),
+                              text: "\n",
+                            ),
+                          ]),
                         ),
                         PromptContentTokenStr(
                           type: "str",
@@ -55,6 +69,9 @@ fn join_method() {
                             outer: (39, 43),
                             inner: (39, 43),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -65,8 +82,14 @@ fn join_method() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (10, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
@@ -126,6 +149,8 @@ fn array_simple() {
                 assert_ron_snapshot!(result, @r#"
                 ParseResultSuccess(
                   state: "success",
+                  language: py,
+                  parser: "<parser>",
                   prompts: [
                     Prompt(
                       file: "prompts.py",
@@ -159,6 +184,9 @@ fn array_simple() {
                             outer: (30, 34),
                             inner: (30, 34),
                           ),
+                          expression: "user",
+                          kind: identifier,
+                          root: Some("user"),
                         ),
                       ],
                       annotations: [
@@ -169,8 +197,14 @@ fn array_simple() {
                               inner: (1, 9),
                             ),
                           ],
+                          attributes: [],
                         ),
                       ],
+                      binding: PromptBinding(
+                        name: "prompt",
+                        span: (10, 16),
+                        kind: local,
+                      ),
                     ),
                   ],
                 )
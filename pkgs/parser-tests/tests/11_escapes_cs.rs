@@ -0,0 +1,203 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            string userPrompt = $"Hello, {name}!\n\tBye {{name}}";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 54),
+                      span: SpanShape(
+                        outer: (20, 53),
+                        inner: (22, 52),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 29),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (29, 35),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (35, 52),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (36, 38),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (38, 40),
+                              text: "\t",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (44, 46),
+                              text: "{",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (50, 52),
+                              text: "}",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (29, 35),
+                            inner: (30, 34),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "string userPrompt = $\"Hello, {name}!\\n\\tBye {{name}}\";",
+                    "outer": "$\"Hello, {name}!\\n\\tBye {{name}}\"",
+                    "inner": "Hello, {name}!\\n\\tBye {{name}}",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}!\\n\\tBye {{name}}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn verbatim() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            string userPrompt = @"C:\new ""quoted""";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 41),
+                      span: SpanShape(
+                        outer: (20, 40),
+                        inner: (22, 39),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 39),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (29, 31),
+                              text: "\"",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (37, 39),
+                              text: "\"",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "string userPrompt = @\"C:\\new \"\"quoted\"\"\";",
+                    "outer": "@\"C:\\new \"\"quoted\"\"\"",
+                    "inner": "C:\\new \"\"quoted\"\"",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "C:\\new \"\"quoted\"\""
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
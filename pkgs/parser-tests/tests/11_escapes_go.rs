@@ -0,0 +1,169 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            userPrompt := "Hello!\n\tBye \u00e9\x41"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 40),
+                      span: SpanShape(
+                        outer: (14, 40),
+                        inner: (15, 39),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (15, 39),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (21, 23),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (23, 25),
+                              text: "\t",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (29, 35),
+                              text: "é",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (35, 39),
+                              text: "A",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (0, 10),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "userPrompt := \"Hello!\\n\\tBye \\u00e9\\x41\"",
+                    "outer": "\"Hello!\\n\\tBye \\u00e9\\x41\"",
+                    "inner": "Hello!\\n\\tBye \\u00e9\\x41",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!\\n\\tBye \\u00e9\\x41"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn raw() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            userPrompt := `C:\new\table`
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 28),
+                      span: SpanShape(
+                        outer: (14, 28),
+                        inner: (15, 27),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (15, 27),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (0, 10),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "userPrompt := `C:\\new\\table`",
+                    "outer": "`C:\\new\\table`",
+                    "inner": "C:\\new\\table",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "C:\\new\\table"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
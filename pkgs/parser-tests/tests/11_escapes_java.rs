@@ -0,0 +1,181 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            String userPrompt = "Hello!\n\tBye \u00e9\101";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 47),
+                      span: SpanShape(
+                        outer: (20, 46),
+                        inner: (21, 45),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (21, 45),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (27, 29),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (29, 31),
+                              text: "\t",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (35, 41),
+                              text: "é",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (41, 45),
+                              text: "A",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "String userPrompt = \"Hello!\\n\\tBye \\u00e9\\101\";",
+                    "outer": "\"Hello!\\n\\tBye \\u00e9\\101\"",
+                    "inner": "Hello!\\n\\tBye \\u00e9\\101",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!\\n\\tBye \\u00e9\\101"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn text_block() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            String userPrompt = """
+                Hello!\tBye\s
+                """;
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 50),
+                      span: SpanShape(
+                        outer: (20, 49),
+                        inner: (23, 46),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (28, 42),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (34, 36),
+                              text: "\t",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (39, 41),
+                              text: " ",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (7, 17),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "String userPrompt = \"\"\"\n    Hello!\\tBye\\s\n    \"\"\";",
+                    "outer": "\"\"\"\n    Hello!\\tBye\\s\n    \"\"\"",
+                    "inner": "\n    Hello!\\tBye\\s\n    ",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello!\\tBye\\s\n"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -0,0 +1,201 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $user_prompt = "Hello, {$name}!\n\tBye \$name \q";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 55),
+                      span: SpanShape(
+                        outer: (21, 55),
+                        inner: (22, 54),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 29),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (29, 36),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (36, 54),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (37, 39),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (39, 41),
+                              text: "\t",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (45, 47),
+                              text: "$",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (29, 36),
+                            inner: (30, 35),
+                          ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "$user_prompt = \"Hello, {$name}!\\n\\tBye \\$name \\q\"",
+                    "outer": "\"Hello, {$name}!\\n\\tBye \\$name \\q\"",
+                    "inner": "Hello, {$name}!\\n\\tBye \\$name \\q",
+                    "vars": [
+                      {
+                        "outer": "{$name}",
+                        "inner": "$name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}!\\n\\tBye \\$name \\q"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn single_quoted() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $user_prompt = 'It\'s C:\\new\table';
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 42),
+                      span: SpanShape(
+                        outer: (21, 42),
+                        inner: (22, 41),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 41),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (24, 26),
+                              text: "\'",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (30, 32),
+                              text: "\\",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "$user_prompt",
+                        span: (6, 18),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "$user_prompt = 'It\\'s C:\\\\new\\table'",
+                    "outer": "'It\\'s C:\\\\new\\table'",
+                    "inner": "It\\'s C:\\\\new\\table",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "It\\'s C:\\\\new\\table"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -0,0 +1,193 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            user_prompt = f"Hello, {name}!\n\tBye {{name}}"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 47),
+                      span: SpanShape(
+                        outer: (14, 47),
+                        inner: (16, 46),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (16, 23),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (23, 29),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 46),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (30, 32),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (32, 34),
+                              text: "\t",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (38, 40),
+                              text: "{",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (44, 46),
+                              text: "}",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (23, 29),
+                            inner: (24, 28),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "user_prompt = f\"Hello, {name}!\\n\\tBye {{name}}\"",
+                    "outer": "f\"Hello, {name}!\\n\\tBye {{name}}\"",
+                    "inner": "Hello, {name}!\\n\\tBye {{name}}",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}!\\n\\tBye {{name}}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn raw() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            user_prompt = r"C:\new\table"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 29),
+                      span: SpanShape(
+                        outer: (14, 29),
+                        inner: (16, 28),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (16, 28),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "user_prompt = r\"C:\\new\\table\"",
+                    "outer": "r\"C:\\new\\table\"",
+                    "inner": "C:\\new\\table",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "C:\\new\\table"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -0,0 +1,199 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::rb(indoc! {r#"
+            user_prompt = "Hello, #{name}!\n\sBye \#{name}"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r##"
+                ParseResultSuccess(
+                  state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (0, 47),
+                      span: SpanShape(
+                        outer: (14, 47),
+                        inner: (15, 46),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (15, 22),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (22, 29),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 46),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (30, 32),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (32, 34),
+                              text: " ",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (38, 40),
+                              text: "#",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (22, 29),
+                            inner: (24, 28),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "##);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "user_prompt = \"Hello, #{name}!\\n\\sBye \\#{name}\"",
+                    "outer": "\"Hello, #{name}!\\n\\sBye \\#{name}\"",
+                    "inner": "Hello, #{name}!\\n\\sBye \\#{name}",
+                    "vars": [
+                      {
+                        "outer": "#{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}!\\n\\sBye \\#{name}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn single_quoted() {
+    ParseTest::test(
+        &ParseTestLang::rb(indoc! {r#"
+            user_prompt = 'It\'s C:\\new\table'
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (0, 35),
+                      span: SpanShape(
+                        outer: (14, 35),
+                        inner: (15, 34),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (15, 34),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (17, 19),
+                              text: "\'",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (23, 25),
+                              text: "\\",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "user_prompt",
+                        span: (0, 11),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "user_prompt = 'It\\'s C:\\\\new\\table'",
+                    "outer": "'It\\'s C:\\\\new\\table'",
+                    "inner": "It\\'s C:\\\\new\\table",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "It\\'s C:\\\\new\\table"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
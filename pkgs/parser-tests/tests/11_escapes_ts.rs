@@ -0,0 +1,116 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn decoded() {
+    ParseTest::test(
+        &ParseTestLang::ts(indoc! {r#"
+            const userPrompt = `Hello, ${name}!\n\u{1F44B} \${x}`;
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: js,
+                  parser: "oxc",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.js",
+                      enclosure: (0, 54),
+                      span: SpanShape(
+                        outer: (19, 53),
+                        inner: (20, 52),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (20, 27),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (27, 34),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (34, 52),
+                          escapes: Some([
+                            PromptContentTokenEscape(
+                              span: (35, 37),
+                              text: "\n",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (37, 46),
+                              text: "👋",
+                            ),
+                            PromptContentTokenEscape(
+                              span: (47, 49),
+                              text: "$",
+                            ),
+                          ]),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (27, 34),
+                            inner: (29, 33),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "userPrompt",
+                        span: (6, 16),
+                        kind: const,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "const userPrompt = `Hello, ${name}!\\n\\u{1F44B} \\${x}`;",
+                    "outer": "`Hello, ${name}!\\n\\u{1F44B} \\${x}`",
+                    "inner": "Hello, ${name}!\\n\\u{1F44B} \\${x}",
+                    "vars": [
+                      {
+                        "outer": "${name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}!\\n\\u{1F44B} \\${x}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
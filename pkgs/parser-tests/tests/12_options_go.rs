@@ -0,0 +1,103 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+use volumen_types::ParseOptions;
+
+mod utils;
+use utils::*;
+
+#[test]
+fn custom_markers() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            // @llm
+            system := "You are a helpful assistant."
+            // @prompt
+            greeting := "Hello!"
+        "#})
+        .with_options(ParseOptions {
+            markers: Some(vec!["@llm".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 48),
+                      span: SpanShape(
+                        outer: (18, 48),
+                        inner: (19, 47),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (19, 47),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 7),
+                              inner: (2, 7),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (8, 14),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @llm\nsystem := \"You are a helpful assistant.\"",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @llm",
+                        "inner": " @llm"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -0,0 +1,103 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+use volumen_types::ParseOptions;
+
+mod utils;
+use utils::*;
+
+#[test]
+fn custom_markers() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            // @llm
+            String system = "You are a helpful assistant.";
+            // @prompt
+            String greeting = "Hello!";
+        "#})
+        .with_options(ParseOptions {
+            markers: Some(vec!["@llm".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 55),
+                      span: SpanShape(
+                        outer: (24, 54),
+                        inner: (25, 53),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (25, 53),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 7),
+                              inner: (2, 7),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (15, 21),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @llm\nString system = \"You are a helpful assistant.\";",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @llm",
+                        "inner": " @llm"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
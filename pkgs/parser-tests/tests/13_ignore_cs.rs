@@ -0,0 +1,131 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn ignored() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            // @prompt-ignore
+            string userPrompt = "https://example.com/prompt";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
+
+#[test]
+fn ignored_inline() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            string userPrompt = "https://example.com/prompt"; // @noprompt
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
+
+#[test]
+fn ignored_reassigned() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            // @prompt-ignore
+            string userPrompt = "Hello";
+            userPrompt = "Hello!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
+
+#[test]
+fn ignored_annotated() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            // @prompt-ignore
+            string userPrompt = "Hello";
+            // @prompt
+            userPrompt = "Hi!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
@@ -0,0 +1,195 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn ignored() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt-ignore
+            $userPrompt = "https://example.com/prompt";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
+
+#[test]
+fn ignored_inline() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $userPrompt = "https://example.com/prompt"; // @noprompt
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
+
+#[test]
+fn ignored_reassigned() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt-ignore
+            $userPrompt = "Hello";
+            $userPrompt = "Hello, {$name}!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @"[]");
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @"[]");
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"[]");
+            }),
+        },
+    );
+}
+
+#[test]
+fn ignored_annotated() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt-ignore
+            $userPrompt = "Hello";
+            // @prompt
+            $userPrompt = "Hi!";
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (47, 77),
+                      span: SpanShape(
+                        outer: (72, 77),
+                        inner: (73, 76),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (73, 76),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (47, 57),
+                              inner: (49, 57),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "$userPrompt",
+                        span: (58, 69),
+                        kind: local,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n$userPrompt = \"Hi!\"",
+                    "outer": "\"Hi!\"",
+                    "inner": "Hi!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hi!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -0,0 +1,266 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn message() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            // @prompt
+            var message = new ChatMessage { Role = ChatRole.System, Content = "You are a helpful assistant." };
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 110),
+                      span: SpanShape(
+                        outer: (77, 107),
+                        inner: (78, 106),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (78, 106),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (15, 22),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nvar message = new ChatMessage { Role = ChatRole.System, Content = \"You are a helpful assistant.\" };",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            // @prompt
+            var messages = new[]
+            {
+                new { role = "system", content = "You are a helpful assistant." },
+                new { role = "user", content = "Hello!" },
+            };
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 154),
+                      span: SpanShape(
+                        outer: (71, 101),
+                        inner: (72, 100),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (72, 100),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 154),
+                      span: SpanShape(
+                        outer: (140, 148),
+                        inner: (141, 147),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (141, 147),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "Prompts.cs",
+                      enclosure: (0, 154),
+                      span: (26, 153),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nvar messages = new[]\n{\n    new { role = \"system\", content = \"You are a helpful assistant.\" },\n    new { role = \"user\", content = \"Hello!\" },\n};",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\nvar messages = new[]\n{\n    new { role = \"system\", content = \"You are a helpful assistant.\" },\n    new { role = \"user\", content = \"Hello!\" },\n};",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
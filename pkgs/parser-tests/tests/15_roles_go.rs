@@ -0,0 +1,265 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn message() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            // @prompt
+            message := openai.ChatCompletionMessage{Role: openai.ChatMessageRoleSystem, Content: "You are a helpful assistant."}
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 127),
+                      span: SpanShape(
+                        outer: (96, 126),
+                        inner: (97, 125),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (97, 125),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "message",
+                        span: (11, 18),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nmessage := openai.ChatCompletionMessage{Role: openai.ChatMessageRoleSystem, Content: \"You are a helpful assistant.\"}",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            // @prompt
+            messages := []map[string]string{
+            	{"role": "system", "content": "You are a helpful assistant."},
+            	{"role": "user", "content": "Hello!"},
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 149),
+                      span: SpanShape(
+                        outer: (75, 105),
+                        inner: (76, 104),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (76, 104),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (11, 19),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 149),
+                      span: SpanShape(
+                        outer: (137, 145),
+                        inner: (138, 144),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (138, 144),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (11, 19),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.go",
+                      enclosure: (0, 149),
+                      span: (23, 149),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (11, 19),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nmessages := []map[string]string{\n\t{\"role\": \"system\", \"content\": \"You are a helpful assistant.\"},\n\t{\"role\": \"user\", \"content\": \"Hello!\"},\n}",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\nmessages := []map[string]string{\n\t{\"role\": \"system\", \"content\": \"You are a helpful assistant.\"},\n\t{\"role\": \"user\", \"content\": \"Hello!\"},\n}",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
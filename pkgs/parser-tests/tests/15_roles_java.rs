@@ -0,0 +1,173 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            // @prompt
+            var messages = List.of(
+                Map.of("role", "system", "content", "You are a helpful assistant."),
+                Map.of("role", "user", "content", "Hello!")
+            );
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 158),
+                      span: SpanShape(
+                        outer: (75, 105),
+                        inner: (76, 104),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (76, 104),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (0, 158),
+                      span: SpanShape(
+                        outer: (146, 154),
+                        inner: (147, 153),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (147, 153),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "Prompts.java",
+                      enclosure: (0, 158),
+                      span: (26, 157),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (15, 23),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\nvar messages = List.of(\n    Map.of(\"role\", \"system\", \"content\", \"You are a helpful assistant.\"),\n    Map.of(\"role\", \"user\", \"content\", \"Hello!\")\n);",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\nvar messages = List.of(\n    Map.of(\"role\", \"system\", \"content\", \"You are a helpful assistant.\"),\n    Map.of(\"role\", \"user\", \"content\", \"Hello!\")\n);",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
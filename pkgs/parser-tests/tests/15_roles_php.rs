@@ -0,0 +1,174 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt
+            $messages = [
+                ['role' => 'system', 'content' => "You are a helpful assistant."],
+                array("role" => "user", "content" => "Hello!"),
+            ];
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 155),
+                      span: SpanShape(
+                        outer: (69, 99),
+                        inner: (70, 98),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (70, 98),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (6, 16),
+                              inner: (8, 16),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "$messages",
+                        span: (17, 26),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 155),
+                      span: SpanShape(
+                        outer: (143, 151),
+                        inner: (144, 150),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (144, 150),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (6, 16),
+                              inner: (8, 16),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "$messages",
+                        span: (17, 26),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.php",
+                      enclosure: (6, 155),
+                      span: (29, 155),
+                      binding: PromptBinding(
+                        name: "$messages",
+                        span: (17, 26),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "// @prompt\n$messages = [\n    ['role' => 'system', 'content' => \"You are a helpful assistant.\"],\n    array(\"role\" => \"user\", \"content\" => \"Hello!\"),\n]",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "// @prompt\n$messages = [\n    ['role' => 'system', 'content' => \"You are a helpful assistant.\"],\n    array(\"role\" => \"user\", \"content\" => \"Hello!\"),\n]",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
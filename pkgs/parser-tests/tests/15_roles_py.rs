@@ -0,0 +1,173 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            # @prompt
+            messages = [
+                {"role": "system", "content": "You are a helpful assistant."},
+                dict(role="user", content="Hello!"),
+            ]
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 132),
+                      span: SpanShape(
+                        outer: (57, 87),
+                        inner: (58, 86),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (58, 86),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (10, 18),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (0, 132),
+                      span: SpanShape(
+                        outer: (120, 128),
+                        inner: (121, 127),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (121, 127),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (10, 18),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.py",
+                      enclosure: (0, 132),
+                      span: (21, 132),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (10, 18),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "# @prompt\nmessages = [\n    {\"role\": \"system\", \"content\": \"You are a helpful assistant.\"},\n    dict(role=\"user\", content=\"Hello!\"),\n]",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "# @prompt\nmessages = [\n    {\"role\": \"system\", \"content\": \"You are a helpful assistant.\"},\n    dict(role=\"user\", content=\"Hello!\"),\n]",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ],
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
@@ -0,0 +1,163 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn conversation() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            var messages = new[]
+            {
+                new { role = "system", content = "You are a helpful assistant." },
+                new { role = "user", content = $"Hello, {name}!" },
+            };
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 152),
+                      span: SpanShape(
+                        outer: (60, 90),
+                        inner: (61, 89),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (61, 89),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (4, 12),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 152),
+                      span: SpanShape(
+                        outer: (129, 146),
+                        inner: (131, 145),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (131, 138),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (138, 144),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (144, 145),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (138, 144),
+                            inner: (139, 143),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (4, 12),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "Prompts.cs",
+                      enclosure: (0, 152),
+                      span: (15, 151),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (4, 12),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "var messages = new[]\n{\n    new { role = \"system\", content = \"You are a helpful assistant.\" },\n    new { role = \"user\", content = $\"Hello, {name}!\" },\n};",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "var messages = new[]\n{\n    new { role = \"system\", content = \"You are a helpful assistant.\" },\n    new { role = \"user\", content = $\"Hello, {name}!\" },\n};",
+                    "outer": "$\"Hello, {name}!\"",
+                    "inner": "Hello, {name}!",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
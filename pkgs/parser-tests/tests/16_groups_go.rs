@@ -0,0 +1,138 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn conversation() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            messages := []openai.ChatCompletionMessage{
+            	{Role: openai.ChatMessageRoleSystem, Content: "You are a helpful assistant."},
+            	{Role: openai.ChatMessageRoleUser, Content: "Hello!"},
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 181),
+                      span: SpanShape(
+                        outer: (91, 121),
+                        inner: (92, 120),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (92, 120),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (0, 8),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 181),
+                      span: SpanShape(
+                        outer: (169, 177),
+                        inner: (170, 176),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (170, 176),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (0, 8),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.go",
+                      enclosure: (0, 181),
+                      span: (12, 181),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (0, 8),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "messages := []openai.ChatCompletionMessage{\n\t{Role: openai.ChatMessageRoleSystem, Content: \"You are a helpful assistant.\"},\n\t{Role: openai.ChatMessageRoleUser, Content: \"Hello!\"},\n}",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "messages := []openai.ChatCompletionMessage{\n\t{Role: openai.ChatMessageRoleSystem, Content: \"You are a helpful assistant.\"},\n\t{Role: openai.ChatMessageRoleUser, Content: \"Hello!\"},\n}",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -0,0 +1,163 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn conversation() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $messages = [
+                ['role' => 'system', 'content' => "You are a helpful assistant."],
+                ['role' => 'user', 'content' => "Hello, {$name}!"],
+            ];
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 148),
+                      span: SpanShape(
+                        outer: (58, 88),
+                        inner: (59, 87),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (59, 87),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "$messages",
+                        span: (6, 15),
+                        kind: local,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 148),
+                      span: SpanShape(
+                        outer: (127, 144),
+                        inner: (128, 143),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (128, 135),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (135, 142),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (142, 143),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (135, 142),
+                            inner: (136, 141),
+                          ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "$messages",
+                        span: (6, 15),
+                        kind: local,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.php",
+                      enclosure: (6, 148),
+                      span: (18, 148),
+                      binding: PromptBinding(
+                        name: "$messages",
+                        span: (6, 15),
+                        kind: local,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "$messages = [\n    ['role' => 'system', 'content' => \"You are a helpful assistant.\"],\n    ['role' => 'user', 'content' => \"Hello, {$name}!\"],\n]",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "$messages = [\n    ['role' => 'system', 'content' => \"You are a helpful assistant.\"],\n    ['role' => 'user', 'content' => \"Hello, {$name}!\"],\n]",
+                    "outer": "\"Hello, {$name}!\"",
+                    "inner": "Hello, {$name}!",
+                    "vars": [
+                      {
+                        "outer": "{$name}",
+                        "inner": "$name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
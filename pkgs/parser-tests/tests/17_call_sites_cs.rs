@@ -0,0 +1,249 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+use volumen_types::ParseOptions;
+
+mod utils;
+use utils::*;
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            client.Chat.Completions.Create(
+                model: "gpt-4o",
+                messages: new[]
+                {
+                    new { role = "system", content = "You are a helpful assistant." },
+                    new { role = "user", content = $"Hello, {name}!" },
+                }
+            );
+        "#})
+        .with_options(ParseOptions {
+            call_site_presets: Some(vec!["openai".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 221),
+                      span: SpanShape(
+                        outer: (120, 150),
+                        inner: (121, 149),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (121, 149),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (57, 65),
+                        kind: argument,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (0, 221),
+                      span: SpanShape(
+                        outer: (193, 210),
+                        inner: (195, 209),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (195, 202),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (202, 208),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (208, 209),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (202, 208),
+                            inner: (203, 207),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (57, 65),
+                        kind: argument,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "Prompts.cs",
+                      enclosure: (0, 221),
+                      span: (67, 219),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (57, 65),
+                        kind: argument,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "client.Chat.Completions.Create(\n    model: \"gpt-4o\",\n    messages: new[]\n    {\n        new { role = \"system\", content = \"You are a helpful assistant.\" },\n        new { role = \"user\", content = $\"Hello, {name}!\" },\n    }\n)",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "client.Chat.Completions.Create(\n    model: \"gpt-4o\",\n    messages: new[]\n    {\n        new { role = \"system\", content = \"You are a helpful assistant.\" },\n        new { role = \"user\", content = $\"Hello, {name}!\" },\n    }\n)",
+                    "outer": "$\"Hello, {name}!\"",
+                    "inner": "Hello, {name}!",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn argument() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            var message = new SystemChatMessage("You are a helpful assistant.");
+        "#})
+        .with_options(ParseOptions {
+            call_site_presets: Some(vec!["openai".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (14, 67),
+                      span: SpanShape(
+                        outer: (36, 66),
+                        inner: (37, 65),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (37, 65),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "SystemChatMessage",
+                        span: (18, 35),
+                        kind: argument,
+                      ),
+                      role: Some(system),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "new SystemChatMessage(\"You are a helpful assistant.\")",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
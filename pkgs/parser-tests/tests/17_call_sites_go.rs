@@ -0,0 +1,224 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+use volumen_types::ParseOptions;
+
+mod utils;
+use utils::*;
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            client.CreateChatCompletion(ctx, openai.ChatCompletionRequest{
+            	Model: "gpt-4o",
+            	Messages: []openai.ChatCompletionMessage{
+            		{Role: openai.ChatMessageRoleSystem, Content: "You are a helpful assistant."},
+            		{Role: openai.ChatMessageRoleUser, Content: "Hello!"},
+            	},
+            })
+        "#})
+        .with_options(ParseOptions {
+            call_site_presets: Some(vec!["openai".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 268),
+                      span: SpanShape(
+                        outer: (172, 202),
+                        inner: (173, 201),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (173, 201),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "Messages",
+                        span: (82, 90),
+                        kind: argument,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 268),
+                      span: SpanShape(
+                        outer: (251, 259),
+                        inner: (252, 258),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (252, 258),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "Messages",
+                        span: (82, 90),
+                        kind: argument,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.go",
+                      enclosure: (0, 268),
+                      span: (92, 264),
+                      binding: PromptBinding(
+                        name: "Messages",
+                        span: (82, 90),
+                        kind: argument,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "client.CreateChatCompletion(ctx, openai.ChatCompletionRequest{\n\tModel: \"gpt-4o\",\n\tMessages: []openai.ChatCompletionMessage{\n\t\t{Role: openai.ChatMessageRoleSystem, Content: \"You are a helpful assistant.\"},\n\t\t{Role: openai.ChatMessageRoleUser, Content: \"Hello!\"},\n\t},\n})",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "client.CreateChatCompletion(ctx, openai.ChatCompletionRequest{\n\tModel: \"gpt-4o\",\n\tMessages: []openai.ChatCompletionMessage{\n\t\t{Role: openai.ChatMessageRoleSystem, Content: \"You are a helpful assistant.\"},\n\t\t{Role: openai.ChatMessageRoleUser, Content: \"Hello!\"},\n\t},\n})",
+                    "outer": "\"Hello!\"",
+                    "inner": "Hello!",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn argument() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            openai.SystemMessage("You are a helpful assistant.")
+        "#})
+        .with_options(ParseOptions {
+            call_site_presets: Some(vec!["openai".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (0, 52),
+                      span: SpanShape(
+                        outer: (21, 51),
+                        inner: (22, 50),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (22, 50),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "SystemMessage",
+                        span: (7, 20),
+                        kind: argument,
+                      ),
+                      role: Some(system),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "openai.SystemMessage(\"You are a helpful assistant.\")",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
@@ -0,0 +1,250 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+use volumen_types::ParseOptions;
+
+mod utils;
+use utils::*;
+
+#[test]
+fn messages() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $client->chat()->create([
+                'model' => 'gpt-4o',
+                'messages' => [
+                    ['role' => 'system', 'content' => "You are a helpful assistant."],
+                    ['role' => 'user', 'content' => "Hello, {$name}!"],
+                ],
+            ]);
+        "#})
+        .with_options(ParseOptions {
+            call_site_presets: Some(vec!["openai".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 221),
+                      span: SpanShape(
+                        outer: (119, 149),
+                        inner: (120, 148),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (120, 148),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (62, 70),
+                        kind: argument,
+                      ),
+                      role: Some(system),
+                    ),
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 221),
+                      span: SpanShape(
+                        outer: (192, 209),
+                        inner: (193, 208),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (193, 200),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (200, 207),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (207, 208),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (200, 207),
+                            inner: (201, 206),
+                          ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (62, 70),
+                        kind: argument,
+                      ),
+                      role: Some(user),
+                    ),
+                  ],
+                  groups: Some([
+                    PromptGroup(
+                      file: "prompts.php",
+                      enclosure: (6, 221),
+                      span: (75, 217),
+                      binding: PromptBinding(
+                        name: "messages",
+                        span: (62, 70),
+                        kind: argument,
+                      ),
+                      messages: [
+                        PromptGroupMessage(
+                          prompt: 0,
+                          role: Some(system),
+                        ),
+                        PromptGroupMessage(
+                          prompt: 1,
+                          role: Some(user),
+                        ),
+                      ],
+                    ),
+                  ]),
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "$client->chat()->create([\n    'model' => 'gpt-4o',\n    'messages' => [\n        ['role' => 'system', 'content' => \"You are a helpful assistant.\"],\n        ['role' => 'user', 'content' => \"Hello, {$name}!\"],\n    ],\n])",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  },
+                  {
+                    "enclosure": "$client->chat()->create([\n    'model' => 'gpt-4o',\n    'messages' => [\n        ['role' => 'system', 'content' => \"You are a helpful assistant.\"],\n        ['role' => 'user', 'content' => \"Hello, {$name}!\"],\n    ],\n])",
+                    "outer": "\"Hello, {$name}!\"",
+                    "inner": "Hello, {$name}!",
+                    "vars": [
+                      {
+                        "outer": "{$name}",
+                        "inner": "$name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant.",
+                  "Hello, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn argument() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            $client->messages()->create(['model' => 'claude', 'system' => "You are a helpful assistant."]);
+        "#})
+        .with_options(ParseOptions {
+            call_site_presets: Some(vec!["anthropic".into()]),
+            ..Default::default()
+        }),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (6, 100),
+                      span: SpanShape(
+                        outer: (68, 98),
+                        inner: (69, 97),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (69, 97),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "system",
+                        span: (57, 63),
+                        kind: argument,
+                      ),
+                      role: Some(system),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "$client->messages()->create(['model' => 'claude', 'system' => \"You are a helpful assistant.\"])",
+                    "outer": "\"You are a helpful assistant.\"",
+                    "inner": "You are a helpful assistant.",
+                    "vars": []
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "You are a helpful assistant."
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
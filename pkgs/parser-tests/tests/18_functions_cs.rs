@@ -0,0 +1,246 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn returned() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            class Prompts
+            {
+                string BuildPrompt(User user)
+                {
+                    return $"Hello, {user.Name}! {greeting}";
+                }
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (64, 105),
+                      span: SpanShape(
+                        outer: (71, 104),
+                        inner: (73, 103),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (73, 80),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (80, 91),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (91, 93),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (93, 103),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (80, 91),
+                            inner: (81, 90),
+                          ),
+                          expression: "user.Name",
+                          kind: member,
+                          root: Some("user"),
+                          parameter: Some(true),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (93, 103),
+                            inner: (94, 102),
+                          ),
+                          expression: "greeting",
+                          kind: identifier,
+                          root: Some("greeting"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "BuildPrompt",
+                        span: (27, 38),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return $\"Hello, {user.Name}! {greeting}\";",
+                    "outer": "$\"Hello, {user.Name}! {greeting}\"",
+                    "inner": "Hello, {user.Name}! {greeting}",
+                    "vars": [
+                      {
+                        "outer": "{user.Name}",
+                        "inner": "user.Name"
+                      },
+                      {
+                        "outer": "{greeting}",
+                        "inner": "greeting"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}! {1}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn expression_bodied() {
+    ParseTest::test(
+        &ParseTestLang::cs(indoc! {r#"
+            class Prompts
+            {
+                // @prompt
+                string Greet(string name) => $"Welcome, {name}!";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: cs,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.cs",
+                      enclosure: (61, 83),
+                      span: SpanShape(
+                        outer: (64, 83),
+                        inner: (66, 82),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (66, 75),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (75, 81),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (81, 82),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (75, 81),
+                            inner: (76, 80),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          parameter: Some(true),
+                        ),
+                      ],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (20, 30),
+                              inner: (22, 30),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "Greet",
+                        span: (42, 47),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "=> $\"Welcome, {name}!\"",
+                    "outer": "$\"Welcome, {name}!\"",
+                    "inner": "Welcome, {name}!",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Welcome, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
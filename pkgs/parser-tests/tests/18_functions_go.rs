@@ -0,0 +1,243 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn returned() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            func buildPrompt(user User) string {
+            	return fmt.Sprintf("Hello, %s! %s", user.Name, greeting)
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (38, 94),
+                      span: SpanShape(
+                        outer: (45, 94),
+                        inner: (58, 71),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (58, 65),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (65, 67),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (67, 69),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (69, 71),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (74, 83),
+                            inner: (74, 83),
+                          ),
+                          expression: "user.Name",
+                          kind: member,
+                          root: Some("user"),
+                          format: Some("%s"),
+                          parameter: Some(true),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (85, 93),
+                            inner: (85, 93),
+                          ),
+                          expression: "greeting",
+                          kind: identifier,
+                          root: Some("greeting"),
+                          format: Some("%s"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "buildPrompt",
+                        span: (5, 16),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return fmt.Sprintf(\"Hello, %s! %s\", user.Name, greeting)",
+                    "outer": "fmt.Sprintf(\"Hello, %s! %s\", user.Name, greeting)",
+                    "inner": "Hello, %s! %s",
+                    "vars": [
+                      {
+                        "outer": "user.Name",
+                        "inner": "user.Name"
+                      },
+                      {
+                        "outer": "greeting",
+                        "inner": "greeting"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}! {0}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn annotated() {
+    ParseTest::test(
+        &ParseTestLang::go(indoc! {r#"
+            // @prompt
+            func greet(name string) string {
+            	return "Welcome, " + name + "!"
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: go,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.go",
+                      enclosure: (45, 76),
+                      span: SpanShape(
+                        outer: (52, 76),
+                        inner: (53, 75),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (53, 62),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (66, 70),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (74, 75),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (63, 73),
+                            inner: (66, 70),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          parameter: Some(true),
+                        ),
+                      ],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 10),
+                              inner: (2, 10),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greet",
+                        span: (16, 21),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return \"Welcome, \" + name + \"!\"",
+                    "outer": "\"Welcome, \" + name + \"!\"",
+                    "inner": "Welcome, \" + name + \"!",
+                    "vars": [
+                      {
+                        "outer": " + name + ",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Welcome, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -0,0 +1,245 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn returned() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            class Prompts {
+                String buildPrompt(User user) {
+                    return "Hello, " + user.name + "! " + greeting;
+                }
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (60, 107),
+                      span: SpanShape(
+                        outer: (67, 106),
+                        inner: (68, 106),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (68, 75),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (79, 88),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (92, 94),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (98, 106),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (76, 91),
+                            inner: (79, 88),
+                          ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
+                          parameter: Some(true),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (95, 106),
+                            inner: (98, 106),
+                          ),
+                          expression: "greeting",
+                          kind: identifier,
+                          root: Some("greeting"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "buildPrompt",
+                        span: (27, 38),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return \"Hello, \" + user.name + \"! \" + greeting;",
+                    "outer": "\"Hello, \" + user.name + \"! \" + greeting",
+                    "inner": "Hello, \" + user.name + \"! \" + greeting",
+                    "vars": [
+                      {
+                        "outer": " + user.name + ",
+                        "inner": "user.name"
+                      },
+                      {
+                        "outer": " + greeting",
+                        "inner": "greeting"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}! {0}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn annotated() {
+    ParseTest::test(
+        &ParseTestLang::java(indoc! {r#"
+            class Prompts {
+                // @prompt
+                String greet(String name) {
+                    return "Welcome, " + name + "!";
+                }
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: java,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "Prompts.java",
+                      enclosure: (71, 103),
+                      span: SpanShape(
+                        outer: (78, 102),
+                        inner: (79, 101),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (79, 88),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (92, 96),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (100, 101),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (89, 99),
+                            inner: (92, 96),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          parameter: Some(true),
+                        ),
+                      ],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (20, 30),
+                              inner: (22, 30),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greet",
+                        span: (42, 47),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return \"Welcome, \" + name + \"!\";",
+                    "outer": "\"Welcome, \" + name + \"!\"",
+                    "inner": "Welcome, \" + name + \"!",
+                    "vars": [
+                      {
+                        "outer": " + name + ",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Welcome, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
@@ -0,0 +1,243 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn returned() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            function buildPrompt($user) {
+                return "Hello, {$user->name}! {$greeting}";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (40, 83),
+                      span: SpanShape(
+                        outer: (47, 82),
+                        inner: (48, 81),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (48, 55),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (55, 68),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (68, 70),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (70, 81),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (55, 68),
+                            inner: (56, 67),
+                          ),
+                          expression: "$user->name",
+                          kind: member,
+                          root: Some("$user"),
+                          parameter: Some(true),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (70, 81),
+                            inner: (71, 80),
+                          ),
+                          expression: "$greeting",
+                          kind: identifier,
+                          root: Some("$greeting"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "buildPrompt",
+                        span: (15, 26),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return \"Hello, {$user->name}! {$greeting}\";",
+                    "outer": "\"Hello, {$user->name}! {$greeting}\"",
+                    "inner": "Hello, {$user->name}! {$greeting}",
+                    "vars": [
+                      {
+                        "outer": "{$user->name}",
+                        "inner": "$user->name"
+                      },
+                      {
+                        "outer": "{$greeting}",
+                        "inner": "$greeting"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}! {1}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn annotated() {
+    ParseTest::test(
+        &ParseTestLang::php(indoc! {r#"
+            <?php
+            // @prompt
+            function greet($name) {
+                return "Welcome, {$name}!";
+            }
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: php,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.php",
+                      enclosure: (45, 72),
+                      span: SpanShape(
+                        outer: (52, 71),
+                        inner: (53, 70),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (53, 62),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (62, 69),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (69, 70),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (62, 69),
+                            inner: (63, 68),
+                          ),
+                          expression: "$name",
+                          kind: identifier,
+                          root: Some("$name"),
+                          parameter: Some(true),
+                        ),
+                      ],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (6, 16),
+                              inner: (8, 16),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greet",
+                        span: (26, 31),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return \"Welcome, {$name}!\";",
+                    "outer": "\"Welcome, {$name}!\"",
+                    "inner": "Welcome, {$name}!",
+                    "vars": [
+                      {
+                        "outer": "{$name}",
+                        "inner": "$name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Welcome, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r#"
+                [
+                  [
+                    [
+                      {
+                        "outer": "// @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "#);
+            }),
+        },
+    );
+}
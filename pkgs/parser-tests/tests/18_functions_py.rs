@@ -0,0 +1,239 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn returned() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            def build_prompt(user):
+                return f"Hello, {user.name}! {greeting}"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (28, 68),
+                      span: SpanShape(
+                        outer: (35, 68),
+                        inner: (37, 67),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (37, 44),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (44, 55),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (55, 57),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (57, 67),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (44, 55),
+                            inner: (45, 54),
+                          ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
+                          parameter: Some(true),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (57, 67),
+                            inner: (58, 66),
+                          ),
+                          expression: "greeting",
+                          kind: identifier,
+                          root: Some("greeting"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "build_prompt",
+                        span: (4, 16),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return f\"Hello, {user.name}! {greeting}\"",
+                    "outer": "f\"Hello, {user.name}! {greeting}\"",
+                    "inner": "Hello, {user.name}! {greeting}",
+                    "vars": [
+                      {
+                        "outer": "{user.name}",
+                        "inner": "user.name"
+                      },
+                      {
+                        "outer": "{greeting}",
+                        "inner": "greeting"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}! {1}"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn annotated() {
+    ParseTest::test(
+        &ParseTestLang::py(indoc! {r#"
+            # @prompt
+            def greet(name):
+                return f"Welcome, {name}!"
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: py,
+                  parser: "<parser>",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.py",
+                      enclosure: (31, 57),
+                      span: SpanShape(
+                        outer: (38, 57),
+                        inner: (40, 56),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (40, 49),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (49, 55),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (55, 56),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (49, 55),
+                            inner: (50, 54),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          parameter: Some(true),
+                        ),
+                      ],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greet",
+                        span: (14, 19),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r#"
+                [
+                  {
+                    "enclosure": "return f\"Welcome, {name}!\"",
+                    "outer": "f\"Welcome, {name}!\"",
+                    "inner": "Welcome, {name}!",
+                    "vars": [
+                      {
+                        "outer": "{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "#);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Welcome, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
@@ -0,0 +1,275 @@
+use indoc::indoc;
+use insta::{assert_json_snapshot, assert_ron_snapshot};
+
+mod utils;
+use utils::*;
+
+#[test]
+fn returned() {
+    ParseTest::test(
+        &ParseTestLang::rb(indoc! {r#"
+            def build_prompt(user)
+              return "Hello, #{user.name}! #{greeting}" if user
+              "Hello, stranger!"
+            end
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (25, 66),
+                      span: SpanShape(
+                        outer: (32, 66),
+                        inner: (33, 65),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (33, 40),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (40, 52),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (52, 54),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (54, 65),
+                          index: 1,
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (40, 52),
+                            inner: (42, 51),
+                          ),
+                          expression: "user.name",
+                          kind: member,
+                          root: Some("user"),
+                          parameter: Some(true),
+                        ),
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (54, 65),
+                            inner: (56, 64),
+                          ),
+                          expression: "greeting",
+                          kind: identifier,
+                          root: Some("greeting"),
+                        ),
+                      ],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "build_prompt",
+                        span: (4, 16),
+                        kind: function,
+                      ),
+                    ),
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (77, 95),
+                      span: SpanShape(
+                        outer: (77, 95),
+                        inner: (78, 94),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (78, 94),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [],
+                      annotations: [],
+                      binding: PromptBinding(
+                        name: "build_prompt",
+                        span: (4, 16),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "return \"Hello, #{user.name}! #{greeting}\"",
+                    "outer": "\"Hello, #{user.name}! #{greeting}\"",
+                    "inner": "Hello, #{user.name}! #{greeting}",
+                    "vars": [
+                      {
+                        "outer": "#{user.name}",
+                        "inner": "user.name"
+                      },
+                      {
+                        "outer": "#{greeting}",
+                        "inner": "greeting"
+                      }
+                    ]
+                  },
+                  {
+                    "enclosure": "\"Hello, stranger!\"",
+                    "outer": "\"Hello, stranger!\"",
+                    "inner": "Hello, stranger!",
+                    "vars": []
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Hello, {0}! {1}",
+                  "Hello, stranger!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @"
+                [
+                  [],
+                  []
+                ]
+                ");
+            }),
+        },
+    );
+}
+
+#[test]
+fn annotated() {
+    ParseTest::test(
+        &ParseTestLang::rb(indoc! {r#"
+            # @prompt
+            def greet(name)
+              "Welcome, #{name}!"
+            end
+        "#}),
+        ParseAssertions {
+            result: Box::new(|result| {
+                assert_ron_snapshot!(result, @r#"
+                ParseResultSuccess(
+                  state: "success",
+                  language: rb,
+                  parser: "tree-sitter",
+                  prompts: [
+                    Prompt(
+                      file: "prompts.rb",
+                      enclosure: (28, 47),
+                      span: SpanShape(
+                        outer: (28, 47),
+                        inner: (29, 46),
+                      ),
+                      content: [
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (29, 38),
+                        ),
+                        PromptContentTokenVar(
+                          type: "var",
+                          span: (38, 45),
+                          index: 0,
+                        ),
+                        PromptContentTokenStr(
+                          type: "str",
+                          span: (45, 46),
+                        ),
+                      ],
+                      joint: SpanShape(
+                        outer: (0, 0),
+                        inner: (0, 0),
+                      ),
+                      vars: [
+                        PromptVar(
+                          span: SpanShape(
+                            outer: (38, 45),
+                            inner: (40, 44),
+                          ),
+                          expression: "name",
+                          kind: identifier,
+                          root: Some("name"),
+                          parameter: Some(true),
+                        ),
+                      ],
+                      annotations: [
+                        PromptAnnotation(
+                          spans: [
+                            SpanShape(
+                              outer: (0, 9),
+                              inner: (1, 9),
+                            ),
+                          ],
+                          attributes: [],
+                        ),
+                      ],
+                      binding: PromptBinding(
+                        name: "greet",
+                        span: (14, 19),
+                        kind: function,
+                      ),
+                    ),
+                  ],
+                )
+                "#);
+            }),
+            cuts: Box::new(|cuts| {
+                assert_json_snapshot!(cuts, @r##"
+                [
+                  {
+                    "enclosure": "\"Welcome, #{name}!\"",
+                    "outer": "\"Welcome, #{name}!\"",
+                    "inner": "Welcome, #{name}!",
+                    "vars": [
+                      {
+                        "outer": "#{name}",
+                        "inner": "name"
+                      }
+                    ]
+                  }
+                ]
+                "##);
+            }),
+            interpolate: Box::new(|interp| {
+                assert_json_snapshot!(interp, @r#"
+                [
+                  "Welcome, {0}!"
+                ]
+                "#);
+            }),
+            annotations: Box::new(|annot| {
+                assert_json_snapshot!(annot, @r##"
+                [
+                  [
+                    [
+                      {
+                        "outer": "# @prompt",
+                        "inner": " @prompt"
+                      }
+                    ]
+                  ]
+                ]
+                "##);
+            }),
+        },
+    );
+}
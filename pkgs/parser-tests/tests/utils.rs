@@ -8,28 +8,40 @@ use volumen_parser_py::ParserPy as ParserPyRuff;
 use volumen_parser_py_tree_sitter::ParserPy as ParserPyTreeSitter;
 use volumen_parser_rb::ParserRb;
 use volumen_parser_ts::ParserTs as ParserTsOxc;
-use volumen_types::{ParseResult, Prompt, PromptContentToken};
+use volumen_types::{
+    ParseOptions, ParseResult, ParseResultPartial, ParseResultSuccess, Prompt, PromptContentToken,
+};
 
 type Parsers = [(&'static str, Parse)];
 
-type Parse = fn(&str, &str) -> ParseResult;
+type Parse = fn(&str, &str, &ParseOptions) -> ParseResult;
 
-static TS_PARSERS: &Parsers = &[("ParserTsOxc", ParserTsOxc::parse)];
+/// Parses the source with a fresh parser instance, so the tests don't share
+/// the parser state.
+fn parse_with<P: VolumenParser + Default>(
+    source: &str,
+    filename: &str,
+    options: &ParseOptions,
+) -> ParseResult {
+    P::default().parse(source, filename, options)
+}
+
+static TS_PARSERS: &Parsers = &[("ParserTsOxc", parse_with::<ParserTsOxc>)];
 
 static PY_PARSERS: &Parsers = &[
-    ("ParserPyRuff", ParserPyRuff::parse),
-    ("ParserPyTreeSitter", ParserPyTreeSitter::parse),
+    ("ParserPyRuff", parse_with::<ParserPyRuff>),
+    ("ParserPyTreeSitter", parse_with::<ParserPyTreeSitter>),
 ];
 
-static RB_PARSERS: &Parsers = &[("ParserRb", ParserRb::parse)];
+static RB_PARSERS: &Parsers = &[("ParserRb", parse_with::<ParserRb>)];
 
-static PHP_PARSERS: &Parsers = &[("ParserPhp", ParserPhp::parse)];
+static PHP_PARSERS: &Parsers = &[("ParserPhp", parse_with::<ParserPhp>)];
 
-static JAVA_PARSERS: &Parsers = &[("ParserJava", ParserJava::parse)];
+static JAVA_PARSERS: &Parsers = &[("ParserJava", parse_with::<ParserJava>)];
 
-static GO_PARSERS: &Parsers = &[("ParserGo", ParserGo::parse)];
+static GO_PARSERS: &Parsers = &[("ParserGo", parse_with::<ParserGo>)];
 
-static CSHARP_PARSERS: &Parsers = &[("ParserCs", ParserCs::parse)];
+static CSHARP_PARSERS: &Parsers = &[("ParserCs", parse_with::<ParserCs>)];
 
 #[derive(Serialize)]
 pub struct PromptSourceCuts {
@@ -80,11 +92,18 @@ impl ParseTest {
     pub fn test(lang: &ParseTestLang, assertions: ParseAssertions) {
         let mut insta_settings = insta::Settings::new();
         insta_settings.add_redaction(".error", "<error>");
+        // Diagnostics and the parser id are specific to a parser implementation,
+        // so when the snapshot is shared between multiple parsers, they must be
+        // redacted.
+        if lang.parsers().len() > 1 {
+            insta_settings.add_redaction(".diagnostics", "<diagnostics>");
+            insta_settings.add_redaction(".parser", "<parser>");
+        }
 
         insta_settings.bind(|| {
             let mut results = vec![];
             for (parser, parse) in lang.parsers() {
-                let result = parse(lang.source, lang.filename);
+                let result = parse(lang.source, lang.filename, &lang.options);
                 results.push(ParseTestResult { result, parser });
             }
 
@@ -100,9 +119,9 @@ impl ParseTest {
                     });
 
                     match &result.result {
-                        ParseResult::ParseResultSuccess(result_success) => {
-                            let cuts: Vec<PromptSourceCuts> = result_success
-                                .prompts
+                        ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. })
+                        | ParseResult::ParseResultPartial(ParseResultPartial { prompts, .. }) => {
+                            let cuts: Vec<PromptSourceCuts> = prompts
                                 .iter()
                                 .map(|prompt| PromptSourceCuts::cut(lang.source, prompt))
                                 .collect();
@@ -111,8 +130,7 @@ impl ParseTest {
                                 (assertions.cuts)(cuts);
                             });
 
-                            let interpolations : Vec<String> = result_success
-                                .prompts
+                            let interpolations : Vec<String> = prompts
                                 .iter()
                                 .map(|prompt| {
                                     // Iterate through content tokens, mapping str tokens to substrings
@@ -149,8 +167,7 @@ impl ParseTest {
                                 (assertions.interpolate)(interpolations);
                             });
 
-                            let annotations : Vec<Vec<Vec<PromptAnnotationCuts>>> = result_success
-                                .prompts
+                            let annotations : Vec<Vec<Vec<PromptAnnotationCuts>>> = prompts
                                 .iter()
                                 .map(|prompt| {
                                     prompt
@@ -220,6 +237,7 @@ pub struct ParseTestLang {
     pub source: &'static str,
     pub lang: ParseLang,
     pub filename: &'static str,
+    pub options: ParseOptions,
 }
 
 impl ParseTestLang {
@@ -232,6 +250,7 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Ts,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
@@ -244,6 +263,7 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Py,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
@@ -256,6 +276,7 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Rb,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
@@ -268,6 +289,7 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Php,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
@@ -280,6 +302,7 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Java,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
@@ -292,6 +315,7 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Go,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
@@ -304,9 +328,15 @@ impl ParseTestLang {
             source,
             lang: ParseLang::Cs,
             filename,
+            options: ParseOptions::default(),
         }
     }
 
+    pub fn with_options(mut self, options: ParseOptions) -> ParseTestLang {
+        self.options = options;
+        self
+    }
+
     pub fn parsers(&self) -> &Parsers {
         self.lang.parsers()
     }
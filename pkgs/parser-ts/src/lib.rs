@@ -1,41 +1,105 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use oxc_allocator::Allocator;
 use oxc_allocator::Vec as OxcVec;
 use oxc_ast::{Comment, Visit, ast};
-use oxc_parser::{ParseOptions, Parser};
+use oxc_parser::{ParseOptions as OxcParseOptions, Parser};
 use oxc_span::{GetSpan, SourceType};
 pub use volumen_parser_core::VolumenParser;
 use volumen_parser_core::*;
 use volumen_types::*;
 
-pub struct ParserTs {}
-
-/// Represents a segment in a string concatenation expression
-#[derive(Debug, Clone)]
-enum ConcatSegment {
-    /// String literal segment with its span
-    String(SpanShape),
-    /// Variable identifier segment with its span
-    Variable(SpanShape),
-    /// Primitive value (number, boolean) that should be stringified
-    Primitive(SpanShape),
-    /// Other expression types (objects, arrays, etc.) that we don't handle
-    Other,
+pub struct ParserTs {
+    /// AST arena, reset before each parse to reuse the allocated memory.
+    allocator: Allocator,
+}
+
+impl ParserTs {
+    pub fn new() -> Self {
+        Self {
+            allocator: Allocator::default(),
+        }
+    }
+}
+
+impl Default for ParserTs {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl VolumenParser for ParserTs {
-    fn parse(source: &str, filename: &str) -> ParseResult {
-        let allocator = Allocator::default();
+    fn parse(&mut self, source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        // Unknown extensions are parsed as TypeScript
+        let (language, dialect) = match file_language(filename) {
+            Some((language @ (ParseLanguage::Ts | ParseLanguage::Js), dialect)) => {
+                (language, dialect)
+            }
+            _ => (ParseLanguage::Ts, None),
+        };
+        self.parse_as(source, filename, language, dialect, options)
+    }
 
-        let source_type =
-            SourceType::from_path(filename).unwrap_or(SourceType::default().with_typescript(true));
+    fn parse_as(
+        &mut self,
+        source: &str,
+        filename: &str,
+        language: ParseLanguage,
+        dialect: Option<ParseDialect>,
+        options: &ParseOptions,
+    ) -> ParseResult {
+        self.allocator.reset();
+        let allocator = &self.allocator;
+
+        // oxc resolves the source type details from the path, e.g., `.d.ts`
+        // declarations, unless it contradicts the resolved language, e.g.,
+        // for the mapped `.es` or the detected extensionless files. Scripts
+        // are parsed with JSX, e.g., for `#!/usr/bin/env node`, as `.js`
+        // files may contain it too
+        let source_type = SourceType::from_path(filename)
+            .ok()
+            .filter(|source_type| {
+                source_type.is_typescript() == (language == ParseLanguage::Ts)
+                    && (dialect.is_none() || source_type.is_jsx())
+            })
+            .unwrap_or_else(|| match (&language, &dialect) {
+                (ParseLanguage::Js, _) => SourceType::jsx(),
+                (_, Some(_)) => SourceType::tsx(),
+                _ => SourceType::ts(),
+            });
+
+        // Skip sources exceeding the maximum file size
+        if let Some(result) =
+            file_size_error(source, options, language.clone(), dialect.clone(), "oxc")
+        {
+            return result;
+        }
 
-        let parser_return = Parser::new(&allocator, source, source_type)
-            .with_options(ParseOptions::default())
+        let parser_return = Parser::new(allocator, source, source_type)
+            .with_options(OxcParseOptions::default())
             .parse();
 
-        if !parser_return.errors.is_empty() {
+        let diagnostics: Vec<ParseDiagnostic> = parser_return
+            .errors
+            .iter()
+            .map(|e| {
+                // Primary label points to the error location, fallback to
+                // the source start when the error has no labels.
+                let span = e
+                    .labels
+                    .as_ref()
+                    .and_then(|labels| labels.first())
+                    .map(|label| {
+                        let start = label.offset() as u32;
+                        (start, start + label.len() as u32)
+                    })
+                    .unwrap_or((0, 0));
+                error_diagnostic("oxc", "syntax-error", e.message.to_string(), span)
+            })
+            .collect();
+
+        // Unrecoverable errors leave the program empty
+        if parser_return.panicked {
             let error_messages: Vec<String> = parser_return
                 .errors
                 .iter()
@@ -45,20 +109,78 @@ impl VolumenParser for ParserTs {
             return ParseResult::ParseResultError(ParseResultError {
                 state: ParseResultErrorStateError,
                 error: error_messages.join("; "),
+                diagnostics,
+                language: Some(language),
+                dialect,
+                detection: None,
+                parser: Some("oxc".to_string()),
             });
         }
 
+        let detector = PromptDetector::new(options);
         let mut visitor = PromptVisitor::new(
             source,
             filename.to_string(),
             &parser_return.program.comments,
+            detector.clone(),
         );
         visitor.visit_program(&parser_return.program);
 
-        ParseResult::ParseResultSuccess(ParseResultSuccess {
-            state: ParseResultSuccessStateSuccess,
-            prompts: visitor.prompts,
-        })
+        // Detect prompts passed to LLM SDK call sites
+        CallSiteVisitor {
+            visitor: &mut visitor,
+            detector: detector.clone(),
+            stmt_start_stack: Vec::new(),
+        }
+        .visit_program(&parser_return.program);
+
+        // Track where the prompt bindings are referenced, e.g., `send(prompt)`
+        let mut references = ReferenceVisitor {
+            references: PromptReferences::new(&visitor.prompts),
+        };
+        references.visit_program(&parser_return.program);
+        references.references.apply(&mut visitor.prompts);
+
+        // Collect the module imports and exports for the project analysis
+        let (imports, exports) = visitor.module_links(&parser_return.program);
+
+        // Decode escape sequences in string tokens
+        let mut prompts = visitor.prompts;
+        let mut groups = visitor.groups;
+        resolve_escapes(&mut prompts, source, |_| EscapeStyle::Js);
+
+        // Assign role hints from annotations, e.g., `// @prompt system`
+        detector.assign_annotation_roles(source, &mut prompts);
+
+        if diagnostics.is_empty() {
+            ParseResult::ParseResultSuccess(ParseResultSuccess {
+                state: ParseResultSuccessStateSuccess,
+                language,
+                dialect,
+                detection: None,
+                parser: "oxc".to_string(),
+                prompts,
+                groups: (!groups.is_empty()).then_some(groups),
+                imports: (!imports.is_empty()).then_some(imports),
+                exports: (!exports.is_empty()).then_some(exports),
+            })
+        } else {
+            retain_prompts(&mut prompts, &mut groups, |prompt| {
+                !overlaps_diagnostics(prompt.enclosure, &diagnostics)
+            });
+            ParseResult::ParseResultPartial(ParseResultPartial {
+                state: ParseResultPartialStatePartial,
+                language,
+                dialect,
+                detection: None,
+                parser: "oxc".to_string(),
+                prompts,
+                groups: (!groups.is_empty()).then_some(groups),
+                imports: (!imports.is_empty()).then_some(imports),
+                exports: (!exports.is_empty()).then_some(exports),
+                diagnostics,
+            })
+        }
     }
 }
 
@@ -69,32 +191,57 @@ struct PromptVisitor<'a> {
     file: String,
     /// Collected prompts.
     prompts: Vec<Prompt>,
-    /// Stack of identifier sets with prompt annotations.
-    prompt_idents_stack: Vec<HashSet<String>>,
+    /// Collected chat message groups.
+    groups: Vec<PromptGroup>,
+    /// Identifier definitions across the function and block scopes.
+    scope: ScopeTracker,
     /// Parsed comments.
     comments: &'a OxcVec<'a, Comment>,
+    /// Ranges marked with `@prompts` and `@prompt-begin`/`@prompt-end`.
+    regions: PromptRegions,
     /// Stack of current statement spans (VariableDeclaration/ExpressionStatement)
     stmt_span_stack: Vec<oxc_span::Span>,
     /// Stack of annotations collected for the current statement
     stmt_annotations_stack: Vec<Vec<PromptAnnotation>>,
+    /// Stack of opt-out flags for the current statement
+    stmt_ignored_stack: Vec<bool>,
     /// Earliest leading annotation start for current statement
     stmt_leading_start_stack: Vec<Option<u32>>,
-    /// Per-scope map of identifier -> def-time annotations
-    def_prompt_annotations_stack: Vec<HashMap<String, Vec<PromptAnnotation>>>,
+    /// Anonymous function start -> name and definition spans, e.g., for
+    /// `const buildPrompt = () => ...`
+    function_names: HashMap<u32, (oxc_span::Span, oxc_span::Span)>,
+    /// Prompt detection rules.
+    detector: PromptDetector,
 }
 
 impl<'a> PromptVisitor<'a> {
-    fn new(code: &'a str, file: String, comments: &'a OxcVec<'a, Comment>) -> Self {
+    fn new(
+        code: &'a str,
+        file: String,
+        comments: &'a OxcVec<'a, Comment>,
+        detector: PromptDetector,
+    ) -> Self {
+        let regions = PromptRegions::new(
+            code,
+            comments
+                .iter()
+                .map(|c| (c.span.start, c.span.end, c.span.source_text(code))),
+            &detector,
+        );
         Self {
             code,
             file,
             prompts: Vec::new(),
-            prompt_idents_stack: vec![HashSet::new()],
+            groups: Vec::new(),
+            scope: ScopeTracker::new(detector.clone()),
             comments,
+            regions,
             stmt_span_stack: Vec::new(),
             stmt_annotations_stack: Vec::new(),
+            stmt_ignored_stack: Vec::new(),
             stmt_leading_start_stack: Vec::new(),
-            def_prompt_annotations_stack: vec![HashMap::new()],
+            function_names: HashMap::new(),
+            detector,
         }
     }
 
@@ -110,61 +257,31 @@ impl<'a> PromptVisitor<'a> {
         SpanShape { outer, inner }
     }
 
-    fn build_content_tokens(&self, span: &SpanShape, vars: &[PromptVar]) -> Vec<PromptContentToken> {
-        if vars.is_empty() {
-            // Simple case: single str token
-            return vec![PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: span.inner,
-                },
-            )];
-        }
-
-        let mut tokens = Vec::new();
-        let mut pos = span.inner.0;
-
-        for (var_idx, var) in vars.iter().enumerate() {
-            // Add str token before variable (if any content)
-            if pos < var.span.outer.0 {
-                tokens.push(PromptContentToken::PromptContentTokenStr(
-                    PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: (pos, var.span.outer.0),
-                    },
-                ));
-            }
-
-            // Add var token
-            tokens.push(PromptContentToken::PromptContentTokenVar(
-                PromptContentTokenVar {
-                    r#type: PromptContentTokenVarTypeVar,
-                    span: var.span.outer,
-                    index: var_idx as u32,
-                },
-            ));
-
-            pos = var.span.outer.1;
-        }
-
-        // Add trailing str token (if any content)
-        if pos < span.inner.1 {
-            tokens.push(PromptContentToken::PromptContentTokenStr(
-                PromptContentTokenStr {
-                    r#type: PromptContentTokenStrTypeStr,
-                    span: (pos, span.inner.1),
-                },
-            ));
-        }
-
-        tokens
-    }
-
     fn process_variable_declarator(
         &mut self,
         declarator: &ast::VariableDeclarator<'a>,
         has_stmt_prompt: bool,
     ) {
+        // Declarations shadow the outer identifiers, while `var` is hoisted
+        // to the function scope
+        let mut identifiers = Vec::new();
+        self.extract_binding_identifiers(&declarator.id, &mut identifiers);
+        for (ident_name, _) in &identifiers {
+            if matches!(declarator.kind, ast::VariableDeclarationKind::Var) {
+                self.scope.declare_hoisted(ident_name);
+            } else {
+                self.scope.declare(ident_name);
+            }
+        }
+
+        // Opt-out annotations keep the identifiers ignored in reassignments
+        if self.is_statement_ignored() {
+            for (ident_name, _) in &identifiers {
+                self.scope.mark_ignored_ident(ident_name);
+            }
+            return;
+        }
+
         // Check if this is a destructuring pattern (object or array)
         let is_destructuring = matches!(
             &declarator.id.kind,
@@ -174,32 +291,30 @@ impl<'a> PromptVisitor<'a> {
         if is_destructuring && has_stmt_prompt {
             // Handle destructuring patterns
             if let Some(init) = &declarator.init {
-                // Extract all identifiers from the left side
-                let mut identifiers = Vec::new();
-                self.extract_binding_identifiers(&declarator.id, &mut identifiers);
-
                 // Extract all string/template values from the right side
                 let mut values = Vec::new();
                 self.extract_expression_values(init, &mut values);
 
                 // Match identifiers with values by position
-                for (i, ident_name) in identifiers.iter().enumerate() {
+                for (i, (ident_name, ident_span)) in identifiers.iter().enumerate() {
                     if let Some((value_span, is_template)) = values.get(i) {
                         if self.is_prompt(ident_name, has_stmt_prompt) {
                             // Mark this identifier as a prompt variable
-                            if let Some(scope) = self.prompt_idents_stack.last_mut() {
-                                scope.insert(ident_name.clone());
-                            }
+                            self.scope.mark_prompt_ident(ident_name);
 
                             // Store definition-time annotations
                             if let Some(ann) = self.stmt_annotations_stack.last()
                                 && !ann.is_empty()
-                                && let Some(scope) = self.def_prompt_annotations_stack.last_mut()
                             {
-                                scope.insert(ident_name.clone(), ann.clone());
+                                self.scope.store_def_annotation(ident_name, ann.clone());
                             }
 
                             // Create a prompt for this value
+                            let binding = PromptBinding {
+                                name: ident_name.clone(),
+                                span: self.span_outer(ident_span),
+                                kind: PromptBindingKind::Destructured,
+                            };
                             let (has_prompt, annotations, enclosure) =
                                 self.resolve_prompt_meta(ident_name, value_span);
 
@@ -215,7 +330,7 @@ impl<'a> PromptVisitor<'a> {
                                     Vec::new()
                                 };
 
-                                let content = self.build_content_tokens(&span, &vars);
+                                let content = build_content_tokens(&span, &vars);
 
             let prompt = Prompt {
                 file: self.file.clone(),
@@ -223,11 +338,14 @@ impl<'a> PromptVisitor<'a> {
                 enclosure,
                 vars,
                 annotations,
+                binding,
                 content,
                 joint: SpanShape {
                     outer: (0, 0),
                     inner: (0, 0),
                 },
+                role: None,
+                references: None,
             };
             self.prompts.push(prompt);
                             }
@@ -238,15 +356,12 @@ impl<'a> PromptVisitor<'a> {
         } else if let ast::BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind {
             // Handle simple identifier (existing code)
             if has_stmt_prompt {
-                if let Some(scope) = self.prompt_idents_stack.last_mut() {
-                    scope.insert(ident.name.to_string());
-                }
+                self.scope.mark_prompt_ident(&ident.name);
                 // Persist definition-time annotations for later reassignments
                 if let Some(ann) = self.stmt_annotations_stack.last()
                     && !ann.is_empty()
-                    && let Some(scope) = self.def_prompt_annotations_stack.last_mut()
                 {
-                    scope.insert(ident.name.to_string(), ann.clone());
+                    self.scope.store_def_annotation(&ident.name, ann.clone());
                 }
             }
 
@@ -272,21 +387,22 @@ impl<'a> PromptVisitor<'a> {
             if has_type_annotation
                 && let Some(ann) = self.stmt_annotations_stack.last()
                 && !ann.is_empty()
-                && let Some(scope) = self.def_prompt_annotations_stack.last_mut()
             {
-                scope.insert(ident.name.to_string(), ann.clone());
+                self.scope.store_def_annotation(&ident.name, ann.clone());
             }
 
+            let binding = PromptBinding {
+                name: ident.name.to_string(),
+                span: self.span_outer(&ident.span),
+                kind: if matches!(declarator.kind, ast::VariableDeclarationKind::Const) {
+                    PromptBindingKind::Const
+                } else {
+                    PromptBindingKind::Local
+                },
+            };
+
             if let Some(init) = &declarator.init {
                 match init {
-                    ast::Expression::TemplateLiteral(template) => {
-                        self.process_template_literal(&ident.name, template);
-                    }
-
-                    ast::Expression::StringLiteral(string_literal) => {
-                        self.process_string_literal(&ident.name, string_literal);
-                    }
-
                     ast::Expression::AssignmentExpression(assign_expr) => {
                         // Handle chained assignment in initializer: const hello = world = "Hi"
                         // Walk the chain to find the ultimate value
@@ -299,12 +415,12 @@ impl<'a> PromptVisitor<'a> {
                                 }
                                 ast::Expression::TemplateLiteral(template) => {
                                     // Found the ultimate value - process it for current identifier
-                                    self.process_template_literal(&ident.name, template);
+                                    self.process_template_literal(&binding, template);
                                     break;
                                 }
                                 ast::Expression::StringLiteral(string_literal) => {
                                     // Found the ultimate value - process it for current identifier
-                                    self.process_string_literal(&ident.name, string_literal);
+                                    self.process_string_literal(&binding, string_literal);
                                     break;
                                 }
                                 _ => {
@@ -318,19 +434,7 @@ impl<'a> PromptVisitor<'a> {
                         // The visitor will automatically handle it via enter_node
                     }
 
-                    ast::Expression::BinaryExpression(binary) => {
-                        self.process_binary_expression(&ident.name, binary);
-                    }
-
-                    ast::Expression::ArrayExpression(array) => {
-                        self.process_array_expression(&ident.name, array);
-                    }
-
-                    ast::Expression::CallExpression(call) => {
-                        self.process_call_expression(&ident.name, call);
-                    }
-
-                    _ => {}
+                    _ => self.process_value(&binding, init),
                 }
             }
         }
@@ -338,15 +442,19 @@ impl<'a> PromptVisitor<'a> {
 
     fn process_assignment_expression(&mut self, expr: &ast::AssignmentExpression<'a>) {
         if let ast::AssignmentTarget::AssignmentTargetIdentifier(ident) = &expr.left {
-            match &expr.right {
-                ast::Expression::TemplateLiteral(template) => {
-                    self.process_template_literal(&ident.name, template);
-                }
+            // Opt-out annotations keep the identifier ignored in reassignments
+            if self.is_statement_ignored() {
+                self.scope.mark_ignored_ident(&ident.name);
+                return;
+            }
 
-                ast::Expression::StringLiteral(string_literal) => {
-                    self.process_string_literal(&ident.name, string_literal);
-                }
+            let binding = PromptBinding {
+                name: ident.name.to_string(),
+                span: self.span_outer(&ident.span),
+                kind: PromptBindingKind::Local,
+            };
 
+            match &expr.right {
                 ast::Expression::AssignmentExpression(nested_assign) => {
                     // Handle chained assignment: a = b = "value"
                     // Walk the chain to find the ultimate value
@@ -359,12 +467,12 @@ impl<'a> PromptVisitor<'a> {
                             }
                             ast::Expression::TemplateLiteral(template) => {
                                 // Found the ultimate value - process it for current identifier
-                                self.process_template_literal(&ident.name, template);
+                                self.process_template_literal(&binding, template);
                                 break;
                             }
                             ast::Expression::StringLiteral(string_literal) => {
                                 // Found the ultimate value - process it for current identifier
-                                self.process_string_literal(&ident.name, string_literal);
+                                self.process_string_literal(&binding, string_literal);
                                 break;
                             }
                             _ => {
@@ -378,21 +486,105 @@ impl<'a> PromptVisitor<'a> {
                     self.process_assignment_expression(nested_assign.as_ref());
                 }
 
-                ast::Expression::BinaryExpression(binary) => {
-                    self.process_binary_expression(&ident.name, binary);
-                }
+                right => self.process_value(&binding, right),
+            }
+        }
+    }
 
-                ast::Expression::ArrayExpression(array) => {
-                    self.process_array_expression(&ident.name, array);
-                }
+    /// Process a value bound to the prompt binding, i.e., an assigned or
+    /// returned string, template, concatenation, array join or messages array.
+    fn process_value(&mut self, binding: &PromptBinding, value: &ast::Expression<'a>) {
+        // Handle messages: [{ role: "system", content: "..." }]
+        let messages = self.extract_messages(value);
+        if !messages.is_empty() {
+            self.process_messages(binding, value, messages);
+            return;
+        }
 
-                ast::Expression::CallExpression(call) => {
-                    self.process_call_expression(&ident.name, call);
-                }
+        match value {
+            ast::Expression::TemplateLiteral(template) => {
+                self.process_template_literal(binding, template);
+            }
 
-                _ => {}
+            ast::Expression::StringLiteral(string_literal) => {
+                self.process_string_literal(binding, string_literal);
+            }
+
+            ast::Expression::BinaryExpression(binary) => {
+                self.process_binary_expression(binding, binary);
+            }
+
+            ast::Expression::ArrayExpression(array) => {
+                self.process_array_expression(binding, array);
             }
+
+            ast::Expression::CallExpression(call) => {
+                self.process_call_expression(binding, call);
+            }
+
+            _ => {}
+        }
+    }
+
+    /// Process a value returned from the enclosing prompt function as a prompt
+    /// bound to the function name, marking the variables rooted at the
+    /// function parameters.
+    fn process_return(&mut self, function: &PromptFunction, value: &ast::Expression<'a>) {
+        if self.is_statement_ignored() {
+            return;
         }
+        let prompts_len = self.prompts.len();
+        self.scope.mark_prompt_ident(&function.binding.name);
+        self.process_value(&function.binding, value);
+        function.mark_parameter_vars(&mut self.prompts, prompts_len);
+    }
+
+    /// Creates the prompt function for a function with a prompt name or
+    /// a leading `@prompt` annotation. Anonymous functions take the name of
+    /// the variable, property or method they're assigned to, e.g.,
+    /// `const buildPrompt = (user) => ...`.
+    fn prompt_function(
+        &self,
+        span: &oxc_span::Span,
+        id: Option<&ast::BindingIdentifier<'a>>,
+        params: &ast::FormalParameters<'a>,
+    ) -> Option<PromptFunction> {
+        let (name_span, def_span) = match id {
+            Some(id) => (id.span, *span),
+            None => self.function_names.get(&span.start).copied()?,
+        };
+        if self.has_ignore_annotation(&oxc_span::Span::new(def_span.start, name_span.end)) {
+            return None;
+        }
+
+        PromptFunction::new(
+            &self.detector,
+            self.code,
+            self.span_outer(&name_span),
+            self.param_identifiers(params),
+            self.collect_adjacent_leading_comments(&def_span),
+        )
+    }
+
+    /// Declare the function parameters in the function scope, shadowing the
+    /// outer identifiers.
+    fn declare_params(&mut self, params: &ast::FormalParameters<'a>) {
+        for name in self.param_identifiers(params) {
+            self.scope.declare(&name);
+        }
+    }
+
+    /// Collect the function parameter names, including the destructured and
+    /// rest ones.
+    fn param_identifiers(&self, params: &ast::FormalParameters<'a>) -> Vec<String> {
+        let mut identifiers = Vec::new();
+        for param in &params.items {
+            self.extract_binding_identifiers(&param.pattern, &mut identifiers);
+        }
+        if let Some(rest) = &params.rest {
+            self.extract_binding_identifiers(&rest.argument, &mut identifiers);
+        }
+        identifiers.into_iter().map(|(name, _)| name).collect()
     }
 
     fn extract_template_vars(&self, template: &ast::TemplateLiteral<'a>) -> Vec<PromptVar> {
@@ -418,9 +610,7 @@ impl<'a> PromptVisitor<'a> {
             let exp = &self.code[start as usize..end as usize];
             let outer = (start, end);
             let inner = (expr_span.start, expr_span.end);
-            vars.push(PromptVar {
-                span: SpanShape { outer, inner },
-            });
+            vars.push(prompt_var(self.code, SpanShape { outer, inner }, inner));
         }
 
         vars
@@ -430,113 +620,181 @@ impl<'a> PromptVisitor<'a> {
         template.span().source_text(self.code).to_string()
     }
 
-    fn process_template_literal(&mut self, ident_name: &str, template: &ast::TemplateLiteral<'a>) {
+    fn process_template_literal(
+        &mut self,
+        binding: &PromptBinding,
+        template: &ast::TemplateLiteral<'a>,
+    ) {
         let (has_prompt, annotations, enclosure) =
-            self.resolve_prompt_meta(ident_name, &template.span);
+            self.resolve_prompt_meta(&binding.name, &template.span);
         if has_prompt {
             let span = self.span_shape_literal(&template.span);
             let vars = self.extract_template_vars(template);
-            let content = self.build_content_tokens(&span, &vars);
+            let content = build_content_tokens(&span, &vars);
             let prompt = Prompt {
                 file: self.file.clone(),
                 span,
                 enclosure,
                 vars,
                 annotations,
+                binding: binding.clone(),
                 content,
                 joint: SpanShape {
                     outer: (0, 0),
                     inner: (0, 0),
                 },
+                role: None,
+                references: None,
             };
             self.prompts.push(prompt);
         }
     }
 
-    fn process_string_literal(&mut self, ident_name: &str, string: &ast::StringLiteral<'a>) {
+    fn process_string_literal(&mut self, binding: &PromptBinding, string: &ast::StringLiteral<'a>) {
         let (has_prompt, annotations, enclosure) =
-            self.resolve_prompt_meta(ident_name, &string.span);
+            self.resolve_prompt_meta(&binding.name, &string.span);
         if has_prompt {
             let span = self.span_shape_literal(&string.span);
             let vars = Vec::new();
-            let content = self.build_content_tokens(&span, &vars);
+            let content = build_content_tokens(&span, &vars);
             let prompt = Prompt {
                 file: self.file.clone(),
                 span,
                 enclosure,
                 vars,
                 annotations,
+                binding: binding.clone(),
                 content,
                 joint: SpanShape {
                     outer: (0, 0),
                     inner: (0, 0),
                 },
+                role: None,
+                references: None,
             };
             self.prompts.push(prompt);
         }
     }
 
-    fn process_binary_expression(&mut self, ident_name: &str, binary: &ast::BinaryExpression<'a>) {
+    /// Extract chat message roles and string contents from a message object,
+    /// e.g., `{ role: "system", content: "..." }`, or from an array of message
+    /// objects.
+    fn extract_messages<'b>(
+        &self,
+        expr: &'b ast::Expression<'a>,
+    ) -> Vec<(Option<PromptRole>, &'b ast::Expression<'a>)> {
+        match expr {
+            ast::Expression::ObjectExpression(obj) => {
+                self.extract_message(obj).into_iter().collect()
+            }
+            ast::Expression::ArrayExpression(array) => array
+                .elements
+                .iter()
+                .filter_map(|element| match element {
+                    ast::ArrayExpressionElement::ObjectExpression(obj) => self.extract_message(obj),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Extract the role and the string content from a message object. Objects
+    /// without the role property aren't messages.
+    fn extract_message<'b>(
+        &self,
+        obj: &'b ast::ObjectExpression<'a>,
+    ) -> Option<(Option<PromptRole>, &'b ast::Expression<'a>)> {
+        let mut role = None;
+        let mut content = None;
+        for prop in &obj.properties {
+            let ast::ObjectPropertyKind::ObjectProperty(prop) = prop else {
+                continue;
+            };
+            let key = prop.key.span().source_text(self.code);
+            if is_role_key(key) {
+                role = Some(parse_role_value(prop.value.span().source_text(self.code)));
+            } else if is_content_key(key)
+                && matches!(
+                    prop.value,
+                    ast::Expression::StringLiteral(_) | ast::Expression::TemplateLiteral(_)
+                )
+            {
+                content = Some(&prop.value);
+            }
+        }
+        Some((role?, content?))
+    }
+
+    /// Process message contents as prompts with the message roles. Messages
+    /// arrays are grouped into conversations.
+    fn process_messages(
+        &mut self,
+        binding: &PromptBinding,
+        value: &ast::Expression<'a>,
+        messages: Vec<(Option<PromptRole>, &ast::Expression<'a>)>,
+    ) {
+        // Chat messages are prompts by structure
+        self.scope.mark_prompt_ident(&binding.name);
+
+        let group_start = self.prompts.len();
+        for (role, content) in messages {
+            let prompts_len = self.prompts.len();
+            match content {
+                ast::Expression::StringLiteral(string) => {
+                    self.process_string_literal(binding, string);
+                }
+                ast::Expression::TemplateLiteral(template) => {
+                    self.process_template_literal(binding, template);
+                }
+                _ => {}
+            }
+            for prompt in &mut self.prompts[prompts_len..] {
+                prompt.role = role;
+            }
+        }
+
+        // Group the messages array into a conversation
+        if let ast::Expression::ArrayExpression(array) = value
+            && let Some(group) = prompt_group(
+                &self.file,
+                self.span_outer(&array.span),
+                binding,
+                &self.prompts,
+                group_start,
+            )
+        {
+            self.groups.push(group);
+        }
+    }
+
+    fn process_binary_expression(
+        &mut self,
+        binding: &PromptBinding,
+        binary: &ast::BinaryExpression<'a>,
+    ) {
         // Check if this is a concatenation (+ operator)
         if !matches!(binary.operator, ast::BinaryOperator::Addition) {
             return;
         }
 
         let (has_prompt, annotations, enclosure) =
-            self.resolve_prompt_meta(ident_name, &binary.span);
+            self.resolve_prompt_meta(&binding.name, &binary.span);
         if !has_prompt {
             return;
         }
 
         // Extract segments from the binary expression tree
         let segments = self.extract_concat_segments_from_binary(binary);
-        
-        // Check if we have any non-string/non-identifier segments (objects, arrays, etc.)
-        // In that case, don't treat this as a prompt
-        if segments.iter().any(|s| matches!(s, ConcatSegment::Other)) {
-            return;
-        }
-
-        if segments.is_empty() {
-            return;
-        }
-
-        // Build synthetic span for the concatenated result
-        // outer: entire expression "Hello, " + name + "!"
-        // inner: without outer quotes Hello, " + name + "!
         let outer = (binary.span.start, binary.span.end);
-        let inner_start = if let Some(first_seg) = segments.first() {
-            match first_seg {
-                ConcatSegment::String(span) => span.inner.0,
-                ConcatSegment::Variable(span) | ConcatSegment::Primitive(span) => span.outer.0,
-                ConcatSegment::Other => binary.span.start,
-            }
-        } else {
-            binary.span.start
-        };
-        let inner_end = if let Some(last_seg) = segments.last() {
-            match last_seg {
-                ConcatSegment::String(span) => span.inner.1,
-                ConcatSegment::Variable(span) | ConcatSegment::Primitive(span) => span.outer.1,
-                ConcatSegment::Other => binary.span.end,
-            }
-        } else {
-            binary.span.end
+        let Some(ConcatContent {
+            span,
+            vars,
+            content,
+        }) = concat_content(self.code, outer, "+", &segments)
+        else {
+            return;
         };
-        let inner = (inner_start, inner_end);
-        let span = SpanShape { outer, inner };
-
-        // Extract vars (variables only, not primitives)
-        let vars: Vec<PromptVar> = segments
-            .iter()
-            .filter_map(|seg| match seg {
-                ConcatSegment::Variable(var_span) => Some(PromptVar { span: var_span.clone() }),
-                _ => None,
-            })
-            .collect();
-
-        // Build content tokens from segments
-        let content = self.build_concat_content_tokens(&segments);
 
         let prompt = Prompt {
             file: self.file.clone(),
@@ -544,18 +802,25 @@ impl<'a> PromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint: SpanShape {
                 outer: (0, 0),
                 inner: (0, 0),
             },
+            role: None,
+            references: None,
         };
         self.prompts.push(prompt);
     }
 
-    fn process_array_expression(&mut self, ident_name: &str, array: &ast::ArrayExpression<'a>) {
+    fn process_array_expression(
+        &mut self,
+        binding: &PromptBinding,
+        array: &ast::ArrayExpression<'a>,
+    ) {
         let (has_prompt, annotations, enclosure) =
-            self.resolve_prompt_meta(ident_name, &array.span);
+            self.resolve_prompt_meta(&binding.name, &array.span);
         if !has_prompt {
             return;
         }
@@ -585,6 +850,7 @@ impl<'a> PromptVisitor<'a> {
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -592,9 +858,7 @@ impl<'a> PromptVisitor<'a> {
                     // Variable - add as var token
                     let outer = self.span_outer(&ident.span);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(self.code, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -609,9 +873,7 @@ impl<'a> PromptVisitor<'a> {
                     let expr_span = element.span();
                     let outer = self.span_outer(&expr_span);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(self.code, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -635,22 +897,25 @@ impl<'a> PromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint: SpanShape {
                 outer: (0, 0),
                 inner: (0, 0),
             },
+            role: None,
+            references: None,
         };
         self.prompts.push(prompt);
     }
 
-    fn process_call_expression(&mut self, ident_name: &str, call: &ast::CallExpression<'a>) {
+    fn process_call_expression(&mut self, binding: &PromptBinding, call: &ast::CallExpression<'a>) {
         // Check if this is an array.join() call
         if let ast::Expression::StaticMemberExpression(member) = &call.callee {
             if member.property.name == "join" {
                 // This is a .join() call - check if the object is an array
                 if let ast::Expression::ArrayExpression(array) = &member.object {
-                    self.process_array_join(ident_name, array, call);
+                    self.process_array_join(binding, array, call);
                     return;
                 }
             }
@@ -661,12 +926,12 @@ impl<'a> PromptVisitor<'a> {
 
     fn process_array_join(
         &mut self,
-        ident_name: &str,
+        binding: &PromptBinding,
         array: &ast::ArrayExpression<'a>,
         call: &ast::CallExpression<'a>,
     ) {
         let (has_prompt, annotations, enclosure) =
-            self.resolve_prompt_meta(ident_name, &call.span);
+            self.resolve_prompt_meta(&binding.name, &call.span);
         if !has_prompt {
             return;
         }
@@ -706,6 +971,7 @@ impl<'a> PromptVisitor<'a> {
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -716,6 +982,7 @@ impl<'a> PromptVisitor<'a> {
                         PromptContentTokenStr {
                             r#type: PromptContentTokenStrTypeStr,
                             span: span.inner,
+                            escapes: None,
                         },
                     ));
                 }
@@ -725,6 +992,7 @@ impl<'a> PromptVisitor<'a> {
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -732,9 +1000,7 @@ impl<'a> PromptVisitor<'a> {
                     // Variable - add as var token
                     let outer = self.span_outer(&ident.span);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(self.code, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -750,6 +1016,7 @@ impl<'a> PromptVisitor<'a> {
                         content.push(PromptContentToken::PromptContentTokenJoint(
                             PromptContentTokenJoint {
                                 r#type: PromptContentTokenJointTypeJoint,
+                                escapes: None,
                             },
                         ));
                     }
@@ -758,9 +1025,7 @@ impl<'a> PromptVisitor<'a> {
                     let expr_span = element.span();
                     let outer = self.span_outer(&expr_span);
                     let inner = outer;
-                    vars.push(PromptVar {
-                        span: SpanShape { outer, inner },
-                    });
+                    vars.push(prompt_var(self.code, SpanShape { outer, inner }, inner));
                     content.push(PromptContentToken::PromptContentTokenVar(
                         PromptContentTokenVar {
                             r#type: PromptContentTokenVarTypeVar,
@@ -784,8 +1049,11 @@ impl<'a> PromptVisitor<'a> {
             enclosure,
             vars,
             annotations,
+            binding: binding.clone(),
             content,
             joint,
+            role: None,
+            references: None,
         };
         self.prompts.push(prompt);
     }
@@ -798,211 +1066,53 @@ impl<'a> PromptVisitor<'a> {
 
     fn extract_concat_segments(&self, expr: &ast::Expression<'a>) -> Vec<ConcatSegment> {
         match expr {
-            ast::Expression::BinaryExpression(binary) 
-                if matches!(binary.operator, ast::BinaryOperator::Addition) => {
+            ast::Expression::BinaryExpression(binary)
+                if matches!(binary.operator, ast::BinaryOperator::Addition) =>
+            {
                 self.extract_concat_segments_from_binary(binary)
             }
             ast::Expression::StringLiteral(string) => {
-                let span = self.span_shape_literal(&string.span);
-                vec![ConcatSegment::String(span)]
-            }
-            ast::Expression::Identifier(ident) => {
-                // For identifiers: outer includes " + name + ", inner is just "name"
-                let outer = self.span_outer(&ident.span);
-                let inner = outer; // Identifier has no delimiters
-                
-                // Expand outer to include surrounding operators and spaces
-                let outer_expanded = self.expand_to_operators(outer);
-                
-                vec![ConcatSegment::Variable(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
+                vec![ConcatSegment::String(self.span_shape_literal(&string.span))]
             }
-            ast::Expression::CallExpression(call) => {
-                // Treat function calls as variables: format(x) is a variable
-                let outer = self.span_outer(&call.span);
-                let inner = outer;
-                let outer_expanded = self.expand_to_operators(outer);
-                
-                vec![ConcatSegment::Variable(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
-            }
-            ast::Expression::StaticMemberExpression(member) => {
-                // Treat member access as variables: obj.prop
-                let outer = self.span_outer(&member.span);
-                let inner = outer;
-                let outer_expanded = self.expand_to_operators(outer);
-                
-                vec![ConcatSegment::Variable(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
-            }
-            ast::Expression::ComputedMemberExpression(member) => {
-                // Treat member access as variables: obj[key]
-                let outer = self.span_outer(&member.span);
-                let inner = outer;
-                let outer_expanded = self.expand_to_operators(outer);
-                
-                vec![ConcatSegment::Variable(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
-            }
-            ast::Expression::PrivateFieldExpression(field) => {
-                // Treat private field access as variables: obj.#field
-                let outer = self.span_outer(&field.span);
-                let inner = outer;
-                let outer_expanded = self.expand_to_operators(outer);
-                
-                vec![ConcatSegment::Variable(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
-            }
-            ast::Expression::NumericLiteral(num) => {
-                let outer = self.span_outer(&num.span);
-                let inner = outer;
-                let outer_expanded = self.expand_to_operators(outer);
-                vec![ConcatSegment::Primitive(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
-            }
-            ast::Expression::BooleanLiteral(bool) => {
-                let outer = self.span_outer(&bool.span);
-                let inner = outer;
-                let outer_expanded = self.expand_to_operators(outer);
-                vec![ConcatSegment::Primitive(SpanShape {
-                    outer: outer_expanded,
-                    inner,
-                })]
-            }
-            // Objects, arrays, function calls, etc. - mark as "other" to skip prompt detection
-            ast::Expression::ObjectExpression(_)
-            | ast::Expression::ArrayExpression(_)
+            // Treat identifiers, function calls and member access as variables:
+            // name, format(x), obj.prop, obj[key] and obj.#field
+            ast::Expression::Identifier(_)
             | ast::Expression::CallExpression(_)
-            | ast::Expression::NewExpression(_) => {
-                vec![ConcatSegment::Other]
-            }
-            _ => vec![],
-        }
-    }
-
-    fn expand_to_operators(&self, span: Span) -> Span {
-        let (start, end) = span;
-        let mut new_start = start;
-        let mut new_end = end;
-
-        // Expand left to include " + "
-        let code_bytes = self.code.as_bytes();
-        let mut pos = start.saturating_sub(1) as usize;
-        while pos > 0 {
-            let ch = code_bytes.get(pos);
-            match ch {
-                Some(b' ') | Some(b'\t') => {
-                    pos -= 1;
-                }
-                Some(b'+') => {
-                    new_start = pos as u32;
-                    // Also consume space before +
-                    if pos > 0 && matches!(code_bytes.get(pos - 1), Some(b' ') | Some(b'\t')) {
-                        new_start = (pos - 1) as u32;
-                    }
-                    break;
-                }
-                _ => break,
+            | ast::Expression::StaticMemberExpression(_)
+            | ast::Expression::ComputedMemberExpression(_)
+            | ast::Expression::PrivateFieldExpression(_) => {
+                vec![ConcatSegment::Variable(self.span_outer(&expr.span()))]
             }
-        }
-
-        // Expand right to include " + "
-        let mut pos = end as usize;
-        while pos < code_bytes.len() {
-            let ch = code_bytes.get(pos);
-            match ch {
-                Some(b' ') | Some(b'\t') => {
-                    pos += 1;
-                }
-                Some(b'+') => {
-                    new_end = (pos + 1) as u32;
-                    // Also consume space after +
-                    if pos + 1 < code_bytes.len()
-                        && matches!(code_bytes.get(pos + 1), Some(b' ') | Some(b'\t'))
-                    {
-                        new_end = (pos + 2) as u32;
-                    }
-                    break;
-                }
-                _ => break,
+            ast::Expression::NumericLiteral(_) | ast::Expression::BooleanLiteral(_) => {
+                vec![ConcatSegment::Primitive(self.span_outer(&expr.span()))]
             }
+            // Objects, arrays, new expressions, etc. - mark as "other" to skip prompt detection
+            _ => vec![ConcatSegment::Other],
         }
-
-        (new_start, new_end)
-    }
-
-    fn build_concat_content_tokens(&self, segments: &[ConcatSegment]) -> Vec<PromptContentToken> {
-        let mut var_idx = 0u32;
-        segments
-            .iter()
-            .map(|seg| match seg {
-                ConcatSegment::String(span) => {
-                    PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: span.inner,
-                    })
-                }
-                ConcatSegment::Variable(span) => {
-                    let token = PromptContentToken::PromptContentTokenVar(PromptContentTokenVar {
-                        r#type: PromptContentTokenVarTypeVar,
-                        span: span.inner,
-                        index: var_idx,
-                    });
-                    var_idx += 1;
-                    token
-                }
-                ConcatSegment::Primitive(span) => {
-                    PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: span.inner,
-                    })
-                }
-                ConcatSegment::Other => {
-                    // This shouldn't happen as we filter these out earlier
-                    PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
-                        r#type: PromptContentTokenStrTypeStr,
-                        span: (0, 0),
-                    })
-                }
-            })
-            .collect()
     }
 
     fn is_prompt(&self, ident_name: &str, has_stmt_prompt: bool) -> bool {
-        if ident_name.to_lowercase().contains("prompt") || has_stmt_prompt {
+        if has_stmt_prompt {
             return true;
         }
 
-        for scope in self.prompt_idents_stack.iter().rev() {
-            if scope.contains(ident_name) {
-                return true;
-            }
-        }
+        // The nearest definition of the identifier wins over name detection
+        self.scope.is_prompt_ident(ident_name) || self.scope.is_prompt_name(ident_name)
+    }
 
-        false
+    fn is_statement_ignored(&self) -> bool {
+        self.stmt_ignored_stack.last().copied().unwrap_or(false)
     }
 
     /// Recursively extract all binding identifiers from a binding pattern
     fn extract_binding_identifiers(
         &self,
         pattern: &ast::BindingPattern<'a>,
-        identifiers: &mut Vec<String>,
+        identifiers: &mut Vec<(String, oxc_span::Span)>,
     ) {
         match &pattern.kind {
             ast::BindingPatternKind::BindingIdentifier(ident) => {
-                identifiers.push(ident.name.to_string());
+                identifiers.push((ident.name.to_string(), ident.span));
             }
             ast::BindingPatternKind::ObjectPattern(obj_pattern) => {
                 for prop in &obj_pattern.properties {
@@ -1094,16 +1204,13 @@ impl<'a> PromptVisitor<'a> {
         }
     }
 
-    /// Collect the block of leading comments immediately adjacent to the statement,
-    /// and merge them into a single annotation if any line contains @prompt.
-    fn collect_adjacent_leading_comments(
-        &self,
-        stmt_span: &oxc_span::Span,
-    ) -> Vec<PromptAnnotation> {
-        let mut block: Vec<&Comment> = Vec::new();
+    /// Collect the block of leading comments immediately adjacent to the statement.
+    fn leading_comment_block(&self, stmt_span: &oxc_span::Span) -> Vec<&'a Comment> {
+        let comments = self.comments;
+        let mut block: Vec<&'a Comment> = Vec::new();
         let mut comment_idx: isize = (self.comments.len() as isize) - 1;
         while comment_idx >= 0 {
-            let comment = &self.comments.get(comment_idx as usize);
+            let comment = &comments.get(comment_idx as usize);
             let comment = match comment {
                 Some(comment) => comment,
                 None => panic!("Unexpected missing comment at index {}", comment_idx),
@@ -1122,7 +1229,7 @@ impl<'a> PromptVisitor<'a> {
                     let mut j = comment_idx;
                     let mut last_start = stmt_span.start;
                     while j >= 0 {
-                        let cj = &self.comments[j as usize];
+                        let cj = &comments[j as usize];
                         if cj.span.end <= last_start {
                             let s = cj.span.end as usize;
                             let e = last_start as usize;
@@ -1147,7 +1254,16 @@ impl<'a> PromptVisitor<'a> {
 
             comment_idx -= 1;
         }
+        block
+    }
 
+    /// Merge the leading comment block into a single annotation if any line
+    /// contains @prompt.
+    fn collect_adjacent_leading_comments(
+        &self,
+        stmt_span: &oxc_span::Span,
+    ) -> Vec<PromptAnnotation> {
+        let block = self.leading_comment_block(stmt_span);
         if block.is_empty() {
             return Vec::new();
         }
@@ -1155,7 +1271,7 @@ impl<'a> PromptVisitor<'a> {
         // Check if any comment in the block contains a valid @prompt
         let has_prompt = block
             .iter()
-            .any(|c| parse_annotation(c.span.source_text(self.code)).unwrap_or(false));
+            .any(|c| self.detector.annotation(c.span.source_text(self.code)).unwrap_or(false));
         if !has_prompt {
             return Vec::new();
         }
@@ -1178,9 +1294,8 @@ impl<'a> PromptVisitor<'a> {
             })
             .collect();
 
-        vec![PromptAnnotation {
-            spans,
-        }]
+        let attributes = self.detector.annotation_attributes(self.code, &spans);
+        vec![PromptAnnotation { spans, attributes }]
     }
 
     /// Collect inline @prompt comments within the statement range, optionally before a node.
@@ -1194,20 +1309,37 @@ impl<'a> PromptVisitor<'a> {
         for c in self.comments.iter() {
             if c.span.start >= stmt_span.start && c.span.start < end_limit {
                 let full = c.span.source_text(self.code);
-                if parse_annotation(full).unwrap_or(false) {
+                if self.detector.annotation(full).unwrap_or(false) {
                     let (inner_start_offset, inner_end_offset) = compute_comment_inner_offsets(full);
-                    out.push(PromptAnnotation {
-                        spans: vec![SpanShape {
-                            outer: (c.span.start, c.span.end),
-                            inner: (c.span.start + inner_start_offset, c.span.start + inner_end_offset),
-                        }],
-                    });
+                    let spans = vec![SpanShape {
+                        outer: (c.span.start, c.span.end),
+                        inner: (c.span.start + inner_start_offset, c.span.start + inner_end_offset),
+                    }];
+                    let attributes = self.detector.annotation_attributes(self.code, &spans);
+                    out.push(PromptAnnotation { spans, attributes });
                 }
             }
         }
         out
     }
 
+    /// Check the leading block, inline and trailing comments on the statement's
+    /// last line for an opt-out annotation.
+    fn has_ignore_annotation(&self, stmt_span: &oxc_span::Span) -> bool {
+        let end = self.code[stmt_span.end as usize..]
+            .find('\n')
+            .map_or(self.code.len(), |offset| stmt_span.end as usize + offset)
+            as u32;
+        let inline = self
+            .comments
+            .iter()
+            .filter(|c| c.span.start >= stmt_span.start && c.span.start < end);
+        self.leading_comment_block(stmt_span)
+            .into_iter()
+            .chain(inline)
+            .any(|c| self.detector.ignore_annotation(c.span.source_text(self.code)))
+    }
+
     fn current_stmt_span(&self) -> Option<oxc_span::Span> {
         self.stmt_span_stack.last().copied()
     }
@@ -1226,20 +1358,19 @@ impl<'a> PromptVisitor<'a> {
 
         // Annotations from comment tracker are already validated to contain @prompt
         // If no annotations in current statement, use stored definition annotations
-        if annotations.is_empty() {
-            for scope in self.def_prompt_annotations_stack.iter().rev() {
-                if let Some(def) = scope.get(ident_name) {
-                    annotations = def.clone();
-                    break;
-                }
-            }
+        if annotations.is_empty()
+            && let Some(def) = self.scope.get_def_annotation(ident_name)
+        {
+            annotations = def;
         }
 
-        // Annotations are already validated to contain @prompt
-        let has_stmt_prompt = !annotations.is_empty();
+        let stmt_span = self.current_stmt_span().unwrap_or(*node_span);
+
+        // Annotations are already validated to contain @prompt, and statements
+        // in `@prompts` files and `@prompt-begin` regions are annotated too
+        let has_stmt_prompt = !annotations.is_empty() || self.regions.contains(stmt_span.start);
         let is_prompt = self.is_prompt(ident_name, has_stmt_prompt);
 
-        let stmt_span = self.current_stmt_span().unwrap_or(*node_span);
         let leading_start = self
             .stmt_leading_start_stack
             .last()
@@ -1265,12 +1396,91 @@ impl<'a> Visit<'a> for PromptVisitor<'a> {
                     annotations.push(a);
                 }
                 self.stmt_annotations_stack.push(annotations);
+                self.stmt_ignored_stack.push(self.has_ignore_annotation(&expr.span));
                 self.stmt_leading_start_stack.push(leading_start);
             }
 
-            oxc_ast::AstKind::Function(_) | oxc_ast::AstKind::ArrowFunctionExpression(_) => {
-                self.prompt_idents_stack.push(HashSet::new());
-                self.def_prompt_annotations_stack.push(HashMap::new());
+            oxc_ast::AstKind::Function(func) => {
+                let function = self.prompt_function(&func.span, func.id.as_ref(), &func.params);
+                self.scope.enter_function(function);
+                self.declare_params(&func.params);
+            }
+
+            oxc_ast::AstKind::ArrowFunctionExpression(arrow) => {
+                let function = self.prompt_function(&arrow.span, None, &arrow.params);
+                // Expression bodies are returned, e.g., `(user) => `Hello, ${user}``
+                if let Some(function) = &function
+                    && let Some(value) = arrow.get_expression()
+                {
+                    self.process_return(function, value);
+                }
+                self.scope.enter_function(function);
+                self.declare_params(&arrow.params);
+            }
+
+            oxc_ast::AstKind::BlockStatement(_)
+            | oxc_ast::AstKind::ForStatement(_)
+            | oxc_ast::AstKind::ForInStatement(_)
+            | oxc_ast::AstKind::ForOfStatement(_) => {
+                self.scope.enter_scope();
+            }
+
+            oxc_ast::AstKind::VariableDeclarator(declarator) => {
+                if let ast::BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind
+                    && let Some(init) = &declarator.init
+                    && is_function_expression(init)
+                {
+                    let def_span = self.current_stmt_span().unwrap_or(declarator.span);
+                    self.function_names
+                        .insert(init.span().start, (ident.span, def_span));
+                }
+            }
+
+            oxc_ast::AstKind::MethodDefinition(method) => {
+                self.function_names
+                    .insert(method.value.span.start, (method.key.span(), method.span));
+            }
+
+            oxc_ast::AstKind::PropertyDefinition(prop) => {
+                if let Some(value) = &prop.value
+                    && is_function_expression(value)
+                {
+                    self.function_names
+                        .insert(value.span().start, (prop.key.span(), prop.span));
+                }
+            }
+
+            oxc_ast::AstKind::ObjectProperty(prop) if is_function_expression(&prop.value) => {
+                self.function_names
+                    .insert(prop.value.span().start, (prop.key.span(), prop.span));
+            }
+
+            oxc_ast::AstKind::ReturnStatement(ret) => {
+                let function = self.scope.current_function().cloned();
+                self.stmt_span_stack.push(ret.span);
+                let leading = self.collect_adjacent_leading_comments(&ret.span);
+                let inline = self.collect_inline_prompt_comments(&ret.span, None);
+                let leading_start = leading
+                    .first()
+                    .and_then(|first| first.spans.first())
+                    .map(|s| s.outer.0);
+                let mut annotations: Vec<PromptAnnotation> =
+                    leading.into_iter().chain(inline).collect();
+                // Returns without own annotations take the function ones
+                if annotations.is_empty()
+                    && let Some(function) = &function
+                {
+                    annotations = function.annotations.clone();
+                }
+                self.stmt_annotations_stack.push(annotations);
+                self.stmt_ignored_stack
+                    .push(self.has_ignore_annotation(&ret.span));
+                self.stmt_leading_start_stack.push(leading_start);
+                if let Some(function) = &function
+                    && let Some(value) = &ret.argument
+                {
+                    self.process_return(function, value);
+                }
             }
 
             oxc_ast::AstKind::VariableDeclaration(decl) => {
@@ -1282,9 +1492,13 @@ impl<'a> Visit<'a> for PromptVisitor<'a> {
                 for a in leading.into_iter().chain(inline.into_iter()) {
                     annotations.push(a);
                 }
-                // Annotations are already validated to contain @prompt
-                let has_stmt_prompt = !annotations.is_empty();
+                // Annotations are already validated to contain @prompt, and
+                // statements in `@prompts` files and `@prompt-begin` regions
+                // are annotated too
+                let has_stmt_prompt =
+                    !annotations.is_empty() || self.regions.contains(decl.span.start);
                 self.stmt_annotations_stack.push(annotations);
+                self.stmt_ignored_stack.push(self.has_ignore_annotation(&decl.span));
                 self.stmt_leading_start_stack.push(leading_start);
                 for declarator in &decl.declarations {
                     self.process_variable_declarator(declarator, has_stmt_prompt);
@@ -1304,18 +1518,411 @@ impl<'a> Visit<'a> for PromptVisitor<'a> {
             oxc_ast::AstKind::ExpressionStatement(_) => {
                 self.stmt_span_stack.pop();
                 self.stmt_annotations_stack.pop();
+                self.stmt_ignored_stack.pop();
                 self.stmt_leading_start_stack.pop();
             }
             oxc_ast::AstKind::Function(_) | oxc_ast::AstKind::ArrowFunctionExpression(_) => {
-                self.prompt_idents_stack.pop();
-                self.def_prompt_annotations_stack.pop();
+                self.scope.exit_function();
+            }
+            oxc_ast::AstKind::BlockStatement(_)
+            | oxc_ast::AstKind::ForStatement(_)
+            | oxc_ast::AstKind::ForInStatement(_)
+            | oxc_ast::AstKind::ForOfStatement(_) => {
+                self.scope.exit_scope();
             }
-            oxc_ast::AstKind::VariableDeclaration(_) => {
+            oxc_ast::AstKind::VariableDeclaration(_) | oxc_ast::AstKind::ReturnStatement(_) => {
                 self.stmt_span_stack.pop();
                 self.stmt_annotations_stack.pop();
+                self.stmt_ignored_stack.pop();
                 self.stmt_leading_start_stack.pop();
             }
             _ => {}
         }
     }
 }
+
+/// Whether the expression is a function or an arrow function expression.
+fn is_function_expression(expr: &ast::Expression) -> bool {
+    matches!(
+        expr,
+        ast::Expression::ArrowFunctionExpression(_) | ast::Expression::FunctionExpression(_)
+    )
+}
+
+/// Visitor detecting prompts passed to LLM SDK call sites, e.g.,
+/// `client.messages.create({ system: "..." })`. It runs after the prompt
+/// visitor, so the call site prompts follow the assignment prompts.
+struct CallSiteVisitor<'v, 'a> {
+    /// Prompt visitor collecting the prompts and the groups.
+    visitor: &'v mut PromptVisitor<'a>,
+    /// Prompt detection rules.
+    detector: PromptDetector,
+    /// Stack of expression statement starts with the leading annotation starts.
+    stmt_start_stack: Vec<(u32, Option<u32>)>,
+}
+
+impl<'a> CallSiteVisitor<'_, 'a> {
+    /// Process the call arguments matching the call site signatures, including
+    /// properties of object literal arguments.
+    fn process_call(
+        &mut self,
+        call_span: &oxc_span::Span,
+        callee: &ast::Expression<'a>,
+        arguments: &[ast::Argument<'a>],
+    ) {
+        let code = self.visitor.code;
+        let Some(call_site) = self.detector.call_site(callee.span().source_text(code)) else {
+            return;
+        };
+
+        // Leading annotations only extend calls starting the statement
+        let enclosure_start = match self.stmt_start_stack.last() {
+            Some((stmt_start, Some(leading_start))) if *stmt_start == call_span.start => {
+                *leading_start
+            }
+            _ => call_span.start,
+        };
+        let enclosure = (enclosure_start, call_span.end);
+
+        let mut properties = Vec::new();
+        for (position, argument) in arguments.iter().enumerate() {
+            let Some(value) = argument.as_expression() else {
+                continue;
+            };
+            match value {
+                ast::Expression::ObjectExpression(obj)
+                    if self.visitor.extract_message(obj).is_none() =>
+                {
+                    properties.extend(obj.properties.iter().filter_map(|prop| match prop {
+                        ast::ObjectPropertyKind::ObjectProperty(prop) => Some(prop),
+                        _ => None,
+                    }));
+                }
+                _ => {
+                    if let Some(signature) = call_site.positional(position) {
+                        let binding =
+                            argument_binding(code, self.visitor.span_outer(&callee.span()));
+                        self.visitor.process_call_argument(
+                            value,
+                            signature.role,
+                            &binding,
+                            enclosure,
+                        );
+                    }
+                }
+            }
+        }
+
+        for prop in properties {
+            let key_span = prop.key.span();
+            if let Some(signature) = call_site.named(key_span.source_text(code)) {
+                let binding = argument_binding(code, self.visitor.span_outer(&key_span));
+                self.visitor.process_call_argument(
+                    &prop.value,
+                    signature.role,
+                    &binding,
+                    enclosure,
+                );
+            }
+        }
+    }
+}
+
+impl<'a> Visit<'a> for CallSiteVisitor<'_, 'a> {
+    fn enter_node(&mut self, kind: oxc_ast::AstKind<'a>) {
+        match kind {
+            oxc_ast::AstKind::ExpressionStatement(expr) => {
+                let leading_start = self
+                    .visitor
+                    .collect_adjacent_leading_comments(&expr.span)
+                    .first()
+                    .and_then(|first| first.spans.first())
+                    .map(|s| s.outer.0);
+                self.stmt_start_stack.push((expr.span.start, leading_start));
+            }
+
+            oxc_ast::AstKind::CallExpression(call) => {
+                self.process_call(&call.span, &call.callee, &call.arguments);
+            }
+
+            // Constructors are matched by the class, e.g., `new SystemMessage(...)`
+            oxc_ast::AstKind::NewExpression(new) => {
+                self.process_call(&new.span, &new.callee, &new.arguments);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn leave_node(&mut self, kind: oxc_ast::AstKind<'a>) {
+        if let oxc_ast::AstKind::ExpressionStatement(_) = kind {
+            self.stmt_start_stack.pop();
+        }
+    }
+}
+
+/// Visitor resolving the identifiers referencing prompt bindings, e.g.,
+/// `send(userPrompt)`, tracking the function and class scopes. Binding
+/// identifiers, including parameters, shadow the outer bindings.
+struct ReferenceVisitor {
+    /// Prompt references resolver.
+    references: PromptReferences,
+}
+
+impl ReferenceVisitor {
+    /// Resolve the identifier occurrence referencing a prompt binding.
+    fn identifier(&mut self, ident: &ast::IdentifierReference, kind: PromptReferenceKind) {
+        self.references
+            .identifier(&ident.name, (ident.span.start, ident.span.end), kind);
+    }
+
+    /// Resolve the identifiers passed directly as call arguments, e.g.,
+    /// `send(userPrompt)`.
+    fn arguments(&mut self, arguments: &[ast::Argument]) {
+        for argument in arguments {
+            if let Some(ast::Expression::Identifier(ident)) = argument.as_expression() {
+                self.identifier(ident, PromptReferenceKind::Argument);
+            }
+        }
+    }
+}
+
+impl<'a> Visit<'a> for ReferenceVisitor {
+    fn enter_node(&mut self, kind: oxc_ast::AstKind<'a>) {
+        match kind {
+            oxc_ast::AstKind::Function(_)
+            | oxc_ast::AstKind::ArrowFunctionExpression(_)
+            | oxc_ast::AstKind::Class(_) => {
+                self.references.enter_scope();
+            }
+
+            oxc_ast::AstKind::BindingIdentifier(ident) => {
+                self.references
+                    .declaration(&ident.name, (ident.span.start, ident.span.end));
+            }
+
+            oxc_ast::AstKind::IdentifierReference(ident) => {
+                self.identifier(ident, PromptReferenceKind::Read);
+            }
+
+            oxc_ast::AstKind::AssignmentExpression(expr) => {
+                if let ast::AssignmentTarget::AssignmentTargetIdentifier(ident) = &expr.left {
+                    self.identifier(ident, PromptReferenceKind::Reassign);
+                }
+            }
+
+            oxc_ast::AstKind::CallExpression(call) => self.arguments(&call.arguments),
+
+            oxc_ast::AstKind::NewExpression(new) => self.arguments(&new.arguments),
+
+            _ => {}
+        }
+    }
+
+    fn leave_node(&mut self, kind: oxc_ast::AstKind<'a>) {
+        if let oxc_ast::AstKind::Function(_)
+        | oxc_ast::AstKind::ArrowFunctionExpression(_)
+        | oxc_ast::AstKind::Class(_) = kind
+        {
+            self.references.exit_scope();
+        }
+    }
+}
+
+impl<'a> PromptVisitor<'a> {
+    /// Process a call argument value as a prompt, either a string or chat
+    /// messages. Messages arrays are grouped into conversations.
+    fn process_call_argument(
+        &mut self,
+        value: &ast::Expression<'a>,
+        role: Option<PromptRole>,
+        binding: &PromptBinding,
+        enclosure: Span,
+    ) {
+        let prompts_len = self.prompts.len();
+        let messages = self.extract_messages(value);
+        if messages.is_empty() {
+            self.push_call_argument(value, binding, enclosure);
+            for prompt in &mut self.prompts[prompts_len..] {
+                prompt.role = role;
+            }
+            return;
+        }
+
+        for (message_role, content) in messages {
+            let message_len = self.prompts.len();
+            self.push_call_argument(content, binding, enclosure);
+            for prompt in &mut self.prompts[message_len..] {
+                prompt.role = message_role.or(role);
+            }
+        }
+
+        // Group the messages array into a conversation
+        if let ast::Expression::ArrayExpression(array) = value
+            && let Some(group) = prompt_group(
+                &self.file,
+                self.span_outer(&array.span),
+                binding,
+                &self.prompts,
+                prompts_len,
+            )
+        {
+            self.groups.push(group);
+        }
+    }
+
+    /// Push a string call argument as a prompt. Call arguments are prompts by
+    /// the call site signature, so unlike `resolve_prompt_meta`, the binding
+    /// isn't checked.
+    fn push_call_argument(
+        &mut self,
+        value: &ast::Expression<'a>,
+        binding: &PromptBinding,
+        enclosure: Span,
+    ) {
+        let (span, vars) = match value {
+            ast::Expression::StringLiteral(string) => (string.span, Vec::new()),
+            ast::Expression::TemplateLiteral(template) => {
+                (template.span, self.extract_template_vars(template))
+            }
+            _ => return,
+        };
+        let span = self.span_shape_literal(&span);
+        let content = build_content_tokens(&span, &vars);
+        self.prompts.push(Prompt {
+            file: self.file.clone(),
+            span,
+            enclosure,
+            vars,
+            annotations: Vec::new(),
+            binding: binding.clone(),
+            content,
+            joint: SpanShape {
+                outer: (0, 0),
+                inner: (0, 0),
+            },
+            role: None,
+            references: None,
+        });
+    }
+}
+
+impl<'a> PromptVisitor<'a> {
+    /// Collect the module imports and exports for the project analysis,
+    /// e.g., `SYSTEM` for `import { SYSTEM } from "./prompts"` or
+    /// `export const SYSTEM = "..."`.
+    fn module_links(&self, program: &ast::Program<'a>) -> (Vec<ParseImport>, Vec<ParseExport>) {
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+        for stmt in &program.body {
+            match stmt {
+                ast::Statement::ImportDeclaration(decl) => {
+                    let source = decl.source.value.to_string();
+                    for specifier in decl.specifiers.iter().flatten() {
+                        let (name, span, local) = match specifier {
+                            ast::ImportDeclarationSpecifier::ImportSpecifier(specifier) => (
+                                specifier.imported.name().to_string(),
+                                specifier.imported.span(),
+                                &specifier.local,
+                            ),
+                            ast::ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => (
+                                "default".to_string(),
+                                specifier.local.span,
+                                &specifier.local,
+                            ),
+                            ast::ImportDeclarationSpecifier::ImportNamespaceSpecifier(
+                                specifier,
+                            ) => ("*".to_string(), specifier.local.span, &specifier.local),
+                        };
+                        imports.push(ParseImport {
+                            source: source.clone(),
+                            name,
+                            local: local.name.to_string(),
+                            span: self.span_outer(&span),
+                        });
+                    }
+                }
+
+                ast::Statement::ExportNamedDeclaration(decl) => {
+                    // Exported declarations, e.g., `export const SYSTEM = "..."`
+                    let mut identifiers = Vec::new();
+                    match &decl.declaration {
+                        Some(ast::Declaration::VariableDeclaration(var)) => {
+                            for declarator in &var.declarations {
+                                self.extract_binding_identifiers(&declarator.id, &mut identifiers);
+                            }
+                        }
+                        Some(ast::Declaration::FunctionDeclaration(func)) => {
+                            identifiers
+                                .extend(func.id.iter().map(|id| (id.name.to_string(), id.span)));
+                        }
+                        Some(ast::Declaration::ClassDeclaration(class)) => {
+                            identifiers
+                                .extend(class.id.iter().map(|id| (id.name.to_string(), id.span)));
+                        }
+                        _ => {}
+                    }
+                    for (name, span) in identifiers {
+                        exports.push(ParseExport {
+                            name: name.clone(),
+                            local: name,
+                            span: self.span_outer(&span),
+                            source: None,
+                        });
+                    }
+
+                    // Export lists, e.g., `export { system as SYSTEM }`
+                    let source = decl.source.as_ref().map(|source| source.value.to_string());
+                    for specifier in &decl.specifiers {
+                        exports.push(ParseExport {
+                            name: specifier.exported.name().to_string(),
+                            local: specifier.local.name().to_string(),
+                            span: self.span_outer(&specifier.local.span()),
+                            source: source.clone(),
+                        });
+                    }
+                }
+
+                ast::Statement::ExportDefaultDeclaration(decl) => {
+                    let local = match &decl.declaration {
+                        ast::ExportDefaultDeclarationKind::Identifier(ident) => {
+                            Some((ident.name.to_string(), ident.span))
+                        }
+                        ast::ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+                            func.id.as_ref().map(|id| (id.name.to_string(), id.span))
+                        }
+                        ast::ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                            class.id.as_ref().map(|id| (id.name.to_string(), id.span))
+                        }
+                        _ => None,
+                    };
+                    if let Some((local, span)) = local {
+                        exports.push(ParseExport {
+                            name: "default".to_string(),
+                            local,
+                            span: self.span_outer(&span),
+                            source: None,
+                        });
+                    }
+                }
+
+                // Wildcard re-exports, e.g., `export * from "./prompts"`
+                ast::Statement::ExportAllDeclaration(decl) => {
+                    let name = decl
+                        .exported
+                        .as_ref()
+                        .map_or("*".to_string(), |exported| exported.name().to_string());
+                    exports.push(ParseExport {
+                        name,
+                        local: "*".to_string(),
+                        span: self.span_outer(&decl.span),
+                        source: Some(decl.source.value.to_string()),
+                    });
+                }
+
+                _ => {}
+            }
+        }
+        (imports, exports)
+    }
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+
+set -e
+
+echo -e "⚡️ Testing language features\n"
+
+for feature in ts py rb php cs java go; do
+	echo -e "🌀 Testing $feature...\n"
+	cargo test -p volumen_parser --no-default-features --features "$feature"
+	cargo build -p volumen-wasm --no-default-features --features "$feature" \
+		--target wasm32-unknown-unknown
+	echo
+done
+
+echo -e "🌀 Building without languages...\n"
+cargo build -p volumen_parser --no-default-features
+echo
+
+echo -e "🟢 All language features work!"
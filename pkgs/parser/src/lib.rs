@@ -1,42 +1,73 @@
-use volumen_parser_core::VolumenParser;
+use std::cell::RefCell;
+#[cfg(feature = "cs")]
 use volumen_parser_cs::ParserCs;
+#[cfg(feature = "go")]
 use volumen_parser_go::ParserGo;
+#[cfg(feature = "java")]
 use volumen_parser_java::ParserJava;
+#[cfg(feature = "php")]
 use volumen_parser_php::ParserPhp;
+#[cfg(feature = "py")]
 use volumen_parser_py::ParserPy;
+#[cfg(feature = "rb")]
 use volumen_parser_rb::ParserRb;
+#[cfg(feature = "ts")]
 use volumen_parser_ts::ParserTs;
 use volumen_types::*;
 
+pub use volumen_parser_core::{
+    LineIndex, ParserRegistry, VolumenParser, file_language, render, template,
+};
+
 pub struct Parser {}
 
 impl Parser {
-    pub fn parse(source: &str, filename: &str) -> ParseResult {
-        let lower = filename.to_ascii_lowercase();
-        let ext = lower.rsplit('.').next().unwrap_or("");
-        match ext {
-            "js" | "jsx" | "mjs" | "mjsx" | "cjs" | "cjsx" | "ts" | "tsx" => {
-                ParserTs::parse(source, filename)
-            }
-
-            "py" | "pyi" => ParserPy::parse(source, filename),
-
-            "rb" | "ruby" => ParserRb::parse(source, filename),
-
-            "php" => ParserPhp::parse(source, filename),
-
-            "cs" => ParserCs::parse(source, filename),
+    /// Creates the registry with the default language parsers, i.e., oxc for
+    /// TypeScript and JavaScript, Ruff for Python and Tree-sitter for the
+    /// rest. Use it to map custom file types or to swap the parsers. Only the
+    /// languages enabled with the cargo features are registered, so the
+    /// others produce the `language-not-enabled` error.
+    pub fn registry() -> ParserRegistry {
+        #[allow(unused_mut)]
+        let mut registry = ParserRegistry::new();
+        #[cfg(feature = "ts")]
+        registry
+            .parser::<ParserTs>(ParseLanguage::Ts)
+            .parser::<ParserTs>(ParseLanguage::Js);
+        #[cfg(feature = "py")]
+        registry.parser::<ParserPy>(ParseLanguage::Py);
+        #[cfg(feature = "rb")]
+        registry.parser::<ParserRb>(ParseLanguage::Rb);
+        #[cfg(feature = "php")]
+        registry.parser::<ParserPhp>(ParseLanguage::Php);
+        #[cfg(feature = "cs")]
+        registry.parser::<ParserCs>(ParseLanguage::Cs);
+        #[cfg(feature = "go")]
+        registry.parser::<ParserGo>(ParseLanguage::Go);
+        #[cfg(feature = "java")]
+        registry.parser::<ParserJava>(ParseLanguage::Java);
+        registry
+    }
 
-            "go" => ParserGo::parse(source, filename),
+    pub fn parse(source: &str, filename: &str, options: &ParseOptions) -> ParseResult {
+        with_default_registry(|registry| registry.parse(source, filename, options))
+    }
 
-            "java" => ParserJava::parse(source, filename),
+    /// Parse the project files and link the prompts imported across them,
+    /// e.g., `SYSTEM` in `import { SYSTEM } from "./prompts"`.
+    pub fn parse_project(files: &[ProjectFile], options: &ParseOptions) -> ProjectResult {
+        with_default_registry(|registry| registry.parse_project(files, options))
+    }
+}
 
-            _ => ParseResult::ParseResultError(ParseResultError {
-                state: ParseResultErrorStateError,
-                error: format!("Unsupported file extension for file: {}", filename),
-            }),
-        }
+/// Runs the callback with the thread default registry. The registry reuses
+/// the parser instances, which aren't shareable between threads, so each
+/// thread gets its own.
+fn with_default_registry<T>(callback: impl FnOnce(&mut ParserRegistry) -> T) -> T {
+    thread_local! {
+        static REGISTRY: RefCell<ParserRegistry> = RefCell::new(Parser::registry());
     }
+    REGISTRY.with_borrow_mut(callback)
 }
 
 #[cfg(test)]
@@ -46,108 +77,134 @@ mod tests {
     use volumen_parser_test::*;
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_js() {
         let js_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
         "# };
-        let js_result = Parser::parse(js_source, "example.js");
+        let js_result = Parser::parse(js_source, "example.js", &ParseOptions::default());
         assert_prompts_size(js_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_jsx() {
         let jsx_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
           const jsx = <div>{prompt}</div>;
         "# };
-        let jsx_result = Parser::parse(jsx_source, "example.jsx");
+        let jsx_result = Parser::parse(jsx_source, "example.jsx", &ParseOptions::default());
         assert_prompts_size(jsx_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_ts() {
         let ts_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
         "# };
-        let ts_result = Parser::parse(ts_source, "example.ts");
+        let ts_result = Parser::parse(ts_source, "example.ts", &ParseOptions::default());
         assert_prompts_size(ts_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_tsx() {
         let tsx_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
           const tsx = <div>{prompt}</div>;
         "# };
-        let tsx_result = Parser::parse(tsx_source, "example.tsx");
+        let tsx_result = Parser::parse(tsx_source, "example.tsx", &ParseOptions::default());
         assert_prompts_size(tsx_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_mjs() {
         let mjs_source = r#"
 // This is a comment
 const prompt = "Hello, {name}!";
 "#;
-        let mjs_result = Parser::parse(mjs_source, "example.mjs");
+        let mjs_result = Parser::parse(mjs_source, "example.mjs", &ParseOptions::default());
         assert_prompts_size(mjs_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_cjs() {
         let cjs_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
         "# };
-        let cjs_result = Parser::parse(cjs_source, "example.cjs");
+        let cjs_result = Parser::parse(cjs_source, "example.cjs", &ParseOptions::default());
         assert_prompts_size(cjs_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
+    fn parse_es6_and_jsm() {
+        let js_source = indoc! { r#"
+          // This is a comment
+          const prompt = "Hello, {name}!";
+        "# };
+
+        for ext in ["es6", "jsm"] {
+            let filename = format!("example.{ext}");
+            let js_result = Parser::parse(js_source, &filename, &ParseOptions::default());
+            assert_prompts_size(js_result, 1);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "py")]
     fn parse_py() {
         let py_source = indoc! { r#"
           # This is a comment
           prompt = "Hello, {name}!"
         "# };
-        let py_result = Parser::parse(py_source, "example.py");
+        let py_result = Parser::parse(py_source, "example.py", &ParseOptions::default());
         assert_prompts_size(py_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "py")]
     fn parse_pyi() {
         let pyi_source = indoc! { r#"
           # This is a comment
           prompt: str = "Hello, {name}!"
         "# };
-        let pyi_result = Parser::parse(pyi_source, "example.pyi");
+        let pyi_result = Parser::parse(pyi_source, "example.pyi", &ParseOptions::default());
         assert_prompts_size(pyi_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_mjsx() {
         let mjsx_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
         "# };
-        let mjsx_result = Parser::parse(mjsx_source, "example.mjsx");
+        let mjsx_result = Parser::parse(mjsx_source, "example.mjsx", &ParseOptions::default());
         assert_prompts_size(mjsx_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "ts")]
     fn parse_cjsx() {
         let cjsx_source = indoc! { r#"
           // This is a comment
           const prompt = "Hello, {name}!";
         "# };
-        let cjsx_result = Parser::parse(cjsx_source, "example.cjsx");
+        let cjsx_result = Parser::parse(cjsx_source, "example.cjsx", &ParseOptions::default());
         assert_prompts_size(cjsx_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "rb")]
     fn parse_rb_and_ruby() {
         let ruby_source = indoc! { r#"
           prompt = "Hello, {name}!"
@@ -155,22 +212,100 @@ const prompt = "Hello, {name}!";
 
         for ext in ["rb", "ruby"] {
             let filename = format!("example.{ext}");
-            let ruby_result = Parser::parse(ruby_source, &filename);
+            let ruby_result = Parser::parse(ruby_source, &filename, &ParseOptions::default());
+            assert_prompts_size(ruby_result, 1);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rb")]
+    fn parse_rb_filenames() {
+        let ruby_source = indoc! { r#"
+          prompt = "Hello, {name}!"
+        "# };
+
+        for filename in ["Rakefile", "lib/tasks/prompts.rake", "prompts.gemspec"] {
+            let ruby_result = Parser::parse(ruby_source, filename, &ParseOptions::default());
             assert_prompts_size(ruby_result, 1);
         }
     }
 
     #[test]
+    #[cfg(feature = "rb")]
+    fn parse_reused() {
+        let sources = [
+            indoc! { r#"
+              prompt = "Hello, {name}!"
+            "# },
+            indoc! { r#"
+              def broken(
+              # @prompt
+              system = "You are a helpful assistant."
+            "# },
+            indoc! { r#"
+              # @prompt
+              greeting = "Hi, #{name}!"
+            "# },
+        ];
+
+        let mut parser = ParserRb::new();
+        for source in sources {
+            assert_eq!(
+                parser.parse(source, "prompts.rb", &ParseOptions::default()),
+                ParserRb::new().parse(source, "prompts.rb", &ParseOptions::default())
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "rb", feature = "php"))]
+    fn parse_detected() {
+        let rb_source = indoc! { r#"
+          #!/usr/bin/env ruby
+          prompt = "Hello, {name}!"
+        "# };
+        let php_source = indoc! { r#"
+          <html>
+          <?php $prompt = "Hello, {name}!"; ?>
+        "# };
+
+        for (source, filename, language, detection) in [
+            (
+                rb_source,
+                "bin/setup",
+                ParseLanguage::Rb,
+                ParseDetection::Shebang,
+            ),
+            (
+                php_source,
+                "views/index.tpl",
+                ParseLanguage::Php,
+                ParseDetection::Opener,
+            ),
+        ] {
+            let result = Parser::parse(source, filename, &ParseOptions::default());
+            let ParseResult::ParseResultSuccess(ref success) = result else {
+                panic!("Expected ParseResultSuccess, got {result:?}");
+            };
+            assert_eq!(success.language, language);
+            assert_eq!(success.detection, Some(detection));
+            assert_prompts_size(result, 1);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "php")]
     fn parse_php() {
         let php_source = indoc! { r#"
           <?php
           $prompt = "Hello, {name}!";
         "# };
-        let php_result = Parser::parse(php_source, "example.php");
+        let php_result = Parser::parse(php_source, "example.php", &ParseOptions::default());
         assert_prompts_size(php_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "cs")]
     fn parse_cs() {
         let cs_source = indoc! { r#"
           using System;
@@ -179,22 +314,24 @@ const prompt = "Hello, {name}!";
               string prompt = "Hello, {name}!";
           }
         "# };
-        let cs_result = Parser::parse(cs_source, "example.cs");
+        let cs_result = Parser::parse(cs_source, "example.cs", &ParseOptions::default());
         assert_prompts_size(cs_result, 1);
     }
 
     #[test]
+    #[cfg(feature = "go")]
     fn parse_go() {
         let go_source = indoc! { r#"
           package main
 
           var prompt = "Hello, {name}!"
         "# };
-        let go_result = Parser::parse(go_source, "example.go");
+        let go_result = Parser::parse(go_source, "example.go", &ParseOptions::default());
         assert_prompts_size(go_result, 0);
     }
 
     #[test]
+    #[cfg(feature = "java")]
     #[ignore = "Wasm tests fail, fix them first"]
     fn parse_java() {
         let java_source = indoc! { r#"
@@ -202,20 +339,340 @@ const prompt = "Hello, {name}!";
             String prompt = "Hello, {name}!";
           }
         "# };
-        let java_result = Parser::parse(java_source, "Example.java");
+        let java_result = Parser::parse(java_source, "Example.java", &ParseOptions::default());
         assert_prompts_size(java_result, 1);
     }
 
+    #[test]
+    #[cfg(feature = "rb")]
+    fn positions_multibyte() {
+        let rb_source = indoc! { r#"
+          # 👋 Greeting
+          prompt = "你好, #{name}! 🌍"
+        "# };
+        let result = Parser::parse(rb_source, "example.rb", &ParseOptions::default());
+        let ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. }) = result else {
+            panic!("Expected ParseResultSuccess");
+        };
+        let index = LineIndex::new(rb_source);
+        let utf16: Vec<u16> = rb_source.encode_utf16().collect();
+        let slice = |span: Span| {
+            let positions = index.span(span);
+            String::from_utf16(&utf16[positions.start.utf16 as usize..positions.end.utf16 as usize])
+                .unwrap()
+        };
+
+        let prompt = &prompts[0];
+        assert_eq!(slice(prompt.span.outer), r#""你好, #{name}! 🌍""#);
+        assert_eq!(slice(prompt.vars[0].span.outer), "#{name}");
+        assert_eq!(
+            index.span(prompt.vars[0].span.outer),
+            SpanPositions {
+                start: Position {
+                    line: 1,
+                    column: 14,
+                    utf16: 28,
+                },
+                end: Position {
+                    line: 1,
+                    column: 21,
+                    utf16: 35,
+                },
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "ts", feature = "py", feature = "rb"))]
+    fn result_language() {
+        let source = indoc! { r#"
+          // @prompt
+          const prompt = "Hello!";
+        "# };
+        let ParseResult::ParseResultSuccess(result) =
+            Parser::parse(source, "example.tsx", &ParseOptions::default())
+        else {
+            panic!("Expected ParseResultSuccess");
+        };
+        assert_eq!(result.language, ParseLanguage::Ts);
+        assert_eq!(result.dialect, Some(ParseDialect::Tsx));
+        assert_eq!(result.parser, "oxc");
+
+        let ParseResult::ParseResultSuccess(result) =
+            Parser::parse(source, "example.jsx", &ParseOptions::default())
+        else {
+            panic!("Expected ParseResultSuccess");
+        };
+        assert_eq!(result.language, ParseLanguage::Js);
+        assert_eq!(result.dialect, Some(ParseDialect::Jsx));
+
+        let source = indoc! { r#"
+          # @prompt
+          prompt = "Hello!"
+        "# };
+        let ParseResult::ParseResultSuccess(result) =
+            Parser::parse(source, "example.pyi", &ParseOptions::default())
+        else {
+            panic!("Expected ParseResultSuccess");
+        };
+        assert_eq!(result.language, ParseLanguage::Py);
+        assert_eq!(result.dialect, Some(ParseDialect::Pyi));
+        assert_eq!(result.parser, "ruff");
+
+        let ParseResult::ParseResultSuccess(result) =
+            Parser::parse(source, "example.rb", &ParseOptions::default())
+        else {
+            panic!("Expected ParseResultSuccess");
+        };
+        assert_eq!(result.language, ParseLanguage::Rb);
+        assert_eq!(result.dialect, None);
+        assert_eq!(result.parser, "tree-sitter");
+    }
+
+    #[test]
+    #[cfg(feature = "rb")]
+    fn parse_options() {
+        let source = indoc! { r#"
+          # @llm
+          greeting = "Hello!"
+          user_template = "Hi, #{name}!"
+          user_prompt = "Bye!"
+        "# };
+        let options = ParseOptions {
+            markers: Some(vec!["@llm".to_string()]),
+            names: Some(vec!["*_template".to_string()]),
+            ..Default::default()
+        };
+        assert_prompts_size(Parser::parse(source, "example.rb", &options), 2);
+
+        let options = ParseOptions {
+            name_detection: Some(false),
+            ..Default::default()
+        };
+        assert_prompts_size(Parser::parse(source, "example.rb", &options), 0);
+
+        let options = ParseOptions {
+            max_file_size: Some(16),
+            ..Default::default()
+        };
+        let ParseResult::ParseResultError(result) = Parser::parse(source, "example.rb", &options)
+        else {
+            panic!("Expected ParseResultError");
+        };
+        assert_eq!(result.diagnostics[0].code, "file-too-large");
+        assert_eq!(result.language, Some(ParseLanguage::Rb));
+    }
+
+    #[test]
+    #[cfg(feature = "ts")]
+    fn parse_project_ts() {
+        let prompts_source = indoc! { r#"
+          export const SYSTEM_PROMPT = "You are a helpful assistant.";
+          export const UNUSED_PROMPT = "Hello!";
+        "# };
+        let app_source = indoc! { r#"
+          import { SYSTEM_PROMPT } from "./prompts";
+          send(SYSTEM_PROMPT);
+        "# };
+        let files = vec![
+            ProjectFile {
+                path: "src/prompts.ts".to_string(),
+                source: prompts_source.to_string(),
+            },
+            ProjectFile {
+                path: "src/app.ts".to_string(),
+                source: app_source.to_string(),
+            },
+        ];
+        let project = Parser::parse_project(&files, &ParseOptions::default());
+        assert_eq!(
+            project.links,
+            vec![ProjectLink {
+                file: 1,
+                span: (9, 22),
+                prompt: ProjectPrompt { file: 0, prompt: 0 },
+            }]
+        );
+        assert_eq!(project.unused, vec![ProjectPrompt { file: 0, prompt: 1 }]);
+    }
+
+    #[test]
+    #[cfg(feature = "py")]
+    fn parse_project_py() {
+        let prompts_source = indoc! { r#"
+          SYSTEM_PROMPT = "You are a helpful assistant."
+          UNUSED_PROMPT = "Hello!"
+        "# };
+        let app_source = indoc! { r#"
+          from .prompts import SYSTEM_PROMPT
+
+          send(SYSTEM_PROMPT)
+        "# };
+        let files = vec![
+            ProjectFile {
+                path: "app/prompts.py".to_string(),
+                source: prompts_source.to_string(),
+            },
+            ProjectFile {
+                path: "app/main.py".to_string(),
+                source: app_source.to_string(),
+            },
+        ];
+        let project = Parser::parse_project(&files, &ParseOptions::default());
+        assert_eq!(
+            project.links,
+            vec![ProjectLink {
+                file: 1,
+                span: (21, 34),
+                prompt: ProjectPrompt { file: 0, prompt: 0 },
+            }]
+        );
+        assert_eq!(project.unused, vec![ProjectPrompt { file: 0, prompt: 1 }]);
+    }
+
     #[test]
     fn unsupported_extension_returns_error() {
-        let result = Parser::parse("prompt = \"Hello, {name}!\"", "example.txt");
+        let result = Parser::parse(
+            "prompt = \"Hello, {name}!\"",
+            "example.txt",
+            &ParseOptions::default(),
+        );
 
         match result {
-            ParseResult::ParseResultError(ParseResultError { state, error }) => {
+            ParseResult::ParseResultError(ParseResultError {
+                state,
+                error,
+                diagnostics,
+                language,
+                ..
+            }) => {
                 assert_eq!(state, ParseResultErrorStateError);
                 assert_eq!(error, "Unsupported file extension for file: example.txt");
+                assert_eq!(diagnostics.len(), 1);
+                assert_eq!(diagnostics[0].code, "unsupported-file");
+                assert_eq!(language, None);
             }
             _ => panic!("Expected ParseResultError for unsupported extension"),
         }
     }
+
+    #[test]
+    #[cfg(feature = "php")]
+    fn registry_overrides() {
+        let mut registry = Parser::registry();
+        registry
+            .parser::<volumen_parser_py_tree_sitter::ParserPy>(ParseLanguage::Py)
+            .extension("pyw", ParseLanguage::Py, None)
+            .glob("templates/**/*.html", ParseLanguage::Php, None);
+
+        let py_source = indoc! { r#"
+          prompt = "Hello, {name}!"
+        "# };
+        match registry.parse(py_source, "script.pyw", &ParseOptions::default()) {
+            ParseResult::ParseResultSuccess(result) => {
+                assert_eq!(result.language, ParseLanguage::Py);
+                assert_eq!(result.parser, "tree-sitter");
+                assert_eq!(result.prompts.len(), 1);
+            }
+            result => panic!("Expected ParseResultSuccess, got {result:?}"),
+        }
+
+        let php_source = indoc! { r#"
+          <?php
+          $prompt = "Hello, {name}!";
+        "# };
+        match registry.parse(
+            php_source,
+            "templates/users/show.html",
+            &ParseOptions::default(),
+        ) {
+            ParseResult::ParseResultSuccess(result) => {
+                assert_eq!(result.language, ParseLanguage::Php);
+                assert_eq!(result.prompts.len(), 1);
+            }
+            result => panic!("Expected ParseResultSuccess, got {result:?}"),
+        }
+
+        // The default registry is unaffected
+        assert!(matches!(
+            Parser::parse(py_source, "script.pyw", &ParseOptions::default()),
+            ParseResult::ParseResultError(_)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "ts")]
+    fn registry_dialects() {
+        let mut registry = Parser::registry();
+        registry.extension("es", ParseLanguage::Js, Some(ParseDialect::Jsx));
+
+        let jsx_source = indoc! { r#"
+          const prompt = "Hello, {name}!";
+          const jsx = <div>{prompt}</div>;
+        "# };
+        match registry.parse(jsx_source, "App.es", &ParseOptions::default()) {
+            ParseResult::ParseResultSuccess(result) => {
+                assert_eq!(result.language, ParseLanguage::Js);
+                assert_eq!(result.dialect, Some(ParseDialect::Jsx));
+                assert_eq!(result.prompts.len(), 1);
+            }
+            result => panic!("Expected ParseResultSuccess, got {result:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ts")]
+    fn parse_detected_dialects() {
+        let node_source = indoc! { r#"
+          #!/usr/bin/env node
+          const prompt = "Hello, {name}!";
+          render(<div>{prompt}</div>);
+        "# };
+        let tsx_source = indoc! { r#"
+          // vim: filetype=typescriptreact
+          const prompt: string = "Hello, {name}!";
+          render(<div>{prompt}</div>);
+        "# };
+
+        for (source, filename, language, dialect) in [
+            (node_source, "bin/cli", ParseLanguage::Js, None),
+            (
+                tsx_source,
+                "bin/app",
+                ParseLanguage::Ts,
+                Some(ParseDialect::Tsx),
+            ),
+        ] {
+            let result = Parser::parse(source, filename, &ParseOptions::default());
+            let ParseResult::ParseResultSuccess(ref success) = result else {
+                panic!("Expected ParseResultSuccess, got {result:?}");
+            };
+            assert_eq!(success.language, language);
+            assert_eq!(success.dialect, dialect);
+            assert_prompts_size(result, 1);
+        }
+    }
+
+    #[test]
+    fn disabled_languages() {
+        let files = [
+            (cfg!(feature = "ts"), "example.ts"),
+            (cfg!(feature = "ts"), "example.js"),
+            (cfg!(feature = "py"), "example.py"),
+            (cfg!(feature = "rb"), "example.rb"),
+            (cfg!(feature = "php"), "example.php"),
+            (cfg!(feature = "cs"), "Example.cs"),
+            (cfg!(feature = "go"), "example.go"),
+            (cfg!(feature = "java"), "Example.java"),
+        ];
+        for (enabled, filename) in files {
+            let result = Parser::parse("", filename, &ParseOptions::default());
+            let disabled = matches!(
+                &result,
+                ParseResult::ParseResultError(ParseResultError { diagnostics, .. })
+                    if diagnostics[0].code == "language-not-enabled"
+            );
+            assert_eq!(disabled, !enabled, "{filename}: {result:?}");
+        }
+    }
 }
@@ -1,6 +1,12 @@
 pub(crate) mod parse;
 pub use parse::*;
+pub(crate) mod project;
+pub use project::*;
 pub(crate) mod prompt;
 pub use prompt::*;
+pub(crate) mod render;
+pub use render::*;
 pub(crate) mod span;
 pub use span::*;
+pub(crate) mod template;
+pub use template::*;
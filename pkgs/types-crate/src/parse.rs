@@ -1,17 +1,112 @@
 use serde::{Deserialize, Serialize};
 use litty::literal;
 
+/// Options controlling prompt detection. All fields are optional and fall
+/// back to the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParseOptions {
+    /// Annotation marker keywords, e.g., `["prompt", "llm"]` to detect both
+    /// `@prompt` and `@llm` comments. Markers are case-insensitive. Defaults to
+    /// `["prompt"]`. Each marker also enables the opt-out (`@prompt-ignore`,
+    /// `@noprompt`), file-level (`@prompts`) and region (`@prompt-begin`,
+    /// `@prompt-end`) markers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markers: Option<Vec<String>>,
+    /// Identifier name patterns detecting prompts without annotations, e.g.,
+    /// `["prompt", "*_template"]`. Patterns are case-insensitive, `*` matches
+    /// any characters, and patterns without `*` match anywhere in the name.
+    /// Defaults to `["prompt"]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub names: Option<Vec<String>>,
+    /// Whether to detect prompts by identifier names. When disabled, only
+    /// annotated prompts are detected. Defaults to `true`.
+    #[serde(
+        rename = "nameDetection",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name_detection: Option<bool>,
+    /// Maximum source size in bytes. Larger sources aren't parsed and produce
+    /// an error with the `file-too-large` diagnostic. Unlimited by default.
+    #[serde(
+        rename = "maxFileSize",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_file_size: Option<u32>,
+    /// Call site signatures detecting prompts passed directly to LLM SDK calls,
+    /// in addition to the enabled presets.
+    #[serde(rename = "callSites", default, skip_serializing_if = "Option::is_none")]
+    pub call_sites: Option<Vec<ParseCallSite>>,
+    /// Built-in call site signature presets for the common LLM SDKs, i.e.,
+    /// `openai`, `anthropic`, `ai-sdk`, `gemini` and `langchain`. The presets
+    /// are opt-in, as their callees can match unrelated code, so none are
+    /// enabled by default.
+    #[serde(
+        rename = "callSitePresets",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub call_site_presets: Option<Vec<String>>,
+}
+
+/// Call site signature, e.g., `{ callee: "messages.create", name: "system",
+/// role: "system" }` detects the `system` argument of
+/// `client.messages.create(system="...")`. String arguments become prompts,
+/// and chat message arrays become prompt groups.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseCallSite {
+    /// Callee path matched against the end of the call expression path, e.g.,
+    /// `chat.completions.create` matches `client.chat.completions.create(...)`
+    /// and `$client->chat->completions->create(...)`. Path segments are
+    /// case-insensitive and ignore underscores, so `generateContent` also
+    /// matches `generate_content`.
+    pub callee: String,
+    /// Argument name matched against keyword arguments and keys of object,
+    /// hash, dict, map or struct literal arguments, e.g., `system`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Zero-based positional argument index, e.g., `0` for
+    /// `SystemMessage("...")`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u32>,
+    /// Chat role of the prompts passed in the argument.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<super::prompt::PromptRole>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ParseResult {
     ParseResultError(ParseResultError),
     ParseResultSuccess(ParseResultSuccess),
+    ParseResultPartial(ParseResultPartial),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParseResultError {
     pub state: ParseResultErrorStateError,
+    /// Error message summarizing all diagnostics.
     pub error: String,
+    /// Diagnostics that caused the parse to fail. The order corresponds to
+    /// the order of appearance in the source code.
+    pub diagnostics: Vec<ParseDiagnostic>,
+    /// Source language, unless the failure happened before resolving it,
+    /// e.g., for unsupported files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<ParseLanguage>,
+    /// Source language dialect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<ParseDialect>,
+    /// Content-based language detection rule that resolved the language of
+    /// the file without a known extension, e.g., `shebang` for
+    /// `#!/usr/bin/env python3`. Absent when the language is resolved from the
+    /// file name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection: Option<ParseDetection>,
+    /// Parser implementation that produced the result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parser: Option<String>,
 }
 
 #[literal("error")]
@@ -20,8 +115,191 @@ pub struct ParseResultErrorStateError;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParseResultSuccess {
     pub state: ParseResultSuccessStateSuccess,
+    /// Source language.
+    pub language: ParseLanguage,
+    /// Source language dialect, e.g., `tsx` for TypeScript with JSX.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<ParseDialect>,
+    /// Content-based language detection rule that resolved the language of
+    /// the file without a known extension, e.g., `shebang` for
+    /// `#!/usr/bin/env python3`. Absent when the language is resolved from the
+    /// file name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection: Option<ParseDetection>,
+    /// Parser implementation that produced the result, i.e., `oxc`, `ruff` or
+    /// `tree-sitter`.
+    pub parser: String,
     pub prompts: Vec<super::prompt::Prompt>,
+    /// Chat message groups, e.g., messages arrays. Absent when the source has
+    /// no message groups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<super::prompt::PromptGroup>>,
+    /// Module imports, e.g., `SYSTEM` for `import { SYSTEM } from "./prompts"`.
+    /// Absent when the source has no imports or the language module analysis
+    /// isn't supported yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Vec<ParseImport>>,
+    /// Module exports, e.g., `SYSTEM` for `export const SYSTEM = "..."`. Absent
+    /// when the source has no exports. Python modules export the top-level
+    /// bindings implicitly, so they have no exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exports: Option<Vec<ParseExport>>,
 }
 
 #[literal("success")]
 pub struct ParseResultSuccessStateSuccess;
+
+/// Result of parsing source with recoverable syntax errors, e.g., half-typed
+/// code in an editor. The prompts are extracted from the recovered syntax tree
+/// skipping the code affected by the errors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseResultPartial {
+    pub state: ParseResultPartialStatePartial,
+    /// Source language.
+    pub language: ParseLanguage,
+    /// Source language dialect, e.g., `tsx` for TypeScript with JSX.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<ParseDialect>,
+    /// Content-based language detection rule that resolved the language of
+    /// the file without a known extension, e.g., `shebang` for
+    /// `#!/usr/bin/env python3`. Absent when the language is resolved from the
+    /// file name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection: Option<ParseDetection>,
+    /// Parser implementation that produced the result, i.e., `oxc`, `ruff` or
+    /// `tree-sitter`.
+    pub parser: String,
+    pub prompts: Vec<super::prompt::Prompt>,
+    /// Chat message groups, e.g., messages arrays. Absent when the source has
+    /// no message groups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<super::prompt::PromptGroup>>,
+    /// Module imports, e.g., `SYSTEM` for `import { SYSTEM } from "./prompts"`.
+    /// Absent when the source has no imports or the language module analysis
+    /// isn't supported yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imports: Option<Vec<ParseImport>>,
+    /// Module exports, e.g., `SYSTEM` for `export const SYSTEM = "..."`. Absent
+    /// when the source has no exports. Python modules export the top-level
+    /// bindings implicitly, so they have no exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exports: Option<Vec<ParseExport>>,
+    /// Diagnostics describing the syntax errors the parser recovered from. The
+    /// order corresponds to the order of appearance in the source code.
+    pub diagnostics: Vec<ParseDiagnostic>,
+}
+
+#[literal("partial")]
+pub struct ParseResultPartialStatePartial;
+
+/// Module import binding, e.g., `SYSTEM` for
+/// `import { SYSTEM } from "./prompts"` or `from .prompts import SYSTEM`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseImport {
+    /// Module specifier as written in the source, e.g., `./prompts` or
+    /// `.prompts`.
+    pub source: String,
+    /// Imported name, e.g., `SYSTEM`. Default imports are `default` and
+    /// wildcard imports are `*`.
+    pub name: String,
+    /// Local binding name, e.g., `system` for
+    /// `import { SYSTEM as system } from "./prompts"`.
+    pub local: String,
+    /// Imported name span.
+    pub span: super::span::Span,
+}
+
+/// Module export binding, e.g., `SYSTEM` for `export const SYSTEM = "..."`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseExport {
+    /// Exported name, e.g., `SYSTEM` for `export { system as SYSTEM }`. Default
+    /// exports are `default` and wildcard re-exports are `*`.
+    pub name: String,
+    /// Local name, e.g., `system` for `export { system as SYSTEM }`. For
+    /// re-exports, it's the name imported from the source module.
+    pub local: String,
+    /// Local name span. For exported declarations, e.g.,
+    /// `export const SYSTEM = "..."`, it matches the prompt binding span, and
+    /// otherwise the prompt reference span.
+    pub span: super::span::Span,
+    /// Re-exported module specifier, e.g., `./prompts` for
+    /// `export { SYSTEM } from "./prompts"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Source language:
+/// - `ts`: TypeScript
+/// - `js`: JavaScript
+/// - `py`: Python
+/// - `rb`: Ruby
+/// - `php`: PHP
+/// - `go`: Go
+/// - `java`: Java
+/// - `cs`: C#
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseLanguage {
+    Ts,
+    Js,
+    Py,
+    Rb,
+    Php,
+    Go,
+    Java,
+    Cs,
+}
+
+/// Source language dialect:
+/// - `jsx`: JavaScript with JSX
+/// - `tsx`: TypeScript with JSX
+/// - `pyi`: Python stub file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseDialect {
+    Jsx,
+    Tsx,
+    Pyi,
+}
+
+/// Content-based language detection rule:
+/// - `shebang`: interpreter directive, e.g., `#!/usr/bin/env node`
+/// - `modeline`: Vim or Emacs modeline, e.g., `# vim: set ft=ruby:`
+/// - `opener`: PHP opening tag, i.e., `<?php`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseDetection {
+    Shebang,
+    Modeline,
+    Opener,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseDiagnostic {
+    /// Human-readable diagnostic message, e.g., `Unexpected token`.
+    pub message: String,
+    /// Diagnostic severity.
+    pub severity: ParseDiagnosticSeverity,
+    /// Machine-readable diagnostic code:
+    /// - `syntax-error`: invalid syntax reported by the underlying parser
+    /// - `missing-node`: expected token or node is missing, e.g., `;`
+    /// - `parse-failed`: the underlying parser failed to produce a tree
+    /// - `unsupported-file`: file type isn't supported
+    /// - `language-not-enabled`: file language parser isn't enabled, e.g., with
+    ///   the cargo features
+    /// - `file-too-large`: source exceeds the `maxFileSize` option
+    pub code: String,
+    /// Span pointing to the diagnostic location. It allows to highlight the
+    /// exact code that failed to parse.
+    pub span: super::span::Span,
+    /// Parser implementation that produced the diagnostic, i.e., `oxc`, `ruff`,
+    /// `tree-sitter` or `volumen` for errors produced before parsing.
+    pub parser: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseDiagnosticSeverity {
+    Error,
+    Warning,
+}
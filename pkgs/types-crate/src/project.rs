@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Project source file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    /// File path relative to the project root, e.g., `src/prompts.ts`. Relative
+    /// imports are resolved against it.
+    pub path: String,
+    /// File source code.
+    pub source: String,
+}
+
+/// Result of the project analysis linking the prompts across the files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectResult {
+    /// Parse results. The order corresponds to the order of the files.
+    pub files: Vec<super::parse::ParseResult>,
+    /// Imports linked to the prompts defined in other files, e.g.,
+    /// `import { SYSTEM } from "./prompts"`. The order corresponds to the order
+    /// of the files and the imports.
+    pub links: Vec<ProjectLink>,
+    /// Prompts bound to variables that are neither referenced in the file nor
+    /// imported by other files. The order corresponds to the order of the files
+    /// and the prompts.
+    pub unused: Vec<ProjectPrompt>,
+}
+
+/// Prompt location in the project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectPrompt {
+    /// File index in the project files.
+    pub file: u32,
+    /// Prompt index in the file parse result prompts.
+    pub prompt: u32,
+}
+
+/// Import linked to the imported prompt, e.g., `SYSTEM` in
+/// `import { SYSTEM } from "./prompts"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectLink {
+    /// Importing file index in the project files.
+    pub file: u32,
+    /// Imported name span in the importing file.
+    pub span: super::span::Span,
+    /// Imported prompt.
+    pub prompt: ProjectPrompt,
+}
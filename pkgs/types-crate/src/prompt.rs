@@ -4,6 +4,45 @@ use litty::literal;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PromptVar {
     pub span: super::span::SpanShape,
+    /// Variable expression text, e.g., `user.name` for `{user.name!r:>10}`.
+    pub expression: String,
+    /// Expression kind.
+    pub kind: PromptVarKind,
+    /// Root identifier of the expression, e.g., `items` for
+    /// `items.map(x => x.id).join()`. Absent when the expression isn't
+    /// an identifier, member access, call or index chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Format specification following the expression without the leading `:`,
+    /// e.g., `>10` for `{name:>10}` in Python, `,10:N2` for `{price,10:N2}` in
+    /// C# or `%5.2f` for `fmt.Sprintf("%5.2f", price)` in Go.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Conversion applied to the expression value, e.g., `r` for `{name!r}` in
+    /// Python.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<String>,
+    /// Whether the expression root is a parameter of the enclosing prompt
+    /// function, e.g., `user` for `def build_prompt(user): return f"{user}"`.
+    /// Absent when the prompt isn't returned from a function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<bool>,
+}
+
+/// Kind of the variable expression:
+/// - `identifier`: plain identifier, e.g., `name`
+/// - `member`: member access, e.g., `user.name` or `$user->name`
+/// - `call`: function or method call, e.g., `user.getName()`
+/// - `index`: index access, e.g., `items[0]`
+/// - `other`: any other expression, e.g., `price > 100 ? "high" : "low"`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptVarKind {
+    Identifier,
+    Member,
+    Call,
+    Index,
+    Other,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -38,6 +77,117 @@ pub struct Prompt {
     /// comment chunk. The order corresponds to the order of appearance in
     /// the source code.
     pub annotations: Vec<PromptAnnotation>,
+    /// Identifier the prompt is bound to, e.g., for `const userPrompt = "Hi!";`,
+    /// the binding is `userPrompt`. It allows to refer to prompts by name
+    /// rather than by location.
+    pub binding: PromptBinding,
+    /// Chat message role, e.g., `system` for `// @prompt system` or
+    /// `{ role: "system", content: "..." }`. Absent when the prompt isn't
+    /// a chat message or the role is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<PromptRole>,
+    /// Places in the file consuming the prompt through its binding, e.g.,
+    /// `send(userPrompt)` for `const userPrompt = "Hi!";`. The order
+    /// corresponds to the order of appearance. Absent when the binding isn't
+    /// referenced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<Vec<PromptReference>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptReference {
+    /// Referencing identifier span.
+    pub span: super::span::Span,
+    /// Reference kind.
+    pub kind: PromptReferenceKind,
+}
+
+/// Kind of the prompt reference:
+/// - `read`: the binding value is read, e.g., `userPrompt + "!"`
+/// - `reassign`: the binding is reassigned, e.g., `userPrompt = "Bye!"`
+/// - `argument`: the binding is passed as a call argument, e.g.,
+///   `send(userPrompt)`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptReferenceKind {
+    Read,
+    Reassign,
+    Argument,
+}
+
+/// Chat message role:
+/// - `system`: system instructions, including `developer` messages
+/// - `user`: user message
+/// - `assistant`: assistant (model) message
+/// - `tool`: tool or function call result
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// Group of chat message prompts forming one conversation, e.g., for
+/// `messages = [{"role": "system", "content": "..."}, {"role": "user", "content": "..."}]`,
+/// the group has two messages bound to `messages`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptGroup {
+    pub file: String,
+    /// Enclosure span pointing to the group with associated expression, e.g.,
+    /// for `const messages = [...];`, the enclosure spans from `const` to `;`.
+    pub enclosure: super::span::Span,
+    /// Group expression span, e.g., the messages array from `[` to `]`.
+    pub span: super::span::Span,
+    /// Identifier the group is bound to, e.g., `messages`.
+    pub binding: PromptBinding,
+    /// Group messages. The order corresponds to the conversation order.
+    pub messages: Vec<PromptGroupMessage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptGroupMessage {
+    /// Index of the message prompt in the parse result prompts.
+    pub prompt: u32,
+    /// Message role. Absent when the role is unknown, e.g., for a variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<PromptRole>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptBinding {
+    /// Bound identifier name as it appears in source code, e.g., `userPrompt`,
+    /// `$prompt` or `@prompt`.
+    pub name: String,
+    /// Identifier span.
+    pub span: super::span::Span,
+    /// Binding kind.
+    pub kind: PromptBindingKind,
+}
+
+/// Kind of the prompt binding:
+/// - `local`: local or module-level variable, e.g., `let prompt = "Hi!";`
+/// - `const`: constant, e.g., `const prompt = "Hi!";` or `static final String PROMPT = "Hi!";` in Java
+/// - `field`: class or struct field, e.g., `private String prompt = "Hi!";`
+/// - `property`: object or class property, e.g., `$this->prompt = "Hi!";`
+/// - `destructured`: element of a destructuring pattern, e.g.,
+///   `const [a, b] = ["Hi!", "Bye!"];`
+/// - `argument`: call argument, e.g., `system` for
+///   `client.messages.create(system="Hi!")` or `SystemMessage` for
+///   `SystemMessage("Hi!")`
+/// - `function`: function returning the prompt, e.g., `build_prompt` for
+///   `def build_prompt(user): return f"Hi, {user}!"`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptBindingKind {
+    Local,
+    Const,
+    Field,
+    Property,
+    Destructured,
+    Argument,
+    Function,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -52,6 +202,11 @@ pub enum PromptContentToken {
 pub struct PromptContentTokenStr {
     pub r#type: PromptContentTokenStrTypeStr,
     pub span: super::span::Span,
+    /// Escape sequences found in the string chunk, e.g., `\n` in `"Hi!\n"`.
+    /// The decoded text is the raw span text with each escape span replaced by
+    /// its decoded text. Absent when the chunk has no escape sequences.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escapes: Option<Vec<PromptContentTokenEscape>>,
 }
 
 #[literal("str")]
@@ -70,11 +225,24 @@ pub struct PromptContentTokenVarTypeVar;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PromptContentTokenJoint {
     pub r#type: PromptContentTokenJointTypeJoint,
+    /// Escape sequences found in the joint inner span, e.g., `\n` in
+    /// `.join("\n")`. Absent when the joint has no escape sequences.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escapes: Option<Vec<PromptContentTokenEscape>>,
 }
 
 #[literal("joint")]
 pub struct PromptContentTokenJointTypeJoint;
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptContentTokenEscape {
+    /// Raw escape sequence span, e.g., `\n` or `\u00e9`.
+    pub span: super::span::Span,
+    /// Decoded text, e.g., a line break for `\n`. Line continuations decode
+    /// into an empty string.
+    pub text: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PromptAnnotation {
     /// Prompt annotation span shapes. Each span shape represents a line as it
@@ -82,4 +250,20 @@ pub struct PromptAnnotation {
     /// e.g., `// @prompt hello`, and the inner span covers just the content,
     /// e.g., ` @prompt hello`.
     pub spans: Vec<super::span::SpanShape>,
+    /// Structured attributes parsed from the marker arguments, e.g., for
+    /// `// @prompt name=greeting model=gpt-4o`, there will be two attributes
+    /// `name` and `model`. The order corresponds to the order of appearance.
+    pub attributes: Vec<PromptAnnotationAttribute>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptAnnotationAttribute {
+    /// Attribute key, e.g., `name` for `name=greeting`.
+    pub key: String,
+    /// Attribute value with surrounding quotes removed, e.g., `greeting` for
+    /// `name=greeting` or `Hello world` for `name="Hello world"`.
+    pub value: String,
+    /// Attribute span covering the whole `key=value` pair as it appears in
+    /// source code.
+    pub span: super::span::Span,
 }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Rendered prompt text with the source map pointing back to the source code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptRender {
+    /// Rendered prompt text.
+    pub text: String,
+    /// Source map segments. Each segment corresponds to a prompt content token,
+    /// and the order corresponds to the order of appearance in the text.
+    pub map: Vec<PromptRenderSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptRenderSegment {
+    /// Segment span in the rendered text.
+    pub span: super::span::Span,
+    /// Source span the segment originates from, i.e., the string token span,
+    /// the variable expression span or the joint span.
+    pub source: super::span::Span,
+    /// Segment kind.
+    pub kind: PromptRenderSegmentKind,
+}
+
+/// Kind of the rendered text segment:
+/// - `str`: string chunk with escape sequences decoded
+/// - `var`: variable substituted with the provided value
+/// - `joint`: joint separator, e.g., `, ` for `["foo", "bar"].join(", ")`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptRenderSegmentKind {
+    Str,
+    Var,
+    Joint,
+}
@@ -7,3 +7,25 @@ pub struct SpanShape {
     pub outer: Span,
     pub inner: Span,
 }
+
+/// Source position resolved from a UTF-8 byte offset, e.g., for the `{name}`
+/// offset in `"👋 {name}"`, the column and the UTF-16 offset account for
+/// the emoji taking 4 bytes but only 2 UTF-16 code units.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Zero-based line number.
+    pub line: u32,
+    /// Zero-based column in UTF-16 code units relative to the line start, as
+    /// used by editors and the Language Server Protocol.
+    pub column: u32,
+    /// Zero-based offset in UTF-16 code units relative to the source start. It
+    /// corresponds to JavaScript string indices.
+    pub utf16: u32,
+}
+
+/// Span resolved into start and end positions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpanPositions {
+    pub start: Position,
+    pub end: Position,
+}
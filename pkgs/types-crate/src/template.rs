@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Prompt exported as a template with canonical placeholders, e.g.,
+/// `Hello, {{name}}!`, regardless of the interpolation syntax used in
+/// source code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    /// Template text with string escape sequences decoded and variables
+    /// replaced with `{{placeholder}}`. Literal `{{` is escaped as `\{{`, and
+    /// the backslashes preceding it are doubled. Single braces next to the
+    /// placeholders are escaped as `\{` and `\}`, e.g., `\{{{name}}\}`.
+    pub text: String,
+    /// Placeholder names. The order corresponds to the prompt variables order,
+    /// so the same placeholder might appear multiple times.
+    pub placeholders: Vec<String>,
+}
+
+/// Placeholder naming strategy:
+/// - `index`: variable index, e.g., `{{var_0}}`
+/// - `name`: variable expression text, e.g., `{{user.name}}` for
+///   `${user.name}`. Expressions that aren't identifiers or member access
+///   chains fall back to the index name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptTemplatePlaceholder {
+    Index,
+    Name,
+}
@@ -1,3 +1,6 @@
 export * from "./parse.js";
+export * from "./project.js";
 export * from "./prompt.js";
+export * from "./render.js";
 export * from "./span.js";
+export * from "./template.js";
@@ -1,11 +1,215 @@
-export type ParseResult = ParseResultError | ParseResultSuccess;
+/** Options controlling prompt detection. All fields are optional and fall
+ * back to the defaults. */
+export interface ParseOptions {
+  /** Annotation marker keywords, e.g., `["prompt", "llm"]` to detect both
+   * `@prompt` and `@llm` comments. Markers are case-insensitive. Defaults to
+   * `["prompt"]`. Each marker also enables the opt-out (`@prompt-ignore`,
+   * `@noprompt`), file-level (`@prompts`) and region (`@prompt-begin`,
+   * `@prompt-end`) markers. */
+  markers?: Array<string>;
+  /** Identifier name patterns detecting prompts without annotations, e.g.,
+   * `["prompt", "*_template"]`. Patterns are case-insensitive, `*` matches
+   * any characters, and patterns without `*` match anywhere in the name.
+   * Defaults to `["prompt"]`. */
+  names?: Array<string>;
+  /** Whether to detect prompts by identifier names. When disabled, only
+   * annotated prompts are detected. Defaults to `true`. */
+  nameDetection?: boolean;
+  /** Maximum source size in bytes. Larger sources aren't parsed and produce
+   * an error with the `file-too-large` diagnostic. Unlimited by default. */
+  maxFileSize?: number;
+  /** Call site signatures detecting prompts passed directly to LLM SDK calls,
+   * in addition to the enabled presets. */
+  callSites?: Array<ParseCallSite>;
+  /** Built-in call site signature presets for the common LLM SDKs, i.e.,
+   * `openai`, `anthropic`, `ai-sdk`, `gemini` and `langchain`. The presets
+   * are opt-in, as their callees can match unrelated code, so none are
+   * enabled by default. */
+  callSitePresets?: Array<string>;
+}
+
+/** Call site signature, e.g., `{ callee: "messages.create", name: "system",
+ * role: "system" }` detects the `system` argument of
+ * `client.messages.create(system="...")`. String arguments become prompts,
+ * and chat message arrays become prompt groups. */
+export interface ParseCallSite {
+  /** Callee path matched against the end of the call expression path, e.g.,
+   * `chat.completions.create` matches `client.chat.completions.create(...)`
+   * and `$client->chat->completions->create(...)`. Path segments are
+   * case-insensitive and ignore underscores, so `generateContent` also
+   * matches `generate_content`. */
+  callee: string;
+  /** Argument name matched against keyword arguments and keys of object,
+   * hash, dict, map or struct literal arguments, e.g., `system`. */
+  name?: string;
+  /** Zero-based positional argument index, e.g., `0` for
+   * `SystemMessage("...")`. */
+  position?: number;
+  /** Chat role of the prompts passed in the argument. */
+  role?: import("./prompt.js").PromptRole;
+}
+
+export type ParseResult = ParseResultError | ParseResultSuccess | ParseResultPartial;
 
 export interface ParseResultError {
   state: "error";
+  /** Error message summarizing all diagnostics. */
   error: string;
+  /** Diagnostics that caused the parse to fail. The order corresponds to
+   * the order of appearance in the source code. */
+  diagnostics: Array<ParseDiagnostic>;
+  /** Source language, unless the failure happened before resolving it,
+   * e.g., for unsupported files. */
+  language?: ParseLanguage;
+  /** Source language dialect. */
+  dialect?: ParseDialect;
+  /** Content-based language detection rule that resolved the language of
+   * the file without a known extension, e.g., `shebang` for
+   * `#!/usr/bin/env python3`. Absent when the language is resolved from the
+   * file name. */
+  detection?: ParseDetection;
+  /** Parser implementation that produced the result. */
+  parser?: string;
 }
 
 export interface ParseResultSuccess {
   state: "success";
+  /** Source language. */
+  language: ParseLanguage;
+  /** Source language dialect, e.g., `tsx` for TypeScript with JSX. */
+  dialect?: ParseDialect;
+  /** Content-based language detection rule that resolved the language of
+   * the file without a known extension, e.g., `shebang` for
+   * `#!/usr/bin/env python3`. Absent when the language is resolved from the
+   * file name. */
+  detection?: ParseDetection;
+  /** Parser implementation that produced the result, i.e., `oxc`, `ruff` or
+   * `tree-sitter`. */
+  parser: string;
   prompts: Array<import("./prompt.js").Prompt>;
+  /** Chat message groups, e.g., messages arrays. Absent when the source has
+   * no message groups. */
+  groups?: Array<import("./prompt.js").PromptGroup>;
+  /** Module imports, e.g., `SYSTEM` for `import { SYSTEM } from "./prompts"`.
+   * Absent when the source has no imports or the language module analysis
+   * isn't supported yet. */
+  imports?: Array<ParseImport>;
+  /** Module exports, e.g., `SYSTEM` for `export const SYSTEM = "..."`. Absent
+   * when the source has no exports. Python modules export the top-level
+   * bindings implicitly, so they have no exports. */
+  exports?: Array<ParseExport>;
+}
+
+/** Result of parsing source with recoverable syntax errors, e.g., half-typed
+ * code in an editor. The prompts are extracted from the recovered syntax tree
+ * skipping the code affected by the errors. */
+export interface ParseResultPartial {
+  state: "partial";
+  /** Source language. */
+  language: ParseLanguage;
+  /** Source language dialect, e.g., `tsx` for TypeScript with JSX. */
+  dialect?: ParseDialect;
+  /** Content-based language detection rule that resolved the language of
+   * the file without a known extension, e.g., `shebang` for
+   * `#!/usr/bin/env python3`. Absent when the language is resolved from the
+   * file name. */
+  detection?: ParseDetection;
+  /** Parser implementation that produced the result, i.e., `oxc`, `ruff` or
+   * `tree-sitter`. */
+  parser: string;
+  prompts: Array<import("./prompt.js").Prompt>;
+  /** Chat message groups, e.g., messages arrays. Absent when the source has
+   * no message groups. */
+  groups?: Array<import("./prompt.js").PromptGroup>;
+  /** Module imports, e.g., `SYSTEM` for `import { SYSTEM } from "./prompts"`.
+   * Absent when the source has no imports or the language module analysis
+   * isn't supported yet. */
+  imports?: Array<ParseImport>;
+  /** Module exports, e.g., `SYSTEM` for `export const SYSTEM = "..."`. Absent
+   * when the source has no exports. Python modules export the top-level
+   * bindings implicitly, so they have no exports. */
+  exports?: Array<ParseExport>;
+  /** Diagnostics describing the syntax errors the parser recovered from. The
+   * order corresponds to the order of appearance in the source code. */
+  diagnostics: Array<ParseDiagnostic>;
+}
+
+/** Module import binding, e.g., `SYSTEM` for
+ * `import { SYSTEM } from "./prompts"` or `from .prompts import SYSTEM`. */
+export interface ParseImport {
+  /** Module specifier as written in the source, e.g., `./prompts` or
+   * `.prompts`. */
+  source: string;
+  /** Imported name, e.g., `SYSTEM`. Default imports are `default` and
+   * wildcard imports are `*`. */
+  name: string;
+  /** Local binding name, e.g., `system` for
+   * `import { SYSTEM as system } from "./prompts"`. */
+  local: string;
+  /** Imported name span. */
+  span: import("./span.js").Span;
 }
+
+/** Module export binding, e.g., `SYSTEM` for `export const SYSTEM = "..."`. */
+export interface ParseExport {
+  /** Exported name, e.g., `SYSTEM` for `export { system as SYSTEM }`. Default
+   * exports are `default` and wildcard re-exports are `*`. */
+  name: string;
+  /** Local name, e.g., `system` for `export { system as SYSTEM }`. For
+   * re-exports, it's the name imported from the source module. */
+  local: string;
+  /** Local name span. For exported declarations, e.g.,
+   * `export const SYSTEM = "..."`, it matches the prompt binding span, and
+   * otherwise the prompt reference span. */
+  span: import("./span.js").Span;
+  /** Re-exported module specifier, e.g., `./prompts` for
+   * `export { SYSTEM } from "./prompts"`. */
+  source?: string;
+}
+
+/** Source language:
+ * - `ts`: TypeScript
+ * - `js`: JavaScript
+ * - `py`: Python
+ * - `rb`: Ruby
+ * - `php`: PHP
+ * - `go`: Go
+ * - `java`: Java
+ * - `cs`: C# */
+export type ParseLanguage = "ts" | "js" | "py" | "rb" | "php" | "go" | "java" | "cs";
+
+/** Source language dialect:
+ * - `jsx`: JavaScript with JSX
+ * - `tsx`: TypeScript with JSX
+ * - `pyi`: Python stub file */
+export type ParseDialect = "jsx" | "tsx" | "pyi";
+
+/** Content-based language detection rule:
+ * - `shebang`: interpreter directive, e.g., `#!/usr/bin/env node`
+ * - `modeline`: Vim or Emacs modeline, e.g., `# vim: set ft=ruby:`
+ * - `opener`: PHP opening tag, i.e., `<?php` */
+export type ParseDetection = "shebang" | "modeline" | "opener";
+
+export interface ParseDiagnostic {
+  /** Human-readable diagnostic message, e.g., `Unexpected token`. */
+  message: string;
+  /** Diagnostic severity. */
+  severity: ParseDiagnosticSeverity;
+  /** Machine-readable diagnostic code:
+   * - `syntax-error`: invalid syntax reported by the underlying parser
+   * - `missing-node`: expected token or node is missing, e.g., `;`
+   * - `parse-failed`: the underlying parser failed to produce a tree
+   * - `unsupported-file`: file type isn't supported
+   * - `language-not-enabled`: file language parser isn't enabled, e.g., with
+   *   the cargo features
+   * - `file-too-large`: source exceeds the `maxFileSize` option */
+  code: string;
+  /** Span pointing to the diagnostic location. It allows to highlight the
+   * exact code that failed to parse. */
+  span: import("./span.js").Span;
+  /** Parser implementation that produced the diagnostic, i.e., `oxc`, `ruff`,
+   * `tree-sitter` or `volumen` for errors produced before parsing. */
+  parser: string;
+}
+
+export type ParseDiagnosticSeverity = "error" | "warning";
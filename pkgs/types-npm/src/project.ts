@@ -0,0 +1,41 @@
+/** Project source file. */
+export interface ProjectFile {
+  /** File path relative to the project root, e.g., `src/prompts.ts`. Relative
+   * imports are resolved against it. */
+  path: string;
+  /** File source code. */
+  source: string;
+}
+
+/** Result of the project analysis linking the prompts across the files. */
+export interface ProjectResult {
+  /** Parse results. The order corresponds to the order of the files. */
+  files: Array<import("./parse.js").ParseResult>;
+  /** Imports linked to the prompts defined in other files, e.g.,
+   * `import { SYSTEM } from "./prompts"`. The order corresponds to the order
+   * of the files and the imports. */
+  links: Array<ProjectLink>;
+  /** Prompts bound to variables that are neither referenced in the file nor
+   * imported by other files. The order corresponds to the order of the files
+   * and the prompts. */
+  unused: Array<ProjectPrompt>;
+}
+
+/** Prompt location in the project. */
+export interface ProjectPrompt {
+  /** File index in the project files. */
+  file: number;
+  /** Prompt index in the file parse result prompts. */
+  prompt: number;
+}
+
+/** Import linked to the imported prompt, e.g., `SYSTEM` in
+ * `import { SYSTEM } from "./prompts"`. */
+export interface ProjectLink {
+  /** Importing file index in the project files. */
+  file: number;
+  /** Imported name span in the importing file. */
+  span: import("./span.js").Span;
+  /** Imported prompt. */
+  prompt: ProjectPrompt;
+}
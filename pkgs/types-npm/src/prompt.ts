@@ -1,7 +1,34 @@
 export interface PromptVar {
   span: import("./span.js").SpanShape;
+  /** Variable expression text, e.g., `user.name` for `{user.name!r:>10}`. */
+  expression: string;
+  /** Expression kind. */
+  kind: PromptVarKind;
+  /** Root identifier of the expression, e.g., `items` for
+   * `items.map(x => x.id).join()`. Absent when the expression isn't
+   * an identifier, member access, call or index chain. */
+  root?: string;
+  /** Format specification following the expression without the leading `:`,
+   * e.g., `>10` for `{name:>10}` in Python, `,10:N2` for `{price,10:N2}` in
+   * C# or `%5.2f` for `fmt.Sprintf("%5.2f", price)` in Go. */
+  format?: string;
+  /** Conversion applied to the expression value, e.g., `r` for `{name!r}` in
+   * Python. */
+  conversion?: string;
+  /** Whether the expression root is a parameter of the enclosing prompt
+   * function, e.g., `user` for `def build_prompt(user): return f"{user}"`.
+   * Absent when the prompt isn't returned from a function. */
+  parameter?: boolean;
 }
 
+/** Kind of the variable expression:
+ * - `identifier`: plain identifier, e.g., `name`
+ * - `member`: member access, e.g., `user.name` or `$user->name`
+ * - `call`: function or method call, e.g., `user.getName()`
+ * - `index`: index access, e.g., `items[0]`
+ * - `other`: any other expression, e.g., `price > 100 ? "high" : "low"` */
+export type PromptVarKind = "identifier" | "member" | "call" | "index" | "other";
+
 export interface Prompt {
   file: string;
   /** Enclosure span pointing to the prompt with associated expression, e.g.,
@@ -33,13 +60,98 @@ export interface Prompt {
    * comment chunk. The order corresponds to the order of appearance in
    * the source code. */
   annotations: Array<PromptAnnotation>;
+  /** Identifier the prompt is bound to, e.g., for `const userPrompt = "Hi!";`,
+   * the binding is `userPrompt`. It allows to refer to prompts by name
+   * rather than by location. */
+  binding: PromptBinding;
+  /** Chat message role, e.g., `system` for `// @prompt system` or
+   * `{ role: "system", content: "..." }`. Absent when the prompt isn't
+   * a chat message or the role is unknown. */
+  role?: PromptRole;
+  /** Places in the file consuming the prompt through its binding, e.g.,
+   * `send(userPrompt)` for `const userPrompt = "Hi!";`. The order
+   * corresponds to the order of appearance. Absent when the binding isn't
+   * referenced. */
+  references?: Array<PromptReference>;
+}
+
+export interface PromptReference {
+  /** Referencing identifier span. */
+  span: import("./span.js").Span;
+  /** Reference kind. */
+  kind: PromptReferenceKind;
+}
+
+/** Kind of the prompt reference:
+ * - `read`: the binding value is read, e.g., `userPrompt + "!"`
+ * - `reassign`: the binding is reassigned, e.g., `userPrompt = "Bye!"`
+ * - `argument`: the binding is passed as a call argument, e.g.,
+ *   `send(userPrompt)` */
+export type PromptReferenceKind = "read" | "reassign" | "argument";
+
+/** Chat message role:
+ * - `system`: system instructions, including `developer` messages
+ * - `user`: user message
+ * - `assistant`: assistant (model) message
+ * - `tool`: tool or function call result */
+export type PromptRole = "system" | "user" | "assistant" | "tool";
+
+/** Group of chat message prompts forming one conversation, e.g., for
+ * `messages = [{"role": "system", "content": "..."}, {"role": "user", "content": "..."}]`,
+ * the group has two messages bound to `messages`. */
+export interface PromptGroup {
+  file: string;
+  /** Enclosure span pointing to the group with associated expression, e.g.,
+   * for `const messages = [...];`, the enclosure spans from `const` to `;`. */
+  enclosure: import("./span.js").Span;
+  /** Group expression span, e.g., the messages array from `[` to `]`. */
+  span: import("./span.js").Span;
+  /** Identifier the group is bound to, e.g., `messages`. */
+  binding: PromptBinding;
+  /** Group messages. The order corresponds to the conversation order. */
+  messages: Array<PromptGroupMessage>;
 }
 
+export interface PromptGroupMessage {
+  /** Index of the message prompt in the parse result prompts. */
+  prompt: number;
+  /** Message role. Absent when the role is unknown, e.g., for a variable. */
+  role?: PromptRole;
+}
+
+export interface PromptBinding {
+  /** Bound identifier name as it appears in source code, e.g., `userPrompt`,
+   * `$prompt` or `@prompt`. */
+  name: string;
+  /** Identifier span. */
+  span: import("./span.js").Span;
+  /** Binding kind. */
+  kind: PromptBindingKind;
+}
+
+/** Kind of the prompt binding:
+ * - `local`: local or module-level variable, e.g., `let prompt = "Hi!";`
+ * - `const`: constant, e.g., `const prompt = "Hi!";` or `static final String PROMPT = "Hi!";` in Java
+ * - `field`: class or struct field, e.g., `private String prompt = "Hi!";`
+ * - `property`: object or class property, e.g., `$this->prompt = "Hi!";`
+ * - `destructured`: element of a destructuring pattern, e.g.,
+ *   `const [a, b] = ["Hi!", "Bye!"];`
+ * - `argument`: call argument, e.g., `system` for
+ *   `client.messages.create(system="Hi!")` or `SystemMessage` for
+ *   `SystemMessage("Hi!")`
+ * - `function`: function returning the prompt, e.g., `build_prompt` for
+ *   `def build_prompt(user): return f"Hi, {user}!"` */
+export type PromptBindingKind = "local" | "const" | "field" | "property" | "destructured" | "argument" | "function";
+
 export type PromptContentToken = PromptContentTokenStr | PromptContentTokenVar | PromptContentTokenJoint;
 
 export interface PromptContentTokenStr {
   type: "str";
   span: import("./span.js").Span;
+  /** Escape sequences found in the string chunk, e.g., `\n` in `"Hi!\n"`.
+   * The decoded text is the raw span text with each escape span replaced by
+   * its decoded text. Absent when the chunk has no escape sequences. */
+  escapes?: Array<PromptContentTokenEscape>;
 }
 
 export interface PromptContentTokenVar {
@@ -50,6 +162,17 @@ export interface PromptContentTokenVar {
 
 export interface PromptContentTokenJoint {
   type: "joint";
+  /** Escape sequences found in the joint inner span, e.g., `\n` in
+   * `.join("\n")`. Absent when the joint has no escape sequences. */
+  escapes?: Array<PromptContentTokenEscape>;
+}
+
+export interface PromptContentTokenEscape {
+  /** Raw escape sequence span, e.g., `\n` or `\u00e9`. */
+  span: import("./span.js").Span;
+  /** Decoded text, e.g., a line break for `\n`. Line continuations decode
+   * into an empty string. */
+  text: string;
 }
 
 export interface PromptAnnotation {
@@ -58,4 +181,19 @@ export interface PromptAnnotation {
    * e.g., `// @prompt hello`, and the inner span covers just the content,
    * e.g., ` @prompt hello`. */
   spans: Array<import("./span.js").SpanShape>;
+  /** Structured attributes parsed from the marker arguments, e.g., for
+   * `// @prompt name=greeting model=gpt-4o`, there will be two attributes
+   * `name` and `model`. The order corresponds to the order of appearance. */
+  attributes: Array<PromptAnnotationAttribute>;
+}
+
+export interface PromptAnnotationAttribute {
+  /** Attribute key, e.g., `name` for `name=greeting`. */
+  key: string;
+  /** Attribute value with surrounding quotes removed, e.g., `greeting` for
+   * `name=greeting` or `Hello world` for `name="Hello world"`. */
+  value: string;
+  /** Attribute span covering the whole `key=value` pair as it appears in
+   * source code. */
+  span: import("./span.js").Span;
 }
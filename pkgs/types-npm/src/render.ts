@@ -0,0 +1,24 @@
+/** Rendered prompt text with the source map pointing back to the source code. */
+export interface PromptRender {
+  /** Rendered prompt text. */
+  text: string;
+  /** Source map segments. Each segment corresponds to a prompt content token,
+   * and the order corresponds to the order of appearance in the text. */
+  map: Array<PromptRenderSegment>;
+}
+
+export interface PromptRenderSegment {
+  /** Segment span in the rendered text. */
+  span: import("./span.js").Span;
+  /** Source span the segment originates from, i.e., the string token span,
+   * the variable expression span or the joint span. */
+  source: import("./span.js").Span;
+  /** Segment kind. */
+  kind: PromptRenderSegmentKind;
+}
+
+/** Kind of the rendered text segment:
+ * - `str`: string chunk with escape sequences decoded
+ * - `var`: variable substituted with the provided value
+ * - `joint`: joint separator, e.g., `, ` for `["foo", "bar"].join(", ")` */
+export type PromptRenderSegmentKind = "str" | "var" | "joint";
@@ -4,3 +4,23 @@ export interface SpanShape {
   outer: Span;
   inner: Span;
 }
+
+/** Source position resolved from a UTF-8 byte offset, e.g., for the `{name}`
+ * offset in `"👋 {name}"`, the column and the UTF-16 offset account for
+ * the emoji taking 4 bytes but only 2 UTF-16 code units. */
+export interface Position {
+  /** Zero-based line number. */
+  line: number;
+  /** Zero-based column in UTF-16 code units relative to the line start, as
+   * used by editors and the Language Server Protocol. */
+  column: number;
+  /** Zero-based offset in UTF-16 code units relative to the source start. It
+   * corresponds to JavaScript string indices. */
+  utf16: number;
+}
+
+/** Span resolved into start and end positions. */
+export interface SpanPositions {
+  start: Position;
+  end: Position;
+}
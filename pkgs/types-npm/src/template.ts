@@ -0,0 +1,20 @@
+/** Prompt exported as a template with canonical placeholders, e.g.,
+ * `Hello, {{name}}!`, regardless of the interpolation syntax used in
+ * source code. */
+export interface PromptTemplate {
+  /** Template text with string escape sequences decoded and variables
+   * replaced with `{{placeholder}}`. Literal `{{` is escaped as `\{{`, and
+   * the backslashes preceding it are doubled. Single braces next to the
+   * placeholders are escaped as `\{` and `\}`, e.g., `\{{{name}}\}`. */
+  text: string;
+  /** Placeholder names. The order corresponds to the prompt variables order,
+   * so the same placeholder might appear multiple times. */
+  placeholders: Array<string>;
+}
+
+/** Placeholder naming strategy:
+ * - `index`: variable index, e.g., `{{var_0}}`
+ * - `name`: variable expression text, e.g., `{{user.name}}` for
+ *   `${user.name}`. Expressions that aren't identifiers or member access
+ *   chains fall back to the index name. */
+export type PromptTemplatePlaceholder = "index" | "name";
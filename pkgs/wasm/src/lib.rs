@@ -5,7 +5,21 @@ extern crate console_error_panic_hook;
 
 #[wasm_bindgen(typescript_custom_section)]
 const TYPES_IMPORTS: &'static str = r#"
-import type { ParseResult } from "@volumen/types";
+import type {
+  ParseDialect,
+  ParseLanguage,
+  ParseOptions,
+  ParseResult,
+  Position,
+  ProjectFile,
+  ProjectResult,
+  Prompt,
+  PromptRender,
+  PromptTemplate,
+  PromptTemplatePlaceholder,
+  Span,
+  SpanPositions,
+} from "@volumen/types";
 
 export * from "@volumen/types";
 "#;
@@ -13,8 +27,182 @@ export * from "@volumen/types";
 static INIT: std::sync::Once = std::sync::Once::new();
 
 #[wasm_bindgen(js_name = parsePrompts, unchecked_return_type = "ParseResult")]
-pub fn parse_prompts(source: &str, filename: &str) -> Result<JsValue, JsValue> {
+pub fn parse_prompts(
+    source: &str,
+    filename: &str,
+    #[wasm_bindgen(unchecked_param_type = "ParseOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
     INIT.call_once(|| console_error_panic_hook::set_once());
-    let result = Parser::parse(source, filename);
+    let options = options_value(options)?;
+    let result = Parser::parse(source, filename, &options);
     Ok(serde_wasm_bindgen::to_value(&result)?)
 }
+
+#[wasm_bindgen(js_name = parseProject, unchecked_return_type = "ProjectResult")]
+pub fn parse_project(
+    #[wasm_bindgen(unchecked_param_type = "Array<ProjectFile>")] files: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "ParseOptions | undefined")] options: JsValue,
+) -> Result<JsValue, JsValue> {
+    INIT.call_once(|| console_error_panic_hook::set_once());
+    let files: Vec<volumen_types::ProjectFile> = serde_wasm_bindgen::from_value(files)?;
+    let options = options_value(options)?;
+    let result = Parser::parse_project(&files, &options);
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[wasm_bindgen(js_name = renderPrompt, unchecked_return_type = "PromptRender")]
+pub fn render_prompt(
+    #[wasm_bindgen(unchecked_param_type = "Prompt")] prompt: JsValue,
+    source: &str,
+    values: Vec<String>,
+) -> Result<JsValue, JsValue> {
+    let prompt: volumen_types::Prompt = serde_wasm_bindgen::from_value(prompt)?;
+    let rendered = render(&prompt, source, &values);
+    Ok(serde_wasm_bindgen::to_value(&rendered)?)
+}
+
+#[wasm_bindgen(js_name = exportTemplate, unchecked_return_type = "PromptTemplate")]
+pub fn export_template(
+    #[wasm_bindgen(unchecked_param_type = "Prompt")] prompt: JsValue,
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "PromptTemplatePlaceholder")] placeholder: JsValue,
+) -> Result<JsValue, JsValue> {
+    let prompt: volumen_types::Prompt = serde_wasm_bindgen::from_value(prompt)?;
+    let placeholder: volumen_types::PromptTemplatePlaceholder =
+        serde_wasm_bindgen::from_value(placeholder)?;
+    let template = template(&prompt, source, placeholder);
+    Ok(serde_wasm_bindgen::to_value(&template)?)
+}
+
+/// Line index resolving span byte offsets into line/column and UTF-16
+/// positions. Create it once per source and reuse for all prompt spans.
+#[wasm_bindgen(js_name = LineIndex)]
+pub struct JsLineIndex(LineIndex);
+
+#[wasm_bindgen(js_class = LineIndex)]
+impl JsLineIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: &str) -> JsLineIndex {
+        JsLineIndex(LineIndex::new(source))
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "Position")]
+    pub fn position(&self, offset: u32) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.0.position(offset))?)
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "SpanPositions")]
+    pub fn span(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Span")] span: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let span: volumen_types::Span = serde_wasm_bindgen::from_value(span)?;
+        Ok(serde_wasm_bindgen::to_value(&self.0.span(span))?)
+    }
+}
+
+/// Parser registry mapping custom file types to the languages, e.g., `.es6`
+/// to JavaScript or `Dangerfile` to Ruby, on top of the built-in ones.
+#[wasm_bindgen(js_name = ParserRegistry)]
+pub struct JsParserRegistry(ParserRegistry);
+
+#[wasm_bindgen(js_class = ParserRegistry)]
+impl JsParserRegistry {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsParserRegistry {
+        JsParserRegistry(Parser::registry())
+    }
+
+    pub fn extension(
+        &mut self,
+        extension: &str,
+        #[wasm_bindgen(unchecked_param_type = "ParseLanguage")] language: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "ParseDialect | undefined")] dialect: JsValue,
+    ) -> Result<(), JsValue> {
+        let (language, dialect) = file_language_value(language, dialect)?;
+        self.0.extension(extension, language, dialect);
+        Ok(())
+    }
+
+    pub fn filename(
+        &mut self,
+        filename: &str,
+        #[wasm_bindgen(unchecked_param_type = "ParseLanguage")] language: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "ParseDialect | undefined")] dialect: JsValue,
+    ) -> Result<(), JsValue> {
+        let (language, dialect) = file_language_value(language, dialect)?;
+        self.0.filename(filename, language, dialect);
+        Ok(())
+    }
+
+    pub fn glob(
+        &mut self,
+        pattern: &str,
+        #[wasm_bindgen(unchecked_param_type = "ParseLanguage")] language: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "ParseDialect | undefined")] dialect: JsValue,
+    ) -> Result<(), JsValue> {
+        let (language, dialect) = file_language_value(language, dialect)?;
+        self.0.glob(pattern, language, dialect);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = parsePrompts, unchecked_return_type = "ParseResult")]
+    pub fn parse_prompts(
+        &mut self,
+        source: &str,
+        filename: &str,
+        #[wasm_bindgen(unchecked_param_type = "ParseOptions | undefined")] options: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        INIT.call_once(|| console_error_panic_hook::set_once());
+        let options = options_value(options)?;
+        let result = self.0.parse(source, filename, &options);
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+
+    #[wasm_bindgen(js_name = parseProject, unchecked_return_type = "ProjectResult")]
+    pub fn parse_project(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "Array<ProjectFile>")] files: JsValue,
+        #[wasm_bindgen(unchecked_param_type = "ParseOptions | undefined")] options: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        INIT.call_once(|| console_error_panic_hook::set_once());
+        let files: Vec<volumen_types::ProjectFile> = serde_wasm_bindgen::from_value(files)?;
+        let options = options_value(options)?;
+        let result = self.0.parse_project(&files, &options);
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+}
+
+impl Default for JsParserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Deserializes the parse options, defaulting them when not passed.
+fn options_value(options: JsValue) -> Result<volumen_types::ParseOptions, JsValue> {
+    if options.is_undefined() || options.is_null() {
+        Ok(Default::default())
+    } else {
+        Ok(serde_wasm_bindgen::from_value(options)?)
+    }
+}
+
+fn file_language_value(
+    language: JsValue,
+    dialect: JsValue,
+) -> Result<
+    (
+        volumen_types::ParseLanguage,
+        Option<volumen_types::ParseDialect>,
+    ),
+    JsValue,
+> {
+    let language = serde_wasm_bindgen::from_value(language)?;
+    let dialect = if dialect.is_undefined() || dialect.is_null() {
+        None
+    } else {
+        Some(serde_wasm_bindgen::from_value(dialect)?)
+    };
+    Ok((language, dialect))
+}
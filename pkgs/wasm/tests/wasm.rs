@@ -1,10 +1,12 @@
 use indoc::indoc;
 use pretty_assertions::assert_eq;
 use volumen_types::*;
-use volumen_wasm::parse_prompts;
+use volumen_wasm::{JsLineIndex, JsParserRegistry, export_template, parse_prompts, render_prompt};
+use wasm_bindgen::JsValue;
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen_test]
+#[cfg(feature = "ts")]
 #[allow(dead_code)]
 fn parse_js_value() {
     let js_source = indoc! {r#"
@@ -15,6 +17,10 @@ fn parse_js_value() {
         js_result,
         ParseResult::ParseResultSuccess(ParseResultSuccess {
             state: ParseResultSuccessStateSuccess,
+            language: ParseLanguage::Js,
+            dialect: None,
+            detection: None,
+            parser: "oxc".into(),
             prompts: vec![Prompt {
                 file: "prompts.js".into(),
                 span: SpanShape {
@@ -28,23 +34,36 @@ fn parse_js_value() {
                         outer: (12, 26),
                         inner: (14, 24),
                     }],
+                    attributes: vec![],
                 }],
+                binding: PromptBinding {
+                    name: "msg".into(),
+                    span: (6, 9),
+                    kind: PromptBindingKind::Const,
+                },
                 content: vec![PromptContentToken::PromptContentTokenStr(
                     PromptContentTokenStr {
                         r#type: PromptContentTokenStrTypeStr,
                         span: (28, 41),
+                        escapes: None,
                     }
                 )],
                 joint: SpanShape {
                     outer: (0, 0),
                     inner: (0, 0),
                 },
-            }]
+                role: None,
+                references: None,
+            }],
+            groups: None,
+            imports: None,
+            exports: None,
         })
     );
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "py")]
 #[allow(dead_code)]
 fn parse_py_value() {
     let py_source = indoc! {r#"
@@ -56,6 +75,10 @@ fn parse_py_value() {
         py_result,
         ParseResult::ParseResultSuccess(ParseResultSuccess {
             state: ParseResultSuccessStateSuccess,
+            language: ParseLanguage::Py,
+            dialect: None,
+            detection: None,
+            parser: "ruff".into(),
             prompts: vec![Prompt {
                 file: "prompts.py".into(),
                 span: SpanShape {
@@ -67,18 +90,31 @@ fn parse_py_value() {
                     span: SpanShape {
                         outer: (24, 30),
                         inner: (25, 29),
-                    }
+                    },
+                    expression: "name".into(),
+                    kind: PromptVarKind::Identifier,
+                    root: Some("name".into()),
+                    format: None,
+                    conversion: None,
+                    parameter: None,
                 }],
                 annotations: vec![PromptAnnotation {
                     spans: vec![SpanShape {
                         outer: (0, 9),
                         inner: (1, 9),
                     }],
+                    attributes: vec![],
                 }],
+                binding: PromptBinding {
+                    name: "msg".into(),
+                    span: (10, 13),
+                    kind: PromptBindingKind::Local,
+                },
                 content: vec![
                     PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
                         r#type: PromptContentTokenStrTypeStr,
                         span: (18, 24),
+                        escapes: None,
                     }),
                     PromptContentToken::PromptContentTokenVar(PromptContentTokenVar {
                         r#type: PromptContentTokenVarTypeVar,
@@ -88,18 +124,25 @@ fn parse_py_value() {
                     PromptContentToken::PromptContentTokenStr(PromptContentTokenStr {
                         r#type: PromptContentTokenStrTypeStr,
                         span: (30, 31),
+                        escapes: None,
                     }),
                 ],
                 joint: SpanShape {
                     outer: (0, 0),
                     inner: (0, 0),
                 },
-            }]
+                role: None,
+                references: None,
+            }],
+            groups: None,
+            imports: None,
+            exports: None,
         })
     );
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "ts")]
 #[allow(dead_code)]
 fn parse_ts_value() {
     let ts_source = indoc! {r#"
@@ -110,6 +153,7 @@ fn parse_ts_value() {
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "rb")]
 #[allow(dead_code)]
 fn parse_rb_value() {
     let rb_source = indoc! {r#"
@@ -120,6 +164,7 @@ fn parse_rb_value() {
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "php")]
 #[allow(dead_code)]
 fn parse_php_value() {
     let php_source = indoc! {r#"
@@ -131,6 +176,7 @@ fn parse_php_value() {
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "cs")]
 #[allow(dead_code)]
 fn parse_cs_value() {
     let cs_source = indoc! {r#"
@@ -145,6 +191,7 @@ fn parse_cs_value() {
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "go")]
 #[allow(dead_code)]
 fn parse_go_value() {
     let go_source = indoc! {r#"
@@ -157,6 +204,7 @@ fn parse_go_value() {
 }
 
 #[wasm_bindgen_test]
+#[cfg(feature = "java")]
 #[allow(dead_code)]
 fn parse_java_value() {
     let java_source = indoc! {r#"
@@ -173,7 +221,7 @@ fn parse_java_value() {
 fn parse_unsupported_extension() {
     let result = parse_test_code_wasm("prompt = \"Hello\"", "prompts.txt");
     match result {
-        ParseResult::ParseResultError(ParseResultError { state, error }) => {
+        ParseResult::ParseResultError(ParseResultError { state, error, .. }) => {
             assert_eq!(state, ParseResultErrorStateError);
             assert_eq!(error, "Unsupported file extension for file: prompts.txt");
         }
@@ -181,6 +229,155 @@ fn parse_unsupported_extension() {
     }
 }
 
+#[wasm_bindgen_test]
+#[cfg(feature = "rb")]
+#[allow(dead_code)]
+fn parser_registry_value() {
+    let mut registry = JsParserRegistry::new();
+    registry
+        .filename(
+            "Dangerfile",
+            serde_wasm_bindgen::to_value(&ParseLanguage::Rb).unwrap(),
+            JsValue::UNDEFINED,
+        )
+        .unwrap();
+    let result = registry
+        .parse_prompts("prompt = \"Hello\"", "Dangerfile", JsValue::UNDEFINED)
+        .unwrap();
+    let result: ParseResult = serde_wasm_bindgen::from_value(result).unwrap();
+    assert_prompt_count(result, 1);
+
+    let result = registry
+        .parse_prompts("prompt = \"Hello\"", "prompts.txt", JsValue::UNDEFINED)
+        .unwrap();
+    let result: ParseResult = serde_wasm_bindgen::from_value(result).unwrap();
+    assert!(matches!(result, ParseResult::ParseResultError(_)));
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "rb")]
+#[allow(dead_code)]
+fn line_index_positions() {
+    let rb_source = indoc! {r#"
+        # 👋
+        prompt = "你好, #{name}!"
+    "#};
+    let result = parse_test_code_wasm(rb_source, "prompts.rb");
+    let ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. }) = result else {
+        panic!("Expected ParseResultSuccess");
+    };
+    let index = JsLineIndex::new(rb_source);
+    let span = serde_wasm_bindgen::to_value(&prompts[0].vars[0].span.outer).unwrap();
+    let positions: SpanPositions =
+        serde_wasm_bindgen::from_value(index.span(span).unwrap()).unwrap();
+    assert_eq!(
+        positions,
+        SpanPositions {
+            start: Position {
+                line: 1,
+                column: 14,
+                utf16: 19,
+            },
+            end: Position {
+                line: 1,
+                column: 21,
+                utf16: 26,
+            },
+        }
+    );
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "rb")]
+#[allow(dead_code)]
+fn parse_options_value() {
+    let rb_source = indoc! {r#"
+        # @llm
+        greeting = "Hello!"
+        prompt = "Bye!"
+    "#};
+    let options = serde_wasm_bindgen::to_value(&ParseOptions {
+        markers: Some(vec!["llm".into()]),
+        name_detection: Some(false),
+        ..Default::default()
+    })
+    .unwrap();
+    let result: ParseResult =
+        serde_wasm_bindgen::from_value(parse_prompts(rb_source, "prompts.rb", options).unwrap())
+            .unwrap();
+    let ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. }) = result else {
+        panic!("Expected ParseResultSuccess");
+    };
+    assert_eq!(prompts.len(), 1);
+    assert_eq!(prompts[0].binding.name, "greeting");
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "rb")]
+#[allow(dead_code)]
+fn render_prompt_value() {
+    let rb_source = indoc! {r#"
+        prompt = "Hello, #{name}!"
+    "#};
+    let result = parse_test_code_wasm(rb_source, "prompts.rb");
+    let ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. }) = result else {
+        panic!("Expected ParseResultSuccess");
+    };
+    let prompt = serde_wasm_bindgen::to_value(&prompts[0]).unwrap();
+    let rendered: PromptRender = serde_wasm_bindgen::from_value(
+        render_prompt(prompt, rb_source, vec!["Sasha".into()]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        rendered,
+        PromptRender {
+            text: "Hello, Sasha!".into(),
+            map: vec![
+                PromptRenderSegment {
+                    span: (0, 7),
+                    source: (10, 17),
+                    kind: PromptRenderSegmentKind::Str,
+                },
+                PromptRenderSegment {
+                    span: (7, 12),
+                    source: (17, 24),
+                    kind: PromptRenderSegmentKind::Var,
+                },
+                PromptRenderSegment {
+                    span: (12, 13),
+                    source: (24, 25),
+                    kind: PromptRenderSegmentKind::Str,
+                },
+            ],
+        }
+    );
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "py")]
+#[allow(dead_code)]
+fn export_template_value() {
+    let py_source = indoc! {r#"
+        prompt = f"Hello, {user.name}!"
+    "#};
+    let result = parse_test_code_wasm(py_source, "prompts.py");
+    let ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. }) = result else {
+        panic!("Expected ParseResultSuccess");
+    };
+    let prompt = serde_wasm_bindgen::to_value(&prompts[0]).unwrap();
+    let placeholder = serde_wasm_bindgen::to_value(&PromptTemplatePlaceholder::Name).unwrap();
+    let template: PromptTemplate =
+        serde_wasm_bindgen::from_value(export_template(prompt, py_source, placeholder).unwrap())
+            .unwrap();
+    assert_eq!(
+        template,
+        PromptTemplate {
+            text: "Hello, {{user.name}}!".into(),
+            placeholders: vec!["user.name".into()],
+        }
+    );
+}
+
 fn assert_prompt_count(result: ParseResult, expected: usize) {
     match result {
         ParseResult::ParseResultSuccess(ParseResultSuccess { prompts, .. }) => {
@@ -189,11 +386,15 @@ fn assert_prompt_count(result: ParseResult, expected: usize) {
         ParseResult::ParseResultError(ParseResultError { error, .. }) => {
             panic!("Parsing failed: {error}");
         }
+        ParseResult::ParseResultPartial(ParseResultPartial { diagnostics, .. }) => {
+            panic!("Parsing partially failed: {diagnostics:?}");
+        }
     }
 }
 
 fn parse_test_code_wasm(source: &str, filename: &str) -> ParseResult {
-    let result = parse_prompts(source, filename).expect("Parsing should succeed");
+    let result =
+        parse_prompts(source, filename, JsValue::UNDEFINED).expect("Parsing should succeed");
     let parse_result: ParseResult =
         serde_wasm_bindgen::from_value(result).expect("Deserialization should succeed");
     parse_result